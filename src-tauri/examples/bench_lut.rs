@@ -0,0 +1,131 @@
+//! LUT application benchmark on a 24MP image.
+//!
+//! Compares the original single-threaded `put_pixel` loop against the
+//! parallel raw-buffer path for every bake / interpolation combination.
+//!
+//!     cargo run --release --example bench_lut [path/to/filter.cube]
+
+use bonio_booth_lib::lut::{self, BakeMode, Interpolation, LutOptions};
+use image::{ImageBuffer, Rgba, RgbaImage};
+use std::time::{Duration, Instant};
+
+const WIDTH: u32 = 6000;
+const HEIGHT: u32 = 4000;
+const RUNS: u32 = 3;
+const DEFAULT_LUT: &str = "../filters/Orange & Teal.cube";
+
+/// Smooth gradients plus a noise term so neighbouring pixels hit different LUT cells
+fn test_image() -> RgbaImage {
+    ImageBuffer::from_fn(WIDTH, HEIGHT, |x, y| {
+        let noise = (x.wrapping_mul(7919) ^ y.wrapping_mul(104729)) % 32;
+        Rgba([
+            ((x * 255 / WIDTH) as u8).wrapping_add(noise as u8),
+            (y * 255 / HEIGHT) as u8,
+            (((x + y) * 255 / (WIDTH + HEIGHT)) as u8) ^ (noise as u8),
+            255,
+        ])
+    })
+}
+
+/// The pre-registry implementation from `apply_lut_filter`
+fn legacy_apply(lut: &lut::Lut3D, img: &RgbaImage) -> RgbaImage {
+    let (width, height) = img.dimensions();
+    let mut output: RgbaImage = ImageBuffer::new(width, height);
+    for (x, y, pixel) in img.enumerate_pixels() {
+        let r = pixel[0] as f32 / 255.0;
+        let g = pixel[1] as f32 / 255.0;
+        let b = pixel[2] as f32 / 255.0;
+        let (nr, ng, nb) = lut.apply(r, g, b);
+        output.put_pixel(
+            x,
+            y,
+            Rgba([
+                (nr * 255.0) as u8,
+                (ng * 255.0) as u8,
+                (nb * 255.0) as u8,
+                pixel[3],
+            ]),
+        );
+    }
+    output
+}
+
+fn best_of(mut f: impl FnMut()) -> Duration {
+    (0..RUNS)
+        .map(|_| {
+            let started = Instant::now();
+            f();
+            started.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn max_channel_diff(a: &RgbaImage, b: &RgbaImage) -> u8 {
+    a.as_raw()
+        .iter()
+        .zip(b.as_raw())
+        .map(|(x, y)| x.abs_diff(*y))
+        .max()
+        .unwrap_or(0)
+}
+
+fn report(label: &str, elapsed: Duration, baseline: Duration, diff: Option<u8>) {
+    let mpx = (WIDTH * HEIGHT) as f64 / 1_000_000.0;
+    println!(
+        "{:<28} {:>8.1} ms {:>8.1} MP/s {:>6.1}x{}",
+        label,
+        elapsed.as_secs_f64() * 1000.0,
+        mpx / elapsed.as_secs_f64(),
+        baseline.as_secs_f64() / elapsed.as_secs_f64(),
+        diff.map(|d| format!("   max diff {}", d)).unwrap_or_default()
+    );
+}
+
+fn main() {
+    let lut_path = std::env::args().nth(1).unwrap_or_else(|| DEFAULT_LUT.to_string());
+    let source = test_image();
+    println!(
+        "LUT: {}\nImage: {}x{} ({:.1} MP), best of {} runs, {} threads\n",
+        lut_path,
+        WIDTH,
+        HEIGHT,
+        (WIDTH * HEIGHT) as f64 / 1_000_000.0,
+        RUNS,
+        rayon::current_num_threads()
+    );
+
+    let reference_lut = lut::Lut3D::parse_cube_file(&lut_path).expect("load LUT");
+    let mut reference = RgbaImage::new(1, 1);
+    let baseline = best_of(|| reference = legacy_apply(&reference_lut, &source));
+    report("legacy (put_pixel, 1 core)", baseline, baseline, None);
+
+    let combos = [
+        ("none / trilinear", BakeMode::None, Interpolation::Trilinear),
+        ("none / tetrahedral", BakeMode::None, Interpolation::Tetrahedral),
+        ("grid64 / trilinear", BakeMode::Grid64, Interpolation::Trilinear),
+        ("grid64 / tetrahedral", BakeMode::Grid64, Interpolation::Tetrahedral),
+        ("full8bit / trilinear", BakeMode::Full8Bit, Interpolation::Trilinear),
+        ("full8bit / tetrahedral", BakeMode::Full8Bit, Interpolation::Tetrahedral),
+    ];
+
+    for (label, bake, interpolation) in combos {
+        let cached = lut::load_with(&lut_path, LutOptions { bake, interpolation })
+            .expect("load LUT");
+
+        // First call bakes the table; time it separately
+        let mut output = source.clone();
+        let started = Instant::now();
+        cached.apply_to_image(&mut output, 1.0);
+        let first = started.elapsed();
+
+        let elapsed = best_of(|| {
+            output.copy_from_slice(&source);
+            cached.apply_to_image(&mut output, 1.0);
+        });
+        report(label, elapsed, baseline, Some(max_channel_diff(&reference, &output)));
+        if bake != BakeMode::None {
+            println!("{:<28} {:>8.1} ms (incl. bake)", "", first.as_secs_f64() * 1000.0);
+        }
+    }
+}
@@ -0,0 +1,146 @@
+//! Shared harness of the check examples (`test_*.rs`): the check runner and a
+//! local HTTP server standing in for the backend or a printer.
+//!
+//! Included by each example with `mod common;` — not an example of its own.
+
+#![allow(dead_code)]
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+
+pub type Check = fn() -> Result<(), String>;
+
+/// Run every check, print the results and exit non-zero if any failed
+pub fn run(checks: &[(&str, Check)]) {
+    let mut failed = 0;
+    for (name, check) in checks {
+        match check() {
+            Ok(()) => println!("ok      {}", name),
+            Err(e) => {
+                failed += 1;
+                println!("FAILED  {}: {}", name, e);
+            }
+        }
+    }
+    println!("\n{} passed, {} failed", checks.len() - failed, failed);
+    if failed > 0 {
+        std::process::exit(1);
+    }
+}
+
+pub fn ensure(condition: bool, message: impl Into<String>) -> Result<(), String> {
+    if condition {
+        Ok(())
+    } else {
+        Err(message.into())
+    }
+}
+
+// =============================================================================
+// Mock HTTP Server
+// =============================================================================
+
+/// A request as the mock server saw it
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: String,
+    /// Path with query
+    pub target: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn json(&self) -> Result<serde_json::Value, String> {
+        serde_json::from_slice(&self.body).map_err(|e| format!("request body: {}", e))
+    }
+}
+
+fn read_request(stream: &mut TcpStream) -> Option<Request> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 8192];
+    let head_end = loop {
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+        let n = stream.read(&mut chunk).ok()?;
+        if n == 0 {
+            return None;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+    let head = String::from_utf8_lossy(&buf[..head_end]).to_string();
+    let mut lines = head.lines();
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let target = request_line.next()?.to_string();
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect();
+    let length = headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, v)| v.parse::<usize>().ok())
+        .unwrap_or(0);
+    let mut body = buf[head_end + 4..].to_vec();
+    while body.len() < length {
+        let n = stream.read(&mut chunk).ok()?;
+        if n == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..n]);
+    }
+    Some(Request { method, target, headers, body })
+}
+
+/// `Connection: close` response with a body
+pub fn http_response(status: u16, content_type: &str, body: &[u8]) -> Vec<u8> {
+    let mut out = format!(
+        "HTTP/1.1 {} X\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    )
+    .into_bytes();
+    out.extend(body);
+    out
+}
+
+/// Serve `connections` requests on a local port, each on its own thread.
+/// `handler` gets the request's number (from 0) and returns the raw HTTP
+/// response. Returns the port and the requests received, logged as they
+/// arrive.
+pub fn serve<H>(connections: usize, handler: H) -> (u16, Arc<Mutex<Vec<Request>>>)
+where
+    H: Fn(usize, &Request) -> Vec<u8> + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind mock server");
+    let port = listener.local_addr().unwrap().port();
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let log = received.clone();
+    let handler = Arc::new(handler);
+
+    std::thread::spawn(move || {
+        for (index, stream) in listener.incoming().take(connections).enumerate() {
+            let Ok(mut stream) = stream else { continue };
+            let log = log.clone();
+            let handler = handler.clone();
+            std::thread::spawn(move || {
+                let Some(request) = read_request(&mut stream) else { return };
+                log.lock().unwrap().push(request.clone());
+                let response = handler(index, &request);
+                let _ = stream.write_all(&response);
+            });
+        }
+    });
+    (port, received)
+}
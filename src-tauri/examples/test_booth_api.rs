@@ -0,0 +1,468 @@
+//! Booth API client checks for `booth_api`.
+//!
+//! Runs `BoothApiClient` against a small in-process HTTP server standing in
+//! for the backend: headers and query of every request, typed bodies, how
+//! each kind of failure maps onto `ApiError`, and which of them are retried.
+//!
+//!     cargo run --example test_booth_api
+//!
+//! Exits non-zero if any check fails.
+
+mod common;
+
+use bonio_booth_lib::booth_api::{self, ApiError, BoothApiClient, Endpoint, RetryPolicy};
+use common::{ensure, Check, Request};
+use reqwest::Method;
+use serde_json::{json, Value};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const CHECKS: &[(&str, Check)] = &[
+    ("machine headers and query", machine_headers),
+    ("verify uses the given machine id", verify_machine_id),
+    ("init decodes typed fields and keeps the rest", init_typed),
+    ("payment request body", payment_body),
+    ("coupon use body", coupon_body),
+    ("device status report body", device_status_report),
+    ("HTTP status error", http_status),
+    ("backend error code", backend_code),
+    ("nested backend error", backend_nested),
+    ("HTML error page", html_error),
+    ("timeout", timeout),
+    ("invalid JSON", invalid_json),
+    ("unexpected body shape", unexpected_shape),
+    ("empty body", empty_body),
+    ("connection refused", connection_refused),
+    ("presigned upload", presigned_upload),
+    ("retryable errors", retryable_errors),
+    ("backoff bounds", backoff_bounds),
+    ("retries a 503 with the same idempotency key", retry_same_key),
+    ("retries a timeout", retry_timeout),
+    ("retries an upload", retry_upload),
+    ("does not retry a rejection", no_retry_rejection),
+    ("gives up after the last attempt", gives_up),
+    ("does not retry single-shot endpoints", no_retry_endpoint),
+];
+
+fn main() {
+    common::run(CHECKS);
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("runtime")
+        .block_on(future)
+}
+
+/// Mock answer: status, body, delay before answering
+struct Reply {
+    status: u16,
+    body: String,
+    delay: Duration,
+}
+
+fn reply(status: u16, body: impl Into<String>) -> Reply {
+    Reply {
+        status,
+        body: body.into(),
+        delay: Duration::ZERO,
+    }
+}
+
+/// Mock backend answering `connections` JSON requests. Returns the base URL
+/// and the requests received.
+fn serve(connections: usize, handler: fn(usize, &Request) -> Reply) -> (String, Arc<Mutex<Vec<Request>>>) {
+    let (port, received) = common::serve(connections, move |index, request| {
+        let answer = handler(index, request);
+        std::thread::sleep(answer.delay);
+        common::http_response(answer.status, "application/json", answer.body.as_bytes())
+    });
+    (format!("http://127.0.0.1:{}", port), received)
+}
+
+/// Client that sends every request once
+fn client(base_url: &str) -> BoothApiClient {
+    BoothApiClient::new(reqwest::Client::new(), base_url, "machine-42", "44444").with_retry_policy(RetryPolicy::NONE)
+}
+
+/// Client with three attempts and short backoff
+fn retrying_client(base_url: &str) -> BoothApiClient {
+    client(base_url).with_retry_policy(RetryPolicy {
+        max_attempts: 3,
+        base_delay: Duration::from_millis(20),
+        max_delay: Duration::from_millis(50),
+    })
+}
+
+fn requests(received: &Arc<Mutex<Vec<Request>>>) -> Vec<Request> {
+    received.lock().unwrap().clone()
+}
+
+fn only_request(received: &Arc<Mutex<Vec<Request>>>) -> Result<Request, String> {
+    let received = received.lock().unwrap();
+    ensure(received.len() == 1, format!("expected 1 request, got {}", received.len()))?;
+    Ok(received[0].clone())
+}
+
+// =============================================================================
+// Checks
+// =============================================================================
+
+fn machine_headers() -> Result<(), String> {
+    let (url, received) = serve(1, |_, _| reply(200, r#"{"ok":true}"#));
+    block_on(client(&url).status()).map_err(|e| e.to_string())?;
+    let request = only_request(&received)?;
+    ensure(request.method == "GET", format!("method {}", request.method))?;
+    ensure(
+        request.target == "/api/machines-public/status?machineId=machine-42",
+        format!("target {}", request.target),
+    )?;
+    ensure(request.header("X-Machine-Id") == Some("machine-42"), "X-Machine-Id missing")?;
+    ensure(request.header("X-Machine-Port") == Some("44444"), "X-Machine-Port missing")
+}
+
+fn verify_machine_id() -> Result<(), String> {
+    let (url, received) = serve(1, |_, _| reply(200, r#"{"valid":true}"#));
+    let body = block_on(client(&url).verify("other-7")).map_err(|e| e.to_string())?;
+    ensure(body["valid"] == json!(true), format!("body {}", body))?;
+    let request = only_request(&received)?;
+    ensure(request.target.ends_with("/verify?machineId=other-7"), format!("target {}", request.target))?;
+    ensure(request.header("X-Machine-Id") == Some("other-7"), "X-Machine-Id not the verified id")
+}
+
+fn init_typed() -> Result<(), String> {
+    let (url, _) = serve(1, |_, _| {
+        reply(
+            200,
+            r#"{"machine":{"_id":"m1","paperLevel":120,"machineName":"Booth"},"theme":{"color":"red"},"isShutdownReady":false,"workspace":{"lineUrl":"x"}}"#,
+        )
+    });
+    let init = block_on(client(&url).init()).map_err(|e| e.to_string())?;
+    let machine = init.machine.as_ref().ok_or("no machine")?;
+    ensure(machine.id.as_deref() == Some("m1"), "machine._id")?;
+    ensure(machine.paper_level == Some(120), "machine.paperLevel")?;
+    ensure(machine.extra.get("machineName") == Some(&json!("Booth")), "machine extra field lost")?;
+    ensure(init.extra.contains_key("workspace"), "top-level extra field lost")?;
+
+    // The frontend gets the body back as it came
+    let value = serde_json::to_value(&init).map_err(|e| e.to_string())?;
+    ensure(value["machine"]["_id"] == json!("m1"), format!("re-serialized {}", value))?;
+    ensure(value.get("isClosedAppReady").is_none(), "absent field serialized as null")
+}
+
+fn payment_body() -> Result<(), String> {
+    let (url, received) = serve(1, |_, _| {
+        reply(200, r#"{"qr_code":"qr","reference_id":"ref-1","transactionId":"t-9"}"#)
+    });
+    let request = booth_api::CreatePaymentRequest {
+        amount: 150.0,
+        number_photo: Some(2),
+        coupon_code_id: None,
+    };
+    let created = block_on(client(&url).create_payment(&request, Some("pay-1"))).map_err(|e| e.to_string())?;
+    ensure(created.reference_id.as_deref() == Some("ref-1"), "reference_id")?;
+    ensure(created.transaction_id.as_deref() == Some("t-9"), "transactionId")?;
+    let sent = only_request(&received)?;
+    ensure(sent.method == "POST", format!("method {}", sent.method))?;
+    ensure(sent.target.starts_with("/api/machines-public/payment/create?"), format!("target {}", sent.target))?;
+    ensure(sent.header("Idempotency-Key") == Some("pay-1"), "caller's Idempotency-Key not sent")?;
+    let body = sent.json()?;
+    ensure(body == json!({ "amount": 150.0, "numberPhoto": 2 }), format!("body {}", body))
+}
+
+fn coupon_body() -> Result<(), String> {
+    let (url, received) = serve(1, |_, _| reply(200, "{}"));
+    let request = booth_api::UseCouponRequest {
+        code: "FREE".to_string(),
+        transaction_id: Some("t-1".to_string()),
+    };
+    block_on(client(&url).use_coupon(&request, None)).map_err(|e| e.to_string())?;
+    let sent = only_request(&received)?;
+    ensure(sent.header("X-Machine-Id") == Some("machine-42"), "X-Machine-Id missing")?;
+    ensure(sent.header("Idempotency-Key").is_some_and(|key| !key.is_empty()), "no generated Idempotency-Key")?;
+    let body = sent.json()?;
+    ensure(body == json!({ "code": "FREE", "transactionId": "t-1" }), format!("body {}", body))
+}
+
+fn device_status_report() -> Result<(), String> {
+    let (url, received) = serve(1, |_, _| reply(200, "{}"));
+    let report = booth_api::DeviceStatusReport {
+        is_startup: true,
+        camera: booth_api::CameraReport {
+            configured: true,
+            found: false,
+            device_name: "Canon".to_string(),
+        },
+        printer: booth_api::PrinterReport {
+            configured: true,
+            found: true,
+            device_detail: "DS-RX1".to_string(),
+            available_printer_names: vec!["DS-RX1".to_string()],
+        },
+    };
+    block_on(client(&url).device_status_report(&report)).map_err(|e| e.to_string())?;
+    let body = only_request(&received)?.json()?;
+    let expected = json!({
+        "isStartup": true,
+        "camera": { "configured": true, "found": false, "deviceName": "Canon" },
+        "printer": {
+            "configured": true,
+            "found": true,
+            "deviceDetail": "DS-RX1",
+            "availablePrinterNames": ["DS-RX1"]
+        }
+    });
+    ensure(body == expected, format!("body {}", body))
+}
+
+fn http_status() -> Result<(), String> {
+    let (url, _) = serve(1, |_, _| reply(404, r#"{"message":"not here"}"#));
+    match block_on(client(&url).frames()) {
+        Err(ApiError::Http { status: 404, body, .. }) => {
+            ensure(body["message"] == json!("not here"), format!("body {}", body))
+        }
+        other => Err(format!("expected Http 404, got {:?}", other)),
+    }
+}
+
+fn backend_code() -> Result<(), String> {
+    let (url, _) = serve(1, |_, _| reply(400, r#"{"code":"COUPON_USED","message":"Coupon already used"}"#));
+    let request = booth_api::CouponCheckRequest { code: "X".to_string() };
+    match block_on(client(&url).check_coupon(&request)) {
+        Err(e @ ApiError::Backend { .. }) => {
+            let text = e.to_string();
+            ensure(e.status() == Some(400), "status")?;
+            ensure(text.contains("COUPON_USED") && text.contains("Coupon already used"), text)
+        }
+        other => Err(format!("expected Backend, got {:?}", other)),
+    }
+}
+
+fn backend_nested() -> Result<(), String> {
+    let (url, _) = serve(1, |_, _| reply(409, r#"{"success":false,"error":{"code":42,"message":"busy"}}"#));
+    match block_on(client(&url).init()) {
+        Err(ApiError::Backend { code, message, .. }) => {
+            ensure(code == "42" && message == "busy", format!("{} / {}", code, message))
+        }
+        other => Err(format!("expected Backend, got {:?}", other)),
+    }
+}
+
+fn html_error() -> Result<(), String> {
+    let (url, _) = serve(1, |_, _| reply(502, "<html>Bad Gateway</html>"));
+    match block_on(client(&url).status()) {
+        Err(ApiError::Http { status: 502, body, .. }) => {
+            ensure(body.as_str().is_some_and(|s| s.contains("Bad Gateway")), format!("body {}", body))
+        }
+        other => Err(format!("expected Http 502, got {:?}", other)),
+    }
+}
+
+fn timeout() -> Result<(), String> {
+    let (url, _) = serve(1, |_, _| Reply {
+        delay: Duration::from_millis(1500),
+        ..reply(200, "{}")
+    });
+    let slow = Endpoint {
+        name: "slow",
+        method: Method::GET,
+        timeout: Duration::from_millis(300),
+        retry: false,
+    };
+    match block_on(client(&url).call::<(), Value>(slow, "/status", None)) {
+        Err(ApiError::Timeout { endpoint: "slow", after }) => {
+            ensure(after == Duration::from_millis(300), format!("after {:?}", after))
+        }
+        other => Err(format!("expected Timeout, got {:?}", other)),
+    }
+}
+
+fn invalid_json() -> Result<(), String> {
+    let (url, _) = serve(1, |_, _| reply(200, "{not json"));
+    match block_on(client(&url).status()) {
+        Err(ApiError::Decode { endpoint: "status", .. }) => Ok(()),
+        other => Err(format!("expected Decode, got {:?}", other)),
+    }
+}
+
+fn unexpected_shape() -> Result<(), String> {
+    let (url, _) = serve(1, |_, _| reply(200, r#"{"machine":"not an object"}"#));
+    match block_on(client(&url).init()) {
+        Err(ApiError::Decode { endpoint: "init", .. }) => Ok(()),
+        other => Err(format!("expected Decode, got {:?}", other)),
+    }
+}
+
+fn empty_body() -> Result<(), String> {
+    let (url, received) = serve(1, |_, _| reply(200, ""));
+    let body = block_on(client(&url).notify_going_offline()).map_err(|e| e.to_string())?;
+    ensure(body.is_null(), format!("body {}", body))?;
+    let sent = only_request(&received)?;
+    ensure(sent.method == "POST", format!("method {}", sent.method))?;
+    ensure(sent.header("Content-Length") == Some("0"), "Content-Length: 0 missing")
+}
+
+fn connection_refused() -> Result<(), String> {
+    // Bind and drop to get a port nobody listens on
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    match block_on(client(&format!("http://127.0.0.1:{}", port)).status()) {
+        Err(ApiError::Network { endpoint: "status", .. }) => Ok(()),
+        other => Err(format!("expected Network, got {:?}", other)),
+    }
+}
+
+fn presigned_upload() -> Result<(), String> {
+    let (url, received) = serve(1, |_, _| reply(200, ""));
+    let target = format!("{}/bucket/photo.jpg?X-Amz-Signature=abc", url);
+    block_on(client(&url).upload_presigned(&target, "image/jpeg", vec![1, 2, 3])).map_err(|e| e.to_string())?;
+    let sent = only_request(&received)?;
+    ensure(sent.method == "PUT", format!("method {}", sent.method))?;
+    ensure(sent.header("Idempotency-Key").is_none(), "Idempotency-Key sent with a PUT")?;
+    ensure(sent.target == "/bucket/photo.jpg?X-Amz-Signature=abc", format!("target {}", sent.target))?;
+    ensure(sent.header("X-Machine-Id").is_none(), "machine header sent to storage")?;
+    ensure(sent.header("Content-Type") == Some("image/jpeg"), "Content-Type")?;
+    ensure(sent.body == [1, 2, 3], "body")
+}
+
+fn retryable_errors() -> Result<(), String> {
+    let http = |status| ApiError::Http {
+        endpoint: "x",
+        status,
+        body: Value::Null,
+    };
+    let retryable = [
+        ApiError::Network { endpoint: "x", message: String::new() },
+        ApiError::Timeout { endpoint: "x", after: Duration::from_secs(1) },
+        http(429),
+        http(500),
+        http(503),
+    ];
+    let fatal = [
+        http(400),
+        http(401),
+        http(404),
+        ApiError::Backend {
+            endpoint: "x",
+            status: 409,
+            code: "COUPON_USED".to_string(),
+            message: String::new(),
+            body: Value::Null,
+        },
+        ApiError::Decode { endpoint: "x", message: String::new() },
+    ];
+    for e in &retryable {
+        ensure(e.is_retryable(), format!("{:?} should be retryable", e))?;
+    }
+    for e in &fatal {
+        ensure(!e.is_retryable(), format!("{:?} should not be retryable", e))?;
+    }
+    Ok(())
+}
+
+fn backoff_bounds() -> Result<(), String> {
+    let policy = RetryPolicy {
+        max_attempts: 8,
+        base_delay: Duration::from_millis(100),
+        max_delay: Duration::from_secs(1),
+    };
+    for retry in 1..=7u32 {
+        let full = Duration::from_millis((100u64 << (retry - 1)).min(1000));
+        for _ in 0..50 {
+            let delay = policy.backoff(retry);
+            ensure(
+                delay >= full / 2 && delay <= full,
+                format!("retry {}: {:?} outside {:?}..={:?}", retry, delay, full / 2, full),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn retry_same_key() -> Result<(), String> {
+    let (url, received) = serve(3, |index, _| match index {
+        0 | 1 => reply(503, r#"{"message":"restarting"}"#),
+        _ => reply(200, r#"{"qr_code":"qr","reference_id":"ref-2"}"#),
+    });
+    let request = booth_api::CreatePaymentRequest {
+        amount: 80.0,
+        number_photo: None,
+        coupon_code_id: None,
+    };
+    let created = block_on(retrying_client(&url).create_payment(&request, None)).map_err(|e| e.to_string())?;
+    ensure(created.reference_id.as_deref() == Some("ref-2"), "reference_id")?;
+    let sent = requests(&received);
+    ensure(sent.len() == 3, format!("expected 3 attempts, got {}", sent.len()))?;
+    let key = sent[0].header("Idempotency-Key").ok_or("no Idempotency-Key")?;
+    ensure(
+        sent.iter().all(|r| r.header("Idempotency-Key") == Some(key)),
+        "Idempotency-Key changed between attempts",
+    )?;
+    ensure(sent.iter().all(|r| r.body == sent[0].body), "body changed between attempts")
+}
+
+fn retry_timeout() -> Result<(), String> {
+    let (url, received) = serve(2, |index, _| Reply {
+        delay: if index == 0 { Duration::from_millis(1500) } else { Duration::ZERO },
+        ..reply(200, r#"{"ok":true}"#)
+    });
+    let slow = Endpoint {
+        name: "slow",
+        method: Method::GET,
+        timeout: Duration::from_millis(300),
+        retry: true,
+    };
+    let body: Value = block_on(retrying_client(&url).call::<(), _>(slow, "/status", None)).map_err(|e| e.to_string())?;
+    ensure(body["ok"] == json!(true), format!("body {}", body))?;
+    ensure(requests(&received).len() == 2, "expected 2 attempts")
+}
+
+fn retry_upload() -> Result<(), String> {
+    let (url, received) = serve(2, |index, _| match index {
+        0 => reply(502, "<html>Bad Gateway</html>"),
+        _ => reply(200, ""),
+    });
+    let target = format!("{}/bucket/photo.jpg", url);
+    block_on(retrying_client(&url).upload_presigned(&target, "image/jpeg", vec![9; 64])).map_err(|e| e.to_string())?;
+    let sent = requests(&received);
+    ensure(sent.len() == 2, format!("expected 2 attempts, got {}", sent.len()))?;
+    ensure(sent.iter().all(|r| r.body == [9; 64]), "upload body not resent")
+}
+
+fn no_retry_rejection() -> Result<(), String> {
+    let (url, received) = serve(3, |_, _| reply(409, r#"{"code":"COUPON_USED","message":"used"}"#));
+    let request = booth_api::UseCouponRequest {
+        code: "X".to_string(),
+        transaction_id: None,
+    };
+    match block_on(retrying_client(&url).use_coupon(&request, None)) {
+        Err(ApiError::Backend { .. }) => {}
+        other => return Err(format!("expected Backend, got {:?}", other)),
+    }
+    ensure(requests(&received).len() == 1, "rejected request was retried")
+}
+
+fn gives_up() -> Result<(), String> {
+    let (url, received) = serve(4, |_, _| reply(502, ""));
+    match block_on(retrying_client(&url).status()) {
+        Err(ApiError::Http { status: 502, .. }) => {}
+        other => return Err(format!("expected Http 502, got {:?}", other)),
+    }
+    let sent = requests(&received).len();
+    ensure(sent == 3, format!("expected 3 attempts, got {}", sent))
+}
+
+fn no_retry_endpoint() -> Result<(), String> {
+    let (url, received) = serve(3, |_, _| reply(503, ""));
+    match block_on(retrying_client(&url).notify_going_offline()) {
+        Err(ApiError::Http { status: 503, .. }) => {}
+        other => return Err(format!("expected Http 503, got {:?}", other)),
+    }
+    ensure(requests(&received).len() == 1, "single-shot endpoint was retried")
+}
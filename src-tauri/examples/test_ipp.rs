@@ -0,0 +1,423 @@
+//! IPP client checks for `ipp`, and the IPP printer backend in
+//! `printer_backend`.
+//!
+//! Runs the client and `IppBackend` against a small in-process IPP server.
+//! Set `BONIO_IPP_URI` to also
+//! run against a real printer or an `ippeveprinter` stand-in, e.g.
+//!
+//!     ippeveprinter -f image/png,image/jpeg -p 8631 TestBooth
+//!     BONIO_IPP_URI=ipp://localhost:8631/ipp/print cargo run --example test_ipp
+//!
+//! Exits non-zero if any check fails.
+
+mod common;
+
+use bonio_booth_lib::ipp::{self, group, job_state, op, printer_state, tag, Attribute, Client, Message, Value};
+use bonio_booth_lib::paper;
+use bonio_booth_lib::printer_backend::{self, IppBackend, PrintDocument, PrinterBackend};
+use common::{ensure, Check};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+
+const CHECKS: &[(&str, Check)] = &[
+    ("encode/decode round trip", round_trip),
+    ("decode rejects truncated message", rejects_truncated),
+    ("printer attributes", printer_attributes),
+    ("print job sends attributes and data", print_job),
+    ("job attributes", job_attributes),
+    ("cancel job", cancel_job),
+    ("cups printers", cups_printers),
+    ("error status is reported", error_status),
+    ("chunked response", chunked_response),
+    ("connection refused", connection_refused),
+    ("printer info from attributes", printer_info),
+    ("select media", select_media),
+    ("driver for job", driver_for_job),
+    ("ipp backend lists and prints", ipp_backend),
+    ("real printer (BONIO_IPP_URI)", real_printer),
+];
+
+fn main() {
+    common::run(CHECKS);
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Response skeleton with the mandatory operation attributes
+fn response(status: u16, request_id: u32) -> Message {
+    Message {
+        version: (1, 1),
+        code: status,
+        request_id,
+        groups: vec![ipp::Group {
+            tag: group::OPERATION,
+            attributes: vec![
+                Attribute::string("attributes-charset", tag::CHARSET, "utf-8"),
+                Attribute::string("attributes-natural-language", tag::LANGUAGE, "en"),
+            ],
+        }],
+        data: Vec::new(),
+    }
+}
+
+type Handler = fn(&Message) -> Message;
+
+/// Serve `connections` requests on a local port. Returns the printer URI and
+/// the requests the server received.
+fn serve(connections: usize, chunked: bool, handler: Handler) -> (String, Arc<Mutex<Vec<Message>>>) {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let log = received.clone();
+    let (port, _) = common::serve(connections, move |_, request| {
+        let Ok(request) = Message::decode(&request.body) else {
+            return common::http_response(400, "text/plain", b"bad IPP request");
+        };
+        let response = handler(&request).encode();
+        log.lock().unwrap().push(request);
+
+        if chunked {
+            let (first, second) = response.split_at(response.len() / 2);
+            let mut out = b"HTTP/1.1 100 Continue\r\n\r\nHTTP/1.1 200 OK\r\nContent-Type: application/ipp\r\nTransfer-Encoding: chunked\r\n\r\n".to_vec();
+            for part in [first, second] {
+                out.extend(format!("{:x}\r\n", part.len()).as_bytes());
+                out.extend(part);
+                out.extend(b"\r\n");
+            }
+            out.extend(b"0\r\n\r\n");
+            out
+        } else {
+            common::http_response(200, "application/ipp", &response)
+        }
+    });
+    (format!("ipp://127.0.0.1:{}/ipp/print", port), received)
+}
+
+/// Answers like a small dye-sub printer. Queues under `/printers/` are
+/// named after their path.
+fn printer_handler(request: &Message) -> Message {
+    let mut response = response(0x0000, request.request_id);
+    match request.code {
+        op::GET_PRINTER_ATTRIBUTES => {
+            let uri = request.get("printer-uri").and_then(Attribute::first_str).unwrap_or_default();
+            let name = uri.split_once("/printers/").map_or("DS-RX1", |(_, name)| name);
+            response.add(group::PRINTER, Attribute::string("printer-name", tag::NAME, name));
+            response.add(group::PRINTER, Attribute::enumeration("printer-state", printer_state::IDLE));
+            response.add(group::PRINTER, Attribute::keywords("printer-state-reasons", &["media-low"]));
+            response.add(group::PRINTER, Attribute::boolean("printer-is-accepting-jobs", true));
+            response.add(
+                group::PRINTER,
+                Attribute::keywords("media-supported", &["na_index-4x6_4x6in", "oe_photo-l_3.5x5in"]),
+            );
+            response.add(group::PRINTER, Attribute::keywords("media-ready", &["na_index-4x6_4x6in"]));
+            response.add(
+                group::PRINTER,
+                Attribute::keywords("document-format-supported", &["image/png", "application/pdf"]),
+            );
+            response.add(group::PRINTER, Attribute::keywords("marker-names", &["Ribbon"]));
+            response.add(group::PRINTER, Attribute::new("marker-levels", tag::INTEGER, vec![Value::Integer(42)]));
+        }
+        op::PRINT_JOB => {
+            response.add(group::JOB, Attribute::integer("job-id", 17));
+            response.add(group::JOB, Attribute::enumeration("job-state", job_state::PENDING));
+        }
+        op::GET_JOB_ATTRIBUTES => {
+            let id = request.get("job-id").and_then(Attribute::first_int).unwrap_or(0);
+            response.add(group::JOB, Attribute::integer("job-id", id));
+            response.add(group::JOB, Attribute::enumeration("job-state", job_state::COMPLETED));
+            response.add(group::JOB, Attribute::keywords("job-state-reasons", &["job-completed-successfully"]));
+        }
+        op::CANCEL_JOB => {}
+        op::CUPS_GET_PRINTERS => {
+            for (name, uri) in [("DS-RX1", "ipp://localhost/printers/DS-RX1"), ("Office", "ipp://localhost/printers/Office")] {
+                response.groups.push(ipp::Group {
+                    tag: group::PRINTER,
+                    attributes: vec![
+                        Attribute::string("printer-name", tag::NAME, name),
+                        Attribute::string("printer-uri-supported", tag::URI, uri),
+                    ],
+                });
+            }
+        }
+        _ => response.code = 0x0501,
+    }
+    response
+}
+
+// =============================================================================
+// Checks
+// =============================================================================
+
+fn round_trip() -> Result<(), String> {
+    let mut message = Message::request(op::PRINT_JOB, 7, "ipp://localhost/ipp/print");
+    message.add(group::JOB, Attribute::integer("copies", 2));
+    message.add(group::JOB, Attribute::keyword("media", "na_index-4x6_4x6in"));
+    message.add(group::JOB, Attribute::boolean("print-scaling-fit", true));
+    message.add(group::JOB, Attribute::keywords("finishings-keywords", &["none", "trim"]));
+    message.data = vec![0x89, b'P', b'N', b'G'];
+
+    let decoded = Message::decode(&message.encode())?;
+    ensure(decoded == message, format!("decoded message differs: {:?}", decoded))
+}
+
+fn rejects_truncated() -> Result<(), String> {
+    let mut message = Message::request(op::GET_PRINTER_ATTRIBUTES, 1, "ipp://localhost/ipp/print");
+    message.add(group::OPERATION, Attribute::keywords("requested-attributes", &["all"]));
+    let bytes = message.encode();
+    // Cut in the middle of the last attribute value
+    ensure(Message::decode(&bytes[..bytes.len() - 3]).is_err(), "truncated message decoded")
+}
+
+fn printer_attributes() -> Result<(), String> {
+    let (uri, received) = serve(1, false, printer_handler);
+    let attributes = Client::new(&uri)?.printer_attributes(&["printer-state", "media-supported"])?;
+
+    ensure(
+        attributes.get("printer-state").and_then(Attribute::first_int) == Some(printer_state::IDLE),
+        "printer-state",
+    )?;
+    ensure(
+        attributes.get("media-supported").map(Attribute::strings).unwrap_or_default().len() == 2,
+        "media-supported",
+    )?;
+    let requests = received.lock().unwrap();
+    let requested = requests[0].get("requested-attributes").map(Attribute::strings).unwrap_or_default();
+    ensure(requested == ["printer-state", "media-supported"], format!("requested-attributes {:?}", requested))?;
+    ensure(
+        requests[0].get("printer-uri").and_then(Attribute::first_str) == Some(uri.as_str()),
+        "printer-uri not sent",
+    )
+}
+
+fn print_job() -> Result<(), String> {
+    let (uri, received) = serve(1, false, printer_handler);
+    let document = vec![7u8; 100_000];
+    let job_id = Client::new(&uri)?.print_job(
+        "Bonio Booth Print",
+        "image/png",
+        vec![Attribute::integer("copies", 3), Attribute::keyword("media", "na_index-4x6_4x6in")],
+        document.clone(),
+    )?;
+    ensure(job_id == 17, format!("job id {}", job_id))?;
+
+    let requests = received.lock().unwrap();
+    let request = &requests[0];
+    ensure(request.code == op::PRINT_JOB, "operation")?;
+    ensure(
+        request.get("document-format").and_then(Attribute::first_str) == Some("image/png"),
+        "document-format",
+    )?;
+    let job = request.groups_of(group::JOB).next().ok_or("no job group")?;
+    ensure(job.get("copies").and_then(Attribute::first_int) == Some(3), "copies")?;
+    ensure(job.get("media").and_then(Attribute::first_str) == Some("na_index-4x6_4x6in"), "media")?;
+    ensure(request.data == document, "document data differs")
+}
+
+fn job_attributes() -> Result<(), String> {
+    let (uri, _) = serve(1, false, printer_handler);
+    let job = Client::new(&uri)?.job_attributes(17)?;
+    ensure(job.get("job-id").and_then(Attribute::first_int) == Some(17), "job-id")?;
+    ensure(
+        job.get("job-state").and_then(Attribute::first_int) == Some(job_state::COMPLETED),
+        "job-state",
+    )
+}
+
+fn cancel_job() -> Result<(), String> {
+    let (uri, received) = serve(1, false, printer_handler);
+    Client::new(&uri)?.cancel_job(17)?;
+    let requests = received.lock().unwrap();
+    ensure(requests[0].code == op::CANCEL_JOB, "operation")?;
+    ensure(requests[0].get("job-id").and_then(Attribute::first_int) == Some(17), "job-id")
+}
+
+fn cups_printers() -> Result<(), String> {
+    let (uri, _) = serve(1, false, printer_handler);
+    let printers = Client::new(&uri)?.cups_printers(&[])?;
+    let names: Vec<_> = printers
+        .iter()
+        .filter_map(|p| p.get("printer-name").and_then(Attribute::first_str))
+        .collect();
+    ensure(names == ["DS-RX1", "Office"], format!("printers {:?}", names))
+}
+
+fn error_status() -> Result<(), String> {
+    fn not_found(request: &Message) -> Message {
+        let mut response = response(0x0406, request.request_id);
+        response.add(group::OPERATION, Attribute::string("status-message", tag::TEXT, "No such job"));
+        response
+    }
+    let (uri, _) = serve(1, false, not_found);
+    match Client::new(&uri)?.job_attributes(99) {
+        Ok(_) => Err("expected an error".to_string()),
+        Err(e) => ensure(
+            e.contains("client-error-not-found") && e.contains("No such job"),
+            format!("error message {:?}", e),
+        ),
+    }
+}
+
+fn chunked_response() -> Result<(), String> {
+    let (uri, _) = serve(1, true, printer_handler);
+    let attributes = Client::new(&uri)?.printer_attributes(&[])?;
+    ensure(
+        attributes.get("printer-name").and_then(Attribute::first_str) == Some("DS-RX1"),
+        "printer-name",
+    )
+}
+
+fn connection_refused() -> Result<(), String> {
+    // Grab a free port and close it again
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let client = Client::new(&format!("ipp://127.0.0.1:{}/ipp/print", port))?;
+    ensure(client.printer_attributes(&[]).is_err(), "expected a connection error")
+}
+
+fn printer_info() -> Result<(), String> {
+    let attrs = ipp::Group {
+        tag: group::PRINTER,
+        attributes: vec![
+            Attribute::enumeration("printer-state", printer_state::IDLE),
+            Attribute::keywords("printer-state-reasons", &["none"]),
+            Attribute::keywords("marker-names", &["Ribbon", "Lamination"]),
+            Attribute::new("marker-levels", tag::INTEGER, vec![Value::Integer(42), Value::Integer(-3)]),
+        ],
+    };
+    let info = printer_backend::info_from_attributes("DS-RX1", &attrs);
+    ensure(info.is_online, format!("idle printer offline: {:?}", info.state_reasons))?;
+    ensure(info.state_reasons.is_empty(), format!("reasons {:?}", info.state_reasons))?;
+    let levels: Vec<_> = info.markers.iter().map(|m| (m.name.as_str(), m.level)).collect();
+    ensure(
+        levels == [("Ribbon", Some(42)), ("Lamination", None)],
+        format!("markers {:?}", levels),
+    )?;
+
+    let stopped = ipp::Group {
+        tag: group::PRINTER,
+        attributes: vec![Attribute::enumeration("printer-state", printer_state::STOPPED)],
+    };
+    let info = printer_backend::info_from_attributes("DS-RX1", &stopped);
+    ensure(!info.is_online, "stopped printer online")?;
+    ensure(info.state_reasons == ["stopped"], format!("reasons {:?}", info.state_reasons))?;
+
+    let not_accepting = ipp::Group {
+        tag: group::PRINTER,
+        attributes: vec![
+            Attribute::enumeration("printer-state", printer_state::IDLE),
+            Attribute::boolean("printer-is-accepting-jobs", false),
+        ],
+    };
+    let info = printer_backend::info_from_attributes("DS-RX1", &not_accepting);
+    ensure(!info.is_online, "printer not accepting jobs online")
+}
+
+fn select_media() -> Result<(), String> {
+    let profile = paper::get("4x6")?;
+    let strings = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    let cases: [(&[&str], &[&str], Option<&str>); 4] = [
+        // Loaded media wins
+        (&["na_index-4x6_4x6in"], &["oe_photo-l_3.5x5in", "na_index-4x6_4x6in"], Some("na_index-4x6_4x6in")),
+        // Loaded media of another size: any supported size that fits
+        (&["oe_photo-l_3.5x5in"], &["oe_photo-l_3.5x5in", "na_index-4x6_4x6in"], Some("na_index-4x6_4x6in")),
+        // No sized match: driverPaper name
+        (&[], &["iso_a6_105x148mm", "custom_4x6"], Some("custom_4x6")),
+        (&[], &["iso_a4_210x297mm"], None),
+    ];
+    for (ready, supported, expected) in cases {
+        let media = printer_backend::select_media(&profile, &strings(ready), &strings(supported));
+        ensure(
+            media.as_deref() == expected,
+            format!("ready {:?} supported {:?}: {:?}", ready, supported, media),
+        )?;
+    }
+    Ok(())
+}
+
+fn driver_for_job() -> Result<(), String> {
+    let queues = ["DS-RX1", "DS-RX1 (CUT)", "Office"];
+    let exists = |name: &str| queues.contains(&name);
+    let cases = [
+        ("DS-RX1", true, "DS-RX1 (CUT)"),
+        ("DS-RX1 (CUT)", true, "DS-RX1 (CUT)"),
+        ("DS-RX1 (CUT)", false, "DS-RX1"),
+        ("DS-RX1", false, "DS-RX1"),
+        // No CUT variant: print as-is
+        ("Office", true, "Office"),
+    ];
+    for (printer, needs_cut, expected) in cases {
+        let queue = printer_backend::driver_for_job(printer, needs_cut, exists);
+        ensure(queue == expected, format!("{} (cut {}): {}", printer, needs_cut, queue))?;
+    }
+    Ok(())
+}
+
+fn ipp_backend() -> Result<(), String> {
+    // Two listings, then printer attributes, Print-Job and job attributes
+    let (uri, received) = serve(5, false, printer_handler);
+    let base_uri = uri.replace("/ipp/print", "/printers/DS-RX1");
+    let cut_uri = uri.replace("/ipp/print", "/printers/DS-RX1_CUT");
+    std::env::set_var("BONIO_CUPS_SERVER", "none");
+    std::env::set_var("BONIO_IPP_PRINTERS", format!("{}, {}", base_uri, cut_uri));
+
+    let backend = IppBackend;
+    let printers = backend.printers()?;
+    let names: Vec<_> = printers.iter().map(|p| p.name.as_str()).collect();
+    ensure(names == ["DS-RX1", "DS-RX1_CUT"], format!("printers {:?}", names))?;
+    ensure(printers.iter().all(|p| p.is_online), "printer offline")?;
+    ensure(backend.owns("DS-RX1") && !backend.owns("Office"), "owns")?;
+
+    // A cut profile goes to the CUT queue found in the listing
+    let sheet = std::env::temp_dir().join(format!("test_ipp-{}.png", std::process::id()));
+    image::DynamicImage::ImageRgb8(image::RgbImage::from_pixel(60, 90, image::Rgb([200, 40, 40])))
+        .save(&sheet)
+        .map_err(|e| e.to_string())?;
+    let pages = [sheet.to_string_lossy().to_string()];
+    let profile = paper::get("2x6")?;
+    let document = PrintDocument {
+        pages: &pages,
+        profile: &profile,
+        copies: 2,
+        collate: true,
+        job_name: "test_ipp",
+    };
+    let job = backend.print("DS-RX1", &document);
+    let _ = std::fs::remove_file(&sheet);
+    let job = job?;
+    ensure(job.id == 17 && job.printer_name == "DS-RX1_CUT", format!("job {:?}", job))?;
+
+    let requests = received.lock().unwrap();
+    let request = requests.iter().find(|r| r.code == op::PRINT_JOB).ok_or("no Print-Job")?;
+    ensure(
+        request.get("printer-uri").and_then(Attribute::first_str) == Some(cut_uri.as_str()),
+        "Print-Job not sent to the CUT queue",
+    )?;
+    ensure(
+        request.get("document-format").and_then(Attribute::first_str) == Some("image/png"),
+        "document-format",
+    )?;
+    let job = request.groups_of(group::JOB).next().ok_or("no job group")?;
+    ensure(job.get("media").and_then(Attribute::first_str) == Some("na_index-4x6_4x6in"), "media")?;
+    ensure(job.get("copies").and_then(Attribute::first_int) == Some(2), "copies")
+}
+
+fn real_printer() -> Result<(), String> {
+    let Ok(uri) = std::env::var("BONIO_IPP_URI") else {
+        println!("        (skipped, BONIO_IPP_URI not set)");
+        return Ok(());
+    };
+    let client = Client::new(&uri)?;
+    let attributes = client.printer_attributes(&["printer-state", "document-format-supported"])?;
+    let formats = attributes
+        .get("document-format-supported")
+        .map(Attribute::strings)
+        .unwrap_or_default();
+    ensure(formats.iter().any(|f| f == "image/png"), format!("no image/png in {:?}", formats))?;
+
+    let mut png = Vec::new();
+    image::DynamicImage::ImageRgb8(image::RgbImage::from_pixel(120, 180, image::Rgb([200, 40, 40])))
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| e.to_string())?;
+    let job_id = client.print_job("test_ipp", "image/png", vec![Attribute::integer("copies", 1)], png)?;
+    let job = client.job_attributes(job_id)?;
+    ensure(job.get("job-state").is_some(), "job-state missing")
+}
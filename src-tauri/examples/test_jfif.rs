@@ -1 +1,327 @@
-﻿use image::codecs::jpeg::JpegEncoder; fn main() { let mut buf = Vec::new(); let img = image::RgbImage::new(10, 10); let mut encoder = JpegEncoder::new_with_quality(&mut buf, 90); encoder.encode_image(&img).unwrap(); println!("Header: {:02X?}", &buf[0..20]); }
+//! JPEG metadata checks for `jpeg_meta`.
+//!
+//! Encodes small images the way the pipeline does and reads the result back
+//! (JFIF density, EXIF via kamadak-exif, ICC and XMP segments). Exits non-zero
+//! if any check fails.
+//!
+//!     cargo run --example test_jfif
+
+mod common;
+
+use bonio_booth_lib::jpeg_meta::{self, BoothInfo, ExifPolicy, MetadataOptions};
+use exif::experimental::Writer;
+use exif::{Field, In, Rational, Tag, Value};
+use image::{DynamicImage, RgbImage};
+use common::{ensure, Check};
+use std::io::Cursor;
+
+const CHECKS: &[(&str, Check)] = &[
+    ("jfif density is print dpi", jfif_density),
+    ("custom dpi", custom_dpi),
+    ("exif resolution matches jfif", exif_resolution),
+    ("srgb profile embedded", srgb_embedded),
+    ("srgb profile header", srgb_header),
+    ("srgb profile can be disabled", srgb_disabled),
+    ("preserve keeps camera tags", preserve_keeps_camera_tags),
+    ("preserve drops gps and makernote", preserve_drops_private_tags),
+    ("strip drops camera tags", strip_drops_camera_tags),
+    ("reset orientation", reset_orientation),
+    ("booth info in exif", booth_exif),
+    ("booth info in xmp", booth_xmp),
+    ("rewrite replaces old segments", rewrite_replaces_segments),
+    ("image data untouched", image_data_untouched),
+    ("rejects non-jpeg input", rejects_non_jpeg),
+];
+
+fn main() {
+    common::run(CHECKS);
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn test_image() -> DynamicImage {
+    DynamicImage::ImageRgb8(RgbImage::from_fn(64, 48, |x, y| {
+        image::Rgb([(x * 4) as u8, (y * 5) as u8, 128])
+    }))
+}
+
+fn plain_jpeg() -> Vec<u8> {
+    let mut buf = Vec::new();
+    let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buf, 90);
+    test_image().to_rgb8().write_with_encoder(encoder).unwrap();
+    buf
+}
+
+fn field(tag: Tag, value: Value) -> Field {
+    Field {
+        tag,
+        ifd_num: In::PRIMARY,
+        value,
+    }
+}
+
+fn ascii(value: &str) -> Value {
+    Value::Ascii(vec![value.as_bytes().to_vec()])
+}
+
+/// A JPEG carrying EXIF like a camera would write (portrait orientation,
+/// exposure, GPS and a MakerNote)
+fn camera_jpeg() -> Vec<u8> {
+    let fields = [
+        field(Tag::Make, ascii("Canon")),
+        field(Tag::Model, ascii("Canon EOS 2000D")),
+        field(Tag::Orientation, Value::Short(vec![6])),
+        field(Tag::XResolution, Value::Rational(vec![Rational { num: 72, denom: 1 }])),
+        field(Tag::ExposureTime, Value::Rational(vec![Rational { num: 1, denom: 125 }])),
+        field(Tag::MakerNote, Value::Undefined(vec![1, 2, 3, 4], 0)),
+        field(Tag::GPSLatitudeRef, ascii("N")),
+    ];
+    let mut writer = Writer::new();
+    for f in &fields {
+        writer.push_field(f);
+    }
+    let mut tiff = Cursor::new(Vec::new());
+    writer.write(&mut tiff, false).unwrap();
+    let tiff = tiff.into_inner();
+
+    let jpeg = plain_jpeg();
+    let mut out = vec![0xFF, 0xD8, 0xFF, 0xE1];
+    out.extend_from_slice(&((tiff.len() + 8) as u16).to_be_bytes());
+    out.extend_from_slice(b"Exif\0\0");
+    out.extend_from_slice(&tiff);
+    out.extend_from_slice(&jpeg[2..]);
+    out
+}
+
+fn booth() -> BoothInfo {
+    BoothInfo {
+        machine_id: "BOOTH-042".to_string(),
+        session_id: "3f2a9c1e5b7d4e8fa0c1d2e3f4a5b6c7".to_string(),
+        timestamp: chrono::Local::now(),
+    }
+}
+
+fn options(exif: ExifPolicy) -> MetadataOptions {
+    MetadataOptions {
+        exif,
+        ..MetadataOptions::default()
+    }
+}
+
+fn exif_of(jpeg: &[u8]) -> Result<exif::Exif, String> {
+    jpeg_meta::read_exif(jpeg).ok_or_else(|| "no EXIF".to_string())
+}
+
+fn ascii_field(exif: &exif::Exif, tag: Tag) -> Option<String> {
+    match &exif.get_field(tag, In::PRIMARY)?.value {
+        Value::Ascii(parts) => parts.first().map(|p| String::from_utf8_lossy(p).to_string()),
+        _ => None,
+    }
+}
+
+fn count_segments(jpeg: &[u8], marker: u8, prefix: &[u8]) -> usize {
+    let (segments, _) = jpeg_meta::segments(jpeg).unwrap();
+    segments
+        .iter()
+        .filter(|s| s.marker == marker && s.data.starts_with(prefix))
+        .count()
+}
+
+// =============================================================================
+// Checks
+// =============================================================================
+
+fn jfif_density() -> Result<(), String> {
+    let jpeg = jpeg_meta::encode_jpeg(&test_image(), 90, None, &MetadataOptions::default())?;
+    let density = jpeg_meta::jfif_density(&jpeg);
+    ensure(
+        density == Some((1, jpeg_meta::PRINT_DPI, jpeg_meta::PRINT_DPI)),
+        format!("density {:?}", density),
+    )?;
+    ensure(count_segments(&jpeg, 0xE0, b"JFIF\0") == 1, "expected one JFIF segment")
+}
+
+fn custom_dpi() -> Result<(), String> {
+    let options = MetadataOptions {
+        dpi: 300,
+        ..MetadataOptions::default()
+    };
+    let jpeg = jpeg_meta::encode_jpeg(&test_image(), 90, None, &options)?;
+    let density = jpeg_meta::jfif_density(&jpeg);
+    ensure(density == Some((1, 300, 300)), format!("density {:?}", density))
+}
+
+fn exif_resolution() -> Result<(), String> {
+    let jpeg = jpeg_meta::encode_jpeg(&test_image(), 90, None, &MetadataOptions::default())?;
+    let exif = exif_of(&jpeg)?;
+    for tag in [Tag::XResolution, Tag::YResolution] {
+        let value = &exif.get_field(tag, In::PRIMARY).ok_or(format!("no {}", tag))?.value;
+        match value {
+            Value::Rational(r) if r[0].to_f64() == jpeg_meta::PRINT_DPI as f64 => {}
+            other => return Err(format!("{} = {:?}", tag, other)),
+        }
+    }
+    let unit = exif
+        .get_field(Tag::ResolutionUnit, In::PRIMARY)
+        .and_then(|f| f.value.get_uint(0));
+    ensure(unit == Some(2), format!("ResolutionUnit {:?}", unit))
+}
+
+fn srgb_embedded() -> Result<(), String> {
+    let jpeg = jpeg_meta::encode_jpeg(&test_image(), 90, None, &MetadataOptions::default())?;
+    let profile = jpeg_meta::icc_profile(&jpeg).ok_or("no ICC profile")?;
+    ensure(profile == jpeg_meta::srgb_profile(), "profile differs from the built-in one")?;
+    let size = u32::from_be_bytes([profile[0], profile[1], profile[2], profile[3]]) as usize;
+    ensure(size == profile.len(), format!("header size {} vs {}", size, profile.len()))?;
+    ensure(&profile[36..40] == b"acsp", "missing 'acsp' signature")?;
+    ensure(&profile[12..20] == b"mntrRGB ", "not an RGB display profile")?;
+    let color_space = exif_of(&jpeg)?
+        .get_field(Tag::ColorSpace, In::PRIMARY)
+        .and_then(|f| f.value.get_uint(0));
+    ensure(color_space == Some(1), format!("ColorSpace {:?}", color_space))
+}
+
+/// Header fields per ICC.1 §7.2: size, version, class, colour space, date, signature
+fn srgb_header() -> Result<(), String> {
+    let profile = jpeg_meta::srgb_profile();
+    ensure(profile.len() >= 132, format!("profile too short: {} bytes", profile.len()))?;
+    let be16 = |at: usize| u16::from_be_bytes([profile[at], profile[at + 1]]);
+    let size = u32::from_be_bytes([profile[0], profile[1], profile[2], profile[3]]) as usize;
+    ensure(size == profile.len(), format!("header size {} vs {}", size, profile.len()))?;
+    ensure(profile[8] == 2, format!("major version {}", profile[8]))?;
+    ensure(&profile[12..24] == b"mntrRGB XYZ ", "class / colour space / PCS")?;
+    let date: Vec<u16> = (0..6).map(|i| be16(24 + i * 2)).collect();
+    ensure(date == [2024, 1, 1, 0, 0, 0], format!("creation date {:?}", date))?;
+    ensure(&profile[36..40] == b"acsp", "missing 'acsp' signature")
+}
+
+fn srgb_disabled() -> Result<(), String> {
+    let options = MetadataOptions {
+        embed_srgb: false,
+        ..MetadataOptions::default()
+    };
+    let jpeg = jpeg_meta::encode_jpeg(&test_image(), 90, None, &options)?;
+    ensure(jpeg_meta::icc_profile(&jpeg).is_none(), "ICC profile present")
+}
+
+fn preserve_keeps_camera_tags() -> Result<(), String> {
+    let source = camera_jpeg();
+    let jpeg = jpeg_meta::encode_jpeg(&test_image(), 90, Some(&source), &options(ExifPolicy::Preserve))?;
+    let exif = exif_of(&jpeg)?;
+    ensure(ascii_field(&exif, Tag::Make).as_deref() == Some("Canon"), "Make lost")?;
+    ensure(jpeg_meta::orientation(&jpeg) == Some(6), "Orientation lost")?;
+    ensure(exif.get_field(Tag::ExposureTime, In::PRIMARY).is_some(), "ExposureTime lost")?;
+    // The camera's 72 dpi must not win over the print DPI
+    let x = exif.get_field(Tag::XResolution, In::PRIMARY).and_then(|f| match &f.value {
+        Value::Rational(r) => Some(r[0].to_f64()),
+        _ => None,
+    });
+    ensure(x == Some(jpeg_meta::PRINT_DPI as f64), format!("XResolution {:?}", x))
+}
+
+fn preserve_drops_private_tags() -> Result<(), String> {
+    let source = camera_jpeg();
+    let jpeg = jpeg_meta::encode_jpeg(&test_image(), 90, Some(&source), &options(ExifPolicy::Preserve))?;
+    let exif = exif_of(&jpeg)?;
+    ensure(exif.get_field(Tag::MakerNote, In::PRIMARY).is_none(), "MakerNote kept")?;
+    ensure(exif.get_field(Tag::GPSLatitudeRef, In::PRIMARY).is_none(), "GPS kept")
+}
+
+fn strip_drops_camera_tags() -> Result<(), String> {
+    let source = camera_jpeg();
+    let jpeg = jpeg_meta::encode_jpeg(&test_image(), 90, Some(&source), &options(ExifPolicy::Strip))?;
+    let exif = exif_of(&jpeg)?;
+    ensure(exif.get_field(Tag::Make, In::PRIMARY).is_none(), "Make kept")?;
+    ensure(jpeg_meta::orientation(&jpeg).is_none(), "Orientation kept")?;
+    ensure(exif.get_field(Tag::XResolution, In::PRIMARY).is_some(), "XResolution missing")
+}
+
+fn reset_orientation() -> Result<(), String> {
+    let source = camera_jpeg();
+    let options = MetadataOptions {
+        exif: ExifPolicy::Preserve,
+        reset_orientation: true,
+        ..MetadataOptions::default()
+    };
+    let jpeg = jpeg_meta::encode_jpeg(&test_image(), 90, Some(&source), &options)?;
+    ensure(jpeg_meta::orientation(&jpeg).is_none(), "Orientation kept")?;
+    ensure(ascii_field(&exif_of(&jpeg)?, Tag::Make).is_some(), "Make lost")
+}
+
+fn booth_exif() -> Result<(), String> {
+    let options = MetadataOptions {
+        booth: Some(booth()),
+        ..MetadataOptions::default()
+    };
+    let jpeg = jpeg_meta::encode_jpeg(&test_image(), 90, None, &options)?;
+    let exif = exif_of(&jpeg)?;
+    let software = ascii_field(&exif, Tag::Software).unwrap_or_default();
+    ensure(software.starts_with("Bonio Booth"), format!("Software '{}'", software))?;
+    ensure(
+        ascii_field(&exif, Tag::ImageUniqueID).as_deref() == Some("3f2a9c1e5b7d4e8fa0c1d2e3f4a5b6c7"),
+        "ImageUniqueID is not the session id",
+    )?;
+    let host = ascii_field(&exif, Tag(exif::Context::Tiff, 0x013c));
+    ensure(host.as_deref() == Some("BOOTH-042"), format!("HostComputer {:?}", host))?;
+    let date = ascii_field(&exif, Tag::DateTime).unwrap_or_default();
+    ensure(date.len() == 19 && date.as_bytes()[4] == b':', format!("DateTime '{}'", date))
+}
+
+fn booth_xmp() -> Result<(), String> {
+    let mut info = booth();
+    info.machine_id = "Booth \"A\" & <B>".to_string();
+    let options = MetadataOptions {
+        booth: Some(info),
+        ..MetadataOptions::default()
+    };
+    let jpeg = jpeg_meta::encode_jpeg(&test_image(), 90, None, &options)?;
+    let xmp = jpeg_meta::xmp(&jpeg).ok_or("no XMP")?;
+    ensure(xmp.contains("bonio:SessionId=\"3f2a9c1e5b7d4e8fa0c1d2e3f4a5b6c7\""), "SessionId missing")?;
+    ensure(
+        xmp.contains("bonio:MachineId=\"Booth &quot;A&quot; &amp; &lt;B&gt;\""),
+        "MachineId not escaped",
+    )?;
+    ensure(xmp.contains("xmp:CreateDate="), "CreateDate missing")?;
+
+    let plain = jpeg_meta::encode_jpeg(&test_image(), 90, None, &MetadataOptions::default())?;
+    ensure(jpeg_meta::xmp(&plain).is_none(), "XMP written without booth info")
+}
+
+fn rewrite_replaces_segments() -> Result<(), String> {
+    let options = MetadataOptions {
+        booth: Some(booth()),
+        ..MetadataOptions::default()
+    };
+    let once = jpeg_meta::encode_jpeg(&test_image(), 90, None, &options)?;
+    let twice = jpeg_meta::write_metadata(&once, Some(&once), &options)?;
+    ensure(count_segments(&twice, 0xE0, b"JFIF\0") == 1, "duplicate JFIF")?;
+    ensure(count_segments(&twice, 0xE1, b"Exif\0\0") == 1, "duplicate EXIF")?;
+    ensure(count_segments(&twice, 0xE1, b"http://ns.adobe.com/xap/1.0/\0") == 1, "duplicate XMP")?;
+    ensure(count_segments(&twice, 0xE2, b"ICC_PROFILE\0") == 1, "duplicate ICC")
+}
+
+fn image_data_untouched() -> Result<(), String> {
+    let plain = plain_jpeg();
+    let jpeg = jpeg_meta::write_metadata(&plain, None, &MetadataOptions::default())?;
+    let (_, plain_scan) = jpeg_meta::segments(&plain)?;
+    let (_, scan) = jpeg_meta::segments(&jpeg)?;
+    ensure(plain[plain_scan..] == jpeg[scan..], "scan data changed")?;
+
+    let a = image::load_from_memory(&plain).map_err(|e| e.to_string())?.to_rgb8();
+    let b = image::load_from_memory(&jpeg).map_err(|e| e.to_string())?.to_rgb8();
+    ensure(a == b, "decoded pixels differ")
+}
+
+fn rejects_non_jpeg() -> Result<(), String> {
+    let mut png = Vec::new();
+    test_image()
+        .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| e.to_string())?;
+    ensure(
+        jpeg_meta::write_metadata(&png, None, &MetadataOptions::default()).is_err(),
+        "PNG accepted",
+    )?;
+    ensure(jpeg_meta::write_metadata(&[0xFF, 0xD8, 0xFF], None, &MetadataOptions::default()).is_err(), "truncated JPEG accepted")
+}
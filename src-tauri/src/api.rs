@@ -1,15 +1,18 @@
+use crate::booth_api::{self, ApiError, BoothApiClient};
+use crate::outbox::{self, OutboxRequest};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::sync::Mutex;
 
-const API_BASE_URL: &str = "https://api-booth.boniolabs.com";
-
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
 pub struct PaperPositionConfig {
     pub scale: f64,
     pub vertical: f64,
     pub horizontal: f64,
+    /// Paper profile selected for this orientation
+    pub paper_profile: Option<String>,
 }
 
 impl Default for PaperPositionConfig {
@@ -18,6 +21,7 @@ impl Default for PaperPositionConfig {
             scale: 100.0,
             vertical: 0.0,
             horizontal: 0.0,
+            paper_profile: None,
         }
     }
 }
@@ -37,19 +41,33 @@ pub struct AppState {
 }
 
 impl AppState {
-    pub fn new() -> Self {
+    /// State with the stored machine config (see `machine_config`)
+    pub fn new(app: &tauri::AppHandle) -> Self {
+        let config = crate::machine_config::load(app);
+        crate::jpeg_meta::set_machine_id(&config.machine_id);
+        crate::backend::init(config.api_environment.as_deref(), config.api_base_url.as_deref());
+        crate::paper::set_selected(
+            config.paper_portrait.paper_profile.as_deref(),
+            config.paper_landscape.paper_profile.as_deref(),
+        );
+        if let Err(e) = crate::orientation::set_mount_rotation(config.camera_mount_rotation) {
+            log::warn!("[Orientation] {}, using 0°", e);
+        }
         Self {
-            machine_id: Mutex::new(String::new()),
-            machine_port: Mutex::new("44444".to_string()),
+            machine_id: Mutex::new(config.machine_id),
+            machine_port: Mutex::new(config.machine_port),
             machine_data: Mutex::new(None),
             theme_data: Mutex::new(None),
-            camera_type: Mutex::new("webcam".to_string()),
-            selected_webcam_id: Mutex::new(String::new()),
-            selected_camera_name: Mutex::new(String::new()),
-            selected_printer: Mutex::new(String::new()),
-            paper_config_portrait: Mutex::new(PaperPositionConfig::default()),
-            paper_config_landscape: Mutex::new(PaperPositionConfig::default()),
-            http_client: Client::new(),
+            camera_type: Mutex::new(config.camera_type),
+            selected_webcam_id: Mutex::new(config.selected_webcam_id),
+            selected_camera_name: Mutex::new(config.selected_camera_name),
+            selected_printer: Mutex::new(config.selected_printer),
+            paper_config_portrait: Mutex::new(config.paper_portrait),
+            paper_config_landscape: Mutex::new(config.paper_landscape),
+            http_client: Client::builder()
+                .connect_timeout(std::time::Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
         }
     }
 }
@@ -61,6 +79,25 @@ pub struct ApiResponse {
     pub error: Option<String>,
 }
 
+/// Answer of a `BoothApiClient` call as the commands have always returned it:
+/// rejections by the backend are `success: false` with the response body,
+/// network / timeout / decode failures (after retries) are `Err`
+pub(crate) fn respond<T: Serialize>(result: Result<T, ApiError>) -> Result<ApiResponse, String> {
+    match result {
+        Ok(data) => Ok(ApiResponse {
+            success: true,
+            data: Some(serde_json::to_value(data).map_err(|e| e.to_string())?),
+            error: None,
+        }),
+        Err(e @ (ApiError::Http { .. } | ApiError::Backend { .. })) => Ok(ApiResponse {
+            success: false,
+            data: e.body().cloned(),
+            error: Some(e.to_string()),
+        }),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
 // ============ Machine Verify & Init ============
 
 #[tauri::command]
@@ -68,100 +105,57 @@ pub async fn verify_machine(
     state: tauri::State<'_, AppState>,
     machine_id: String,
 ) -> Result<ApiResponse, String> {
-    let machine_port = state.machine_port.lock().unwrap().clone();
-    let client = &state.http_client;
-    let url = format!("{}/api/machines-public/verify", API_BASE_URL);
-
-    let res = client
-        .get(&url)
-        .header("X-Machine-Port", &machine_port)
-        .query(&[("machineId", &machine_id)])
-        .send()
-        .await
-        .map_err(|e| format!("Request error: {}", e))?;
-
-    let status = res.status();
-    let body: Value = res.json().await.map_err(|e| format!("Parse error: {}", e))?;
-
-    if status.is_success() {
+    let result = BoothApiClient::from_state(&state).verify(&machine_id).await;
+    if result.is_ok() {
         // Save machine_id
+        crate::jpeg_meta::set_machine_id(&machine_id);
         *state.machine_id.lock().unwrap() = machine_id;
-        Ok(ApiResponse {
-            success: true,
-            data: Some(body),
-            error: None,
-        })
-    } else {
-        Ok(ApiResponse {
-            success: false,
-            data: Some(body),
-            error: Some(format!("Status: {}", status)),
-        })
+        crate::machine_config::save(&state);
     }
+    respond(result)
 }
 
 #[tauri::command]
 pub async fn init_machine(
     state: tauri::State<'_, AppState>,
 ) -> Result<ApiResponse, String> {
-    let machine_id = state.machine_id.lock().unwrap().clone();
-    let machine_port = state.machine_port.lock().unwrap().clone();
-    let client = &state.http_client;
-    let url = format!("{}/api/machines-public/init", API_BASE_URL);
-
-    let res = client
-        .get(&url)
-        .header("X-Machine-Id", &machine_id)
-        .header("X-Machine-Port", &machine_port)
-        .query(&[("machineId", &machine_id)])
-        .send()
-        .await
-        .map_err(|e| format!("Request error: {}", e))?;
-
-    let status = res.status();
-    let body: Value = res.json().await.map_err(|e| format!("Parse error: {}", e))?;
-
-    // Log response data for debugging
-    log::info!("[API] init_machine response: status={}", status);
-    if let Some(is_shutdown_ready) = body.get("isShutdownReady") {
-        log::info!("[API] init_machine - isShutdownReady: {:?}", is_shutdown_ready);
-    }
-    if let Some(is_closed_app_ready) = body.get("isClosedAppReady") {
-        log::info!("[API] init_machine - isClosedAppReady: {:?}", is_closed_app_ready);
-    }
-    if let Some(machine) = body.get("machine") {
-        if let Some(machine_id) = machine.get("_id") {
-            log::info!("[API] init_machine - machine._id: {:?}", machine_id);
+    let result = BoothApiClient::from_state(&state).init().await;
+
+    if let Ok(body) = &result {
+        // Log response data for debugging
+        log::info!("[API] init_machine - isShutdownReady: {:?}", body.is_shutdown_ready);
+        log::info!("[API] init_machine - isClosedAppReady: {:?}", body.is_closed_app_ready);
+        if let Some(machine) = &body.machine {
+            log::info!("[API] init_machine - machine._id: {:?}", machine.id);
+        }
+        // Log full response body (truncated if too large)
+        let body_str = serde_json::to_string(body).unwrap_or_default();
+        if body_str.len() > 1000 {
+            let end = (0..=1000).rev().find(|&i| body_str.is_char_boundary(i)).unwrap_or(0);
+            log::info!("[API] init_machine - response body (truncated): {}...", &body_str[..end]);
+        } else {
+            log::info!("[API] init_machine - response body: {}", body_str);
         }
-    }
-    // Log full response body (truncated if too large)
-    let body_str = serde_json::to_string(&body).unwrap_or_default();
-    if body_str.len() > 1000 {
-        log::info!("[API] init_machine - response body (truncated): {}...", &body_str[..1000]);
-    } else {
-        log::info!("[API] init_machine - response body: {}", body_str);
-    }
 
-    if status.is_success() {
         // Cache machine data and theme (theme is at root level)
-        if let Some(machine) = body.get("machine") {
-            *state.machine_data.lock().unwrap() = Some(machine.clone());
+        if let Some(machine) = &body.machine {
+            *state.machine_data.lock().unwrap() = serde_json::to_value(machine).ok();
+            if let Some(level) = machine.paper_level {
+                if crate::outbox::paper_pending() {
+                    // The backend hasn't seen our queued changes yet
+                    log::info!("[API] Paper level changes still queued, not reconciling");
+                } else {
+                    let printer_name = state.selected_printer.lock().unwrap().clone();
+                    let media = crate::media::selected_media(&state);
+                    crate::media::reconcile(&printer_name, level as i32, &media);
+                }
+            }
         }
-        if let Some(theme) = body.get("theme") {
+        if let Some(theme) = &body.theme {
             *state.theme_data.lock().unwrap() = Some(theme.clone());
         }
-        Ok(ApiResponse {
-            success: true,
-            data: Some(body),
-            error: None,
-        })
-    } else {
-        Ok(ApiResponse {
-            success: false,
-            data: Some(body),
-            error: Some(format!("Status: {}", status)),
-        })
     }
+    respond(result)
 }
 
 #[tauri::command]
@@ -194,69 +188,33 @@ pub async fn get_theme_data(
 pub async fn get_frames(
     state: tauri::State<'_, AppState>,
 ) -> Result<ApiResponse, String> {
-    let machine_id = state.machine_id.lock().unwrap().clone();
-    let machine_port = state.machine_port.lock().unwrap().clone();
-    let client = &state.http_client;
-    let url = format!("{}/api/machines-public/frames", API_BASE_URL);
-
-    let res = client
-        .get(&url)
-        .header("X-Machine-Id", &machine_id)
-        .header("X-Machine-Port", &machine_port)
-        .query(&[("machineId", &machine_id)])
-        .send()
-        .await
-        .map_err(|e| format!("Request error: {}", e))?;
-
-    let status = res.status();
-    let body: Value = res.json().await.map_err(|e| format!("Parse error: {}", e))?;
-
-    Ok(ApiResponse {
-        success: status.is_success(),
-        data: Some(body),
-        error: if !status.is_success() { Some(format!("Status: {}", status)) } else { None },
-    })
+    respond(BoothApiClient::from_state(&state).frames().await)
 }
 
 // ============ Payment ============
 
+/// `idempotency_key`: send the same key again to repeat a request that may
+/// already have created the payment; without it every call is a new payment
 #[tauri::command]
 pub async fn create_payment(
     state: tauri::State<'_, AppState>,
     amount: f64,
     number_photo: Option<i32>,
     coupon_code_id: Option<String>,
+    idempotency_key: Option<String>,
 ) -> Result<ApiResponse, String> {
-    let machine_id = state.machine_id.lock().unwrap().clone();
-    let machine_port = state.machine_port.lock().unwrap().clone();
-    let client = &state.http_client;
-    let url = format!("{}/api/machines-public/payment/create", API_BASE_URL);
-
-    let mut payload = serde_json::json!({ "amount": amount });
-    if let Some(n) = number_photo {
-        payload["numberPhoto"] = serde_json::json!(n);
-    }
-    if let Some(ref cid) = coupon_code_id {
-        payload["couponCodeId"] = serde_json::json!(cid);
-    }
-
-    let res = client
-        .post(&url)
-        .header("X-Machine-Port", &machine_port)
-        .query(&[("machineId", &machine_id)])
-        .json(&payload)
-        .send()
-        .await
-        .map_err(|e| format!("Request error: {}", e))?;
-
-    let status = res.status();
-    let body: Value = res.json().await.map_err(|e| format!("Parse error: {}", e))?;
-
-    Ok(ApiResponse {
-        success: status.is_success(),
-        data: Some(body),
-        error: if !status.is_success() { Some(format!("Status: {}", status)) } else { None },
-    })
+    // Don't take money for prints the printer can't deliver
+    let printer_name = state.selected_printer.lock().unwrap().clone();
+    let sheets = crate::media::package_sheets(&state, number_photo.unwrap_or(1).max(1) as u32);
+    crate::media::ensure_available(&printer_name, sheets)?;
+
+    let request = booth_api::CreatePaymentRequest {
+        amount,
+        number_photo,
+        coupon_code_id,
+    };
+    let client = BoothApiClient::from_state(&state);
+    respond(client.create_payment(&request, idempotency_key.as_deref()).await)
 }
 
 #[tauri::command]
@@ -264,27 +222,7 @@ pub async fn check_payment_status(
     state: tauri::State<'_, AppState>,
     mch_order_no: String,
 ) -> Result<ApiResponse, String> {
-    let machine_id = state.machine_id.lock().unwrap().clone();
-    let machine_port = state.machine_port.lock().unwrap().clone();
-    let client = &state.http_client;
-    let url = format!("{}/api/machines-public/payment/status/{}", API_BASE_URL, mch_order_no);
-
-    let res = client
-        .get(&url)
-        .header("X-Machine-Id", &machine_id)
-        .header("X-Machine-Port", &machine_port)
-        .send()
-        .await
-        .map_err(|e| format!("Request error: {}", e))?;
-
-    let status = res.status();
-    let body: Value = res.json().await.map_err(|e| format!("Parse error: {}", e))?;
-
-    Ok(ApiResponse {
-        success: status.is_success(),
-        data: Some(body),
-        error: if !status.is_success() { Some(format!("Status: {}", status)) } else { None },
-    })
+    respond(BoothApiClient::from_state(&state).payment_status(&mch_order_no).await)
 }
 
 // ============ Coupon ============
@@ -294,64 +232,21 @@ pub async fn check_coupon(
     state: tauri::State<'_, AppState>,
     code: String,
 ) -> Result<ApiResponse, String> {
-    let machine_id = state.machine_id.lock().unwrap().clone();
-    let machine_port = state.machine_port.lock().unwrap().clone();
-    let client = &state.http_client;
-    let url = format!("{}/api/machines-public/coupon/check", API_BASE_URL);
-
-    let res = client
-        .post(&url)
-        .header("X-Machine-Id", &machine_id)
-        .header("X-Machine-Port", &machine_port)
-        .query(&[("machineId", &machine_id)])
-        .json(&serde_json::json!({ "code": code }))
-        .send()
-        .await
-        .map_err(|e| format!("Request error: {}", e))?;
-
-    let status = res.status();
-    let body: Value = res.json().await.map_err(|e| format!("Parse error: {}", e))?;
-
-    Ok(ApiResponse {
-        success: status.is_success(),
-        data: Some(body),
-        error: if !status.is_success() { Some(format!("Status: {}", status)) } else { None },
-    })
+    let request = booth_api::CouponCheckRequest { code };
+    respond(BoothApiClient::from_state(&state).check_coupon(&request).await)
 }
 
+/// `idempotency_key` as for `create_payment`
 #[tauri::command]
 pub async fn use_coupon(
     state: tauri::State<'_, AppState>,
     code: String,
     transaction_id: Option<String>,
+    idempotency_key: Option<String>,
 ) -> Result<ApiResponse, String> {
-    let machine_id = state.machine_id.lock().unwrap().clone();
-    let machine_port = state.machine_port.lock().unwrap().clone();
-    let client = &state.http_client;
-    let url = format!("{}/api/machines-public/coupon/use", API_BASE_URL);
-
-    let mut payload = serde_json::json!({ "code": code });
-    if let Some(ref tid) = transaction_id {
-        payload["transactionId"] = serde_json::json!(tid);
-    }
-
-    let res = client
-        .post(&url)
-        .header("X-Machine-Port", &machine_port)
-        .query(&[("machineId", &machine_id)])
-        .json(&payload)
-        .send()
-        .await
-        .map_err(|e| format!("Request error: {}", e))?;
-
-    let status = res.status();
-    let body: Value = res.json().await.map_err(|e| format!("Parse error: {}", e))?;
-
-    Ok(ApiResponse {
-        success: status.is_success(),
-        data: Some(body),
-        error: if !status.is_success() { Some(format!("Status: {}", status)) } else { None },
-    })
+    let request = booth_api::UseCouponRequest { code, transaction_id };
+    let client = BoothApiClient::from_state(&state);
+    respond(client.use_coupon(&request, idempotency_key.as_deref()).await)
 }
 
 // ============ Photo Session & Upload ============
@@ -361,29 +256,8 @@ pub async fn create_photo_session(
     state: tauri::State<'_, AppState>,
     transaction_id: String,
 ) -> Result<ApiResponse, String> {
-    let machine_id = state.machine_id.lock().unwrap().clone();
-    let machine_port = state.machine_port.lock().unwrap().clone();
-    let client = &state.http_client;
-    let url = format!("{}/api/machines-public/photo-session/create", API_BASE_URL);
-
-    let res = client
-        .post(&url)
-        .header("X-Machine-Id", &machine_id)
-        .header("X-Machine-Port", &machine_port)
-        .query(&[("machineId", &machine_id)])
-        .json(&serde_json::json!({ "transactionId": transaction_id }))
-        .send()
-        .await
-        .map_err(|e| format!("Request error: {}", e))?;
-
-    let status = res.status();
-    let body: Value = res.json().await.map_err(|e| format!("Parse error: {}", e))?;
-
-    Ok(ApiResponse {
-        success: status.is_success(),
-        data: Some(body),
-        error: if !status.is_success() { Some(format!("Status: {}", status)) } else { None },
-    })
+    let request = booth_api::CreatePhotoSessionRequest { transaction_id };
+    respond(BoothApiClient::from_state(&state).create_photo_session(&request).await)
 }
 
 #[tauri::command]
@@ -393,37 +267,12 @@ pub async fn create_presign_upload(
     files: Value,
     transaction_code: Option<String>,
 ) -> Result<ApiResponse, String> {
-    let machine_id = state.machine_id.lock().unwrap().clone();
-    let machine_port = state.machine_port.lock().unwrap().clone();
-    let client = &state.http_client;
-    let url = format!("{}/api/machines-public/photo-session/create-presign-upload", API_BASE_URL);
-
-    let mut body = serde_json::json!({
-        "transactionId": transaction_id,
-        "files": files
-    });
-    if let Some(code) = &transaction_code {
-        body["transactionCode"] = serde_json::json!(code);
-    }
-
-    let res = client
-        .post(&url)
-        .header("X-Machine-Id", &machine_id)
-        .header("X-Machine-Port", &machine_port)
-        .query(&[("machineId", &machine_id)])
-        .json(&body)
-        .send()
-        .await
-        .map_err(|e| format!("Request error: {}", e))?;
-
-    let status = res.status();
-    let body: Value = res.json().await.map_err(|e| format!("Parse error: {}", e))?;
-
-    Ok(ApiResponse {
-        success: status.is_success(),
-        data: Some(body),
-        error: if !status.is_success() { Some(format!("Status: {}", status)) } else { None },
-    })
+    let request = booth_api::PresignUploadRequest {
+        transaction_id,
+        files,
+        transaction_code,
+    };
+    respond(BoothApiClient::from_state(&state).create_presign_upload(&request).await)
 }
 
 #[tauri::command]
@@ -433,27 +282,14 @@ pub async fn upload_to_presigned_url(
     file_path: String,
     content_type: String,
 ) -> Result<ApiResponse, String> {
-    let client = &state.http_client;
     let file_data = tokio::fs::read(&file_path)
         .await
         .map_err(|e| format!("File read error: {}", e))?;
-
-    let res = client
-        .put(&url)
-        .header("Content-Type", &content_type)
-        .header("x-amz-acl", "public-read")
-        .body(file_data)
-        .send()
-        .await
-        .map_err(|e| format!("Upload error: {}", e))?;
-
-    let status = res.status();
-
-    Ok(ApiResponse {
-        success: status.is_success(),
-        data: None,
-        error: if !status.is_success() { Some(format!("Upload status: {}", status)) } else { None },
-    })
+    let result = BoothApiClient::from_state(&state)
+        .upload_presigned(&url, &content_type, file_data)
+        .await;
+    // Only success matters — storage answers with XML
+    respond(result).map(|res| ApiResponse { data: None, ..res })
 }
 
 #[tauri::command]
@@ -462,32 +298,36 @@ pub async fn confirm_upload(
     session_id: String,
     uploaded_files: Value,
 ) -> Result<ApiResponse, String> {
-    let machine_id = state.machine_id.lock().unwrap().clone();
-    let machine_port = state.machine_port.lock().unwrap().clone();
-    let client = &state.http_client;
-    let url = format!(
-        "{}/api/machines-public/photo-session/{}/confirm-upload",
-        API_BASE_URL, session_id
-    );
-
-    let res = client
-        .post(&url)
-        .header("X-Machine-Id", &machine_id)
-        .header("X-Machine-Port", &machine_port)
-        .query(&[("machineId", &machine_id)])
-        .json(&serde_json::json!({ "uploadedFiles": uploaded_files }))
-        .send()
-        .await
-        .map_err(|e| format!("Request error: {}", e))?;
-
-    let status = res.status();
-    let body: Value = res.json().await.map_err(|e| format!("Parse error: {}", e))?;
+    let request = booth_api::ConfirmUploadRequest { uploaded_files };
+    respond(BoothApiClient::from_state(&state).confirm_upload(&session_id, &request).await)
+}
 
-    Ok(ApiResponse {
-        success: status.is_success(),
-        data: Some(body),
-        error: if !status.is_success() { Some(format!("Status: {}", status)) } else { None },
-    })
+/// Upload a transaction's files and confirm them, through the outbox: queued
+/// as one delivery while the network is down. `session_id` / `upload_urls`
+/// from an earlier `create_presign_upload` are used if they still work,
+/// otherwise the delivery presigns again.
+#[tauri::command]
+pub async fn deliver_photos(
+    state: tauri::State<'_, AppState>,
+    transaction_id: String,
+    transaction_code: Option<String>,
+    files: Vec<outbox::DeliveryFile>,
+    session_id: Option<String>,
+    upload_urls: Option<Value>,
+) -> Result<ApiResponse, String> {
+    let presigned = match (session_id, upload_urls) {
+        (Some(session_id), Some(upload_urls)) => serde_json::from_value(upload_urls)
+            .ok()
+            .map(|upload_urls| outbox::Presigned { session_id, upload_urls }),
+        _ => None,
+    };
+    let request = OutboxRequest::Delivery {
+        transaction_id,
+        transaction_code,
+        files,
+        presigned,
+    };
+    outbox::submit(&state, request).await
 }
 
 // ============ Heartbeat & Status ============
@@ -498,63 +338,21 @@ pub async fn confirm_upload(
 pub async fn notify_going_offline(
     state: tauri::State<'_, AppState>,
 ) -> Result<ApiResponse, String> {
-    let machine_id = state.machine_id.lock().unwrap().clone();
-    let machine_port = state.machine_port.lock().unwrap().clone();
-    let client = &state.http_client;
-    let url = format!("{}/api/machines-public/notify-going-offline", API_BASE_URL);
-
-    log::info!("[API] Notifying backend: going offline (machineId={})", machine_id);
-
-    let res = client
-        .post(&url)
-        .header("X-Machine-Port", &machine_port)
-        .query(&[("machineId", &machine_id)])
-        .header("Content-Length", "0")
-        .send()
-        .await
-        .map_err(|e| format!("Request error: {}", e))?;
-
-    let status = res.status();
-    let body: Value = res.json().await.map_err(|e| format!("Parse error: {}", e))?;
-
-    log::info!("[API] notify-going-offline response: status={}, body={}", status, body);
-
-    Ok(ApiResponse {
-        success: status.is_success(),
-        data: Some(body),
-        error: if !status.is_success() { Some(format!("Status: {}", status)) } else { None },
-    })
+    let client = BoothApiClient::from_state(&state);
+    log::info!("[API] Notifying backend: going offline (machineId={})", client.machine_id());
+    let result = client.notify_going_offline().await;
+    log::info!("[API] notify-going-offline response: {:?}", result);
+    respond(result)
 }
 
 /// Internal helper (non-command) for calling notify-going-offline from Rust shutdown flow.
-/// Has an 8-second timeout to avoid blocking shutdown if backend is unreachable.
+/// Short timeout (`booth_api::GOING_OFFLINE`) to avoid blocking shutdown if backend is unreachable.
 pub async fn notify_going_offline_internal(machine_id: &str, machine_port: &str) {
-    let client = Client::new();
-    let url = format!("{}/api/machines-public/notify-going-offline", API_BASE_URL);
-
     log::info!("[API] notify_going_offline_internal: machineId={}", machine_id);
-
-    let result = tokio::time::timeout(
-        std::time::Duration::from_secs(8),
-        client
-            .post(&url)
-            .header("X-Machine-Port", machine_port)
-            .query(&[("machineId", machine_id)])
-            .header("Content-Length", "0")
-            .send(),
-    )
-    .await;
-
-    match result {
-        Ok(Ok(res)) => {
-            log::info!("[API] notify-going-offline response: {}", res.status());
-        }
-        Ok(Err(e)) => {
-            log::error!("[API] notify-going-offline request failed: {}", e);
-        }
-        Err(_) => {
-            log::warn!("[API] notify-going-offline timed out (8s)");
-        }
+    let client = BoothApiClient::new(Client::new(), &crate::backend::base_url(), machine_id, machine_port);
+    match client.notify_going_offline().await {
+        Ok(_) => log::info!("[API] notify-going-offline sent"),
+        Err(e) => log::error!("[API] notify-going-offline failed: {}", e),
     }
 }
 
@@ -562,27 +360,7 @@ pub async fn notify_going_offline_internal(machine_id: &str, machine_port: &str)
 pub async fn get_machine_status(
     state: tauri::State<'_, AppState>,
 ) -> Result<ApiResponse, String> {
-    let machine_id = state.machine_id.lock().unwrap().clone();
-    let machine_port = state.machine_port.lock().unwrap().clone();
-    let client = &state.http_client;
-    let url = format!("{}/api/machines-public/status", API_BASE_URL);
-
-    let res = client
-        .get(&url)
-        .header("X-Machine-Port", &machine_port)
-        .query(&[("machineId", &machine_id)])
-        .send()
-        .await
-        .map_err(|e| format!("Request error: {}", e))?;
-
-    let status = res.status();
-    let body: Value = res.json().await.map_err(|e| format!("Parse error: {}", e))?;
-
-    Ok(ApiResponse {
-        success: status.is_success(),
-        data: Some(body),
-        error: if !status.is_success() { Some(format!("Status: {}", status)) } else { None },
-    })
+    respond(BoothApiClient::from_state(&state).status().await)
 }
 
 // ============ Device Alert ============
@@ -594,32 +372,22 @@ pub async fn send_device_alert(
     device_name: String,
     available_devices: Vec<String>,
 ) -> Result<ApiResponse, String> {
-    let machine_id = state.machine_id.lock().unwrap().clone();
-    let machine_port = state.machine_port.lock().unwrap().clone();
-    let client = &state.http_client;
-    let url = format!("{}/api/machines-public/device-alert", API_BASE_URL);
-
-    let res = client
-        .post(&url)
-        .header("X-Machine-Port", &machine_port)
-        .query(&[("machineId", &machine_id)])
-        .json(&serde_json::json!({
-            "deviceType": device_type,
-            "deviceName": device_name,
-            "availableDevices": available_devices
-        }))
-        .send()
-        .await
-        .map_err(|e| format!("Request error: {}", e))?;
-
-    let status = res.status();
-    let body: Value = res.json().await.map_err(|e| format!("Parse error: {}", e))?;
+    post_device_alert(&state, &device_type, &device_name, &available_devices).await
+}
 
-    Ok(ApiResponse {
-        success: status.is_success(),
-        data: Some(body),
-        error: if !status.is_success() { Some(format!("Status: {}", status)) } else { None },
-    })
+/// POST device-alert (also sent by the printer monitor), through the outbox
+pub async fn post_device_alert(
+    state: &AppState,
+    device_type: &str,
+    device_name: &str,
+    available_devices: &[String],
+) -> Result<ApiResponse, String> {
+    let request = booth_api::DeviceAlertRequest {
+        device_type: device_type.to_string(),
+        device_name: device_name.to_string(),
+        available_devices: available_devices.to_vec(),
+    };
+    outbox::submit(state, OutboxRequest::DeviceAlert(request)).await
 }
 
 // ============ Device Status Report ============
@@ -636,41 +404,21 @@ pub async fn send_device_status_report(
     printer_device_detail: String,
     printer_available_names: Vec<String>,
 ) -> Result<ApiResponse, String> {
-    let machine_id = state.machine_id.lock().unwrap().clone();
-    let machine_port = state.machine_port.lock().unwrap().clone();
-    let client = &state.http_client;
-    let url = format!("{}/api/machines-public/device-status-report", API_BASE_URL);
-
-    let res = client
-        .post(&url)
-        .header("X-Machine-Port", &machine_port)
-        .query(&[("machineId", &machine_id)])
-        .json(&serde_json::json!({
-            "isStartup": is_startup,
-            "camera": {
-                "configured": camera_configured,
-                "found": camera_found,
-                "deviceName": camera_device_name
-            },
-            "printer": {
-                "configured": printer_configured,
-                "found": printer_found,
-                "deviceDetail": printer_device_detail,
-                "availablePrinterNames": printer_available_names
-            }
-        }))
-        .send()
-        .await
-        .map_err(|e| format!("Request error: {}", e))?;
-
-    let status = res.status();
-    let body: Value = res.json().await.map_err(|e| format!("Parse error: {}", e))?;
-
-    Ok(ApiResponse {
-        success: status.is_success(),
-        data: Some(body),
-        error: if !status.is_success() { Some(format!("Status: {}", status)) } else { None },
-    })
+    let report = booth_api::DeviceStatusReport {
+        is_startup,
+        camera: booth_api::CameraReport {
+            configured: camera_configured,
+            found: camera_found,
+            device_name: camera_device_name,
+        },
+        printer: booth_api::PrinterReport {
+            configured: printer_configured,
+            found: printer_found,
+            device_detail: printer_device_detail,
+            available_printer_names: printer_available_names,
+        },
+    };
+    outbox::submit(&state, OutboxRequest::DeviceStatusReport(report)).await
 }
 
 // ============ Device Reconnected ============
@@ -681,62 +429,51 @@ pub async fn send_device_reconnected(
     device_type: String,
     device_name: String,
 ) -> Result<ApiResponse, String> {
-    let machine_id = state.machine_id.lock().unwrap().clone();
-    let machine_port = state.machine_port.lock().unwrap().clone();
-    let client = &state.http_client;
-    let url = format!("{}/api/machines-public/device-reconnected", API_BASE_URL);
+    post_device_reconnected(&state, &device_type, &device_name).await
+}
 
-    let res = client
-        .post(&url)
-        .header("X-Machine-Port", &machine_port)
-        .query(&[("machineId", &machine_id)])
-        .json(&serde_json::json!({
-            "deviceType": device_type,
-            "deviceName": device_name
-        }))
-        .send()
-        .await
-        .map_err(|e| format!("Request error: {}", e))?;
+/// POST device-reconnected (also sent by the printer monitor), through the outbox
+pub async fn post_device_reconnected(
+    state: &AppState,
+    device_type: &str,
+    device_name: &str,
+) -> Result<ApiResponse, String> {
+    let request = booth_api::DeviceReconnectedRequest {
+        device_type: device_type.to_string(),
+        device_name: device_name.to_string(),
+    };
+    outbox::submit(state, OutboxRequest::DeviceReconnected(request)).await
+}
 
-    let status = res.status();
-    let body: Value = res.json().await.map_err(|e| format!("Parse error: {}", e))?;
+// ============ Paper Level ============
 
-    Ok(ApiResponse {
-        success: status.is_success(),
-        data: Some(body),
-        error: if !status.is_success() { Some(format!("Status: {}", status)) } else { None },
-    })
+/// POST the absolute paper level to the backend, through the outbox
+pub async fn send_paper_level(state: &AppState, paper_level: i32) -> Result<ApiResponse, String> {
+    let request = booth_api::PaperLevelRequest { paper_level };
+    outbox::submit(state, OutboxRequest::PaperLevel(request)).await
 }
 
-// ============ Paper Level ============
+/// POST paper-level/reduce — the one place prints are reported to the backend
+pub async fn send_paper_reduce(state: &AppState, reduce_by: i32) -> Result<ApiResponse, String> {
+    let request = booth_api::PaperReduceRequest { reduce_by };
+    outbox::submit(state, OutboxRequest::PaperReduce(request)).await
+}
 
 #[tauri::command]
 pub async fn update_paper_level(
     state: tauri::State<'_, AppState>,
     paper_level: i32,
 ) -> Result<ApiResponse, String> {
-    let machine_id = state.machine_id.lock().unwrap().clone();
-    let machine_port = state.machine_port.lock().unwrap().clone();
-    let client = &state.http_client;
-    let url = format!("{}/api/machines-public/paper-level", API_BASE_URL);
-
-    let res = client
-        .post(&url)
-        .header("X-Machine-Port", &machine_port)
-        .query(&[("machineId", &machine_id)])
-        .json(&serde_json::json!({ "paperLevel": paper_level }))
-        .send()
-        .await
-        .map_err(|e| format!("Request error: {}", e))?;
-
-    let status = res.status();
-    let body: Value = res.json().await.map_err(|e| format!("Parse error: {}", e))?;
-
-    Ok(ApiResponse {
-        success: status.is_success(),
-        data: Some(body),
-        error: if !status.is_success() { Some(format!("Status: {}", status)) } else { None },
-    })
+    let res = send_paper_level(&state, paper_level).await?;
+    if res.success {
+        // An operator set the level by hand: take it as the local count too
+        let printer_name = state.selected_printer.lock().unwrap().clone();
+        crate::media::set_remaining(&printer_name, paper_level.max(0) as u32, &crate::media::selected_media(&state));
+        if !outbox::is_queued(&res) {
+            crate::media::accepted_level(&printer_name, paper_level);
+        }
+    }
+    Ok(res)
 }
 
 #[tauri::command]
@@ -744,31 +481,11 @@ pub async fn reduce_paper_level_api(
     state: tauri::State<'_, AppState>,
     reduce_by: i32,
 ) -> Result<ApiResponse, String> {
-    let machine_id = state.machine_id.lock().unwrap().clone();
-    let machine_port = state.machine_port.lock().unwrap().clone();
-    let client = &state.http_client;
-    let url = format!("{}/api/machines-public/paper-level/reduce", API_BASE_URL);
-
-    let res = client
-        .post(&url)
-        .header("X-Machine-Port", &machine_port)
-        .query(&[("machineId", &machine_id)])
-        .json(&serde_json::json!({ "reduceBy": reduce_by }))
-        .send()
-        .await
-        .map_err(|e| format!("Request error: {}", e))?;
-
-    let status = res.status();
-    let body: Value = res.json().await.map_err(|e| format!("Parse error: {}", e))?;
-
-    Ok(ApiResponse {
-        success: status.is_success(),
-        data: Some(body),
-        error: if !status.is_success() { Some(format!("Status: {}", status)) } else { None },
-    })
+    send_paper_reduce(&state, reduce_by).await
 }
 
 // ============ Config ============
+// Setters are persisted by `machine_config`
 
 #[tauri::command]
 pub async fn set_machine_config(
@@ -776,8 +493,10 @@ pub async fn set_machine_config(
     machine_id: String,
     machine_port: String,
 ) -> Result<ApiResponse, String> {
+    crate::jpeg_meta::set_machine_id(&machine_id);
     *state.machine_id.lock().unwrap() = machine_id;
     *state.machine_port.lock().unwrap() = machine_port;
+    crate::machine_config::save(&state);
     Ok(ApiResponse {
         success: true,
         data: None,
@@ -791,6 +510,7 @@ pub async fn set_camera_type(
     camera_type: String,
 ) -> Result<ApiResponse, String> {
     *state.camera_type.lock().unwrap() = camera_type;
+    crate::machine_config::save(&state);
     Ok(ApiResponse {
         success: true,
         data: None,
@@ -811,6 +531,7 @@ pub async fn set_selected_webcam(
     webcam_id: String,
 ) -> Result<ApiResponse, String> {
     *state.selected_webcam_id.lock().unwrap() = webcam_id;
+    crate::machine_config::save(&state);
     Ok(ApiResponse {
         success: true,
         data: None,
@@ -831,6 +552,7 @@ pub async fn set_selected_camera_name(
     camera_name: String,
 ) -> Result<ApiResponse, String> {
     *state.selected_camera_name.lock().unwrap() = camera_name;
+    crate::machine_config::save(&state);
     Ok(ApiResponse {
         success: true,
         data: None,
@@ -851,6 +573,7 @@ pub async fn set_selected_printer(
     printer_name: String,
 ) -> Result<ApiResponse, String> {
     *state.selected_printer.lock().unwrap() = printer_name;
+    crate::machine_config::save(&state);
     Ok(ApiResponse {
         success: true,
         data: None,
@@ -872,17 +595,24 @@ pub async fn set_paper_config(
     scale: f64,
     vertical: f64,
     horizontal: f64,
+    paper_profile: Option<String>,
 ) -> Result<ApiResponse, String> {
     let config = PaperPositionConfig {
         scale,
         vertical,
         horizontal,
+        paper_profile: paper_profile.filter(|p| !p.is_empty()),
     };
     if orientation == "landscape" {
         *state.paper_config_landscape.lock().unwrap() = config;
     } else {
         *state.paper_config_portrait.lock().unwrap() = config;
     }
+    crate::paper::set_selected(
+        state.paper_config_portrait.lock().unwrap().paper_profile.as_deref(),
+        state.paper_config_landscape.lock().unwrap().paper_profile.as_deref(),
+    );
+    crate::machine_config::save(&state);
     Ok(ApiResponse {
         success: true,
         data: None,
@@ -0,0 +1,177 @@
+//! Backend Environment
+//!
+//! Where the booth API lives. Every request (`booth_api`, `sse`, …) builds
+//! its URL from [`base_url`] instead of a hard-coded host, so a booth can be
+//! pointed at staging, a local stand-in server for QA, or a mock backend for
+//! integration tests.
+//!
+//! The environment is picked, first match wins, from:
+//! 1. `BONIO_API_URL` — a full base URL (environment `custom`)
+//! 2. `BONIO_API_ENV` — `production`, `staging` or `local`
+//! 3. `apiEnvironment` / `apiBaseUrl` in the machine config (`machine.json`,
+//!    set through `set_backend_environment`)
+//! 4. production
+//!
+//! Staging has no built-in URL: it needs `apiBaseUrl` or
+//! `BONIO_API_URL_STAGING`. `BONIO_API_URL_LOCAL` replaces the built-in URL of
+//! `local`.
+
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+
+const PRODUCTION_URL: &str = "https://api-booth.boniolabs.com";
+const LOCAL_URL: &str = "http://localhost:3000";
+
+static BACKEND: RwLock<Option<BackendConfig>> = RwLock::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Environment {
+    Production,
+    Staging,
+    Local,
+    /// Any other server, by URL
+    Custom,
+}
+
+impl Environment {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "production" | "prod" | "" => Ok(Environment::Production),
+            "staging" | "stage" => Ok(Environment::Staging),
+            "local" | "dev" => Ok(Environment::Local),
+            "custom" => Ok(Environment::Custom),
+            other => Err(format!("Unknown API environment '{}' (production, staging, local, custom)", other)),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Environment::Production => "production",
+            Environment::Staging => "staging",
+            Environment::Local => "local",
+            Environment::Custom => "custom",
+        }
+    }
+
+    /// Base URL when none is given: built in, or from the environment's
+    /// `BONIO_API_URL_*` variable
+    fn default_url(self) -> Result<String, String> {
+        let from_env = |var: &str| std::env::var(var).ok().filter(|v| !v.trim().is_empty());
+        match self {
+            Environment::Production => Ok(PRODUCTION_URL.to_string()),
+            Environment::Staging => from_env("BONIO_API_URL_STAGING").ok_or_else(|| {
+                "The staging environment needs a base URL (apiBaseUrl or BONIO_API_URL_STAGING)".to_string()
+            }),
+            Environment::Local => Ok(from_env("BONIO_API_URL_LOCAL").unwrap_or_else(|| LOCAL_URL.to_string())),
+            Environment::Custom => Err("The custom environment needs a base URL".to_string()),
+        }
+    }
+}
+
+/// Where requests go and why
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendConfig {
+    pub environment: Environment,
+    /// Without trailing slash
+    pub base_url: String,
+    /// "env", "config" or "default"
+    pub source: &'static str,
+}
+
+impl BackendConfig {
+    fn new(environment: Environment, base_url: Option<&str>, source: &'static str) -> Result<Self, String> {
+        let base_url = match base_url.map(str::trim).filter(|url| !url.is_empty()) {
+            Some(url) => url.to_string(),
+            None => environment.default_url()?,
+        };
+        if !base_url.starts_with("http://") && !base_url.starts_with("https://") {
+            return Err(format!("API base URL must be http(s): '{}'", base_url));
+        }
+        Ok(Self {
+            environment,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            source,
+        })
+    }
+}
+
+/// Settings from the environment, if any
+fn from_env() -> Option<Result<BackendConfig, String>> {
+    if let Some(url) = std::env::var("BONIO_API_URL").ok().filter(|v| !v.is_empty()) {
+        return Some(BackendConfig::new(Environment::Custom, Some(&url), "env"));
+    }
+    let name = std::env::var("BONIO_API_ENV").ok().filter(|v| !v.is_empty())?;
+    Some(Environment::parse(&name).and_then(|env| BackendConfig::new(env, None, "env")))
+}
+
+/// Pick the environment on startup from the environment variables or the
+/// machine config's `apiEnvironment` / `apiBaseUrl`
+pub fn init(environment: Option<&str>, base_url: Option<&str>) {
+    let from_config = || -> Result<BackendConfig, String> {
+        match environment {
+            Some(name) => BackendConfig::new(Environment::parse(name)?, base_url, "config"),
+            None => BackendConfig::new(Environment::Production, None, "default"),
+        }
+    };
+    let config = from_env()
+        .unwrap_or_else(from_config)
+        .or_else(|e| {
+            log::error!("[Backend] {}, using production", e);
+            BackendConfig::new(Environment::Production, None, "default")
+        })
+        .expect("production URL is valid");
+    log::info!(
+        "[Backend] API: {} ({:?}, from {})",
+        config.base_url,
+        config.environment,
+        config.source
+    );
+    *BACKEND.write().unwrap() = Some(config);
+}
+
+/// Current settings (production until [`init`] ran)
+pub fn current() -> BackendConfig {
+    BACKEND.read().unwrap().clone().unwrap_or_else(|| BackendConfig {
+        environment: Environment::Production,
+        base_url: PRODUCTION_URL.to_string(),
+        source: "default",
+    })
+}
+
+/// API base URL, without trailing slash
+pub fn base_url() -> String {
+    current().base_url
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+#[tauri::command]
+pub fn get_backend_config() -> BackendConfig {
+    current()
+}
+
+/// Switch the API environment and store it in the machine config.
+/// `base_url` is required for `custom` and `staging` (unless
+/// `BONIO_API_URL_STAGING` is set) and overrides the built-in URL of the
+/// others. Environment variables still win over the stored choice.
+/// Reconnect (`connect_sse`) afterwards to move the live connection.
+#[tauri::command]
+pub fn set_backend_environment(
+    state: tauri::State<'_, crate::api::AppState>,
+    environment: String,
+    base_url: Option<String>,
+) -> Result<BackendConfig, String> {
+    let config = BackendConfig::new(Environment::parse(&environment)?, base_url.as_deref(), "config")?;
+    crate::machine_config::set_backend(&state, config.environment, base_url.filter(|url| !url.trim().is_empty()));
+    if from_env().is_some() {
+        log::warn!("[Backend] Stored {:?}, but BONIO_API_URL / BONIO_API_ENV take precedence", config.environment);
+        return Ok(current());
+    }
+    log::info!("[Backend] API switched to {} ({:?})", config.base_url, config.environment);
+    *BACKEND.write().unwrap() = Some(config.clone());
+    Ok(config)
+}
@@ -0,0 +1,718 @@
+//! Booth API Client
+//!
+//! Typed client for the `machines-public` endpoints of the booth backend.
+//! Every request goes out the same way — `X-Machine-Id` / `X-Machine-Port`
+//! headers, `machineId` query, JSON body — with a timeout per endpoint, and
+//! every failure comes back as an [`ApiError`]:
+//!
+//! - `Network` — no connection (DNS, refused, reset, TLS)
+//! - `Timeout` — no answer within the endpoint's timeout
+//! - `Http` — non-2xx status
+//! - `Backend` — non-2xx status with an error code in the body
+//! - `Decode` — a body the endpoint doesn't return
+//!
+//! Failures that may pass on their own (see [`ApiError::is_retryable`]) are
+//! retried with exponential backoff and jitter ([`RetryPolicy`]). Every POST
+//! carries an `Idempotency-Key` that stays the same across the retries, so
+//! a payment or coupon the backend already booked isn't booked twice.
+//!
+//! Responses keep the fields the app relies on typed; everything else is
+//! kept in `extra` so the full body still reaches the frontend.
+
+use reqwest::{Client, Method, RequestBuilder};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+// =============================================================================
+// Endpoints
+// =============================================================================
+
+/// One backend endpoint
+#[derive(Debug, Clone)]
+pub struct Endpoint {
+    pub name: &'static str,
+    pub method: Method,
+    /// Per attempt
+    pub timeout: Duration,
+    /// Retry retryable failures (per the client's [`RetryPolicy`])
+    pub retry: bool,
+}
+
+const fn endpoint(name: &'static str, method: Method, timeout_secs: u64) -> Endpoint {
+    Endpoint {
+        name,
+        method,
+        timeout: Duration::from_secs(timeout_secs),
+        retry: true,
+    }
+}
+
+impl Endpoint {
+    pub const fn no_retry(mut self) -> Self {
+        self.retry = false;
+        self
+    }
+}
+
+pub const VERIFY: Endpoint = endpoint("verify", Method::GET, 10);
+pub const INIT: Endpoint = endpoint("init", Method::GET, 20);
+pub const FRAMES: Endpoint = endpoint("frames", Method::GET, 20);
+pub const PAYMENT_CREATE: Endpoint = endpoint("payment/create", Method::POST, 30);
+pub const PAYMENT_STATUS: Endpoint = endpoint("payment/status", Method::GET, 10);
+pub const COUPON_CHECK: Endpoint = endpoint("coupon/check", Method::POST, 10);
+pub const COUPON_USE: Endpoint = endpoint("coupon/use", Method::POST, 15);
+pub const PHOTO_SESSION_CREATE: Endpoint = endpoint("photo-session/create", Method::POST, 15);
+pub const PRESIGN_UPLOAD: Endpoint = endpoint("photo-session/create-presign-upload", Method::POST, 30);
+pub const CONFIRM_UPLOAD: Endpoint = endpoint("photo-session/confirm-upload", Method::POST, 30);
+/// Short and single-shot — it runs while the app shuts down
+pub const GOING_OFFLINE: Endpoint = endpoint("notify-going-offline", Method::POST, 8).no_retry();
+pub const STATUS: Endpoint = endpoint("status", Method::GET, 10);
+pub const DEVICE_ALERT: Endpoint = endpoint("device-alert", Method::POST, 10);
+pub const DEVICE_STATUS_REPORT: Endpoint = endpoint("device-status-report", Method::POST, 10);
+pub const DEVICE_RECONNECTED: Endpoint = endpoint("device-reconnected", Method::POST, 10);
+pub const PAPER_LEVEL: Endpoint = endpoint("paper-level", Method::POST, 10);
+pub const PAPER_REDUCE: Endpoint = endpoint("paper-level/reduce", Method::POST, 10);
+/// PUT of a photo to its presigned storage URL
+pub const PRESIGNED_PUT: Endpoint = endpoint("presigned-upload", Method::PUT, 120);
+
+// =============================================================================
+// Errors
+// =============================================================================
+
+#[derive(Debug, Clone)]
+pub enum ApiError {
+    Network { endpoint: &'static str, message: String },
+    Timeout { endpoint: &'static str, after: Duration },
+    Http { endpoint: &'static str, status: u16, body: Value },
+    Backend { endpoint: &'static str, status: u16, code: String, message: String, body: Value },
+    Decode { endpoint: &'static str, message: String },
+}
+
+impl ApiError {
+    pub fn endpoint(&self) -> &'static str {
+        match self {
+            ApiError::Network { endpoint, .. }
+            | ApiError::Timeout { endpoint, .. }
+            | ApiError::Http { endpoint, .. }
+            | ApiError::Backend { endpoint, .. }
+            | ApiError::Decode { endpoint, .. } => endpoint,
+        }
+    }
+
+    /// HTTP status, for errors the backend answered
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            ApiError::Http { status, .. } | ApiError::Backend { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+
+    /// Response body, for errors the backend answered
+    pub fn body(&self) -> Option<&Value> {
+        match self {
+            ApiError::Http { body, .. } | ApiError::Backend { body, .. } => Some(body),
+            _ => None,
+        }
+    }
+
+    /// Worth sending again: the request may not have arrived, or the backend
+    /// is overloaded or restarting. Anything else — a rejected request, an
+    /// error code, a body we can't read — fails the same way every time.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ApiError::Network { .. } | ApiError::Timeout { .. } => true,
+            ApiError::Http { status, .. } | ApiError::Backend { status, .. } => {
+                matches!(status, 408 | 425 | 429 | 500 | 502 | 503 | 504)
+            }
+            ApiError::Decode { .. } => false,
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::Network { endpoint, message } => write!(f, "{}: request error: {}", endpoint, message),
+            ApiError::Timeout { endpoint, after } => write!(f, "{}: no response after {}s", endpoint, after.as_secs()),
+            ApiError::Http { endpoint, status, .. } => write!(f, "{}: status {}", endpoint, status_text(*status)),
+            ApiError::Backend { endpoint, status, code, message, .. } => {
+                write!(f, "{}: status {} {}: {}", endpoint, status_text(*status), code, message)
+            }
+            ApiError::Decode { endpoint, message } => write!(f, "{}: parse error: {}", endpoint, message),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+fn status_text(status: u16) -> String {
+    reqwest::StatusCode::from_u16(status)
+        .map(|s| s.to_string())
+        .unwrap_or_else(|_| status.to_string())
+}
+
+/// Backend error code and message of an error body: `{ code, message }`,
+/// `{ errorCode, error }` or `{ error: { code, message } }`
+fn backend_error(body: &Value) -> Option<(String, String)> {
+    let text = |value: Option<&Value>| match value? {
+        Value::String(s) if !s.is_empty() => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    };
+    let source = match body.get("error") {
+        Some(nested @ Value::Object(_)) => nested,
+        _ => body,
+    };
+    let code = text(source.get("code")).or_else(|| text(source.get("errorCode")))?;
+    let message = text(source.get("message"))
+        .or_else(|| text(body.get("error")))
+        .or_else(|| text(body.get("message")))
+        .unwrap_or_default();
+    Some((code, message))
+}
+
+// =============================================================================
+// Retries
+// =============================================================================
+
+/// How often and how fast retryable failures are sent again
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts in total, the first included (1 = no retries)
+    pub max_attempts: u32,
+    /// Backoff before the first retry, doubled for each one after
+    pub base_delay: Duration,
+    /// Cap for a single backoff
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(8),
+        }
+    }
+}
+
+static CONFIGURED: LazyLock<RetryPolicy> = LazyLock::new(RetryPolicy::from_env);
+
+impl RetryPolicy {
+    pub const NONE: RetryPolicy = RetryPolicy {
+        max_attempts: 1,
+        base_delay: Duration::ZERO,
+        max_delay: Duration::ZERO,
+    };
+
+    /// Default, adjusted by `BONIO_API_MAX_ATTEMPTS`, `BONIO_API_RETRY_BASE_MS`
+    /// and `BONIO_API_RETRY_MAX_MS`
+    pub fn from_env() -> Self {
+        let var = |name: &str| std::env::var(name).ok().and_then(|v| v.trim().parse::<u64>().ok());
+        let default = Self::default();
+        let policy = Self {
+            max_attempts: var("BONIO_API_MAX_ATTEMPTS").map_or(default.max_attempts, |n| n.clamp(1, 10) as u32),
+            base_delay: var("BONIO_API_RETRY_BASE_MS").map_or(default.base_delay, Duration::from_millis),
+            max_delay: var("BONIO_API_RETRY_MAX_MS").map_or(default.max_delay, Duration::from_millis),
+        };
+        if policy != default {
+            log::info!("[API] Retry policy: {:?}", policy);
+        }
+        policy
+    }
+
+    /// Policy of clients built with [`BoothApiClient::new`]
+    pub fn configured() -> Self {
+        *CONFIGURED
+    }
+
+    /// Wait before retry number `retry` (from 1): exponential backoff, of
+    /// which a random half is dropped so booths that lost the network
+    /// together don't all come back at the same moment
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let delay = self
+            .base_delay
+            .saturating_mul(1 << retry.saturating_sub(1).min(16))
+            .min(self.max_delay);
+        let half = delay / 2;
+        half + half.mul_f64(fastrand::f64())
+    }
+}
+
+// =============================================================================
+// Requests
+// =============================================================================
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatePaymentRequest {
+    pub amount: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number_photo: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coupon_code_id: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CouponCheckRequest {
+    pub code: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UseCouponRequest {
+    pub code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_id: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatePhotoSessionRequest {
+    pub transaction_id: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PresignUploadRequest {
+    pub transaction_id: String,
+    /// File descriptions as sent by the frontend
+    pub files: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_code: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfirmUploadRequest {
+    pub uploaded_files: Value,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceAlertRequest {
+    pub device_type: String,
+    pub device_name: String,
+    pub available_devices: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceReconnectedRequest {
+    pub device_type: String,
+    pub device_name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceStatusReport {
+    pub is_startup: bool,
+    pub camera: CameraReport,
+    pub printer: PrinterReport,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CameraReport {
+    pub configured: bool,
+    pub found: bool,
+    pub device_name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PrinterReport {
+    pub configured: bool,
+    pub found: bool,
+    pub device_detail: String,
+    pub available_printer_names: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PaperLevelRequest {
+    pub paper_level: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PaperReduceRequest {
+    pub reduce_by: i32,
+}
+
+// =============================================================================
+// Responses
+// =============================================================================
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct InitResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub machine: Option<MachineInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_shutdown_ready: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_closed_app_ready: Option<Value>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MachineInfo {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paper_level: Option<i64>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PaymentCreated {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub qr_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reference_id: Option<String>,
+    #[serde(rename = "transactionId", skip_serializing_if = "Option::is_none")]
+    pub transaction_id: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PaymentStatus {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trade_state: Option<String>,
+    #[serde(rename = "transactionId", skip_serializing_if = "Option::is_none")]
+    pub transaction_id: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CouponCheck {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coupon_code_id: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PresignUpload {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub qrcode_storage_url: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+// =============================================================================
+// Client
+// =============================================================================
+
+#[derive(Clone)]
+pub struct BoothApiClient {
+    http: Client,
+    /// Without trailing slash
+    base_url: String,
+    machine_id: String,
+    machine_port: String,
+    retry: RetryPolicy,
+    /// `Idempotency-Key` for POSTs not given one
+    idempotency_key: Option<String>,
+}
+
+impl BoothApiClient {
+    pub fn new(http: Client, base_url: &str, machine_id: &str, machine_port: &str) -> Self {
+        Self {
+            http,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            machine_id: machine_id.to_string(),
+            machine_port: machine_port.to_string(),
+            retry: RetryPolicy::configured(),
+            idempotency_key: None,
+        }
+    }
+
+    pub fn with_retry_policy(self, retry: RetryPolicy) -> Self {
+        Self { retry, ..self }
+    }
+
+    /// Send `key` as the `Idempotency-Key` of POSTs made through the typed
+    /// methods, for a call that is repeated later (see `outbox`)
+    pub fn with_idempotency_key(self, key: &str) -> Self {
+        Self {
+            idempotency_key: Some(key.to_string()),
+            ..self
+        }
+    }
+
+    /// Client for this booth: current API environment and machine config
+    pub fn from_state(state: &crate::api::AppState) -> Self {
+        Self::new(
+            state.http_client.clone(),
+            &crate::backend::base_url(),
+            &state.machine_id.lock().unwrap(),
+            &state.machine_port.lock().unwrap(),
+        )
+    }
+
+    pub fn machine_id(&self) -> &str {
+        &self.machine_id
+    }
+
+    /// Send a request and decode the JSON answer (an empty body is `null`)
+    pub async fn call<B, T>(&self, endpoint: Endpoint, path: &str, body: Option<&B>) -> Result<T, ApiError>
+    where
+        B: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        self.call_keyed(endpoint, path, body, None).await
+    }
+
+    /// [`call`](Self::call) with the caller's `Idempotency-Key` — reuse it to
+    /// repeat a request the backend may already have seen. POSTs without one
+    /// (here or from [`with_idempotency_key`](Self::with_idempotency_key))
+    /// get a fresh key per call.
+    pub async fn call_keyed<B, T>(
+        &self,
+        endpoint: Endpoint,
+        path: &str,
+        body: Option<&B>,
+        idempotency_key: Option<&str>,
+    ) -> Result<T, ApiError>
+    where
+        B: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        let url = format!("{}/api/machines-public{}", self.base_url, path);
+        let key = match idempotency_key.or(self.idempotency_key.as_deref()) {
+            Some(key) => Some(key.to_string()),
+            None if endpoint.method == Method::POST => Some(uuid::Uuid::new_v4().to_string()),
+            None => None,
+        };
+        let build = || {
+            let mut request = self
+                .http
+                .request(endpoint.method.clone(), &url)
+                .timeout(endpoint.timeout)
+                .header("X-Machine-Id", &self.machine_id)
+                .header("X-Machine-Port", &self.machine_port)
+                .query(&[("machineId", &self.machine_id)]);
+            if let Some(key) = &key {
+                request = request.header("Idempotency-Key", key);
+            }
+            match body {
+                Some(body) => request.json(body),
+                None if endpoint.method == Method::POST => request.header("Content-Length", "0"),
+                None => request,
+            }
+        };
+
+        let value = self.send(&endpoint, build).await?;
+        serde_json::from_value(value).map_err(|e| ApiError::Decode {
+            endpoint: endpoint.name,
+            message: e.to_string(),
+        })
+    }
+
+    /// Send the request `build` makes, again after each retryable failure
+    /// while the policy allows
+    async fn send(&self, endpoint: &Endpoint, build: impl Fn() -> RequestBuilder) -> Result<Value, ApiError> {
+        let attempts = if endpoint.retry { self.retry.max_attempts.max(1) } else { 1 };
+        let mut attempt = 1;
+        loop {
+            match self.exchange(endpoint, build()).await {
+                Err(e) if attempt < attempts && e.is_retryable() => {
+                    let delay = self.retry.backoff(attempt);
+                    log::warn!(
+                        "[API] {} - retrying in {}ms ({}/{})",
+                        e,
+                        delay.as_millis(),
+                        attempt + 1,
+                        attempts
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    if attempt > 1 {
+                        log::warn!("[API] {} (gave up after {} attempts)", e, attempt);
+                    } else {
+                        log::warn!("[API] {}", e);
+                    }
+                    return Err(e);
+                }
+                Ok(value) => return Ok(value),
+            }
+        }
+    }
+
+    async fn exchange(&self, endpoint: &Endpoint, request: RequestBuilder) -> Result<Value, ApiError> {
+        let transport = |e: reqwest::Error| {
+            if e.is_timeout() {
+                ApiError::Timeout {
+                    endpoint: endpoint.name,
+                    after: endpoint.timeout,
+                }
+            } else {
+                ApiError::Network {
+                    endpoint: endpoint.name,
+                    message: e.to_string(),
+                }
+            }
+        };
+        let res = request.send().await.map_err(transport)?;
+        let status = res.status();
+        let text = res.text().await.map_err(transport)?;
+        let body = if text.trim().is_empty() {
+            Value::Null
+        } else {
+            match serde_json::from_str(&text) {
+                Ok(body) => body,
+                // Proxies answer errors with HTML; keep the text for the log
+                Err(_) if !status.is_success() => Value::String(text),
+                Err(e) => {
+                    return Err(ApiError::Decode {
+                        endpoint: endpoint.name,
+                        message: e.to_string(),
+                    })
+                }
+            }
+        };
+
+        if status.is_success() {
+            return Ok(body);
+        }
+        Err(match backend_error(&body) {
+            Some((code, message)) => ApiError::Backend {
+                endpoint: endpoint.name,
+                status: status.as_u16(),
+                code,
+                message,
+                body,
+            },
+            None => ApiError::Http {
+                endpoint: endpoint.name,
+                status: status.as_u16(),
+                body,
+            },
+        })
+    }
+
+    // ---- Machine ----
+
+    /// Check `machine_id` (not necessarily this client's) exists
+    pub async fn verify(&self, machine_id: &str) -> Result<Value, ApiError> {
+        let client = Self {
+            machine_id: machine_id.to_string(),
+            ..self.clone()
+        };
+        client.call::<(), _>(VERIFY, "/verify", None).await
+    }
+
+    pub async fn init(&self) -> Result<InitResponse, ApiError> {
+        self.call::<(), _>(INIT, "/init", None).await
+    }
+
+    /// Frames — a list, or `{ frames: [...] }`
+    pub async fn frames(&self) -> Result<Value, ApiError> {
+        self.call::<(), _>(FRAMES, "/frames", None).await
+    }
+
+    pub async fn status(&self) -> Result<Value, ApiError> {
+        self.call::<(), _>(STATUS, "/status", None).await
+    }
+
+    pub async fn notify_going_offline(&self) -> Result<Value, ApiError> {
+        self.call::<(), _>(GOING_OFFLINE, "/notify-going-offline", None).await
+    }
+
+    // ---- Payment & coupons ----
+
+    pub async fn create_payment(
+        &self,
+        request: &CreatePaymentRequest,
+        idempotency_key: Option<&str>,
+    ) -> Result<PaymentCreated, ApiError> {
+        self.call_keyed(PAYMENT_CREATE, "/payment/create", Some(request), idempotency_key)
+            .await
+    }
+
+    pub async fn payment_status(&self, mch_order_no: &str) -> Result<PaymentStatus, ApiError> {
+        let path = format!("/payment/status/{}", mch_order_no);
+        self.call::<(), _>(PAYMENT_STATUS, &path, None).await
+    }
+
+    pub async fn check_coupon(&self, request: &CouponCheckRequest) -> Result<CouponCheck, ApiError> {
+        self.call(COUPON_CHECK, "/coupon/check", Some(request)).await
+    }
+
+    pub async fn use_coupon(&self, request: &UseCouponRequest, idempotency_key: Option<&str>) -> Result<Value, ApiError> {
+        self.call_keyed(COUPON_USE, "/coupon/use", Some(request), idempotency_key)
+            .await
+    }
+
+    // ---- Photo session ----
+
+    pub async fn create_photo_session(&self, request: &CreatePhotoSessionRequest) -> Result<Value, ApiError> {
+        self.call(PHOTO_SESSION_CREATE, "/photo-session/create", Some(request)).await
+    }
+
+    pub async fn create_presign_upload(&self, request: &PresignUploadRequest) -> Result<PresignUpload, ApiError> {
+        self.call(PRESIGN_UPLOAD, "/photo-session/create-presign-upload", Some(request))
+            .await
+    }
+
+    pub async fn confirm_upload(&self, session_id: &str, request: &ConfirmUploadRequest) -> Result<Value, ApiError> {
+        let path = format!("/photo-session/{}/confirm-upload", session_id);
+        self.call(CONFIRM_UPLOAD, &path, Some(request)).await
+    }
+
+    /// PUT a file to a presigned storage URL. Goes to storage, not the
+    /// backend, so no machine headers.
+    pub async fn upload_presigned(&self, url: &str, content_type: &str, data: Vec<u8>) -> Result<(), ApiError> {
+        let build = || {
+            self.http
+                .put(url)
+                .timeout(PRESIGNED_PUT.timeout)
+                .header("Content-Type", content_type)
+                .header("x-amz-acl", "public-read")
+                .body(data.clone())
+        };
+        self.send(&PRESIGNED_PUT, build).await.map(|_| ())
+    }
+
+    // ---- Devices & paper ----
+
+    pub async fn device_alert(&self, request: &DeviceAlertRequest) -> Result<Value, ApiError> {
+        self.call(DEVICE_ALERT, "/device-alert", Some(request)).await
+    }
+
+    pub async fn device_reconnected(&self, request: &DeviceReconnectedRequest) -> Result<Value, ApiError> {
+        self.call(DEVICE_RECONNECTED, "/device-reconnected", Some(request)).await
+    }
+
+    pub async fn device_status_report(&self, report: &DeviceStatusReport) -> Result<Value, ApiError> {
+        self.call(DEVICE_STATUS_REPORT, "/device-status-report", Some(report)).await
+    }
+
+    pub async fn paper_level(&self, request: &PaperLevelRequest) -> Result<Value, ApiError> {
+        self.call(PAPER_LEVEL, "/paper-level", Some(request)).await
+    }
+
+    pub async fn paper_reduce(&self, request: &PaperReduceRequest) -> Result<Value, ApiError> {
+        self.call(PAPER_REDUCE, "/paper-level/reduce", Some(request)).await
+    }
+}
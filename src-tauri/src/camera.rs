@@ -0,0 +1,143 @@
+//! Camera Backend Abstraction
+//!
+//! Every `canon_*` Tauri command dispatches through the active `CameraBackend`:
+//! - `EdsdkCamera` (canon.rs) — real Canon body via EDSDK.dll (Windows only)
+//! - `SimulatedCamera` (camera_sim.rs) — deterministic fake camera for
+//!   developing and testing the capture flow without hardware
+//!
+//! The active backend defaults to EDSDK on Windows and the simulator
+//! everywhere else. It can be overridden with the `BONIO_CAMERA_BACKEND`
+//! environment variable (`edsdk` / `simulated`) or at runtime through
+//! `canon_set_backend`.
+
+use crate::canon::{CameraInfo, CaptureResult};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// Environment variable used to pick the camera backend at startup
+const BACKEND_ENV_VAR: &str = "BONIO_CAMERA_BACKEND";
+
+// =============================================================================
+// Trait
+// =============================================================================
+
+/// Operations the capture flow needs from a camera.
+///
+/// Methods take `&self` — implementations handle their own locking so that a
+/// long blocking capture never holds up live view or event polling.
+pub trait CameraBackend: Send + Sync {
+    fn kind(&self) -> BackendKind;
+
+    // --- SDK lifecycle ---
+    fn initialize(&self, app: &tauri::AppHandle) -> Result<bool, String>;
+    fn terminate(&self) -> Result<bool, String>;
+    fn is_initialized(&self) -> bool;
+
+    // --- Discovery & session ---
+    fn camera_list(&self) -> Result<Vec<CameraInfo>, String>;
+    fn connect(&self, index: Option<u32>) -> Result<CameraInfo, String>;
+    fn open_session(&self) -> Result<bool, String>;
+    fn close_session(&self) -> Result<bool, String>;
+    fn is_connected(&self) -> bool;
+
+    // --- Capture ---
+    /// Blocking capture — waits until the image has been downloaded
+    fn take_picture(&self) -> Result<CaptureResult, String>;
+    /// Non-blocking shutter — poll `capture_result` for the image
+    fn send_shutter(&self) -> Result<CaptureResult, String>;
+    fn capture_result(&self) -> Result<CaptureResult, String>;
+    fn process_events(&self) -> bool;
+
+    // --- Live view ---
+    fn start_live_view(&self) -> Result<bool, String>;
+    fn stop_live_view(&self) -> Result<bool, String>;
+    /// Raw EVF JPEG bytes, or `None` when no frame is available this cycle
+    fn live_view_frame(&self) -> Result<Option<Vec<u8>>, String>;
+
+    // --- Properties ---
+    fn get_property(&self, property_id: u32) -> Result<Option<u32>, String>;
+    fn set_property(&self, property_id: u32, value: u32) -> Result<bool, String>;
+
+    // --- Movie recording ---
+    fn start_movie_record(&self) -> Result<bool, String>;
+    fn stop_movie_record(&self) -> Result<String, String>;
+    fn stop_movie_record_fast(&self) -> Result<bool, String>;
+    fn take_photo_during_recording(&self) -> Result<CaptureResult, String>;
+    fn finalize_movie_download(&self) -> Result<String, String>;
+    fn is_movie_recording(&self) -> bool;
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BackendKind {
+    Edsdk,
+    Simulated,
+}
+
+impl BackendKind {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "edsdk" | "canon" => Some(BackendKind::Edsdk),
+            "simulated" | "simulator" | "sim" => Some(BackendKind::Simulated),
+            _ => None,
+        }
+    }
+
+    fn default_for_platform() -> Self {
+        if cfg!(target_os = "windows") {
+            BackendKind::Edsdk
+        } else {
+            BackendKind::Simulated
+        }
+    }
+
+    fn create(self) -> Arc<dyn CameraBackend> {
+        match self {
+            BackendKind::Edsdk => Arc::new(crate::canon::EdsdkCamera),
+            BackendKind::Simulated => Arc::new(crate::camera_sim::SimulatedCamera::new()),
+        }
+    }
+}
+
+// =============================================================================
+// Active backend
+// =============================================================================
+
+static ACTIVE_BACKEND: OnceLock<RwLock<Arc<dyn CameraBackend>>> = OnceLock::new();
+
+fn backend_slot() -> &'static RwLock<Arc<dyn CameraBackend>> {
+    ACTIVE_BACKEND.get_or_init(|| {
+        let kind = std::env::var(BACKEND_ENV_VAR)
+            .ok()
+            .and_then(|v| BackendKind::parse(&v))
+            .unwrap_or_else(BackendKind::default_for_platform);
+        info!("[Camera] Using {:?} backend", kind);
+        RwLock::new(kind.create())
+    })
+}
+
+/// Get the currently active camera backend
+pub fn backend() -> Arc<dyn CameraBackend> {
+    backend_slot()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// Swap the active backend. The previous backend is terminated first so the
+/// EDSDK session (if any) is released before the simulator takes over.
+pub fn set_backend(kind: BackendKind) -> Result<(), String> {
+    let current = backend();
+    if current.kind() == kind {
+        return Ok(());
+    }
+    if current.is_movie_recording() {
+        return Err("Cannot switch camera backend while recording".to_string());
+    }
+    let _ = current.terminate();
+
+    *backend_slot().write().unwrap_or_else(|e| e.into_inner()) = kind.create();
+    info!("[Camera] Switched to {:?} backend", kind);
+    Ok(())
+}
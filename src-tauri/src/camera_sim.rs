@@ -0,0 +1,615 @@
+//! Simulated Camera Backend
+//!
+//! A deterministic stand-in for a Canon body so the whole capture flow can be
+//! developed and tested on machines without EDSDK or a camera attached:
+//! - Synthetic JPEG captures (pattern derived from the shot number)
+//! - Animated live view frames
+//! - Fake battery / available-shots counters
+//! - Movie recording (test pattern clip rendered with FFmpeg)
+//! - Injectable EDSDK errors (e.g. `EDS_ERR_DEVICE_BUSY`) per operation
+
+use crate::camera::{BackendKind, CameraBackend};
+use crate::canon::{check_error, store_capture, CameraInfo, CaptureResult};
+use crate::edsdk_sys::*;
+use image::{Rgb, RgbImage};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Synthetic capture size — larger than `paper::capture_max_dimension()` so the
+/// resize path is exercised exactly like a real capture.
+const SIM_CAPTURE_WIDTH: u32 = 3840;
+const SIM_CAPTURE_HEIGHT: u32 = 2560;
+/// Live view frame size (matches a typical Canon EVF stream)
+const SIM_LIVE_VIEW_WIDTH: u32 = 960;
+const SIM_LIVE_VIEW_HEIGHT: u32 = 640;
+/// Time between shutter press and image "download"
+const SIM_CAPTURE_DELAY: Duration = Duration::from_millis(250);
+/// Battery drops by 1% every N shots
+const SIM_SHOTS_PER_BATTERY_PERCENT: u32 = 10;
+const SIM_INITIAL_AVAILABLE_SHOTS: u32 = 999;
+
+// =============================================================================
+// Error injection
+// =============================================================================
+
+/// Operations that can be made to fail on demand
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SimOperation {
+    Connect,
+    OpenSession,
+    TakePicture,
+    LiveView,
+    GetProperty,
+    SetProperty,
+    MovieRecord,
+}
+
+impl SimOperation {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "connect" => Some(SimOperation::Connect),
+            "open_session" => Some(SimOperation::OpenSession),
+            "take_picture" => Some(SimOperation::TakePicture),
+            "live_view" => Some(SimOperation::LiveView),
+            "get_property" => Some(SimOperation::GetProperty),
+            "set_property" => Some(SimOperation::SetProperty),
+            "movie_record" => Some(SimOperation::MovieRecord),
+            _ => None,
+        }
+    }
+}
+
+struct InjectedFault {
+    error: EdsError,
+    remaining: u32,
+}
+
+/// Faults live outside the camera instance so they survive backend switches
+/// and can be armed before the simulator is even selected.
+static INJECTED_FAULTS: OnceLock<Mutex<HashMap<SimOperation, InjectedFault>>> = OnceLock::new();
+
+fn faults() -> &'static Mutex<HashMap<SimOperation, InjectedFault>> {
+    INJECTED_FAULTS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Make the next `times` calls of `operation` fail with `error`.
+/// Passing `EDS_ERR_OK` clears any fault armed for that operation.
+pub fn inject_error(operation: SimOperation, error: EdsError, times: u32) {
+    let mut faults = faults().lock().unwrap();
+    if error == EDS_ERR_OK || times == 0 {
+        faults.remove(&operation);
+        return;
+    }
+    info!(
+        "[CameraSim] Injecting {} for {:?} ({} time(s))",
+        error_to_string(error),
+        operation,
+        times
+    );
+    faults.insert(operation, InjectedFault { error, remaining: times });
+}
+
+pub fn clear_injected_errors() {
+    faults().lock().unwrap().clear();
+}
+
+/// Consume one armed fault for `operation`, if any
+fn take_fault(operation: SimOperation) -> Option<EdsError> {
+    let mut faults = faults().lock().unwrap();
+    let fault = faults.get_mut(&operation)?;
+    let error = fault.error;
+    fault.remaining -= 1;
+    if fault.remaining == 0 {
+        faults.remove(&operation);
+    }
+    warn!("[CameraSim] Injected fault on {:?}: {}", operation, error_to_string(error));
+    Some(error)
+}
+
+fn fail(operation: SimOperation) -> Result<(), String> {
+    match take_fault(operation) {
+        Some(error) => check_error(error),
+        None => Ok(()),
+    }
+}
+
+// =============================================================================
+// Simulated camera
+// =============================================================================
+
+struct SimState {
+    initialized: bool,
+    connected: bool,
+    session_open: bool,
+    live_view: bool,
+    live_view_frames: u64,
+    shots_taken: u32,
+    properties: HashMap<EdsPropertyID, u32>,
+    /// Set by `send_shutter`; the image becomes available after SIM_CAPTURE_DELAY
+    pending_capture: Option<Instant>,
+    /// Recording start time while a movie is being recorded
+    recording_since: Option<Instant>,
+    /// Clip length captured by a fast stop, rendered in `finalize_movie_download`
+    pending_movie: Option<Duration>,
+    movies_recorded: u32,
+}
+
+impl Default for SimState {
+    fn default() -> Self {
+        let mut properties = HashMap::new();
+        properties.insert(kEdsPropID_SaveTo, kEdsSaveTo_Host);
+        properties.insert(kEdsPropID_ImageQuality, kEdsImageQuality_LJF);
+        properties.insert(kEdsPropID_ISOSpeed, 0x48); // ISO 100
+        properties.insert(kEdsPropID_Av, 0x30); // f/5.6
+        properties.insert(kEdsPropID_Tv, 0x68); // 1/60
+        properties.insert(kEdsPropID_AEMode, 0); // Program AE
+        properties.insert(kEdsPropID_Evf_OutputDevice, 0);
+
+        Self {
+            initialized: false,
+            connected: false,
+            session_open: false,
+            live_view: false,
+            live_view_frames: 0,
+            shots_taken: 0,
+            properties,
+            pending_capture: None,
+            recording_since: None,
+            pending_movie: None,
+            movies_recorded: 0,
+        }
+    }
+}
+
+impl SimState {
+    fn battery_level(&self) -> u32 {
+        100u32
+            .saturating_sub(self.shots_taken / SIM_SHOTS_PER_BATTERY_PERCENT)
+            .max(5)
+    }
+
+    fn available_shots(&self) -> u32 {
+        SIM_INITIAL_AVAILABLE_SHOTS.saturating_sub(self.shots_taken)
+    }
+
+    fn require_session(&self) -> Result<(), String> {
+        if !self.initialized {
+            return Err("SDK not initialized".to_string());
+        }
+        if !self.connected {
+            return Err("No camera connected".to_string());
+        }
+        if !self.session_open {
+            return Err("Session not open".to_string());
+        }
+        Ok(())
+    }
+}
+
+pub struct SimulatedCamera {
+    state: Mutex<SimState>,
+}
+
+impl SimulatedCamera {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(SimState::default()),
+        }
+    }
+
+    fn camera_info() -> CameraInfo {
+        CameraInfo {
+            name: "Canon EOS R50 (Simulated)".to_string(),
+            port_name: "SIM:0".to_string(),
+            device_sub_type: 0,
+            body_id: Some("SIM000000001".to_string()),
+        }
+    }
+
+    fn failed_capture(error: String) -> CaptureResult {
+        CaptureResult {
+            success: false,
+            error: Some(error),
+            file: None,
+        }
+    }
+
+    /// Take the next shot number and render it
+    fn shoot(state: &mut SimState) -> CaptureResult {
+        state.shots_taken += 1;
+        let shot = state.shots_taken;
+        match render_capture(shot) {
+            Ok(jpeg) => {
+                info!("[CameraSim] Captured shot #{}", shot);
+                store_capture(&jpeg)
+            }
+            Err(e) => Self::failed_capture(e),
+        }
+    }
+
+    fn stop_recording(state: &mut SimState) -> Result<Duration, String> {
+        let started = state
+            .recording_since
+            .take()
+            .ok_or("Not currently recording")?;
+        state.properties.insert(kEdsPropID_Record, kEdsRecord_End);
+        Ok(started.elapsed())
+    }
+
+    fn render_movie(state: &mut SimState, length: Duration) -> Result<String, String> {
+        state.movies_recorded += 1;
+        render_movie_clip(state.movies_recorded, length)
+    }
+}
+
+impl CameraBackend for SimulatedCamera {
+    fn kind(&self) -> BackendKind {
+        BackendKind::Simulated
+    }
+
+    fn initialize(&self, _app: &tauri::AppHandle) -> Result<bool, String> {
+        self.state.lock().unwrap().initialized = true;
+        info!("[CameraSim] SDK initialized");
+        Ok(true)
+    }
+
+    fn terminate(&self) -> Result<bool, String> {
+        *self.state.lock().unwrap() = SimState::default();
+        info!("[CameraSim] SDK terminated");
+        Ok(true)
+    }
+
+    fn is_initialized(&self) -> bool {
+        self.state.lock().unwrap().initialized
+    }
+
+    fn camera_list(&self) -> Result<Vec<CameraInfo>, String> {
+        if !self.is_initialized() {
+            return Err("SDK not initialized".to_string());
+        }
+        Ok(vec![Self::camera_info()])
+    }
+
+    fn connect(&self, index: Option<u32>) -> Result<CameraInfo, String> {
+        let mut state = self.state.lock().unwrap();
+        if !state.initialized {
+            return Err("SDK not initialized".to_string());
+        }
+        if index.unwrap_or(0) != 0 {
+            return Err("No camera at specified index".to_string());
+        }
+        fail(SimOperation::Connect)?;
+
+        state.session_open = false;
+        state.connected = true;
+        let info = Self::camera_info();
+        info!("[CameraSim] Connected to: {}", info.name);
+        Ok(info)
+    }
+
+    fn open_session(&self) -> Result<bool, String> {
+        let mut state = self.state.lock().unwrap();
+        if !state.initialized {
+            return Err("SDK not initialized".to_string());
+        }
+        if !state.connected {
+            return Err("No camera connected".to_string());
+        }
+        fail(SimOperation::OpenSession)?;
+
+        state.session_open = true;
+        state.properties.insert(kEdsPropID_SaveTo, kEdsSaveTo_Host);
+        state.properties.insert(kEdsPropID_ImageQuality, kEdsImageQuality_LJF);
+        info!("[CameraSim] Session opened");
+        Ok(true)
+    }
+
+    fn close_session(&self) -> Result<bool, String> {
+        let mut state = self.state.lock().unwrap();
+        state.connected = false;
+        state.session_open = false;
+        state.live_view = false;
+        state.pending_capture = None;
+        Ok(true)
+    }
+
+    fn is_connected(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        state.connected && state.session_open
+    }
+
+    fn take_picture(&self) -> Result<CaptureResult, String> {
+        if let Err(e) = self.state.lock().unwrap().require_session() {
+            return Ok(Self::failed_capture(e));
+        }
+        if let Some(error) = take_fault(SimOperation::TakePicture) {
+            return Ok(Self::failed_capture(format!(
+                "Take picture failed: {}",
+                error_to_string(error)
+            )));
+        }
+
+        // Simulate shutter + transfer time without holding the state lock,
+        // so live view and event polling keep running like on real hardware.
+        std::thread::sleep(SIM_CAPTURE_DELAY);
+
+        let mut state = self.state.lock().unwrap();
+        Ok(Self::shoot(&mut state))
+    }
+
+    fn send_shutter(&self) -> Result<CaptureResult, String> {
+        let mut state = self.state.lock().unwrap();
+        if let Err(e) = state.require_session() {
+            return Ok(Self::failed_capture(e));
+        }
+        if let Some(error) = take_fault(SimOperation::TakePicture) {
+            return Ok(Self::failed_capture(format!(
+                "Shutter failed: {}",
+                error_to_string(error)
+            )));
+        }
+
+        state.pending_capture = Some(Instant::now());
+        Ok(CaptureResult {
+            success: true,
+            error: None,
+            file: None,
+        })
+    }
+
+    fn capture_result(&self) -> Result<CaptureResult, String> {
+        let mut state = self.state.lock().unwrap();
+        let pressed_at = match state.pending_capture {
+            Some(t) => t,
+            None => return Ok(Self::failed_capture("No capture in progress".to_string())),
+        };
+
+        if pressed_at.elapsed() < SIM_CAPTURE_DELAY {
+            // Still pending
+            return Ok(CaptureResult {
+                success: false,
+                error: None,
+                file: None,
+            });
+        }
+
+        state.pending_capture = None;
+        Ok(Self::shoot(&mut state))
+    }
+
+    fn process_events(&self) -> bool {
+        self.is_initialized()
+    }
+
+    fn start_live_view(&self) -> Result<bool, String> {
+        let mut state = self.state.lock().unwrap();
+        state.require_session()?;
+        state.live_view = true;
+        state
+            .properties
+            .insert(kEdsPropID_Evf_OutputDevice, kEdsEvfOutputDevice_PC);
+        info!("[CameraSim] Live view started");
+        Ok(true)
+    }
+
+    fn stop_live_view(&self) -> Result<bool, String> {
+        let mut state = self.state.lock().unwrap();
+        state.require_session()?;
+        state.live_view = false;
+        state.properties.insert(kEdsPropID_Evf_OutputDevice, 0);
+        info!("[CameraSim] Live view stopped");
+        Ok(true)
+    }
+
+    fn live_view_frame(&self) -> Result<Option<Vec<u8>>, String> {
+        let frame = {
+            let mut state = self.state.lock().unwrap();
+            state.require_session()?;
+            if !state.live_view {
+                // EdsDownloadEvfImage fails (OBJECT_NOTREADY) when EVF is off
+                return Ok(None);
+            }
+            if take_fault(SimOperation::LiveView).is_some() {
+                return Ok(None);
+            }
+            state.live_view_frames += 1;
+            state.live_view_frames
+        };
+
+        render_live_view(frame).map(Some)
+    }
+
+    fn get_property(&self, property_id: u32) -> Result<Option<u32>, String> {
+        let state = self.state.lock().unwrap();
+        state.require_session()?;
+        fail(SimOperation::GetProperty)?;
+
+        if property_id == kEdsPropID_BatteryLevel {
+            return Ok(Some(state.battery_level()));
+        }
+        if property_id == kEdsPropID_AvailableShots {
+            return Ok(Some(state.available_shots()));
+        }
+        match state.properties.get(&property_id) {
+            Some(value) => Ok(Some(*value)),
+            None => check_error(EDS_ERR_NOT_SUPPORTED).map(|_| None),
+        }
+    }
+
+    fn set_property(&self, property_id: u32, value: u32) -> Result<bool, String> {
+        let mut state = self.state.lock().unwrap();
+        state.require_session()?;
+        fail(SimOperation::SetProperty)?;
+
+        state.properties.insert(property_id, value);
+        Ok(true)
+    }
+
+    fn start_movie_record(&self) -> Result<bool, String> {
+        let mut state = self.state.lock().unwrap();
+        state.require_session()?;
+        if state.recording_since.is_some() {
+            warn!("[CameraSim] Movie recording already in progress");
+            return Ok(true);
+        }
+        if let Some(error) = take_fault(SimOperation::MovieRecord) {
+            return Err(format!("Start recording failed: {}", error_to_string(error)));
+        }
+
+        state.pending_movie = None;
+        state.recording_since = Some(Instant::now());
+        state.live_view = true;
+        state.properties.insert(kEdsPropID_SaveTo, kEdsSaveTo_Camera);
+        state.properties.insert(kEdsPropID_Record, kEdsRecord_Begin);
+        info!("[CameraSim] Movie recording started");
+        Ok(true)
+    }
+
+    fn stop_movie_record(&self) -> Result<String, String> {
+        let mut state = self.state.lock().unwrap();
+        let length = Self::stop_recording(&mut state)?;
+        state.properties.insert(kEdsPropID_SaveTo, kEdsSaveTo_Host);
+        Self::render_movie(&mut state, length)
+    }
+
+    fn stop_movie_record_fast(&self) -> Result<bool, String> {
+        let mut state = self.state.lock().unwrap();
+        let length = Self::stop_recording(&mut state)?;
+        state.pending_movie = Some(length);
+        state.live_view = false;
+        state.properties.insert(kEdsPropID_SaveTo, kEdsSaveTo_Host);
+        info!("[CameraSim] Movie recording stopped (fast), movie download pending");
+        Ok(true)
+    }
+
+    fn take_photo_during_recording(&self) -> Result<CaptureResult, String> {
+        {
+            let mut state = self.state.lock().unwrap();
+            if state.recording_since.is_some() {
+                let length = Self::stop_recording(&mut state)?;
+                state.pending_movie = Some(length);
+                state.live_view = false;
+            }
+        }
+        self.take_picture()
+    }
+
+    fn finalize_movie_download(&self) -> Result<String, String> {
+        let mut state = self.state.lock().unwrap();
+        let length = state
+            .pending_movie
+            .take()
+            .ok_or("Movie download timeout — no file received from camera")?;
+        Self::render_movie(&mut state, length)
+    }
+
+    fn is_movie_recording(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        state.recording_since.is_some() || state.pending_movie.is_some()
+    }
+}
+
+// =============================================================================
+// Synthetic media
+// =============================================================================
+
+/// Pick a saturated colour from a hue in degrees
+fn hue_to_rgb(hue: f32) -> [f32; 3] {
+    let h = (hue % 360.0) / 60.0;
+    let x = 1.0 - (h % 2.0 - 1.0).abs();
+    match h as u32 {
+        0 => [1.0, x, 0.0],
+        1 => [x, 1.0, 0.0],
+        2 => [0.0, 1.0, x],
+        3 => [0.0, x, 1.0],
+        4 => [x, 0.0, 1.0],
+        _ => [1.0, 0.0, x],
+    }
+}
+
+fn encode_jpeg(img: &RgbImage, quality: u8) -> Result<Vec<u8>, String> {
+    let mut buf = Vec::new();
+    let mut encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buf, quality);
+    encoder
+        .encode_image(img)
+        .map_err(|e| format!("JPEG encode error: {}", e))?;
+    Ok(buf)
+}
+
+/// Capture #n: diagonal stripes over a gradient whose hue depends on n,
+/// so consecutive shots are visibly different but always reproducible.
+fn render_capture(shot: u32) -> Result<Vec<u8>, String> {
+    let tint = hue_to_rgb((shot * 47) as f32);
+    let (w, h) = (SIM_CAPTURE_WIDTH, SIM_CAPTURE_HEIGHT);
+    let img = RgbImage::from_fn(w, h, |x, y| {
+        let fx = x as f32 / w as f32;
+        let fy = y as f32 / h as f32;
+        let stripe = ((x + y + shot * 40) / 160).is_multiple_of(2);
+        let shade = if stripe { 1.0 } else { 0.82 };
+        let mut px = [0u8; 3];
+        for c in 0..3 {
+            let v = (0.25 + 0.55 * tint[c] * (0.5 + 0.5 * fx) + 0.2 * fy) * shade;
+            px[c] = (v.clamp(0.0, 1.0) * 255.0) as u8;
+        }
+        Rgb(px)
+    });
+    encode_jpeg(&img, 92)
+}
+
+/// Live view frame #n: a scrolling gradient with a white bar sweeping across
+fn render_live_view(frame: u64) -> Result<Vec<u8>, String> {
+    let (w, h) = (SIM_LIVE_VIEW_WIDTH, SIM_LIVE_VIEW_HEIGHT);
+    let bar_x = ((frame * 12) % w as u64) as u32;
+    let phase = (frame % 360) as f32;
+    let img = RgbImage::from_fn(w, h, |x, y| {
+        if x >= bar_x && x < bar_x + 24 {
+            return Rgb([255, 255, 255]);
+        }
+        let tint = hue_to_rgb(phase + x as f32 * 90.0 / w as f32);
+        let fy = 0.35 + 0.65 * (1.0 - y as f32 / h as f32);
+        Rgb([
+            (tint[0] * fy * 255.0) as u8,
+            (tint[1] * fy * 255.0) as u8,
+            (tint[2] * fy * 255.0) as u8,
+        ])
+    });
+    encode_jpeg(&img, 70)
+}
+
+/// Render a test-pattern clip for a simulated movie recording.
+/// Written to the same temp folder the EDSDK movie download uses.
+fn render_movie_clip(index: u32, length: Duration) -> Result<String, String> {
+    let seconds = length.as_secs_f64().clamp(1.0, 30.0);
+    let temp_dir = std::env::temp_dir().join("bonio-booth").join("videos");
+    std::fs::create_dir_all(&temp_dir).map_err(|e| format!("Create dir error: {}", e))?;
+    let local_path = temp_dir.join(format!("SIM_{:04}.MP4", index));
+
+    let source = format!(
+        "testsrc=duration={:.2}:size={}x{}:rate=30",
+        seconds, SIM_LIVE_VIEW_WIDTH, SIM_LIVE_VIEW_HEIGHT
+    );
+    let output = crate::video::hidden_command(&crate::video::get_ffmpeg_path_public())
+        .args([
+            "-y",
+            "-f", "lavfi",
+            "-i", &source,
+            "-c:v", "libx264",
+            "-pix_fmt", "yuv420p",
+            &local_path.to_string_lossy(),
+        ])
+        .output()
+        .map_err(|e| format!("Movie download failed: simulated movie needs FFmpeg ({})", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Movie download failed: {}", stderr));
+    }
+
+    let path = local_path.to_string_lossy().to_string();
+    info!("[CameraSim] Movie file ready: {} ({:.1}s)", path, seconds);
+    Ok(path)
+}
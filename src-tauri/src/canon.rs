@@ -8,23 +8,30 @@
 //! - Live View (EVF)
 //! - Camera properties (ISO, aperture, shutter speed, etc.)
 //! - Event polling
+//!
+//! All commands dispatch through the active `CameraBackend` (see camera.rs);
+//! the EDSDK implementation lives here.
 
-use log::{error, info, warn};
+#[cfg(target_os = "windows")]
+use log::error;
+use log::{info, warn};
 use serde::{Deserialize, Serialize};
+#[cfg(target_os = "windows")]
 use std::os::raw::c_void;
+#[cfg(target_os = "windows")]
 use std::ptr;
 use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(target_os = "windows")]
 use std::sync::{Arc, Mutex, OnceLock};
 
-/// Max pixel dimension (width or height) for captured photos.
-/// 3600px is enough for 600 DPI 4×6 prints while vastly reducing file size.
-const CAPTURE_MAX_DIMENSION: u32 = 3600;
 /// JPEG quality for re-encoded captures (1–100).
 const CAPTURE_JPEG_QUALITY: u8 = 95;
 
+use crate::camera::{backend, BackendKind, CameraBackend};
+use crate::live_view;
+use crate::workdir::ImageFile;
 #[cfg(target_os = "windows")]
 use crate::edsdk_sys::dynamic::*;
-#[cfg(target_os = "windows")]
 use crate::edsdk_sys::*;
 
 // =============================================================================
@@ -43,8 +50,8 @@ pub struct CameraInfo {
 pub struct CaptureResult {
     pub success: bool,
     pub error: Option<String>,
-    /// Base64-encoded JPEG image data
-    pub image_data: Option<String>,
+    /// The capture, already stored in the session folder
+    pub file: Option<ImageFile>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -58,14 +65,19 @@ pub struct LiveViewFrame {
 // =============================================================================
 
 static SDK_INITIALIZED: AtomicBool = AtomicBool::new(false);
+#[cfg(target_os = "windows")]
 static CAMERA_MANAGER: OnceLock<Arc<Mutex<CameraManager>>> = OnceLock::new();
+#[cfg(target_os = "windows")]
 static CAPTURE_DATA: OnceLock<Arc<Mutex<CaptureData>>> = OnceLock::new();
+#[cfg(target_os = "windows")]
 static IS_CAPTURING: AtomicBool = AtomicBool::new(false);
 
 /// Movie recording state — shared between event handler and Tauri commands
+#[cfg(target_os = "windows")]
 static MOVIE_DATA: OnceLock<Arc<Mutex<MovieData>>> = OnceLock::new();
 static IS_MOVIE_RECORDING: AtomicBool = AtomicBool::new(false);
 
+#[cfg(target_os = "windows")]
 struct MovieData {
     /// Local path to the downloaded movie file (set by the event handler)
     movie_path: Option<String>,
@@ -74,6 +86,7 @@ struct MovieData {
     download_error: Option<String>,
 }
 
+#[cfg(target_os = "windows")]
 impl Default for MovieData {
     fn default() -> Self {
         Self {
@@ -84,12 +97,14 @@ impl Default for MovieData {
     }
 }
 
+#[cfg(target_os = "windows")]
 struct CaptureData {
     image_data: Option<Vec<u8>>,
     capture_complete: bool,
     capture_error: Option<String>,
 }
 
+#[cfg(target_os = "windows")]
 impl Default for CaptureData {
     fn default() -> Self {
         Self {
@@ -100,16 +115,15 @@ impl Default for CaptureData {
     }
 }
 
+#[cfg(target_os = "windows")]
 struct CameraManager {
-    #[cfg(target_os = "windows")]
     camera_ref: Option<EdsCameraRef>,
-    #[cfg(not(target_os = "windows"))]
-    camera_ref: Option<*mut c_void>,
     session_open: bool,
     event_handler_registered: bool,
     state_event_handler_registered: bool,
 }
 
+#[cfg(target_os = "windows")]
 impl Default for CameraManager {
     fn default() -> Self {
         Self {
@@ -121,15 +135,16 @@ impl Default for CameraManager {
     }
 }
 
+#[cfg(target_os = "windows")]
 unsafe impl Send for CameraManager {}
+#[cfg(target_os = "windows")]
 unsafe impl Sync for CameraManager {}
 
 // =============================================================================
 // Helper
 // =============================================================================
 
-#[cfg(target_os = "windows")]
-fn check_error(error: EdsError) -> Result<(), String> {
+pub(crate) fn check_error(error: EdsError) -> Result<(), String> {
     if error == EDS_ERR_OK {
         Ok(())
     } else {
@@ -141,6 +156,7 @@ fn check_error(error: EdsError) -> Result<(), String> {
     }
 }
 
+#[cfg(target_os = "windows")]
 /// Resolve EDSDK.dll path — tries multiple locations
 fn resolve_dll_path(app: &tauri::AppHandle) -> String {
     // Helper: check both "EDSDK/Dll/EDSDK.dll" (dev) and "EDSDK/EDSDK.dll" (bundled/flat)
@@ -202,6 +218,7 @@ fn resolve_dll_path(app: &tauri::AppHandle) -> String {
     "EDSDK/Dll/EDSDK.dll".to_string()
 }
 
+#[cfg(target_os = "windows")]
 use tauri::Manager;
 
 // =============================================================================
@@ -211,12 +228,11 @@ use tauri::Manager;
 /// Resize a captured JPEG to fit within `max_dim` pixels (longest side) and
 /// re-encode as JPEG at the given quality.  If the image is already small
 /// enough it is still re-encoded to optimise compression.
-#[cfg(target_os = "windows")]
 fn resize_captured_jpeg(raw_bytes: &[u8], max_dim: u32, quality: u8) -> Result<Vec<u8>, String> {
     use image::GenericImageView;
 
-    let img = image::load_from_memory(raw_bytes)
-        .map_err(|e| format!("Image load error: {}", e))?;
+    // EXIF orientation + camera mount rotation
+    let img = crate::orientation::decode_upright(raw_bytes, true)?;
 
     let (w, h) = img.dimensions();
 
@@ -226,23 +242,9 @@ fn resize_captured_jpeg(raw_bytes: &[u8], max_dim: u32, quality: u8) -> Result<V
         img
     };
 
-    let mut buf = Vec::new();
-    let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buf, quality);
-    resized
-        .write_with_encoder(encoder)
-        .map_err(|e| format!("JPEG encode error: {}", e))?;
-
-    // Patch JFIF header to set DPI to 350
-    if buf.len() >= 18 && buf[0] == 0xFF && buf[1] == 0xD8 && buf[2] == 0xFF && buf[3] == 0xE0 {
-        if buf[6] == b'J' && buf[7] == b'F' && buf[8] == b'I' && buf[9] == b'F' && buf[10] == 0x00 {
-            let dpi: u16 = 350;
-            buf[13] = 1; // 1 = dots per inch
-            buf[14] = (dpi >> 8) as u8;
-            buf[15] = (dpi & 0xFF) as u8;
-            buf[16] = (dpi >> 8) as u8;
-            buf[17] = (dpi & 0xFF) as u8;
-        }
-    }
+    // Paper profile DPI + sRGB, keeping the camera's EXIF (orientation, exposure)
+    let options = crate::workdir::jpeg_options(resized.width(), resized.height());
+    let buf = crate::jpeg_meta::encode_jpeg(&resized, quality, Some(raw_bytes), &options)?;
 
     info!(
         "[Canon] Resized capture: {}x{} -> {}x{}, {:.1} MB -> {:.1} MB",
@@ -255,6 +257,33 @@ fn resize_captured_jpeg(raw_bytes: &[u8], max_dim: u32, quality: u8) -> Result<V
     Ok(buf)
 }
 
+/// Resize a raw camera JPEG (falling back to the original bytes if that
+/// fails) and store it in the session folder, so the frontend gets a file
+/// instead of base64 over IPC.
+pub(crate) fn store_capture(data: &[u8]) -> CaptureResult {
+    let processed = resize_captured_jpeg(data, crate::paper::capture_max_dimension(), CAPTURE_JPEG_QUALITY)
+        .unwrap_or_else(|e| {
+            warn!("[Canon] Resize failed ({}), using original", e);
+            data.to_vec()
+        });
+
+    match crate::workdir::write_bytes(&processed, "capture") {
+        Ok(file) => {
+            info!("[Canon] Capture saved: {}", file.path);
+            CaptureResult {
+                success: true,
+                error: None,
+                file: Some(file),
+            }
+        }
+        Err(e) => CaptureResult {
+            success: false,
+            error: Some(format!("Save capture error: {}", e)),
+            file: None,
+        },
+    }
+}
+
 // =============================================================================
 // Event Handlers (Windows only)
 // =============================================================================
@@ -496,12 +525,11 @@ unsafe extern "system" fn state_event_handler(
 }
 
 // =============================================================================
-// Tauri Commands
+// EDSDK Operations
 // =============================================================================
 
 /// Initialize the Canon EDSDK
-#[tauri::command]
-pub fn canon_initialize(app: tauri::AppHandle) -> Result<bool, String> {
+fn eds_initialize(app: &tauri::AppHandle) -> Result<bool, String> {
     #[cfg(not(target_os = "windows"))]
     {
         let _ = app;
@@ -515,7 +543,7 @@ pub fn canon_initialize(app: tauri::AppHandle) -> Result<bool, String> {
             return Ok(true);
         }
 
-        let dll_path = resolve_dll_path(&app);
+        let dll_path = resolve_dll_path(app);
         info!("[Canon] Initializing SDK from: {}", dll_path);
 
         unsafe {
@@ -539,8 +567,7 @@ pub fn canon_initialize(app: tauri::AppHandle) -> Result<bool, String> {
 }
 
 /// Terminate the Canon EDSDK
-#[tauri::command]
-pub fn canon_terminate() -> Result<bool, String> {
+fn eds_terminate() -> Result<bool, String> {
     #[cfg(not(target_os = "windows"))]
     {
         return Ok(true);
@@ -553,7 +580,7 @@ pub fn canon_terminate() -> Result<bool, String> {
         }
 
         // Close session first
-        let _ = canon_close_session();
+        let _ = eds_close_session();
 
         unsafe {
             let error = EdsTerminateSDK();
@@ -567,14 +594,12 @@ pub fn canon_terminate() -> Result<bool, String> {
 }
 
 /// Check if SDK is initialized
-#[tauri::command]
-pub fn canon_is_initialized() -> bool {
+fn eds_is_initialized() -> bool {
     SDK_INITIALIZED.load(Ordering::SeqCst)
 }
 
 /// Get list of connected Canon cameras
-#[tauri::command]
-pub fn canon_get_camera_list() -> Result<Vec<CameraInfo>, String> {
+fn eds_get_camera_list() -> Result<Vec<CameraInfo>, String> {
     #[cfg(not(target_os = "windows"))]
     {
         return Err("Canon EDSDK is only supported on Windows".to_string());
@@ -631,8 +656,7 @@ pub fn canon_get_camera_list() -> Result<Vec<CameraInfo>, String> {
 }
 
 /// Connect to camera by index (default: 0)
-#[tauri::command]
-pub fn canon_connect(index: Option<u32>) -> Result<CameraInfo, String> {
+fn eds_connect(index: Option<u32>) -> Result<CameraInfo, String> {
     #[cfg(not(target_os = "windows"))]
     {
         let _ = index;
@@ -715,8 +739,7 @@ pub fn canon_connect(index: Option<u32>) -> Result<CameraInfo, String> {
 }
 
 /// Open session with connected camera
-#[tauri::command]
-pub fn canon_open_session() -> Result<bool, String> {
+fn eds_open_session() -> Result<bool, String> {
     #[cfg(not(target_os = "windows"))]
     {
         return Err("Canon EDSDK is only supported on Windows".to_string());
@@ -797,8 +820,7 @@ pub fn canon_open_session() -> Result<bool, String> {
 }
 
 /// Close session
-#[tauri::command]
-pub fn canon_close_session() -> Result<bool, String> {
+fn eds_close_session() -> Result<bool, String> {
     #[cfg(not(target_os = "windows"))]
     {
         return Ok(true);
@@ -838,8 +860,7 @@ pub fn canon_close_session() -> Result<bool, String> {
 }
 
 /// Check if camera is connected
-#[tauri::command]
-pub fn canon_is_connected() -> bool {
+fn eds_is_connected() -> bool {
     #[cfg(not(target_os = "windows"))]
     {
         return false;
@@ -860,8 +881,7 @@ pub fn canon_is_connected() -> bool {
 }
 
 /// Take a picture (blocking — waits for image download)
-#[tauri::command]
-pub fn canon_take_picture() -> Result<CaptureResult, String> {
+fn eds_take_picture() -> Result<CaptureResult, String> {
     #[cfg(not(target_os = "windows"))]
     {
         return Err("Canon EDSDK is only supported on Windows".to_string());
@@ -873,7 +893,7 @@ pub fn canon_take_picture() -> Result<CaptureResult, String> {
             return Ok(CaptureResult {
                 success: false,
                 error: Some("SDK not initialized".to_string()),
-                image_data: None,
+                file: None,
             });
         }
 
@@ -883,7 +903,7 @@ pub fn canon_take_picture() -> Result<CaptureResult, String> {
             return Ok(CaptureResult {
                 success: false,
                 error: Some("Capture already in progress".to_string()),
-                image_data: None,
+                file: None,
             });
         }
 
@@ -909,7 +929,7 @@ pub fn canon_take_picture() -> Result<CaptureResult, String> {
                     return Ok(CaptureResult {
                         success: false,
                         error: Some("No camera connected".to_string()),
-                        image_data: None,
+                        file: None,
                     });
                 }
             };
@@ -918,7 +938,7 @@ pub fn canon_take_picture() -> Result<CaptureResult, String> {
                 return Ok(CaptureResult {
                     success: false,
                     error: Some("Session not open".to_string()),
-                    image_data: None,
+                    file: None,
                 });
             }
 
@@ -938,7 +958,7 @@ pub fn canon_take_picture() -> Result<CaptureResult, String> {
                                 "Failed to register event handler: {}",
                                 error_to_string(error)
                             )),
-                            image_data: None,
+                            file: None,
                         });
                     }
                 }
@@ -998,7 +1018,7 @@ pub fn canon_take_picture() -> Result<CaptureResult, String> {
                     "Take picture failed: {}",
                     error_to_string(take_error)
                 )),
-                image_data: None,
+                file: None,
             });
         }
 
@@ -1041,41 +1061,18 @@ pub fn canon_take_picture() -> Result<CaptureResult, String> {
             return Ok(CaptureResult {
                 success: false,
                 error: Some(err),
-                image_data: None,
+                file: None,
             });
         }
 
         match image_data {
-            Some(data) => {
-                use base64::Engine;
-
-                // Resize to max CAPTURE_MAX_DIMENSION px and re-encode as
-                // JPEG to dramatically reduce payload size (~27 MB → ~1-2 MB).
-                let processed = resize_captured_jpeg(
-                    &data, CAPTURE_MAX_DIMENSION, CAPTURE_JPEG_QUALITY,
-                ).unwrap_or_else(|e| {
-                    warn!("[Canon] Resize failed ({}), using original", e);
-                    data.clone()
-                });
-
-                let b64 = base64::engine::general_purpose::STANDARD.encode(&processed);
-                info!(
-                    "[Canon] Capture success: original {:.1} MB -> resized {:.1} MB",
-                    data.len() as f64 / 1_048_576.0,
-                    processed.len() as f64 / 1_048_576.0,
-                );
-                Ok(CaptureResult {
-                    success: true,
-                    error: None,
-                    image_data: Some(b64),
-                })
-            }
+            Some(data) => Ok(store_capture(&data)),
             None => {
                 error!("[Canon] Capture timeout - no image received after {:.1}s", start.elapsed().as_secs_f32());
                 Ok(CaptureResult {
                     success: false,
                     error: Some("Capture timeout - no image received".to_string()),
-                    image_data: None,
+                    file: None,
                 })
             }
         }
@@ -1083,8 +1080,7 @@ pub fn canon_take_picture() -> Result<CaptureResult, String> {
 }
 
 /// Send shutter command (non-blocking)
-#[tauri::command]
-pub fn canon_send_shutter() -> Result<CaptureResult, String> {
+fn eds_send_shutter() -> Result<CaptureResult, String> {
     #[cfg(not(target_os = "windows"))]
     {
         return Err("Canon EDSDK is only supported on Windows".to_string());
@@ -1096,7 +1092,7 @@ pub fn canon_send_shutter() -> Result<CaptureResult, String> {
             return Ok(CaptureResult {
                 success: false,
                 error: Some("SDK not initialized".to_string()),
-                image_data: None,
+                file: None,
             });
         }
 
@@ -1113,7 +1109,7 @@ pub fn canon_send_shutter() -> Result<CaptureResult, String> {
                     return Ok(CaptureResult {
                         success: false,
                         error: Some("No camera connected".to_string()),
-                        image_data: None,
+                        file: None,
                     });
                 }
             };
@@ -1122,7 +1118,7 @@ pub fn canon_send_shutter() -> Result<CaptureResult, String> {
                 return Ok(CaptureResult {
                     success: false,
                     error: Some("Session not open".to_string()),
-                    image_data: None,
+                    file: None,
                 });
             }
 
@@ -1141,7 +1137,7 @@ pub fn canon_send_shutter() -> Result<CaptureResult, String> {
                                 "Failed to register handler: {}",
                                 error_to_string(error)
                             )),
-                            image_data: None,
+                            file: None,
                         });
                     }
                 }
@@ -1195,7 +1191,7 @@ pub fn canon_send_shutter() -> Result<CaptureResult, String> {
                     "Shutter failed: {}",
                     error_to_string(take_error)
                 )),
-                image_data: None,
+                file: None,
             });
         }
 
@@ -1203,14 +1199,13 @@ pub fn canon_send_shutter() -> Result<CaptureResult, String> {
         Ok(CaptureResult {
             success: true,
             error: None,
-            image_data: None,
+            file: None,
         })
     }
 }
 
 /// Get capture result (non-blocking check)
-#[tauri::command]
-pub fn canon_get_capture_result() -> Result<CaptureResult, String> {
+fn eds_get_capture_result() -> Result<CaptureResult, String> {
     #[cfg(not(target_os = "windows"))]
     {
         return Err("Canon EDSDK is only supported on Windows".to_string());
@@ -1224,7 +1219,7 @@ pub fn canon_get_capture_result() -> Result<CaptureResult, String> {
                 return Ok(CaptureResult {
                     success: false,
                     error: Some("No capture in progress".to_string()),
-                    image_data: None,
+                    file: None,
                 });
             }
         };
@@ -1235,7 +1230,7 @@ pub fn canon_get_capture_result() -> Result<CaptureResult, String> {
                 return Ok(CaptureResult {
                     success: false,
                     error: None,
-                    image_data: None,
+                    file: None,
                 });
             }
 
@@ -1247,50 +1242,30 @@ pub fn canon_get_capture_result() -> Result<CaptureResult, String> {
                 return Ok(CaptureResult {
                     success: false,
                     error: Some(err),
-                    image_data: None,
+                    file: None,
                 });
             }
 
             match image_data {
-                Some(data) => {
-                    use base64::Engine;
-                    let processed = resize_captured_jpeg(
-                        &data, CAPTURE_MAX_DIMENSION, CAPTURE_JPEG_QUALITY,
-                    ).unwrap_or_else(|e| {
-                        warn!("[Canon] Resize failed in get_capture_result ({}), using original", e);
-                        data.clone()
-                    });
-                    let b64 = base64::engine::general_purpose::STANDARD.encode(&processed);
-                    info!(
-                        "[Canon] get_capture_result: original {:.1} MB -> resized {:.1} MB",
-                        data.len() as f64 / 1_048_576.0,
-                        processed.len() as f64 / 1_048_576.0,
-                    );
-                    Ok(CaptureResult {
-                        success: true,
-                        error: None,
-                        image_data: Some(b64),
-                    })
-                }
+                Some(data) => Ok(store_capture(&data)),
                 None => Ok(CaptureResult {
                     success: false,
                     error: Some("Capture complete but no data".to_string()),
-                    image_data: None,
+                    file: None,
                 }),
             }
         } else {
             Ok(CaptureResult {
                 success: false,
                 error: Some("Lock error".to_string()),
-                image_data: None,
+                file: None,
             })
         }
     }
 }
 
 /// Process pending EDSDK events (call periodically)
-#[tauri::command]
-pub fn canon_process_events() -> bool {
+fn eds_process_events() -> bool {
     #[cfg(not(target_os = "windows"))]
     {
         return false;
@@ -1320,8 +1295,7 @@ pub fn canon_process_events() -> bool {
 }
 
 /// Start live view
-#[tauri::command]
-pub fn canon_start_live_view() -> Result<bool, String> {
+fn eds_start_live_view() -> Result<bool, String> {
     #[cfg(not(target_os = "windows"))]
     {
         return Err("Canon EDSDK is only supported on Windows".to_string());
@@ -1362,8 +1336,7 @@ pub fn canon_start_live_view() -> Result<bool, String> {
 }
 
 /// Stop live view
-#[tauri::command]
-pub fn canon_stop_live_view() -> Result<bool, String> {
+fn eds_stop_live_view() -> Result<bool, String> {
     #[cfg(not(target_os = "windows"))]
     {
         return Err("Canon EDSDK is only supported on Windows".to_string());
@@ -1403,9 +1376,8 @@ pub fn canon_stop_live_view() -> Result<bool, String> {
     }
 }
 
-/// Get a live view frame (returns base64 JPEG)
-#[tauri::command]
-pub fn canon_get_live_view_frame() -> Result<Option<LiveViewFrame>, String> {
+/// Get a live view frame (returns raw EVF JPEG bytes)
+fn eds_get_live_view_frame() -> Result<Option<Vec<u8>>, String> {
     #[cfg(not(target_os = "windows"))]
     {
         return Err("Canon EDSDK is only supported on Windows".to_string());
@@ -1481,20 +1453,18 @@ pub fn canon_get_live_view_frame() -> Result<Option<LiveViewFrame>, String> {
             }
 
             let data_slice = std::slice::from_raw_parts(data_ptr as *const u8, length as usize);
-            use base64::Engine;
-            let b64 = base64::engine::general_purpose::STANDARD.encode(data_slice);
+            let data = data_slice.to_vec();
 
             EdsRelease(evf_image);
             EdsRelease(stream);
 
-            Ok(Some(LiveViewFrame { data: b64 }))
+            Ok(Some(data))
         }
     }
 }
 
 /// Get camera property
-#[tauri::command]
-pub fn canon_get_property(property_id: u32) -> Result<Option<u32>, String> {
+fn eds_get_property(property_id: u32) -> Result<Option<u32>, String> {
     #[cfg(not(target_os = "windows"))]
     {
         let _ = property_id;
@@ -1545,8 +1515,7 @@ pub fn canon_get_property(property_id: u32) -> Result<Option<u32>, String> {
 }
 
 /// Set camera property
-#[tauri::command]
-pub fn canon_set_property(property_id: u32, value: u32) -> Result<bool, String> {
+fn eds_set_property(property_id: u32, value: u32) -> Result<bool, String> {
     #[cfg(not(target_os = "windows"))]
     {
         let _ = (property_id, value);
@@ -1585,18 +1554,6 @@ pub fn canon_set_property(property_id: u32, value: u32) -> Result<bool, String>
     }
 }
 
-/// Get battery level
-#[tauri::command]
-pub fn canon_get_battery_level() -> Result<Option<u32>, String> {
-    canon_get_property(kEdsPropID_BatteryLevel)
-}
-
-/// Get available shots
-#[tauri::command]
-pub fn canon_get_available_shots() -> Result<Option<u32>, String> {
-    canon_get_property(kEdsPropID_AvailableShots)
-}
-
 // =============================================================================
 // Movie Recording via EDSDK
 // =============================================================================
@@ -1609,8 +1566,7 @@ pub fn canon_get_available_shots() -> Result<Option<u32>, String> {
 /// 3. Switch to movie mode (MovieSelectSwON)
 /// 4. Start live view if not already active (required for movie recording)
 /// 5. Set kEdsPropID_Record = 4 (begin recording)
-#[tauri::command]
-pub fn canon_start_movie_record() -> Result<bool, String> {
+fn eds_start_movie_record() -> Result<bool, String> {
     #[cfg(not(target_os = "windows"))]
     {
         return Err("Canon EDSDK is only supported on Windows".to_string());
@@ -1777,8 +1733,7 @@ pub fn canon_start_movie_record() -> Result<bool, String> {
 /// 4. Switch back to photo mode (MovieSelectSwOFF)
 /// 5. Restore SaveTo = Host
 /// 6. Return local file path
-#[tauri::command]
-pub fn canon_stop_movie_record() -> Result<String, String> {
+fn eds_stop_movie_record() -> Result<String, String> {
     #[cfg(not(target_os = "windows"))]
     {
         return Err("Canon EDSDK is only supported on Windows".to_string());
@@ -1899,8 +1854,7 @@ pub fn canon_stop_movie_record() -> Result<String, String> {
 /// 3. Restore SaveTo = Host + capacity
 /// 4. Return immediately — IS_MOVIE_RECORDING stays true so the event
 ///    handler still catches the DirItemCreated download event later.
-#[tauri::command]
-pub fn canon_stop_movie_record_fast() -> Result<bool, String> {
+fn eds_stop_movie_record_fast() -> Result<bool, String> {
     #[cfg(not(target_os = "windows"))]
     {
         return Err("Canon EDSDK is only supported on Windows".to_string());
@@ -1998,8 +1952,7 @@ pub fn canon_stop_movie_record_fast() -> Result<bool, String> {
 ///
 /// If PressShutterButton fails (camera/firmware doesn't support photo-in-movie),
 /// falls back to stop-then-shoot: stop recording fast → TakePicture.
-#[tauri::command]
-pub fn canon_take_photo_during_recording() -> Result<CaptureResult, String> {
+fn eds_take_photo_during_recording() -> Result<CaptureResult, String> {
     #[cfg(not(target_os = "windows"))]
     {
         return Err("Canon EDSDK is only supported on Windows".to_string());
@@ -2010,14 +1963,14 @@ pub fn canon_take_photo_during_recording() -> Result<CaptureResult, String> {
         if !IS_MOVIE_RECORDING.load(Ordering::SeqCst) {
             // Not recording — fall back to normal photo capture
             info!("[Canon] Not recording, delegating to normal take_picture");
-            return canon_take_picture();
+            return eds_take_picture();
         }
 
         if !SDK_INITIALIZED.load(Ordering::SeqCst) {
             return Ok(CaptureResult {
                 success: false,
                 error: Some("SDK not initialized".to_string()),
-                image_data: None,
+                file: None,
             });
         }
 
@@ -2026,7 +1979,7 @@ pub fn canon_take_photo_during_recording() -> Result<CaptureResult, String> {
             return Ok(CaptureResult {
                 success: false,
                 error: Some("Capture already in progress".to_string()),
-                image_data: None,
+                file: None,
             });
         }
 
@@ -2112,10 +2065,10 @@ pub fn canon_take_photo_during_recording() -> Result<CaptureResult, String> {
             IS_CAPTURING.store(false, Ordering::SeqCst);
 
             // Stop recording + switch to photo mode
-            let _ = canon_stop_movie_record_fast();
+            let _ = eds_stop_movie_record_fast();
 
             // Normal photo capture
-            return canon_take_picture();
+            return eds_take_picture();
         }
 
         // 6. Pump events until photo arrives (DirItemRequestTransfer)
@@ -2199,33 +2152,12 @@ pub fn canon_take_photo_during_recording() -> Result<CaptureResult, String> {
             return Ok(CaptureResult {
                 success: false,
                 error: Some(err),
-                image_data: None,
+                file: None,
             });
         }
 
         match image_data {
-            Some(data) => {
-                use base64::Engine;
-
-                let processed = resize_captured_jpeg(
-                    &data, CAPTURE_MAX_DIMENSION, CAPTURE_JPEG_QUALITY,
-                ).unwrap_or_else(|e| {
-                    warn!("[Canon] Resize failed ({}), using original", e);
-                    data.clone()
-                });
-
-                let b64 = base64::engine::general_purpose::STANDARD.encode(&processed);
-                info!(
-                    "[Canon] Photo-during-recording success: {:.1} MB -> {:.1} MB",
-                    data.len() as f64 / 1_048_576.0,
-                    processed.len() as f64 / 1_048_576.0,
-                );
-                Ok(CaptureResult {
-                    success: true,
-                    error: None,
-                    image_data: Some(b64),
-                })
-            }
+            Some(data) => Ok(store_capture(&data)),
             None => {
                 warn!("[Canon] No photo received during recording — falling back to stop-then-shoot");
                 // Stop movie mode, take a normal photo
@@ -2251,7 +2183,7 @@ pub fn canon_take_photo_during_recording() -> Result<CaptureResult, String> {
                     // If we restore it now, the movie file download (SaveTo=Camera) might fail or get confused.
                 }
 
-                canon_take_picture()
+                eds_take_picture()
             }
         }
     }
@@ -2264,8 +2196,7 @@ pub fn canon_take_photo_during_recording() -> Result<CaptureResult, String> {
 /// and the file has been downloaded to disk.
 ///
 /// Sets IS_MOVIE_RECORDING = false when done.
-#[tauri::command]
-pub fn canon_finalize_movie_download() -> Result<String, String> {
+fn eds_finalize_movie_download() -> Result<String, String> {
     #[cfg(not(target_os = "windows"))]
     {
         return Err("Canon EDSDK is only supported on Windows".to_string());
@@ -2353,10 +2284,316 @@ pub fn canon_finalize_movie_download() -> Result<String, String> {
     }
 }
 
+/// Check if camera is currently recording a movie
+fn eds_is_movie_recording() -> bool {
+    IS_MOVIE_RECORDING.load(Ordering::SeqCst)
+}
+
+// =============================================================================
+// EDSDK Backend
+// =============================================================================
+
+/// Real Canon camera driven through EDSDK.dll
+pub struct EdsdkCamera;
+
+impl CameraBackend for EdsdkCamera {
+    fn kind(&self) -> BackendKind {
+        BackendKind::Edsdk
+    }
+
+    fn initialize(&self, app: &tauri::AppHandle) -> Result<bool, String> {
+        eds_initialize(app)
+    }
+
+    fn terminate(&self) -> Result<bool, String> {
+        eds_terminate()
+    }
+
+    fn is_initialized(&self) -> bool {
+        eds_is_initialized()
+    }
+
+    fn camera_list(&self) -> Result<Vec<CameraInfo>, String> {
+        eds_get_camera_list()
+    }
+
+    fn connect(&self, index: Option<u32>) -> Result<CameraInfo, String> {
+        eds_connect(index)
+    }
+
+    fn open_session(&self) -> Result<bool, String> {
+        eds_open_session()
+    }
+
+    fn close_session(&self) -> Result<bool, String> {
+        eds_close_session()
+    }
+
+    fn is_connected(&self) -> bool {
+        eds_is_connected()
+    }
+
+    fn take_picture(&self) -> Result<CaptureResult, String> {
+        eds_take_picture()
+    }
+
+    fn send_shutter(&self) -> Result<CaptureResult, String> {
+        eds_send_shutter()
+    }
+
+    fn capture_result(&self) -> Result<CaptureResult, String> {
+        eds_get_capture_result()
+    }
+
+    fn process_events(&self) -> bool {
+        eds_process_events()
+    }
+
+    fn start_live_view(&self) -> Result<bool, String> {
+        eds_start_live_view()
+    }
+
+    fn stop_live_view(&self) -> Result<bool, String> {
+        eds_stop_live_view()
+    }
+
+    fn live_view_frame(&self) -> Result<Option<Vec<u8>>, String> {
+        eds_get_live_view_frame()
+    }
+
+    fn get_property(&self, property_id: u32) -> Result<Option<u32>, String> {
+        eds_get_property(property_id)
+    }
+
+    fn set_property(&self, property_id: u32, value: u32) -> Result<bool, String> {
+        eds_set_property(property_id, value)
+    }
+
+    fn start_movie_record(&self) -> Result<bool, String> {
+        eds_start_movie_record()
+    }
+
+    fn stop_movie_record(&self) -> Result<String, String> {
+        eds_stop_movie_record()
+    }
+
+    fn stop_movie_record_fast(&self) -> Result<bool, String> {
+        eds_stop_movie_record_fast()
+    }
+
+    fn take_photo_during_recording(&self) -> Result<CaptureResult, String> {
+        eds_take_photo_during_recording()
+    }
+
+    fn finalize_movie_download(&self) -> Result<String, String> {
+        eds_finalize_movie_download()
+    }
+
+    fn is_movie_recording(&self) -> bool {
+        eds_is_movie_recording()
+    }
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+/// Initialize the camera SDK
+#[tauri::command]
+pub fn canon_initialize(app: tauri::AppHandle) -> Result<bool, String> {
+    backend().initialize(&app)
+}
+
+/// Terminate the camera SDK
+#[tauri::command]
+pub fn canon_terminate() -> Result<bool, String> {
+    backend().terminate()
+}
+
+/// Check if SDK is initialized
+#[tauri::command]
+pub fn canon_is_initialized() -> bool {
+    backend().is_initialized()
+}
+
+/// Get list of connected Canon cameras
+#[tauri::command]
+pub fn canon_get_camera_list() -> Result<Vec<CameraInfo>, String> {
+    backend().camera_list()
+}
+
+/// Connect to camera by index (default: 0)
+#[tauri::command]
+pub fn canon_connect(index: Option<u32>) -> Result<CameraInfo, String> {
+    backend().connect(index)
+}
+
+/// Open session with connected camera
+#[tauri::command]
+pub fn canon_open_session() -> Result<bool, String> {
+    backend().open_session()
+}
+
+/// Close session
+#[tauri::command]
+pub fn canon_close_session() -> Result<bool, String> {
+    backend().close_session()
+}
+
+/// Check if camera is connected
+#[tauri::command]
+pub fn canon_is_connected() -> bool {
+    backend().is_connected()
+}
+
+/// Take a picture (blocking — waits for image download)
+#[tauri::command]
+pub fn canon_take_picture() -> Result<CaptureResult, String> {
+    let _paused = live_view::pause_for_capture();
+    backend().take_picture()
+}
+
+/// Send shutter command (non-blocking)
+#[tauri::command]
+pub fn canon_send_shutter() -> Result<CaptureResult, String> {
+    backend().send_shutter()
+}
+
+/// Get capture result (non-blocking check)
+#[tauri::command]
+pub fn canon_get_capture_result() -> Result<CaptureResult, String> {
+    backend().capture_result()
+}
+
+/// Process pending SDK events (call periodically)
+#[tauri::command]
+pub fn canon_process_events() -> bool {
+    backend().process_events()
+}
+
+/// Start live view
+#[tauri::command]
+pub fn canon_start_live_view() -> Result<bool, String> {
+    backend().start_live_view()
+}
+
+/// Stop live view
+#[tauri::command]
+pub fn canon_stop_live_view() -> Result<bool, String> {
+    backend().stop_live_view()
+}
+
+/// Get a live view frame (returns base64 JPEG)
+#[tauri::command]
+pub fn canon_get_live_view_frame() -> Result<Option<LiveViewFrame>, String> {
+    use base64::Engine;
+
+    Ok(backend().live_view_frame()?.map(|jpeg| LiveViewFrame {
+        data: base64::engine::general_purpose::STANDARD.encode(jpeg),
+    }))
+}
+
+/// Get camera property
+#[tauri::command]
+pub fn canon_get_property(property_id: u32) -> Result<Option<u32>, String> {
+    backend().get_property(property_id)
+}
+
+/// Set camera property
+#[tauri::command]
+pub fn canon_set_property(property_id: u32, value: u32) -> Result<bool, String> {
+    backend().set_property(property_id, value)
+}
+
+/// Get battery level
+#[tauri::command]
+pub fn canon_get_battery_level() -> Result<Option<u32>, String> {
+    backend().get_property(kEdsPropID_BatteryLevel)
+}
+
+/// Get available shots
+#[tauri::command]
+pub fn canon_get_available_shots() -> Result<Option<u32>, String> {
+    backend().get_property(kEdsPropID_AvailableShots)
+}
+
+/// Start movie recording on the camera
+#[tauri::command]
+pub fn canon_start_movie_record() -> Result<bool, String> {
+    let _paused = live_view::pause_for_capture();
+    backend().start_movie_record()
+}
+
+/// Stop movie recording and return the local path of the downloaded file
+#[tauri::command]
+pub fn canon_stop_movie_record() -> Result<String, String> {
+    let _paused = live_view::pause_for_capture();
+    backend().stop_movie_record()
+}
+
+/// Stop movie recording without waiting for the file download
+#[tauri::command]
+pub fn canon_stop_movie_record_fast() -> Result<bool, String> {
+    let _paused = live_view::pause_for_capture();
+    backend().stop_movie_record_fast()
+}
+
+/// Take a photo while the camera is still recording video
+#[tauri::command]
+pub fn canon_take_photo_during_recording() -> Result<CaptureResult, String> {
+    let _paused = live_view::pause_for_capture();
+    backend().take_photo_during_recording()
+}
+
+/// Wait for the movie file to finish downloading from the camera
+#[tauri::command]
+pub fn canon_finalize_movie_download() -> Result<String, String> {
+    let _paused = live_view::pause_for_capture();
+    backend().finalize_movie_download()
+}
+
 /// Check if camera is currently recording a movie
 #[tauri::command]
 pub fn canon_is_movie_recording() -> bool {
-    IS_MOVIE_RECORDING.load(Ordering::SeqCst)
+    backend().is_movie_recording()
+}
+
+/// Get the active camera backend ("edsdk" or "simulated")
+#[tauri::command]
+pub fn canon_get_backend() -> BackendKind {
+    backend().kind()
+}
+
+/// Switch the camera backend ("edsdk" or "simulated")
+#[tauri::command]
+pub fn canon_set_backend(backend_kind: String) -> Result<BackendKind, String> {
+    let kind = BackendKind::parse(&backend_kind)
+        .ok_or_else(|| format!("Unknown camera backend: {}", backend_kind))?;
+    crate::camera::set_backend(kind)?;
+    Ok(kind)
+}
+
+/// Arm a simulated camera fault: the next `times` calls of `operation`
+/// fail with the given EDSDK error code (e.g. 0x81 = EDS_ERR_DEVICE_BUSY).
+/// Pass error_code 0 to clear. Operations: connect, open_session,
+/// take_picture, live_view, get_property, set_property, movie_record.
+#[tauri::command]
+pub fn canon_sim_inject_error(
+    operation: String,
+    error_code: u32,
+    times: Option<u32>,
+) -> Result<bool, String> {
+    let operation = crate::camera_sim::SimOperation::parse(&operation)
+        .ok_or_else(|| format!("Unknown simulator operation: {}", operation))?;
+    crate::camera_sim::inject_error(operation, error_code, times.unwrap_or(1));
+    Ok(true)
+}
+
+/// Clear every armed simulated camera fault
+#[tauri::command]
+pub fn canon_sim_clear_errors() -> bool {
+    crate::camera_sim::clear_injected_errors();
+    true
 }
 
 // Property ID constants are in edsdk_sys — re-export for frontend reference
@@ -0,0 +1,337 @@
+//! Virtual File Printer
+//!
+//! A printer backend that needs no hardware. "Bonio File Printer" and its CUT
+//! driver variant "Bonio File Printer (CUT)" show up in `get_printers` and
+//! take jobs through the same path as a real printer: CUT driver switching
+//! and driver paper selection happen exactly as on the spooler. Instead of
+//! printing, the page the driver would receive is written to a folder:
+//!
+//! - `<job>.png` (or `.pdf`) — the full page at the profile's dpi, sheet
+//!   fitted and centred the way GDI printing places it. Multi-page jobs get
+//!   `<job>-p<n>.png` per page, or one PDF with every sheet in print order.
+//! - `<job>.json` — job ticket (queue, paper, orientation, copies, collation,
+//!   page order, placement)
+//!
+//! Operators use it as a proofing mode to check `set_paper_config` offsets
+//! without wasting media. It is opt-in so a customer booth never lists a
+//! printer that prints nothing. Configured through the environment:
+//! - `BONIO_FILE_PRINTER` — `on` shows the printer; debug builds show it
+//!   unless set to `off`
+//! - `BONIO_FILE_PRINTER_DIR` — output folder (default
+//!   `Documents/Bonio Booth Proofs`)
+//! - `BONIO_FILE_PRINTER_FORMAT` — `png` (default, lossless) or `pdf`
+
+use crate::paper::{self, PaperProfile};
+use crate::printer::PrinterInfo;
+use crate::printer_backend::{self, PrintDocument, PrinterBackend, SpoolJob, SpoolState};
+use image::{imageops, Rgb, RgbImage};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::Mutex;
+use tauri::Manager;
+
+pub const FILE_PRINTER: &str = "Bonio File Printer";
+pub const FILE_PRINTER_CUT: &str = "Bonio File Printer (CUT)";
+
+/// Driver papers: (id, name, width mm, height mm), portrait as drivers list
+/// them. Modelled on the DNP driver so `driverPaper` names match.
+const PAPERS: &[(i16, &str, f64, f64)] = &[
+    (1, "PC 4x6", 101.6, 152.4),
+    (2, "PC 3.5x5", 88.9, 127.0),
+    (3, "PC 5x7", 127.0, 177.8),
+    (4, "PC 6x8", 152.4, 203.2),
+    (5, "PC 6x9", 152.4, 228.6),
+    (6, "PC 4x6 cut 2x6", 101.6, 152.4),
+];
+
+/// Default output folder, set on startup
+static OUTPUT_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
+static NEXT_JOB_ID: AtomicI32 = AtomicI32::new(1);
+
+pub struct FileBackend;
+
+pub static FILE: FileBackend = FileBackend;
+
+pub fn init(app: &tauri::AppHandle) {
+    let dir = app
+        .path()
+        .document_dir()
+        .map(|dir| dir.join("Bonio Booth Proofs"))
+        .or_else(|_| app.path().app_data_dir().map(|dir| dir.join("file_printer")))
+        .ok();
+    *OUTPUT_DIR.lock().unwrap() = dir;
+}
+
+fn enabled() -> bool {
+    match std::env::var("BONIO_FILE_PRINTER") {
+        Ok(v) if v.eq_ignore_ascii_case("on") => true,
+        Ok(v) if v.eq_ignore_ascii_case("off") => false,
+        _ => cfg!(debug_assertions),
+    }
+}
+
+fn output_dir() -> PathBuf {
+    match std::env::var("BONIO_FILE_PRINTER_DIR") {
+        Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => OUTPUT_DIR
+            .lock()
+            .unwrap()
+            .clone()
+            .unwrap_or_else(|| std::env::temp_dir().join("bonio-booth").join("file_printer")),
+    }
+}
+
+fn pdf_output() -> bool {
+    std::env::var("BONIO_FILE_PRINTER_FORMAT").is_ok_and(|v| v.eq_ignore_ascii_case("pdf"))
+}
+
+fn papers(printer_name: &str) -> Vec<(i16, String)> {
+    PAPERS
+        .iter()
+        // Only the CUT driver offers cut papers
+        .filter(|(_, name, _, _)| printer_name == FILE_PRINTER_CUT || !name.contains("cut"))
+        .map(|(id, name, _, _)| (*id, name.to_string()))
+        .collect()
+}
+
+fn info(printer_name: &str) -> PrinterInfo {
+    PrinterInfo {
+        name: printer_name.to_string(),
+        status: "Normal".to_string(),
+        is_online: true,
+        state_reasons: Vec::new(),
+        markers: Vec::new(),
+        backend: "file".to_string(),
+    }
+}
+
+// =============================================================================
+// Job Ticket
+// =============================================================================
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JobTicket<'a> {
+    job_id: i32,
+    job_name: &'a str,
+    created_at: String,
+    /// Printer the job was sent to
+    printer_name: &'a str,
+    /// Queue after CUT driver switching
+    queue: &'a str,
+    copies: u32,
+    collate: bool,
+    /// Pages (1-based) in the order the sheets come out
+    page_order: Vec<usize>,
+    sheets: u32,
+    paper_profile: &'a PaperProfile,
+    /// Selected driver paper, `None` = driver default (sheet size)
+    driver_paper: Option<TicketPaper>,
+    orientation: &'static str,
+    dpi: u32,
+    page_width_mm: f64,
+    page_height_mm: f64,
+    page_width_px: u32,
+    page_height_px: u32,
+    sheet_images: &'a [String],
+    sheet_width_px: u32,
+    sheet_height_px: u32,
+    /// Where the sheet was drawn on the page, in page pixels
+    placement: TicketPlacement,
+    outputs: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TicketPaper {
+    id: i16,
+    name: String,
+    width_mm: f64,
+    height_mm: f64,
+}
+
+#[derive(Serialize)]
+struct TicketPlacement {
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+}
+
+// =============================================================================
+// Rendering
+// =============================================================================
+
+fn write_ticket(path: &Path, ticket: &JobTicket) -> Result<(), String> {
+    let json = serde_json::to_vec_pretty(ticket).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| format!("Write {} failed: {}", path.display(), e))
+}
+
+// =============================================================================
+// Backend
+// =============================================================================
+
+impl PrinterBackend for FileBackend {
+    fn id(&self) -> &'static str {
+        "file"
+    }
+
+    fn owns(&self, printer_name: &str) -> bool {
+        enabled() && (printer_name == FILE_PRINTER || printer_name == FILE_PRINTER_CUT)
+    }
+
+    fn printers(&self) -> Result<Vec<PrinterInfo>, String> {
+        if !enabled() {
+            return Ok(Vec::new());
+        }
+        Ok(vec![info(FILE_PRINTER), info(FILE_PRINTER_CUT)])
+    }
+
+    fn status(&self, printer_name: &str) -> Result<PrinterInfo, String> {
+        Ok(info(printer_name))
+    }
+
+    fn paper_sizes(&self, printer_name: &str) -> Result<Vec<String>, String> {
+        Ok(papers(printer_name)
+            .iter()
+            .map(|(id, name)| format!("{} (id={})", name, id))
+            .collect())
+    }
+
+    fn print(&self, printer_name: &str, document: &PrintDocument) -> Result<SpoolJob, String> {
+        let profile = document.profile;
+        let queue = printer_backend::driver_for_job(printer_name, profile.cut.driver_cut, |name| self.owns(name));
+        log::info!("[Printer] Actual printer for job: '{}' (file)", queue);
+
+        // Paper and orientation as the spooler driver would get them
+        let papers = papers(&queue);
+        let selected = printer_backend::select_driver_paper(profile, &papers).and_then(|(id, _)| {
+            PAPERS.iter().find(|(paper_id, _, _, _)| paper_id == id)
+        });
+        let (paper_w, paper_h) = match selected {
+            Some((_, name, w, h)) => {
+                log::info!("[Printer] Selected paper: \"{}\"", name);
+                (*w, *h)
+            }
+            None => {
+                log::warn!("[Printer] No matching paper for '{}', using sheet size", profile.id);
+                let (w, h) = profile.sheet_pixels();
+                let to_mm = |px: u32| px as f64 * 25.4 / profile.dpi as f64;
+                (to_mm(w.min(h)), to_mm(w.max(h)))
+            }
+        };
+        let (page_w_mm, page_h_mm) = if profile.landscape { (paper_h, paper_w) } else { (paper_w, paper_h) };
+        let page_w = paper::mm_to_px(page_w_mm, profile.dpi);
+        let page_h = paper::mm_to_px(page_h_mm, profile.dpi);
+
+        // Render the pages: each sheet fitted and centred on white
+        let mut rendered = Vec::new();
+        let mut sheet_size = (0, 0);
+        let mut placement = (0, 0, 0, 0);
+        for sheet_path in document.pages {
+            let bytes = std::fs::read(sheet_path)
+                .map_err(|e| format!("Failed to open image for printing: {}", e))?;
+            let sheet = crate::orientation::decode_upright(&bytes, false)?.to_rgb8();
+            let (x, y, w, h) = printer_backend::fit_on_page(page_w as i32, page_h as i32, sheet.width(), sheet.height());
+            let mut page = RgbImage::from_pixel(page_w, page_h, Rgb([255, 255, 255]));
+            if (w as u32, h as u32) == sheet.dimensions() {
+                imageops::overlay(&mut page, &sheet, x as i64, y as i64);
+            } else {
+                let scaled = imageops::resize(&sheet, w as u32, h as u32, imageops::FilterType::CatmullRom);
+                imageops::overlay(&mut page, &scaled, x as i64, y as i64);
+            }
+            if rendered.is_empty() {
+                sheet_size = sheet.dimensions();
+                placement = (x, y, w, h);
+            }
+            rendered.push(page);
+        }
+
+        let dir = output_dir();
+        std::fs::create_dir_all(&dir).map_err(|e| format!("Create {} failed: {}", dir.display(), e))?;
+        let job_id = NEXT_JOB_ID.fetch_add(1, Ordering::Relaxed);
+        let stem = format!("{}-job{}", chrono::Local::now().format("%Y%m%d-%H%M%S"), job_id);
+        let page_order = document.page_order();
+        let mut outputs = Vec::new();
+        if pdf_output() {
+            // One document, every copy in print order
+            let path = dir.join(format!("{}.pdf", stem));
+            let pdf = crate::pdf::image_pdf(&rendered, &page_order, page_w_mm, page_h_mm)?;
+            std::fs::write(&path, pdf).map_err(|e| format!("Write {} failed: {}", path.display(), e))?;
+            outputs.push(path);
+        } else {
+            for (i, page) in rendered.iter().enumerate() {
+                let path = if rendered.len() == 1 {
+                    dir.join(format!("{}.png", stem))
+                } else {
+                    dir.join(format!("{}-p{}.png", stem, i + 1))
+                };
+                page.save(&path).map_err(|e| format!("Write {} failed: {}", path.display(), e))?;
+                outputs.push(path);
+            }
+        }
+
+        let ticket = JobTicket {
+            job_id,
+            job_name: document.job_name,
+            created_at: chrono::Local::now().to_rfc3339(),
+            printer_name,
+            queue: &queue,
+            copies: document.copies,
+            collate: document.collate,
+            page_order: page_order.iter().map(|i| i + 1).collect(),
+            sheets: document.sheets(),
+            paper_profile: profile,
+            driver_paper: selected.map(|(id, name, w, h)| TicketPaper {
+                id: *id,
+                name: name.to_string(),
+                width_mm: *w,
+                height_mm: *h,
+            }),
+            orientation: if profile.landscape { "landscape" } else { "portrait" },
+            dpi: profile.dpi,
+            page_width_mm: page_w_mm,
+            page_height_mm: page_h_mm,
+            page_width_px: page_w,
+            page_height_px: page_h,
+            sheet_images: document.pages,
+            sheet_width_px: sheet_size.0,
+            sheet_height_px: sheet_size.1,
+            placement: TicketPlacement {
+                x: placement.0,
+                y: placement.1,
+                width: placement.2,
+                height: placement.3,
+            },
+            outputs: outputs.iter().map(|p| p.to_string_lossy().to_string()).collect(),
+        };
+        write_ticket(&dir.join(format!("{}.json", stem)), &ticket)?;
+
+        log::info!(
+            "[Printer] File printer job {} ({} sheets) written to {}",
+            job_id,
+            document.sheets(),
+            outputs[0].display()
+        );
+        Ok(SpoolJob {
+            id: job_id,
+            printer_name: queue,
+            state: SpoolState::Completed,
+            reasons: Vec::new(),
+        })
+    }
+
+    fn job(&self, printer_name: &str, job_id: i32) -> Result<SpoolJob, String> {
+        if job_id <= 0 || job_id >= NEXT_JOB_ID.load(Ordering::Relaxed) {
+            return Err(format!("Job {} not found on '{}'", job_id, printer_name));
+        }
+        // Jobs are written synchronously, so any known job is done
+        Ok(SpoolJob {
+            id: job_id,
+            printer_name: printer_name.to_string(),
+            state: SpoolState::Completed,
+            reasons: Vec::new(),
+        })
+    }
+}
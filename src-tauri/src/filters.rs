@@ -0,0 +1,339 @@
+//! Filter Catalogue
+//!
+//! Lists the LUT files in the filters folder, merged with an optional
+//! `filters.json` manifest next to them:
+//!
+//! ```json
+//! {
+//!   "filters": [
+//!     {
+//!       "file": "Orange & Teal.cube",
+//!       "id": "orange-teal",
+//!       "name": { "en": "Orange & Teal", "th": "ส้มอมฟ้า" },
+//!       "order": 10,
+//!       "default_intensity": 80,
+//!       "interpolation": "tetrahedral",
+//!       "swatch": "swatches/orange-teal.jpg",
+//!       "enabled": true
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Every field except `file` is optional. `file` and `swatch` must be
+//! relative paths inside the filters folder; absolute paths and `..` are
+//! rejected and reported in `errors`. Only the top of the folder is
+//! scanned; a filter in a subfolder is listed through its manifest entry
+//! (`"file": "looks/warm.cube"`). Files without a manifest entry are
+//! listed after the manifest ones using their file stem as the name. Files
+//! that fail to parse, and manifest entries pointing at missing or non-LUT
+//! files, are returned in `errors` instead of being silently dropped.
+
+use crate::lut;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+const MANIFEST_FILE: &str = "filters.json";
+const DEFAULT_INTENSITY: u8 = 100;
+/// Sort position for files without a manifest entry (after all listed ones)
+const UNLISTED_ORDER: i32 = i32::MAX;
+
+// =============================================================================
+// Manifest
+// =============================================================================
+
+#[derive(Debug, Deserialize, Default)]
+struct FilterManifest {
+    #[serde(default)]
+    filters: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct LocalizedName {
+    en: Option<String>,
+    th: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ManifestEntry {
+    /// LUT file name, relative to the filters folder
+    file: String,
+    id: Option<String>,
+    #[serde(default)]
+    name: LocalizedName,
+    order: Option<i32>,
+    default_intensity: Option<u8>,
+    /// "trilinear" or "tetrahedral"; unset uses the LUT default
+    interpolation: Option<lut::Interpolation>,
+    /// Preview image, relative to the filters folder
+    swatch: Option<String>,
+    #[serde(default = "default_enabled")]
+    enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FilterInfo {
+    pub id: String,
+    /// Display name (English, falling back to the file stem)
+    pub name: String,
+    pub name_th: Option<String>,
+    pub file_path: String,
+    /// "none", "cube_3d", "cube_1d", "cube_1d_3d" or "hald_clut"
+    pub kind: String,
+    pub order: i32,
+    /// 0–100
+    pub default_intensity: u8,
+    /// Pass back to `apply_lut_filter`; `None` = default interpolation
+    pub interpolation: Option<lut::Interpolation>,
+    /// Absolute path of the preview swatch, if one exists
+    pub swatch_path: Option<String>,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct FilterLoadError {
+    /// File name relative to the filters folder
+    pub file: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AvailableFilters {
+    pub filters: Vec<FilterInfo>,
+    pub errors: Vec<FilterLoadError>,
+}
+
+/// Whether a file in the filters folder looks like a LUT we can load
+fn is_filter_file(path: &Path) -> bool {
+    match path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .as_deref()
+    {
+        Some("cube") => true,
+        // Only Hald-sized PNGs — the folder may also hold swatch images
+        Some("png") => lut::is_hald_png(path),
+        _ => false,
+    }
+}
+
+/// URL-friendly id from a file stem: "Orange & Teal" → "orange-teal"
+fn slugify(stem: &str) -> String {
+    let mut slug = String::new();
+    for c in stem.chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+/// Whether a manifest path stays inside the filters folder: relative, no `..`
+pub(crate) fn is_contained_path(relative: &str) -> bool {
+    !relative.is_empty()
+        && Path::new(relative)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+/// A contained manifest path without `.` parts (`./looks/warm.cube` →
+/// `looks/warm.cube`), to match it against files found in the folder
+fn normalized(relative: &str) -> PathBuf {
+    Path::new(relative)
+        .components()
+        .filter(|c| matches!(c, Component::Normal(_)))
+        .collect()
+}
+
+fn read_manifest(dir: &Path, errors: &mut Vec<FilterLoadError>) -> FilterManifest {
+    let path = dir.join(MANIFEST_FILE);
+    if !path.exists() {
+        return FilterManifest::default();
+    }
+    let parsed = fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|content| serde_json::from_str(&content).map_err(|e| e.to_string()));
+    match parsed {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            log::warn!("[Filters] Invalid {}: {}", MANIFEST_FILE, e);
+            errors.push(FilterLoadError {
+                file: MANIFEST_FILE.to_string(),
+                error: e,
+            });
+            FilterManifest::default()
+        }
+    }
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+/// List filters in `filters_dir`. Disabled manifest entries are left out
+/// unless `include_disabled` is set (e.g. for an admin screen).
+#[tauri::command]
+pub async fn get_available_filters(
+    filters_dir: String,
+    include_disabled: Option<bool>,
+) -> Result<AvailableFilters, String> {
+    let dir = Path::new(&filters_dir);
+    if !dir.exists() {
+        return Err("Filters directory not found".to_string());
+    }
+
+    let mut errors = Vec::new();
+    let manifest = read_manifest(dir, &mut errors);
+    let mut entries: HashMap<PathBuf, ManifestEntry> = HashMap::new();
+    for mut entry in manifest.filters {
+        if !is_contained_path(&entry.file) {
+            errors.push(FilterLoadError {
+                file: entry.file.clone(),
+                error: "Path must be relative to the filters folder".to_string(),
+            });
+            continue;
+        }
+        if let Some(swatch) = entry.swatch.take() {
+            if is_contained_path(&swatch) {
+                entry.swatch = Some(swatch);
+            } else {
+                errors.push(FilterLoadError {
+                    file: swatch,
+                    error: "Swatch path must be relative to the filters folder".to_string(),
+                });
+            }
+        }
+        if !dir.join(&entry.file).exists() {
+            errors.push(FilterLoadError {
+                file: entry.file.clone(),
+                error: "Listed in manifest but file not found".to_string(),
+            });
+            continue;
+        }
+        entries.insert(normalized(&entry.file), entry);
+    }
+
+    // Filter files at the top of the folder, then manifest entries in
+    // subfolders (`looks/warm.cube`), which aren't scanned otherwise
+    let mut files: Vec<(PathBuf, PathBuf)> = Vec::new();
+    let dir_entries = fs::read_dir(dir).map_err(|e| format!("Read dir error: {}", e))?;
+    for dir_entry in dir_entries {
+        let dir_entry = dir_entry.map_err(|e| format!("Entry error: {}", e))?;
+        let file_path = dir_entry.path();
+        if is_filter_file(&file_path) {
+            files.push((PathBuf::from(dir_entry.file_name()), file_path));
+        }
+    }
+    for relative in entries.keys() {
+        if files.iter().any(|(found, _)| found == relative) {
+            continue;
+        }
+        let file_path = dir.join(relative);
+        if is_filter_file(&file_path) {
+            files.push((relative.clone(), file_path));
+        } else {
+            errors.push(FilterLoadError {
+                file: relative.to_string_lossy().to_string(),
+                error: "Listed in manifest but not a .cube or Hald PNG file".to_string(),
+            });
+        }
+    }
+
+    let mut filters = Vec::new();
+    for (relative, file_path) in files {
+        let file_name = relative.to_string_lossy().to_string();
+        let file_path_str = file_path.to_string_lossy().to_string();
+
+        let lut = match lut::load(&file_path_str) {
+            Ok(lut) => lut,
+            Err(e) => {
+                log::warn!("[Filters] Skipping {}: {}", file_name, e);
+                errors.push(FilterLoadError {
+                    file: file_name,
+                    error: e,
+                });
+                continue;
+            }
+        };
+
+        let stem = file_path
+            .file_stem()
+            .and_then(|n| n.to_str())
+            .unwrap_or("Unknown")
+            .to_string();
+        let entry = entries.remove(&relative);
+        let swatch_path = entry
+            .as_ref()
+            .and_then(|e| e.swatch.as_ref())
+            .map(|swatch| dir.join(swatch))
+            .filter(|swatch| swatch.exists())
+            .map(|swatch| swatch.to_string_lossy().to_string());
+
+        let info = match entry {
+            Some(entry) => FilterInfo {
+                id: entry.id.unwrap_or_else(|| slugify(&stem)),
+                name: entry.name.en.unwrap_or_else(|| stem.clone()),
+                name_th: entry.name.th,
+                file_path: file_path_str,
+                kind: lut.lut().kind.as_str().to_string(),
+                order: entry.order.unwrap_or(UNLISTED_ORDER - 1),
+                default_intensity: entry
+                    .default_intensity
+                    .unwrap_or(DEFAULT_INTENSITY)
+                    .min(100),
+                interpolation: entry.interpolation,
+                swatch_path,
+                enabled: entry.enabled,
+            },
+            None => FilterInfo {
+                id: slugify(&stem),
+                name: stem,
+                name_th: None,
+                file_path: file_path_str,
+                kind: lut.lut().kind.as_str().to_string(),
+                order: UNLISTED_ORDER,
+                default_intensity: DEFAULT_INTENSITY,
+                interpolation: None,
+                swatch_path: None,
+                enabled: true,
+            },
+        };
+        filters.push(info);
+    }
+
+    if !include_disabled.unwrap_or(false) {
+        filters.retain(|f| f.enabled);
+    }
+    filters.sort_by(|a, b| a.order.cmp(&b.order).then_with(|| a.name.cmp(&b.name)));
+
+    filters.insert(
+        0,
+        FilterInfo {
+            id: "none".to_string(),
+            name: "No Filter".to_string(),
+            name_th: Some("ไม่ใช้ฟิลเตอร์".to_string()),
+            file_path: String::new(),
+            kind: "none".to_string(),
+            order: i32::MIN,
+            default_intensity: 0,
+            interpolation: None,
+            swatch_path: None,
+            enabled: true,
+        },
+    );
+
+    Ok(AvailableFilters { filters, errors })
+}
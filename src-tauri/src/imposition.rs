@@ -0,0 +1,269 @@
+//! Print Imposition
+//!
+//! Lays one composed print out on a full sheet according to its paper
+//! profile's `cut` layout (see `paper.rs`):
+//!
+//! - `columns` × `rows` copies (2-up strips, wallets, sticker sheets)
+//! - `gutterMm` between neighbouring copies
+//! - `rotate` turns every copy on the sheet (e.g. portrait wallets on a
+//!   landscape sheet)
+//! - `cutMarks` draws crop marks in the sheet margin, in line with every
+//!   copy edge
+//!
+//! The grid is centred on the sheet. When it fills the sheet exactly, the
+//! bleed is covered by enlarging the whole grid (borderless printing);
+//! otherwise the bleed is left white.
+
+use crate::orientation;
+use crate::paper::{self, mm_to_px, PaperProfile};
+use crate::workdir;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use image::{DynamicImage, Rgba, RgbaImage};
+use serde::Serialize;
+
+/// Gap between the copies' edge and the start of a crop mark
+const MARK_OFFSET_MM: f64 = 1.5;
+const MARK_LENGTH_MM: f64 = 4.0;
+const MARK_WIDTH_MM: f64 = 0.1;
+/// Longest edge of preview images
+const DEFAULT_PREVIEW_SIZE: u32 = 800;
+
+const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+const BLACK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+
+/// Where a copy landed on the sheet, in sheet pixels
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Placement {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Copy rectangles for `profile`, in reading order
+pub fn placements(profile: &PaperProfile) -> Vec<Placement> {
+    let (sheet_w, sheet_h) = profile.sheet_pixels();
+    let (grid_w, grid_h) = profile.grid_pixels();
+    let (cell_w, cell_h) = profile.cell_pixels();
+    let gutter = profile.gutter_pixels();
+    let origin_x = sheet_w.saturating_sub(grid_w) / 2;
+    let origin_y = sheet_h.saturating_sub(grid_h) / 2;
+
+    let mut cells = Vec::with_capacity(profile.copies_per_sheet() as usize);
+    for row in 0..profile.cut.rows {
+        for col in 0..profile.cut.columns {
+            cells.push(Placement {
+                x: origin_x + col * (cell_w + gutter),
+                y: origin_y + row * (cell_h + gutter),
+                width: cell_w,
+                height: cell_h,
+            });
+        }
+    }
+    cells
+}
+
+/// Whether the grid covers the whole sheet except the bleed
+fn fills_sheet(profile: &PaperProfile) -> bool {
+    let (sheet_w, sheet_h) = profile.sheet_pixels();
+    let (grid_w, grid_h) = profile.grid_pixels();
+    let bleed = if profile.bleed_mm > 0.0 { mm_to_px(profile.bleed_mm, profile.dpi) * 2 } else { 0 };
+    grid_w + bleed + 1 >= sheet_w && grid_h + bleed + 1 >= sheet_h
+}
+
+/// Lay `img` out on a full sheet at the profile's DPI
+pub fn impose(img: &DynamicImage, profile: &PaperProfile) -> DynamicImage {
+    let (print_w, print_h) = profile.print_pixels();
+    let copy = img.resize_to_fill(print_w, print_h, image::imageops::FilterType::Lanczos3);
+    let copy = orientation::rotate(copy, profile.cut.rotate).to_rgba8();
+
+    let (sheet_w, sheet_h) = profile.sheet_pixels();
+    let cells = placements(profile);
+
+    if fills_sheet(profile) {
+        // Borderless: build the bare grid, then stretch it over the bleed
+        let (grid_w, grid_h) = profile.grid_pixels();
+        let (origin_x, origin_y) = (cells[0].x, cells[0].y);
+        let mut grid = RgbaImage::from_pixel(grid_w, grid_h, WHITE);
+        for cell in &cells {
+            image::imageops::overlay(&mut grid, &copy, (cell.x - origin_x) as i64, (cell.y - origin_y) as i64);
+        }
+        let grid = DynamicImage::ImageRgba8(grid);
+        if (grid_w, grid_h) == (sheet_w, sheet_h) {
+            return grid;
+        }
+        return grid.resize_to_fill(sheet_w, sheet_h, image::imageops::FilterType::Lanczos3);
+    }
+
+    let mut sheet = RgbaImage::from_pixel(sheet_w, sheet_h, WHITE);
+    for cell in &cells {
+        image::imageops::overlay(&mut sheet, &copy, cell.x as i64, cell.y as i64);
+    }
+    if profile.cut.cut_marks {
+        draw_cut_marks(&mut sheet, profile, &cells);
+    }
+    DynamicImage::ImageRgba8(sheet)
+}
+
+fn fill_rect(img: &mut RgbaImage, x: u32, y: u32, w: u32, h: u32) {
+    for py in y..(y + h).min(img.height()) {
+        for px in x..(x + w).min(img.width()) {
+            img.put_pixel(px, py, BLACK);
+        }
+    }
+}
+
+/// Short lines in the margin around the grid, in line with every cut
+fn draw_cut_marks(sheet: &mut RgbaImage, profile: &PaperProfile, cells: &[Placement]) {
+    let (sheet_w, sheet_h) = sheet.dimensions();
+    let offset = mm_to_px(MARK_OFFSET_MM, profile.dpi);
+    let length = mm_to_px(MARK_LENGTH_MM, profile.dpi);
+    let width = mm_to_px(MARK_WIDTH_MM, profile.dpi);
+
+    let left = cells.iter().map(|c| c.x).min().unwrap_or(0);
+    let top = cells.iter().map(|c| c.y).min().unwrap_or(0);
+    let right = cells.iter().map(|c| c.x + c.width).max().unwrap_or(sheet_w);
+    let bottom = cells.iter().map(|c| c.y + c.height).max().unwrap_or(sheet_h);
+
+    // Marks are shortened to the margin, and skipped where there is none
+    let top_len = top.saturating_sub(offset).min(length);
+    let bottom_len = (sheet_h - bottom).saturating_sub(offset).min(length);
+    let left_len = left.saturating_sub(offset).min(length);
+    let right_len = (sheet_w - right).saturating_sub(offset).min(length);
+    if top_len + bottom_len + left_len + right_len == 0 {
+        log::warn!("[Imposition] '{}': no margin for cut marks", profile.id);
+        return;
+    }
+
+    let mut xs: Vec<u32> = cells.iter().flat_map(|c| [c.x, c.x + c.width]).collect();
+    let mut ys: Vec<u32> = cells.iter().flat_map(|c| [c.y, c.y + c.height]).collect();
+    xs.sort_unstable();
+    xs.dedup();
+    ys.sort_unstable();
+    ys.dedup();
+
+    for &x in &xs {
+        let x = x.saturating_sub(width / 2);
+        if top_len > 0 {
+            fill_rect(sheet, x, top - offset - top_len, width, top_len);
+        }
+        if bottom_len > 0 {
+            fill_rect(sheet, x, bottom + offset, width, bottom_len);
+        }
+    }
+    for &y in &ys {
+        let y = y.saturating_sub(width / 2);
+        if left_len > 0 {
+            fill_rect(sheet, left - offset - left_len, y, left_len, width);
+        }
+        if right_len > 0 {
+            fill_rect(sheet, right + offset, y, right_len, width);
+        }
+    }
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImpositionPreview {
+    pub paper_profile: String,
+    /// What was laid out: "image", "lastPrint" or "testChart"
+    pub source: &'static str,
+    /// JPEG data URL of the whole sheet
+    pub image: String,
+    /// Full-resolution sheet size
+    pub sheet_width: u32,
+    pub sheet_height: u32,
+    pub copies: u32,
+    /// Copy rectangles in full-resolution sheet pixels
+    pub placements: Vec<Placement>,
+}
+
+/// The newest composed print whose aspect matches `profile`
+fn latest_composed(profile: &PaperProfile) -> Option<DynamicImage> {
+    workdir::recent_files("composed")
+        .into_iter()
+        .find(|path| {
+            image::image_dimensions(path)
+                .map(|(w, h)| (w as f64 / h.max(1) as f64 / profile.aspect()).ln().abs() < 0.02)
+                .unwrap_or(false)
+        })
+        .and_then(|path| workdir::load_input(&path.to_string_lossy()).ok())
+}
+
+/// Preview the sheet `print_photo` would print. `image` is a session file id,
+/// path or data URL; without it the last composed print for `paper_profile`
+/// is used when `last_print` is set, otherwise the profile's test chart.
+/// `paper_profile` defaults to the best match for the image's aspect ratio.
+/// `scale` / offsets are the paper position settings, applied as printing
+/// applies them.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn preview_imposition(
+    image: Option<String>,
+    paper_profile: Option<String>,
+    max_size: Option<u32>,
+    last_print: Option<bool>,
+    scale: Option<f64>,
+    vertical_offset: Option<f64>,
+    horizontal_offset: Option<f64>,
+) -> Result<ImpositionPreview, String> {
+    let (img, profile, source) = match image {
+        Some(image) => {
+            let img = workdir::load_input(&image)?;
+            let profile = paper::resolve(paper_profile.as_deref(), img.width(), img.height())?;
+            (img, profile, "image")
+        }
+        None => {
+            let id = paper_profile.ok_or("paper_profile or image is required")?;
+            let profile = paper::get(&id)?;
+            match last_print.unwrap_or(false).then(|| latest_composed(&profile)).flatten() {
+                Some(img) => (img, profile, "lastPrint"),
+                None => (paper::test_chart(&profile), profile, "testChart"),
+            }
+        }
+    };
+
+    // Render at a lower DPI so the sheet's long edge is about `max_size`
+    let (sheet_w, sheet_h) = profile.sheet_pixels();
+    let target = max_size.unwrap_or(DEFAULT_PREVIEW_SIZE).max(64);
+    let mut small = profile.clone();
+    small.dpi = ((profile.dpi as u64 * target as u64) / sheet_w.max(sheet_h) as u64).max(10) as u32;
+
+    // Shrink to about the cell size first; the offsets are in image pixels
+    // so they shrink with it
+    let (cell_w, cell_h) = small.cell_pixels();
+    let ratio = (cell_w.max(cell_h) as f64 / img.width().max(img.height()) as f64).min(1.0);
+    let img = if ratio < 1.0 {
+        let (w, h) = ((img.width() as f64 * ratio).round() as u32, (img.height() as f64 * ratio).round() as u32);
+        img.resize_exact(w.max(1), h.max(1), image::imageops::FilterType::Triangle)
+    } else {
+        img
+    };
+    let img = crate::printer::position_content(
+        img,
+        scale.unwrap_or(100.0),
+        vertical_offset.unwrap_or(0.0) * ratio,
+        horizontal_offset.unwrap_or(0.0) * ratio,
+    );
+    let sheet = impose(&img, &small);
+
+    let mut buf = Vec::new();
+    let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buf, 80);
+    sheet
+        .write_with_encoder(encoder)
+        .map_err(|e| format!("Encode error: {}", e))?;
+
+    Ok(ImpositionPreview {
+        paper_profile: profile.id.clone(),
+        source,
+        image: format!("data:image/jpeg;base64,{}", STANDARD.encode(&buf)),
+        sheet_width: sheet_w,
+        sheet_height: sheet_h,
+        copies: profile.copies_per_sheet(),
+        placements: placements(&profile),
+    })
+}
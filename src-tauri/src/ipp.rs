@@ -0,0 +1,664 @@
+//! Minimal IPP/1.1 client (RFC 8010 / 8011)
+//!
+//! Just enough of the protocol to drive CUPS and IPP Everywhere printers
+//! (including an `ippeveprinter` stand-in): encode a request, POST it over
+//! plain HTTP and decode the response. Used by the CUPS/IPP printer backend
+//! in `printer_backend.rs`; kept free of app types so
+//! `examples/test_ipp.rs` can exercise it on its own.
+//!
+//! Only `ipp://` / `http://` URIs are supported (no TLS) — the booth talks to
+//! a local cupsd or printer.
+
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+const DEFAULT_PORT: u16 = 631;
+/// An unreachable printer fails fast; a busy one gets longer to answer
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const TIMEOUT: Duration = Duration::from_secs(20);
+const USER_NAME: &str = "bonio-booth";
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+pub mod op {
+    pub const PRINT_JOB: u16 = 0x0002;
+    pub const VALIDATE_JOB: u16 = 0x0004;
+    pub const CANCEL_JOB: u16 = 0x0008;
+    pub const GET_JOB_ATTRIBUTES: u16 = 0x0009;
+    pub const GET_PRINTER_ATTRIBUTES: u16 = 0x000B;
+    pub const CUPS_GET_PRINTERS: u16 = 0x4002;
+}
+
+/// Delimiter tags — start of an attribute group
+pub mod group {
+    pub const OPERATION: u8 = 0x01;
+    pub const JOB: u8 = 0x02;
+    pub const END: u8 = 0x03;
+    pub const PRINTER: u8 = 0x04;
+    pub const UNSUPPORTED: u8 = 0x05;
+}
+
+/// Value tags
+pub mod tag {
+    pub const UNSUPPORTED: u8 = 0x10;
+    pub const UNKNOWN: u8 = 0x12;
+    pub const NO_VALUE: u8 = 0x13;
+    pub const INTEGER: u8 = 0x21;
+    pub const BOOLEAN: u8 = 0x22;
+    pub const ENUM: u8 = 0x23;
+    pub const OCTET_STRING: u8 = 0x30;
+    pub const DATE_TIME: u8 = 0x31;
+    pub const RESOLUTION: u8 = 0x32;
+    pub const RANGE: u8 = 0x33;
+    pub const BEGIN_COLLECTION: u8 = 0x34;
+    pub const TEXT_WITH_LANGUAGE: u8 = 0x35;
+    pub const NAME_WITH_LANGUAGE: u8 = 0x36;
+    pub const END_COLLECTION: u8 = 0x37;
+    pub const TEXT: u8 = 0x41;
+    pub const NAME: u8 = 0x42;
+    pub const KEYWORD: u8 = 0x44;
+    pub const URI: u8 = 0x45;
+    pub const CHARSET: u8 = 0x47;
+    pub const LANGUAGE: u8 = 0x48;
+    pub const MIME_TYPE: u8 = 0x49;
+    pub const MEMBER_NAME: u8 = 0x4A;
+}
+
+/// `printer-state` values
+pub mod printer_state {
+    pub const IDLE: i32 = 3;
+    pub const PROCESSING: i32 = 4;
+    pub const STOPPED: i32 = 5;
+}
+
+/// `job-state` values
+pub mod job_state {
+    pub const PENDING: i32 = 3;
+    pub const PENDING_HELD: i32 = 4;
+    pub const PROCESSING: i32 = 5;
+    pub const PROCESSING_STOPPED: i32 = 6;
+    pub const CANCELED: i32 = 7;
+    pub const ABORTED: i32 = 8;
+    pub const COMPLETED: i32 = 9;
+}
+
+// =============================================================================
+// Messages
+// =============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Integer(i32),
+    Boolean(bool),
+    Enum(i32),
+    /// text, name, keyword, uri, charset, language, mimeMediaType, …
+    String(String),
+    Octets(Vec<u8>),
+    Range(i32, i32),
+    Resolution(i32, i32, u8),
+    /// Collections and out-of-band values are kept raw
+    Raw(Vec<u8>),
+}
+
+impl Value {
+    pub fn as_int(&self) -> Option<i32> {
+        match self {
+            Value::Integer(v) | Value::Enum(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attribute {
+    pub name: String,
+    /// Value tag of the values
+    pub tag: u8,
+    pub values: Vec<Value>,
+}
+
+impl Attribute {
+    pub fn new(name: &str, tag: u8, values: Vec<Value>) -> Self {
+        Attribute { name: name.to_string(), tag, values }
+    }
+
+    pub fn string(name: &str, tag: u8, value: &str) -> Self {
+        Self::new(name, tag, vec![Value::String(value.to_string())])
+    }
+
+    pub fn keyword(name: &str, value: &str) -> Self {
+        Self::string(name, tag::KEYWORD, value)
+    }
+
+    pub fn keywords(name: &str, values: &[&str]) -> Self {
+        Self::new(name, tag::KEYWORD, values.iter().map(|v| Value::String(v.to_string())).collect())
+    }
+
+    pub fn integer(name: &str, value: i32) -> Self {
+        Self::new(name, tag::INTEGER, vec![Value::Integer(value)])
+    }
+
+    pub fn enumeration(name: &str, value: i32) -> Self {
+        Self::new(name, tag::ENUM, vec![Value::Enum(value)])
+    }
+
+    pub fn boolean(name: &str, value: bool) -> Self {
+        Self::new(name, tag::BOOLEAN, vec![Value::Boolean(value)])
+    }
+
+    pub fn first_int(&self) -> Option<i32> {
+        self.values.first().and_then(Value::as_int)
+    }
+
+    pub fn first_str(&self) -> Option<&str> {
+        self.values.first().and_then(Value::as_str)
+    }
+
+    pub fn strings(&self) -> Vec<String> {
+        self.values.iter().filter_map(|v| v.as_str().map(str::to_string)).collect()
+    }
+
+    pub fn ints(&self) -> Vec<i32> {
+        self.values.iter().filter_map(Value::as_int).collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Group {
+    pub tag: u8,
+    pub attributes: Vec<Attribute>,
+}
+
+impl Group {
+    pub fn get(&self, name: &str) -> Option<&Attribute> {
+        self.attributes.iter().find(|a| a.name == name)
+    }
+}
+
+/// A request or response. `code` is the operation id in requests and the
+/// status code in responses.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    pub version: (u8, u8),
+    pub code: u16,
+    pub request_id: u32,
+    pub groups: Vec<Group>,
+    /// Document data following the attributes
+    pub data: Vec<u8>,
+}
+
+impl Message {
+    /// Request with the mandatory operation attributes (RFC 8011 §4.1.4)
+    pub fn request(operation: u16, request_id: u32, printer_uri: &str) -> Self {
+        Message {
+            version: (1, 1),
+            code: operation,
+            request_id,
+            groups: vec![Group {
+                tag: group::OPERATION,
+                attributes: vec![
+                    Attribute::string("attributes-charset", tag::CHARSET, "utf-8"),
+                    Attribute::string("attributes-natural-language", tag::LANGUAGE, "en"),
+                    Attribute::string("printer-uri", tag::URI, printer_uri),
+                    Attribute::string("requesting-user-name", tag::NAME, USER_NAME),
+                ],
+            }],
+            data: Vec::new(),
+        }
+    }
+
+    /// Append to the last group with `group_tag`, creating it if needed
+    pub fn add(&mut self, group_tag: u8, attribute: Attribute) {
+        match self.groups.iter_mut().rev().find(|g| g.tag == group_tag) {
+            Some(group) => group.attributes.push(attribute),
+            None => self.groups.push(Group { tag: group_tag, attributes: vec![attribute] }),
+        }
+    }
+
+    /// First attribute called `name` in any group
+    pub fn get(&self, name: &str) -> Option<&Attribute> {
+        self.groups.iter().find_map(|g| g.get(name))
+    }
+
+    pub fn groups_of(&self, group_tag: u8) -> impl Iterator<Item = &Group> {
+        self.groups.iter().filter(move |g| g.tag == group_tag)
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.code < 0x0100
+    }
+
+    pub fn status_message(&self) -> String {
+        let message = self.get("status-message").and_then(|a| a.first_str()).unwrap_or("");
+        format!("{} (0x{:04x}) {}", status_name(self.code), self.code, message).trim_end().to_string()
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(256 + self.data.len());
+        out.extend([self.version.0, self.version.1]);
+        out.extend(self.code.to_be_bytes());
+        out.extend(self.request_id.to_be_bytes());
+        for group in &self.groups {
+            out.push(group.tag);
+            for attribute in &group.attributes {
+                for (i, value) in attribute.values.iter().enumerate() {
+                    // Additional values of a set have an empty name
+                    let name = if i == 0 { attribute.name.as_str() } else { "" };
+                    out.push(attribute.tag);
+                    out.extend((name.len() as u16).to_be_bytes());
+                    out.extend(name.as_bytes());
+                    let bytes = encode_value(value);
+                    out.extend((bytes.len() as u16).to_be_bytes());
+                    out.extend(bytes);
+                }
+            }
+        }
+        out.push(group::END);
+        out.extend(&self.data);
+        out
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, String> {
+        let mut r = Reader { bytes, pos: 0 };
+        let version = (r.u8()?, r.u8()?);
+        let code = r.u16()?;
+        let request_id = r.u32()?;
+        let mut groups: Vec<Group> = Vec::new();
+
+        loop {
+            let tag = r.u8()?;
+            if tag == group::END {
+                break;
+            }
+            if tag <= 0x0F {
+                groups.push(Group { tag, attributes: Vec::new() });
+                continue;
+            }
+            let name_len = r.u16()? as usize;
+            let name = String::from_utf8_lossy(r.take(name_len)?).to_string();
+            let value_len = r.u16()? as usize;
+            let raw = r.take(value_len)?;
+
+            let group = groups.last_mut().ok_or("IPP: attribute outside of a group")?;
+            if tag == tag::BEGIN_COLLECTION {
+                // Skip the members, keep the collection as one raw value
+                let start = r.pos;
+                skip_collection(&mut r)?;
+                let value = Value::Raw(bytes[start..r.pos].to_vec());
+                push_value(group, &name, tag, value);
+                continue;
+            }
+            let value = decode_value(tag, raw);
+            push_value(group, &name, tag, value);
+        }
+
+        Ok(Message {
+            version,
+            code,
+            request_id,
+            groups,
+            data: bytes[r.pos..].to_vec(),
+        })
+    }
+}
+
+fn push_value(group: &mut Group, name: &str, tag: u8, value: Value) {
+    if name.is_empty() {
+        if let Some(last) = group.attributes.last_mut() {
+            last.values.push(value);
+            return;
+        }
+    }
+    group.attributes.push(Attribute::new(name, tag, vec![value]));
+}
+
+fn encode_value(value: &Value) -> Vec<u8> {
+    match value {
+        Value::Integer(v) | Value::Enum(v) => v.to_be_bytes().to_vec(),
+        Value::Boolean(v) => vec![*v as u8],
+        Value::String(s) => s.as_bytes().to_vec(),
+        Value::Octets(b) | Value::Raw(b) => b.clone(),
+        Value::Range(lo, hi) => [lo.to_be_bytes(), hi.to_be_bytes()].concat(),
+        Value::Resolution(x, y, units) => {
+            let mut out = [x.to_be_bytes(), y.to_be_bytes()].concat();
+            out.push(*units);
+            out
+        }
+    }
+}
+
+fn decode_value(value_tag: u8, raw: &[u8]) -> Value {
+    let int = |b: &[u8]| i32::from_be_bytes([b[0], b[1], b[2], b[3]]);
+    match value_tag {
+        tag::INTEGER if raw.len() == 4 => Value::Integer(int(raw)),
+        tag::ENUM if raw.len() == 4 => Value::Enum(int(raw)),
+        tag::BOOLEAN if raw.len() == 1 => Value::Boolean(raw[0] != 0),
+        tag::RANGE if raw.len() == 8 => Value::Range(int(&raw[..4]), int(&raw[4..])),
+        tag::RESOLUTION if raw.len() == 9 => Value::Resolution(int(&raw[..4]), int(&raw[4..8]), raw[8]),
+        tag::TEXT_WITH_LANGUAGE | tag::NAME_WITH_LANGUAGE if raw.len() >= 4 => {
+            // language length + language + text length + text
+            let lang_len = u16::from_be_bytes([raw[0], raw[1]]) as usize;
+            let text = raw.get(2 + lang_len + 2..).unwrap_or_default();
+            Value::String(String::from_utf8_lossy(text).to_string())
+        }
+        0x41..=0x4F => Value::String(String::from_utf8_lossy(raw).to_string()),
+        tag::OCTET_STRING => Value::Octets(raw.to_vec()),
+        _ => Value::Raw(raw.to_vec()),
+    }
+}
+
+/// Advance past a collection's members up to and including its end tag
+fn skip_collection(r: &mut Reader) -> Result<(), String> {
+    let mut depth = 1;
+    while depth > 0 {
+        let tag = r.u8()?;
+        let name_len = r.u16()? as usize;
+        r.take(name_len)?;
+        let value_len = r.u16()? as usize;
+        r.take(value_len)?;
+        match tag {
+            tag::BEGIN_COLLECTION => depth += 1,
+            tag::END_COLLECTION => depth -= 1,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let end = self.pos + n;
+        let slice = self.bytes.get(self.pos..end).ok_or("IPP: truncated message")?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        let b = self.take(2)?;
+        Ok(u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let b = self.take(4)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+}
+
+pub fn status_name(code: u16) -> &'static str {
+    match code {
+        0x0000 => "successful-ok",
+        0x0001 => "successful-ok-ignored-or-substituted-attributes",
+        0x0002 => "successful-ok-conflicting-attributes",
+        0x0400 => "client-error-bad-request",
+        0x0401 => "client-error-forbidden",
+        0x0402 => "client-error-not-authenticated",
+        0x0403 => "client-error-not-authorized",
+        0x0404 => "client-error-not-possible",
+        0x0405 => "client-error-timeout",
+        0x0406 => "client-error-not-found",
+        0x040A => "client-error-document-format-not-supported",
+        0x040B => "client-error-attributes-or-values-not-supported",
+        0x0500 => "server-error-internal-error",
+        0x0501 => "server-error-operation-not-supported",
+        0x0506 => "server-error-not-accepting-jobs",
+        0x0507 => "server-error-busy",
+        _ if code < 0x0100 => "successful-ok",
+        _ if code < 0x0500 => "client-error",
+        _ => "server-error",
+    }
+}
+
+// =============================================================================
+// Client
+// =============================================================================
+
+/// A printer (or cupsd) reachable at an `ipp://host[:port]/path` URI
+#[derive(Debug, Clone)]
+pub struct Client {
+    pub uri: String,
+    host: String,
+    port: u16,
+    path: String,
+}
+
+static NEXT_REQUEST_ID: AtomicU32 = AtomicU32::new(1);
+
+impl Client {
+    pub fn new(uri: &str) -> Result<Self, String> {
+        let rest = uri
+            .strip_prefix("ipp://")
+            .or_else(|| uri.strip_prefix("http://"))
+            .ok_or_else(|| format!("Unsupported printer URI '{}' (use ipp://)", uri))?;
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) if !port.contains(']') => {
+                let port = port.parse().map_err(|_| format!("Invalid port in '{}'", uri))?;
+                (host, port)
+            }
+            _ => (authority, DEFAULT_PORT),
+        };
+        if host.is_empty() {
+            return Err(format!("Missing host in '{}'", uri));
+        }
+        Ok(Client {
+            uri: uri.to_string(),
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+        })
+    }
+
+    pub fn request(&self, operation: u16) -> Message {
+        Message::request(operation, NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed), &self.uri)
+    }
+
+    /// POST a request and decode the response. IPP-level errors are returned
+    /// as `Err` too.
+    pub fn send(&self, request: &Message) -> Result<Message, String> {
+        let body = request.encode();
+        let response = http_post(&self.host, self.port, &self.path, &body)?;
+        let message = Message::decode(&response)?;
+        if !message.is_success() {
+            return Err(format!("IPP {}", message.status_message()));
+        }
+        Ok(message)
+    }
+
+    /// Get-Printer-Attributes (`requested` empty = all)
+    pub fn printer_attributes(&self, requested: &[&str]) -> Result<Group, String> {
+        let mut request = self.request(op::GET_PRINTER_ATTRIBUTES);
+        if !requested.is_empty() {
+            request.add(group::OPERATION, Attribute::keywords("requested-attributes", requested));
+        }
+        let response = self.send(&request)?;
+        let attributes = response.groups_of(group::PRINTER).next().cloned();
+        attributes.ok_or_else(|| "IPP: no printer attributes in response".to_string())
+    }
+
+    /// Print-Job with `job_attributes` (copies, media, …). Returns the
+    /// job-id.
+    pub fn print_job(
+        &self,
+        job_name: &str,
+        document_format: &str,
+        job_attributes: Vec<Attribute>,
+        document: Vec<u8>,
+    ) -> Result<i32, String> {
+        let mut request = self.request(op::PRINT_JOB);
+        request.add(group::OPERATION, Attribute::string("job-name", tag::NAME, job_name));
+        request.add(group::OPERATION, Attribute::string("document-format", tag::MIME_TYPE, document_format));
+        for attribute in job_attributes {
+            request.add(group::JOB, attribute);
+        }
+        request.data = document;
+        let response = self.send(&request)?;
+        response
+            .get("job-id")
+            .and_then(Attribute::first_int)
+            .ok_or_else(|| "IPP: Print-Job response without job-id".to_string())
+    }
+
+    pub fn job_attributes(&self, job_id: i32) -> Result<Group, String> {
+        let mut request = self.request(op::GET_JOB_ATTRIBUTES);
+        request.add(group::OPERATION, Attribute::integer("job-id", job_id));
+        let response = self.send(&request)?;
+        let attributes = response.groups_of(group::JOB).next().cloned();
+        attributes.ok_or_else(|| "IPP: no job attributes in response".to_string())
+    }
+
+    pub fn cancel_job(&self, job_id: i32) -> Result<(), String> {
+        let mut request = self.request(op::CANCEL_JOB);
+        request.add(group::OPERATION, Attribute::integer("job-id", job_id));
+        self.send(&request).map(|_| ())
+    }
+
+    /// CUPS-Get-Printers against a cupsd URI (e.g. `ipp://localhost:631/`):
+    /// one attribute group per queue
+    pub fn cups_printers(&self, requested: &[&str]) -> Result<Vec<Group>, String> {
+        let mut request = self.request(op::CUPS_GET_PRINTERS);
+        // CUPS-Get-Printers has no printer-uri operand
+        if let Some(group) = request.groups.first_mut() {
+            group.attributes.retain(|a| a.name != "printer-uri");
+        }
+        if !requested.is_empty() {
+            request.add(group::OPERATION, Attribute::keywords("requested-attributes", requested));
+        }
+        let response = self.send(&request)?;
+        Ok(response.groups_of(group::PRINTER).cloned().collect())
+    }
+}
+
+// =============================================================================
+// HTTP
+// =============================================================================
+
+fn http_post(host: &str, port: u16, path: &str, body: &[u8]) -> Result<Vec<u8>, String> {
+    let address = format!("{}:{}", host.trim_start_matches('[').trim_end_matches(']'), port);
+    let mut stream = connect(&address)?;
+    stream
+        .set_read_timeout(Some(TIMEOUT))
+        .and_then(|_| stream.set_write_timeout(Some(TIMEOUT)))
+        .map_err(|e| format!("Connect {} failed: {}", address, e))?;
+
+    let header = format!(
+        "POST {} HTTP/1.1\r\nHost: {}:{}\r\nContent-Type: application/ipp\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        path,
+        host,
+        port,
+        body.len()
+    );
+    stream
+        .write_all(header.as_bytes())
+        .and_then(|_| stream.write_all(body))
+        .map_err(|e| format!("Send to {} failed: {}", address, e))?;
+
+    let mut response = Vec::new();
+    stream
+        .read_to_end(&mut response)
+        .map_err(|e| format!("Read from {} failed: {}", address, e))?;
+    parse_http_response(&response)
+}
+
+/// Connect to the first reachable address of `address`, waiting at most
+/// `CONNECT_TIMEOUT` for each
+fn connect(address: &str) -> Result<TcpStream, String> {
+    let mut last_error = format!("Connect {} failed: no address", address);
+    for addr in address
+        .to_socket_addrs()
+        .map_err(|e| format!("Resolve {} failed: {}", address, e))?
+    {
+        match TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT) {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last_error = format!("Connect {} failed: {}", address, e),
+        }
+    }
+    Err(last_error)
+}
+
+/// Body of an HTTP/1.1 response (Content-Length, chunked or read-to-close),
+/// skipping interim `100 Continue` responses
+fn parse_http_response(mut response: &[u8]) -> Result<Vec<u8>, String> {
+    loop {
+        let header_end = response
+            .windows(4)
+            .position(|w| w == b"\r\n\r\n")
+            .ok_or("HTTP: incomplete response")?;
+        let head = String::from_utf8_lossy(&response[..header_end]).to_string();
+        let rest = &response[header_end + 4..];
+
+        let mut lines = head.lines();
+        let status_line = lines.next().unwrap_or_default();
+        let status: u16 = status_line
+            .split_whitespace()
+            .nth(1)
+            .and_then(|s| s.parse().ok())
+            .ok_or_else(|| format!("HTTP: bad status line '{}'", status_line))?;
+        if status == 100 {
+            response = rest;
+            continue;
+        }
+        if status != 200 {
+            return Err(format!("HTTP {}", status_line.split_once(' ').map_or("error", |(_, rest)| rest)));
+        }
+
+        let header = |name: &str| {
+            head.lines()
+                .skip(1)
+                .filter_map(|l| l.split_once(':'))
+                .find(|(k, _)| k.trim().eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.trim().to_string())
+        };
+        if header("Transfer-Encoding").is_some_and(|v| v.eq_ignore_ascii_case("chunked")) {
+            return decode_chunked(rest);
+        }
+        if let Some(length) = header("Content-Length").and_then(|v| v.parse::<usize>().ok()) {
+            return rest
+                .get(..length)
+                .map(<[u8]>::to_vec)
+                .ok_or_else(|| "HTTP: truncated body".to_string());
+        }
+        return Ok(rest.to_vec());
+    }
+}
+
+fn decode_chunked(mut data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut body = Vec::new();
+    loop {
+        let line_end = data
+            .windows(2)
+            .position(|w| w == b"\r\n")
+            .ok_or("HTTP: bad chunk")?;
+        let size_field = String::from_utf8_lossy(&data[..line_end]);
+        let size = usize::from_str_radix(size_field.split(';').next().unwrap_or("").trim(), 16)
+            .map_err(|_| format!("HTTP: bad chunk size '{}'", size_field))?;
+        data = &data[line_end + 2..];
+        if size == 0 {
+            return Ok(body);
+        }
+        body.extend(data.get(..size).ok_or("HTTP: truncated chunk")?);
+        data = data.get(size + 2..).unwrap_or_default();
+    }
+}
@@ -0,0 +1,532 @@
+//! JPEG Metadata
+//!
+//! Rewrites the metadata segments of an encoded JPEG instead of patching
+//! header bytes in place:
+//!
+//! - APP0 JFIF with the print DPI
+//! - APP1 EXIF with X/YResolution (same DPI), optionally the camera's own
+//!   tags (orientation, exposure, …) and booth info (machine id, session id,
+//!   timestamp)
+//! - APP1 XMP with the booth info
+//! - APP2 ICC_PROFILE with a built-in sRGB profile
+//!
+//! Any existing JFIF / EXIF / XMP / ICC segments are replaced; everything else
+//! (quantisation tables, Adobe APP14, comments, scan data) is copied as-is.
+//! Camera tags that point into the original file (MakerNote, thumbnail) and
+//! GPS data are never carried over.
+//!
+//! Exercised by `examples/test_jfif.rs`.
+
+use exif::experimental::Writer as ExifWriter;
+use exif::{Context, Field, In, Rational, Tag, Value};
+use image::DynamicImage;
+use std::io::Cursor;
+use std::sync::{Mutex, OnceLock};
+
+/// Default DPI of [`MetadataOptions`] — the pipeline writes its outputs at
+/// the paper profile's DPI (`workdir::jpeg_options`)
+pub const PRINT_DPI: u16 = 350;
+
+const EXIF_HEADER: &[u8] = b"Exif\0\0";
+const XMP_HEADER: &[u8] = b"http://ns.adobe.com/xap/1.0/\0";
+const ICC_HEADER: &[u8] = b"ICC_PROFILE\0";
+/// Largest payload of one marker segment (65535 minus the length field)
+const MAX_SEGMENT: usize = 65533;
+
+/// TIFF HostComputer (0x013C) — not in kamadak-exif's tag table
+const HOST_COMPUTER: Tag = Tag(Context::Tiff, 0x013c);
+
+const SOI: u8 = 0xD8;
+const SOS: u8 = 0xDA;
+const APP0: u8 = 0xE0;
+const APP1: u8 = 0xE1;
+const APP2: u8 = 0xE2;
+
+static MACHINE_ID: Mutex<String> = Mutex::new(String::new());
+
+/// Machine id stamped into booth info (set once the machine is verified)
+pub fn set_machine_id(machine_id: &str) {
+    *MACHINE_ID.lock().unwrap() = machine_id.to_string();
+}
+
+// =============================================================================
+// Options
+// =============================================================================
+
+/// What happens to the source image's camera EXIF
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExifPolicy {
+    /// Only resolution, colour space and booth info
+    Strip,
+    /// Keep the camera's primary-image tags (minus GPS / MakerNote)
+    Preserve,
+}
+
+impl ExifPolicy {
+    /// `BONIO_EXIF_POLICY=strip|preserve`, default preserve
+    pub fn from_env() -> Self {
+        match std::env::var("BONIO_EXIF_POLICY").ok().as_deref() {
+            Some("strip") => ExifPolicy::Strip,
+            _ => ExifPolicy::Preserve,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BoothInfo {
+    pub machine_id: String,
+    pub session_id: String,
+    pub timestamp: chrono::DateTime<chrono::Local>,
+}
+
+impl BoothInfo {
+    /// Booth info for `session_id`, stamped now with the configured machine id
+    pub fn now(session_id: &str) -> Self {
+        BoothInfo {
+            machine_id: MACHINE_ID.lock().unwrap().clone(),
+            session_id: session_id.to_string(),
+            timestamp: chrono::Local::now(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MetadataOptions {
+    pub dpi: u16,
+    pub embed_srgb: bool,
+    pub exif: ExifPolicy,
+    /// Pixels were already rotated upright — don't copy the source Orientation
+    pub reset_orientation: bool,
+    pub booth: Option<BoothInfo>,
+}
+
+impl Default for MetadataOptions {
+    fn default() -> Self {
+        MetadataOptions {
+            dpi: PRINT_DPI,
+            embed_srgb: true,
+            exif: ExifPolicy::from_env(),
+            reset_orientation: false,
+            booth: None,
+        }
+    }
+}
+
+// =============================================================================
+// Segments
+// =============================================================================
+
+/// One marker segment before the scan data
+#[derive(Debug, Clone, Copy)]
+pub struct Segment<'a> {
+    pub marker: u8,
+    /// Payload without the length field
+    pub data: &'a [u8],
+}
+
+/// Split a JPEG into its header segments and the offset of the SOS marker
+/// (everything from there on is entropy-coded data).
+pub fn segments(jpeg: &[u8]) -> Result<(Vec<Segment<'_>>, usize), String> {
+    if jpeg.len() < 4 || jpeg[0] != 0xFF || jpeg[1] != SOI {
+        return Err("Not a JPEG file".to_string());
+    }
+    let mut segments = Vec::new();
+    let mut pos = 2;
+    loop {
+        if pos >= jpeg.len() || jpeg[pos] != 0xFF {
+            return Err(format!("Broken JPEG: expected marker at {}", pos));
+        }
+        // Markers may be preceded by any number of 0xFF fill bytes
+        while pos < jpeg.len() && jpeg[pos] == 0xFF {
+            pos += 1;
+        }
+        let marker = *jpeg.get(pos).ok_or("Broken JPEG: truncated marker")?;
+        let start = pos - 1;
+        pos += 1;
+        if marker == SOS {
+            return Ok((segments, start));
+        }
+        // Standalone markers carry no length
+        if (0xD0..=0xD7).contains(&marker) || marker == 0x01 {
+            continue;
+        }
+        if pos + 2 > jpeg.len() {
+            return Err("Broken JPEG: truncated segment length".to_string());
+        }
+        let len = u16::from_be_bytes([jpeg[pos], jpeg[pos + 1]]) as usize;
+        if len < 2 || pos + len > jpeg.len() {
+            return Err(format!("Broken JPEG: bad length for marker {:02X}", marker));
+        }
+        segments.push(Segment {
+            marker,
+            data: &jpeg[pos + 2..pos + len],
+        });
+        pos += len;
+    }
+}
+
+fn is_replaced(segment: &Segment) -> bool {
+    match segment.marker {
+        APP0 => segment.data.starts_with(b"JFIF\0") || segment.data.starts_with(b"JFXX\0"),
+        APP1 => segment.data.starts_with(EXIF_HEADER) || segment.data.starts_with(XMP_HEADER),
+        APP2 => segment.data.starts_with(ICC_HEADER),
+        _ => false,
+    }
+}
+
+fn push_segment(out: &mut Vec<u8>, marker: u8, parts: &[&[u8]]) {
+    let len: usize = parts.iter().map(|p| p.len()).sum::<usize>() + 2;
+    out.extend_from_slice(&[0xFF, marker]);
+    out.extend_from_slice(&(len as u16).to_be_bytes());
+    for part in parts {
+        out.extend_from_slice(part);
+    }
+}
+
+// =============================================================================
+// Writing
+// =============================================================================
+
+/// Encode `img` as JPEG and write its metadata. `source` is the original
+/// file whose EXIF may be preserved (e.g. the camera JPEG before resizing).
+pub fn encode_jpeg(
+    img: &DynamicImage,
+    quality: u8,
+    source: Option<&[u8]>,
+    options: &MetadataOptions,
+) -> Result<Vec<u8>, String> {
+    let rgb = img.to_rgb8();
+    let mut buf = Vec::new();
+    let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buf, quality);
+    rgb.write_with_encoder(encoder)
+        .map_err(|e| format!("Encode error: {}", e))?;
+    write_metadata(&buf, source, options)
+}
+
+/// Replace the metadata segments of an encoded JPEG
+pub fn write_metadata(
+    jpeg: &[u8],
+    source: Option<&[u8]>,
+    options: &MetadataOptions,
+) -> Result<Vec<u8>, String> {
+    let (segments, scan_start) = segments(jpeg)?;
+    let mut out = Vec::with_capacity(jpeg.len() + 4096);
+    out.extend_from_slice(&[0xFF, SOI]);
+
+    // JFIF 1.01, units = dots per inch, no thumbnail
+    let dpi = options.dpi.to_be_bytes();
+    push_segment(&mut out, APP0, &[b"JFIF\0", &[1, 1, 1], &dpi, &dpi, &[0, 0]]);
+
+    let source_exif = match (options.exif, source) {
+        (ExifPolicy::Preserve, Some(source)) => read_exif(source),
+        _ => None,
+    };
+    let tiff = build_exif(source_exif.as_ref(), options)?;
+    push_segment(&mut out, APP1, &[EXIF_HEADER, &tiff]);
+
+    if let Some(booth) = &options.booth {
+        push_segment(&mut out, APP1, &[XMP_HEADER, build_xmp(booth).as_bytes()]);
+    }
+
+    if options.embed_srgb {
+        let profile = srgb_profile();
+        let chunk_size = MAX_SEGMENT - ICC_HEADER.len() - 2;
+        let count = profile.len().div_ceil(chunk_size);
+        for (i, chunk) in profile.chunks(chunk_size).enumerate() {
+            push_segment(&mut out, APP2, &[ICC_HEADER, &[(i + 1) as u8, count as u8], chunk]);
+        }
+    }
+
+    for segment in segments.iter().filter(|s| !is_replaced(s)) {
+        push_segment(&mut out, segment.marker, &[segment.data]);
+    }
+    out.extend_from_slice(&jpeg[scan_start..]);
+    Ok(out)
+}
+
+fn ascii(value: &str) -> Value {
+    Value::Ascii(vec![value.as_bytes().to_vec()])
+}
+
+/// Source tags that are rewritten, stale after re-encoding, or unsafe to copy
+fn is_dropped(tag: Tag, options: &MetadataOptions) -> bool {
+    matches!(
+        tag,
+        Tag::XResolution
+            | Tag::YResolution
+            | Tag::ResolutionUnit
+            | Tag::PixelXDimension
+            | Tag::PixelYDimension
+            | Tag::ColorSpace
+            | Tag::MakerNote
+            | Tag::Software
+            | Tag::DateTime
+            | Tag::ImageUniqueID
+            | Tag::Compression
+            | Tag::ImageWidth
+            | Tag::ImageLength
+    ) || tag == HOST_COMPUTER
+        || tag.context() == Context::Gps
+        || (tag == Tag::Orientation && options.reset_orientation)
+}
+
+fn build_exif(source: Option<&exif::Exif>, options: &MetadataOptions) -> Result<Vec<u8>, String> {
+    let dpi = Value::Rational(vec![Rational {
+        num: options.dpi as u32,
+        denom: 1,
+    }]);
+    let field = |tag: Tag, value: Value| Field {
+        tag,
+        ifd_num: In::PRIMARY,
+        value,
+    };
+
+    let mut fields = vec![
+        field(Tag::XResolution, dpi.clone()),
+        field(Tag::YResolution, dpi),
+        field(Tag::ResolutionUnit, Value::Short(vec![2])),
+    ];
+    if options.embed_srgb {
+        fields.push(field(Tag::ColorSpace, Value::Short(vec![1])));
+    }
+    if let Some(booth) = &options.booth {
+        fields.push(field(Tag::Software, ascii(&format!("Bonio Booth {}", env!("CARGO_PKG_VERSION")))));
+        fields.push(field(Tag::DateTime, ascii(&booth.timestamp.format("%Y:%m:%d %H:%M:%S").to_string())));
+        if !booth.session_id.is_empty() {
+            // ImageUniqueID is fixed at 32 hex characters
+            let id: String = booth.session_id.chars().filter(|c| c.is_ascii_hexdigit()).take(32).collect();
+            fields.push(field(Tag::ImageUniqueID, ascii(&format!("{:0<32}", id))));
+        }
+        if !booth.machine_id.is_empty() {
+            fields.push(field(HOST_COMPUTER, ascii(&booth.machine_id)));
+        }
+    }
+    let base_len = fields.len();
+
+    if let Some(exif) = source {
+        fields.extend(
+            exif.fields()
+                .filter(|f| f.ifd_num == In::PRIMARY)
+                .filter(|f| !is_dropped(f.tag, options))
+                .filter(|f| !matches!(f.value, Value::Unknown(..)))
+                .cloned(),
+        );
+    }
+
+    let encode = |fields: &[Field]| -> Result<Vec<u8>, String> {
+        let mut writer = ExifWriter::new();
+        for f in fields {
+            writer.push_field(f);
+        }
+        let mut buf = Cursor::new(Vec::new());
+        writer
+            .write(&mut buf, false)
+            .map_err(|e| format!("EXIF write error: {}", e))?;
+        Ok(buf.into_inner())
+    };
+
+    match encode(&fields) {
+        Ok(tiff) if tiff.len() + EXIF_HEADER.len() <= MAX_SEGMENT => Ok(tiff),
+        // Oversized or malformed camera tags: fall back to our own fields
+        result => {
+            if fields.len() > base_len {
+                log::warn!("[JpegMeta] Dropping camera EXIF: {:?}", result.err());
+            }
+            encode(&fields[..base_len])
+        }
+    }
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn build_xmp(booth: &BoothInfo) -> String {
+    format!(
+        concat!(
+            "<?xpacket begin=\"\u{feff}\" id=\"W5M0MpCehiHzreSzNTczkc9d\"?>",
+            "<x:xmpmeta xmlns:x=\"adobe:ns:meta/\">",
+            "<rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">",
+            "<rdf:Description rdf:about=\"\"",
+            " xmlns:xmp=\"http://ns.adobe.com/xap/1.0/\"",
+            " xmlns:bonio=\"https://boniolabs.com/ns/booth/1.0/\"",
+            " xmp:CreatorTool=\"Bonio Booth {}\"",
+            " xmp:CreateDate=\"{}\"",
+            " bonio:MachineId=\"{}\"",
+            " bonio:SessionId=\"{}\"/>",
+            "</rdf:RDF></x:xmpmeta>",
+            "<?xpacket end=\"w\"?>"
+        ),
+        env!("CARGO_PKG_VERSION"),
+        booth.timestamp.to_rfc3339_opts(chrono::SecondsFormat::Secs, false),
+        xml_escape(&booth.machine_id),
+        xml_escape(&booth.session_id),
+    )
+}
+
+// =============================================================================
+// Reading
+// =============================================================================
+
+/// Parsed EXIF of a JPEG, if it has any
+pub fn read_exif(jpeg: &[u8]) -> Option<exif::Exif> {
+    exif::Reader::new()
+        .read_from_container(&mut Cursor::new(jpeg))
+        .ok()
+}
+
+/// EXIF orientation (1–8), if present
+pub fn orientation(jpeg: &[u8]) -> Option<u32> {
+    read_exif(jpeg)?
+        .get_field(Tag::Orientation, In::PRIMARY)?
+        .value
+        .get_uint(0)
+}
+
+/// JFIF density as (units, x, y) — units 1 = dots per inch
+pub fn jfif_density(jpeg: &[u8]) -> Option<(u8, u16, u16)> {
+    let (segments, _) = segments(jpeg).ok()?;
+    let data = segments
+        .iter()
+        .find(|s| s.marker == APP0 && s.data.starts_with(b"JFIF\0"))?
+        .data;
+    if data.len() < 12 {
+        return None;
+    }
+    Some((
+        data[7],
+        u16::from_be_bytes([data[8], data[9]]),
+        u16::from_be_bytes([data[10], data[11]]),
+    ))
+}
+
+/// Embedded ICC profile, reassembled from its APP2 chunks
+pub fn icc_profile(jpeg: &[u8]) -> Option<Vec<u8>> {
+    let (segments, _) = segments(jpeg).ok()?;
+    let mut chunks: Vec<(u8, &[u8])> = segments
+        .iter()
+        .filter(|s| s.marker == APP2 && s.data.starts_with(ICC_HEADER) && s.data.len() > ICC_HEADER.len() + 2)
+        .map(|s| (s.data[ICC_HEADER.len()], &s.data[ICC_HEADER.len() + 2..]))
+        .collect();
+    if chunks.is_empty() {
+        return None;
+    }
+    chunks.sort_by_key(|(seq, _)| *seq);
+    Some(chunks.into_iter().flat_map(|(_, data)| data.iter().copied()).collect())
+}
+
+/// XMP packet, if present
+pub fn xmp(jpeg: &[u8]) -> Option<String> {
+    let (segments, _) = segments(jpeg).ok()?;
+    segments
+        .iter()
+        .find(|s| s.marker == APP1 && s.data.starts_with(XMP_HEADER))
+        .map(|s| String::from_utf8_lossy(&s.data[XMP_HEADER.len()..]).to_string())
+}
+
+// =============================================================================
+// sRGB ICC Profile
+// =============================================================================
+
+fn s15_fixed16(value: f64) -> [u8; 4] {
+    ((value * 65536.0).round() as i32).to_be_bytes()
+}
+
+fn xyz_tag(x: f64, y: f64, z: f64) -> Vec<u8> {
+    let mut tag = b"XYZ \0\0\0\0".to_vec();
+    for v in [x, y, z] {
+        tag.extend_from_slice(&s15_fixed16(v));
+    }
+    tag
+}
+
+/// Compact ICC v2.1 display profile for sRGB (D50-adapted primaries, 1024
+/// point sRGB tone curve shared by all three channels). Built once.
+pub fn srgb_profile() -> &'static [u8] {
+    static PROFILE: OnceLock<Vec<u8>> = OnceLock::new();
+    PROFILE.get_or_init(|| {
+        let description = b"sRGB (Bonio Booth)";
+        let mut desc = b"desc\0\0\0\0".to_vec();
+        desc.extend_from_slice(&(description.len() as u32 + 1).to_be_bytes());
+        desc.extend_from_slice(description);
+        desc.push(0);
+        desc.extend_from_slice(&[0; 4 + 4 + 2 + 1 + 67]); // empty Unicode + ScriptCode
+
+        let mut curve = b"curv\0\0\0\0".to_vec();
+        let points = 1024u32;
+        curve.extend_from_slice(&points.to_be_bytes());
+        for i in 0..points {
+            let v = i as f64 / (points - 1) as f64;
+            let linear = if v <= 0.04045 {
+                v / 12.92
+            } else {
+                ((v + 0.055) / 1.055).powf(2.4)
+            };
+            curve.extend_from_slice(&((linear * 65535.0).round() as u16).to_be_bytes());
+        }
+
+        let mut cprt = b"text\0\0\0\0".to_vec();
+        cprt.extend_from_slice(b"No copyright, use freely\0");
+
+        // (signature, data) — the three TRC tags share one curve
+        let tags: Vec<(&[u8; 4], Vec<u8>)> = vec![
+            (b"desc", desc),
+            (b"cprt", cprt),
+            (b"wtpt", xyz_tag(0.9642, 1.0, 0.8249)),
+            (b"rXYZ", xyz_tag(0.4360747, 0.2225045, 0.0139322)),
+            (b"gXYZ", xyz_tag(0.3850649, 0.7168786, 0.0971045)),
+            (b"bXYZ", xyz_tag(0.1430804, 0.0606169, 0.7141733)),
+            (b"rTRC", curve),
+        ];
+        let shared: [&[u8; 4]; 2] = [b"gTRC", b"bTRC"];
+        let tag_count = tags.len() + shared.len();
+
+        let mut table = Vec::new();
+        let mut data = Vec::new();
+        let data_start = 128 + 4 + 12 * tag_count;
+        let mut curve_entry = (0u32, 0u32);
+        for (sig, bytes) in &tags {
+            let offset = (data_start + data.len()) as u32;
+            table.extend_from_slice(*sig);
+            table.extend_from_slice(&offset.to_be_bytes());
+            table.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+            if *sig == b"rTRC" {
+                curve_entry = (offset, bytes.len() as u32);
+            }
+            data.extend_from_slice(bytes);
+            while data.len() % 4 != 0 {
+                data.push(0);
+            }
+        }
+        for sig in shared {
+            table.extend_from_slice(sig);
+            table.extend_from_slice(&curve_entry.0.to_be_bytes());
+            table.extend_from_slice(&curve_entry.1.to_be_bytes());
+        }
+
+        let size = (data_start + data.len()) as u32;
+        let mut header = Vec::with_capacity(128);
+        header.extend_from_slice(&size.to_be_bytes());
+        header.extend_from_slice(&[0; 4]); // preferred CMM
+        header.extend_from_slice(&[2, 0x10, 0, 0]); // version 2.1
+        header.extend_from_slice(b"mntrRGB XYZ ");
+        header.extend_from_slice(&[0x07, 0xE8, 0, 1, 0, 1, 0, 0, 0, 0, 0, 0]); // 2024-01-01 00:00:00
+        header.extend_from_slice(b"acsp");
+        header.extend_from_slice(&[0; 4 + 4 + 4 + 4 + 8]); // platform, flags, manufacturer, model, attributes
+        header.extend_from_slice(&0u32.to_be_bytes()); // perceptual intent
+        header.extend_from_slice(&s15_fixed16(0.9642));
+        header.extend_from_slice(&s15_fixed16(1.0));
+        header.extend_from_slice(&s15_fixed16(0.8249));
+        header.extend_from_slice(&[0; 4 + 16 + 28]); // creator, profile id, reserved
+
+        let mut profile = header;
+        profile.extend_from_slice(&(tag_count as u32).to_be_bytes());
+        profile.extend_from_slice(&table);
+        profile.extend_from_slice(&data);
+        profile
+    })
+}
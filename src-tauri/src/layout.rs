@@ -0,0 +1,325 @@
+//! Frame Layout
+//!
+//! Typed model for a frame's photo slots, shared by the still composer
+//! (`image_processing::compose_frame`) and the video composer
+//! (`video::compose_frame_video`). Both go through [`FrameLayout::place`] so
+//! they always agree on where a slot lands on the output canvas.
+//!
+//! Slot coordinates are in the frame's design space (`width` × `height`,
+//! usually the frame image's `imageSize`) and are scaled to whatever canvas
+//! the composer renders at.
+//!
+//! Besides photo slots a layout can carry `layers` — text and QR code stamps
+//! drawn on top of everything (see `stamps.rs`):
+//!
+//! ```json
+//! "layers": [
+//!   { "type": "text", "x": 0, "y": 1700, "width": 1200, "text": "{eventName} · {date}",
+//!     "fontFile": "Kanit-Bold.ttf", "fontSize": 48, "color": "#ffffff", "align": "center" },
+//!   { "type": "qr", "x": 1020, "y": 1620, "size": 160, "data": "{qrUrl}" }
+//! ]
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// How far (in design units) a slot may poke past the frame edge before it is
+/// rejected — absorbs rounding in layouts exported from the editor.
+const EDGE_TOLERANCE: f64 = 1.0;
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Slot {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    /// Corner radius in design units
+    #[serde(default)]
+    pub radius: f64,
+    /// Negative = drawn behind the frame image, otherwise on top
+    #[serde(default)]
+    pub z_index: f64,
+    /// Clockwise rotation in degrees around the slot centre
+    #[serde(default)]
+    pub rotate: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrameLayout {
+    /// Design-space width the slot coordinates are relative to
+    pub width: u32,
+    /// Design-space height the slot coordinates are relative to
+    pub height: u32,
+    pub slots: Vec<Slot>,
+    /// Text / QR stamps, drawn in order above the frame and the photos
+    #[serde(default)]
+    pub layers: Vec<Layer>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextLayer {
+    /// Left edge of the text box
+    pub x: f64,
+    /// Top of the first line
+    pub y: f64,
+    /// Box width the text is aligned in. 0 makes `x` the anchor point
+    /// (left edge, centre or right edge depending on `align`).
+    #[serde(default)]
+    pub width: f64,
+    /// Template, e.g. "{eventName} {date}". `\n` starts a new line.
+    pub text: String,
+    /// .ttf / .otf: absolute path, http(s) URL, or a name in the fonts folder.
+    /// Falls back to a system font when omitted.
+    #[serde(default)]
+    pub font_file: Option<String>,
+    /// Pixel height in design units
+    pub font_size: f64,
+    /// "#RGB", "#RRGGBB" or "#RRGGBBAA"
+    #[serde(default = "default_text_color")]
+    pub color: String,
+    #[serde(default)]
+    pub align: TextAlign,
+    /// Clockwise degrees around the text box centre
+    #[serde(default)]
+    pub rotate: f64,
+    /// chrono format used for `{date}` in this layer
+    #[serde(default)]
+    pub date_format: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QrLayer {
+    pub x: f64,
+    pub y: f64,
+    /// Edge length of the square code (including the quiet zone)
+    pub size: f64,
+    /// Template for the encoded text, usually "{qrUrl}"
+    pub data: String,
+    #[serde(default = "default_qr_dark")]
+    pub dark_color: String,
+    #[serde(default = "default_qr_light")]
+    pub light_color: String,
+    /// Quiet zone in modules around the code
+    #[serde(default = "default_qr_margin")]
+    pub margin: u32,
+    #[serde(default)]
+    pub rotate: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Layer {
+    Text(TextLayer),
+    Qr(QrLayer),
+}
+
+fn default_text_color() -> String {
+    "#000000".to_string()
+}
+
+fn default_qr_dark() -> String {
+    "#000000".to_string()
+}
+
+fn default_qr_light() -> String {
+    "#ffffff".to_string()
+}
+
+fn default_qr_margin() -> u32 {
+    2
+}
+
+/// Parse "#RGB", "#RRGGBB" or "#RRGGBBAA" into RGBA
+pub fn parse_color(value: &str) -> Result<[u8; 4], String> {
+    let hex = value.trim().trim_start_matches('#');
+    let expanded: String = match hex.len() {
+        3 => hex.chars().flat_map(|c| [c, c]).chain("ff".chars()).collect(),
+        6 => format!("{}ff", hex),
+        8 => hex.to_string(),
+        _ => return Err(format!("Invalid colour '{}'", value)),
+    };
+    let mut rgba = [0u8; 4];
+    for (i, channel) in rgba.iter_mut().enumerate() {
+        *channel = u8::from_str_radix(&expanded[i * 2..i * 2 + 2], 16)
+            .map_err(|_| format!("Invalid colour '{}'", value))?;
+    }
+    Ok(rgba)
+}
+
+/// A slot resolved to whole pixels on a concrete canvas
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlacedSlot {
+    /// Index into `FrameLayout::slots` (and the photo / video list)
+    pub index: usize,
+    pub x: i64,
+    pub y: i64,
+    pub width: u32,
+    pub height: u32,
+    pub radius: u32,
+    pub rotate: f64,
+    pub behind_frame: bool,
+}
+
+// =============================================================================
+// Validation & Geometry
+// =============================================================================
+
+impl FrameLayout {
+    /// Check the design size and every slot. Errors name the offending slot.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.width == 0 || self.height == 0 {
+            return Err(format!(
+                "Invalid frame layout size {}x{}",
+                self.width, self.height
+            ));
+        }
+        if self.slots.is_empty() {
+            return Err("Frame layout has no slots".to_string());
+        }
+
+        let (fw, fh) = (self.width as f64, self.height as f64);
+        for (i, slot) in self.slots.iter().enumerate() {
+            let values = [slot.x, slot.y, slot.width, slot.height, slot.radius, slot.z_index, slot.rotate];
+            if values.iter().any(|v| !v.is_finite()) {
+                return Err(format!("Slot {}: non-numeric geometry", i + 1));
+            }
+            if slot.width <= 0.0 || slot.height <= 0.0 {
+                return Err(format!(
+                    "Slot {}: invalid size {}x{}",
+                    i + 1,
+                    slot.width,
+                    slot.height
+                ));
+            }
+            if slot.radius < 0.0 {
+                return Err(format!("Slot {}: negative radius {}", i + 1, slot.radius));
+            }
+            if slot.x < -EDGE_TOLERANCE
+                || slot.y < -EDGE_TOLERANCE
+                || slot.x + slot.width > fw + EDGE_TOLERANCE
+                || slot.y + slot.height > fh + EDGE_TOLERANCE
+            {
+                return Err(format!(
+                    "Slot {}: ({}, {}) {}x{} is outside the {}x{} frame",
+                    i + 1,
+                    slot.x,
+                    slot.y,
+                    slot.width,
+                    slot.height,
+                    self.width,
+                    self.height
+                ));
+            }
+        }
+
+        for (i, layer) in self.layers.iter().enumerate() {
+            let result = match layer {
+                Layer::Text(text) => {
+                    if !(text.font_size.is_finite() && text.font_size > 0.0) {
+                        Err(format!("invalid font size {}", text.font_size))
+                    } else {
+                        parse_color(&text.color).map(|_| ())
+                    }
+                }
+                Layer::Qr(qr) => {
+                    if !(qr.size.is_finite() && qr.size > 0.0) {
+                        Err(format!("invalid size {}", qr.size))
+                    } else {
+                        parse_color(&qr.dark_color)
+                            .and(parse_color(&qr.light_color))
+                            .map(|_| ())
+                    }
+                }
+            };
+            result.map_err(|e| format!("Layer {}: {}", i + 1, e))?;
+        }
+        Ok(())
+    }
+
+    /// Design units → canvas pixels for a `canvas_w` × `canvas_h` canvas
+    pub fn scale_for(&self, canvas_w: u32, canvas_h: u32) -> (f64, f64) {
+        (
+            canvas_w as f64 / self.width as f64,
+            canvas_h as f64 / self.height as f64,
+        )
+    }
+
+    /// Validate, and make sure there is a slot for each of `media_count`
+    /// photos / videos. Fewer media than slots is fine (slots stay empty).
+    pub fn validate_for(&self, media_count: usize) -> Result<(), String> {
+        self.validate()?;
+        if media_count > self.slots.len() {
+            return Err(format!(
+                "{} photos/videos but the frame only has {} slots",
+                media_count,
+                self.slots.len()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Resolve the first `count` slots onto a `canvas_w` × `canvas_h` canvas,
+    /// in drawing order: slots behind the frame first, then the ones on top,
+    /// each group sorted by `zIndex` (ties keep slot order).
+    ///
+    /// Edges are snapped outward (floor the origin, ceil the far edge) so
+    /// neighbouring slots never leave a gap. With `align` > 1 the origin and
+    /// size are also snapped to multiples of it (2 for yuv420p video).
+    pub fn place(&self, canvas_w: u32, canvas_h: u32, count: usize, align: u32) -> Vec<PlacedSlot> {
+        let (scale_x, scale_y) = self.scale_for(canvas_w, canvas_h);
+        let align = align.max(1) as i64;
+
+        let mut placed: Vec<(f64, PlacedSlot)> = self
+            .slots
+            .iter()
+            .take(count)
+            .enumerate()
+            .map(|(index, slot)| {
+                let left = (slot.x * scale_x).floor() as i64;
+                let top = (slot.y * scale_y).floor() as i64;
+                let right = ((slot.x + slot.width) * scale_x).ceil() as i64;
+                let bottom = ((slot.y + slot.height) * scale_y).ceil() as i64;
+
+                let x = left.div_euclid(align) * align;
+                let y = top.div_euclid(align) * align;
+                let width = round_up(right - x, align).max(align) as u32;
+                let height = round_up(bottom - y, align).max(align) as u32;
+
+                let placed = PlacedSlot {
+                    index,
+                    x,
+                    y,
+                    width,
+                    height,
+                    radius: (slot.radius * scale_x).round() as u32,
+                    rotate: slot.rotate,
+                    behind_frame: slot.z_index < 0.0,
+                };
+                (slot.z_index, placed)
+            })
+            .collect();
+
+        // Stable, so equal zIndex keeps slot order; negatives (behind) come first
+        placed.sort_by(|(za, _), (zb, _)| za.total_cmp(zb));
+        placed.into_iter().map(|(_, p)| p).collect()
+    }
+}
+
+fn round_up(value: i64, align: i64) -> i64 {
+    (value + align - 1).div_euclid(align) * align
+}
@@ -1,12 +1,35 @@
 mod api;
+mod backend;
+pub mod booth_api;
+mod camera;
+mod camera_sim;
 mod canon;
-#[cfg(target_os = "windows")]
 mod edsdk_sys;
+mod file_printer;
+mod filters;
 mod image_processing;
+pub mod ipp;
+mod imposition;
+pub mod jpeg_meta;
+mod layout;
+mod live_view;
+mod machine_config;
+mod media;
+pub mod lut;
+mod orientation;
+mod outbox;
+pub mod paper;
+mod pdf;
+mod persist;
+mod print_queue;
 mod printer;
+pub mod printer_backend;
+mod printer_monitor;
 mod shutdown;
 mod sse;
+mod stamps;
 mod video;
+mod workdir;
 
 use api::AppState;
 use shutdown::ShutdownManager;
@@ -121,12 +144,22 @@ fn get_app_dir(app: tauri::AppHandle) -> Result<String, String> {
     Err("Filters directory not found".to_string())
 }
 
+/// Fonts for text stamps live in a `fonts` folder next to `filters`
+fn get_fonts_dir(app: tauri::AppHandle) -> Option<std::path::PathBuf> {
+    let filters_dir = get_app_dir(app).ok()?;
+    let fonts_dir = std::path::Path::new(&filters_dir).parent()?.join("fonts");
+    fonts_dir.exists().then_some(fonts_dir)
+}
+
 /// Resolve LUT file path: takes a .cube filename and returns its absolute path
 #[tauri::command]
 fn resolve_lut_path(app: tauri::AppHandle, lut_file: String) -> Result<String, String> {
     if lut_file.is_empty() {
         return Ok(String::new());
     }
+    if !filters::is_contained_path(&lut_file) {
+        return Err(format!("LUT file must be inside the filters folder: {}", lut_file));
+    }
     let filters_dir = get_app_dir(app)?;
     let lut_path = std::path::Path::new(&filters_dir).join(&lut_file);
     if lut_path.exists() {
@@ -183,7 +216,6 @@ pub fn run() {
         .plugin(tauri_plugin_http::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
-        .manage(AppState::new())
         .manage(Mutex::new(SseClient::new()))
         .manage(Arc::new(ShutdownManager::new()))
         .setup(|app| {
@@ -194,6 +226,15 @@ pub fn run() {
                 window.open_devtools();
             }
 
+            // Machine config first — the other modules read AppState
+            app.manage(AppState::new(app.handle()));
+            paper::init(app.handle());
+            file_printer::init(app.handle());
+            media::init(app.handle());
+            printer_monitor::init(app.handle());
+            print_queue::init(app.handle());
+            outbox::init(app.handle());
+
             // Give shutdown manager an app handle
             if let Some(shutdown_mgr) = app.try_state::<Arc<ShutdownManager>>() {
                 shutdown_mgr.set_app_handle(app.handle().clone());
@@ -245,6 +286,19 @@ pub fn run() {
             canon::canon_take_photo_during_recording,
             canon::canon_finalize_movie_download,
             canon::canon_is_movie_recording,
+            canon::canon_get_backend,
+            canon::canon_set_backend,
+            canon::canon_sim_inject_error,
+            canon::canon_sim_clear_errors,
+            // Live view streaming
+            live_view::live_view_stream_start,
+            live_view::live_view_stream_stop,
+            live_view::live_view_stream_pause,
+            live_view::live_view_stream_resume,
+            live_view::live_view_stream_set_fps,
+            live_view::live_view_stream_stats,
+            live_view::live_view_stream_record_start,
+            live_view::live_view_stream_record_stop,
             // API commands
             api::verify_machine,
             api::init_machine,
@@ -259,6 +313,7 @@ pub fn run() {
             api::create_presign_upload,
             api::upload_to_presigned_url,
             api::confirm_upload,
+            api::deliver_photos,
             api::notify_going_offline,
             api::get_machine_status,
             api::send_device_alert,
@@ -266,6 +321,16 @@ pub fn run() {
             api::send_device_reconnected,
             api::update_paper_level,
             api::reduce_paper_level_api,
+            outbox::get_outbox_status,
+            outbox::get_outbox_items,
+            outbox::drain_outbox,
+            outbox::retry_outbox_item,
+            outbox::discard_outbox_item,
+            media::get_media_status,
+            media::load_media_roll,
+            media::set_media_remaining,
+            media::set_media_thresholds,
+            media::check_media_available,
             api::set_machine_config,
             api::set_camera_type,
             api::get_camera_type,
@@ -277,17 +342,36 @@ pub fn run() {
             api::get_selected_printer,
             api::set_paper_config,
             api::get_paper_config,
+            machine_config::get_machine_config,
+            backend::get_backend_config,
+            backend::set_backend_environment,
+            machine_config::export_machine_config,
+            machine_config::import_machine_config,
             api::download_image_from_url,
             // Image processing
-            image_processing::get_available_filters,
+            filters::get_available_filters,
             image_processing::apply_lut_filter,
             image_processing::apply_lut_filter_preview,
+            image_processing::apply_lut_filter_previews,
             image_processing::compose_frame,
-            image_processing::save_temp_image,
+            workdir::session_start,
+            workdir::session_import_image,
+            orientation::set_camera_mount_rotation,
+            orientation::get_camera_mount_rotation,
             // Printer
+            paper::get_paper_profiles,
+            paper::get_print_sheets,
+            paper::reload_paper_profiles,
+            imposition::preview_imposition,
             printer::get_printers,
             printer::check_printer_status,
+            printer::get_spool_job,
+            printer_monitor::get_printer_health,
             printer::print_photo,
+            print_queue::enqueue_print_job,
+            print_queue::get_print_jobs,
+            print_queue::reprint_print_job,
+            print_queue::cancel_print_job,
             printer::print_test_photo,
             printer::list_dslr_cameras,
             printer::reduce_paper_level,
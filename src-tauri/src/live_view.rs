@@ -0,0 +1,579 @@
+//! Live View Streaming
+//!
+//! A backend-driven pump grabs EVF frames from the active `CameraBackend` on
+//! its own thread and serves them on localhost, so the frontend no longer has
+//! to poll `canon_get_live_view_frame` and base64-decode every frame:
+//! - `GET /stream`    — multipart MJPEG (`<img src>` compatible)
+//! - `GET /frame.jpg` — latest single frame
+//! - `GET /stats`     — pump / client statistics as JSON
+//!
+//! The server only listens on 127.0.0.1 and sends no CORS headers; the
+//! frontend consumes it through `<img src>` only.
+//!
+//! The pump is the only caller of `live_view_frame()` while it runs. Camera
+//! commands that need exclusive access (capture, movie start/stop) hold a
+//! `PauseGuard` for their duration, which waits for any in-flight grab to
+//! finish and restores the previous pause state on drop.
+
+use crate::camera::backend;
+use log::{info, warn};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::watch;
+
+const DEFAULT_TARGET_FPS: u32 = 30;
+const MAX_TARGET_FPS: u32 = 60;
+/// Sleep while paused before re-checking the pause flag
+const PAUSED_POLL_INTERVAL: Duration = Duration::from_millis(20);
+/// Frame-recording fallback cap (~60s at 30fps) so a forgotten recording
+/// cannot grow without bound
+const MAX_RECORDED_FRAMES: usize = 1800;
+const MAX_REQUEST_HEAD: usize = 8 * 1024;
+const MJPEG_BOUNDARY: &str = "bonioframe";
+
+/// Frames buffered by the recording fallback: (unix ms, JPEG)
+type RecordedBuffer = Vec<(u64, Arc<Vec<u8>>)>;
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// A single published EVF frame. `seq == 0` means no frame yet.
+#[derive(Clone, Default)]
+struct Frame {
+    seq: u64,
+    jpeg: Arc<Vec<u8>>,
+    timestamp_ms: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct LiveViewStreamInfo {
+    pub url: String,
+    pub port: u16,
+    pub target_fps: u32,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct LiveViewStats {
+    pub running: bool,
+    pub paused: bool,
+    pub target_fps: u32,
+    /// Frames published over the last measurement window
+    pub measured_fps: f32,
+    /// Sequence number of the latest published frame
+    pub last_frame_seq: u64,
+    pub frames_grabbed: u64,
+    /// Grabs where the camera had no new frame ready
+    pub frames_empty: u64,
+    pub grab_errors: u64,
+    /// Pump ticks skipped because a grab overran the frame interval
+    pub late_ticks: u64,
+    /// Frames written to stream clients
+    pub frames_sent: u64,
+    /// Frames a stream client never saw because it was still sending the previous one
+    pub frames_dropped: u64,
+    pub clients: u32,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RecordedFrames {
+    /// Paths of the JPEG frames in the session folder, in order
+    pub frames: Vec<String>,
+    /// Unix timestamps (ms) matching `frames`
+    pub timestamps: Vec<u64>,
+}
+
+struct Shared {
+    stop: AtomicBool,
+    paused: AtomicBool,
+    target_fps: AtomicU32,
+    /// Held for the duration of each grab so `pause()` can wait it out
+    grab_lock: Mutex<()>,
+    frame_tx: Mutex<Option<watch::Sender<Frame>>>,
+    frame_rx: watch::Receiver<Frame>,
+    recording: Mutex<Option<RecordedBuffer>>,
+
+    frames_grabbed: AtomicU64,
+    frames_empty: AtomicU64,
+    grab_errors: AtomicU64,
+    late_ticks: AtomicU64,
+    frames_sent: AtomicU64,
+    frames_dropped: AtomicU64,
+    clients: AtomicU32,
+    /// Measured FPS × 100 (updated once per second by the pump)
+    measured_fps_x100: AtomicU32,
+}
+
+impl Shared {
+    fn new(target_fps: u32) -> Self {
+        let (tx, rx) = watch::channel(Frame::default());
+        Self {
+            stop: AtomicBool::new(false),
+            paused: AtomicBool::new(false),
+            target_fps: AtomicU32::new(target_fps),
+            grab_lock: Mutex::new(()),
+            frame_tx: Mutex::new(Some(tx)),
+            frame_rx: rx,
+            recording: Mutex::new(None),
+            frames_grabbed: AtomicU64::new(0),
+            frames_empty: AtomicU64::new(0),
+            grab_errors: AtomicU64::new(0),
+            late_ticks: AtomicU64::new(0),
+            frames_sent: AtomicU64::new(0),
+            frames_dropped: AtomicU64::new(0),
+            clients: AtomicU32::new(0),
+            measured_fps_x100: AtomicU32::new(0),
+        }
+    }
+
+    fn latest(&self) -> Frame {
+        self.frame_rx.borrow().clone()
+    }
+
+    fn publish(&self, jpeg: Vec<u8>) {
+        let jpeg = Arc::new(jpeg);
+        let timestamp_ms = now_ms();
+
+        if let Some(frames) = self.recording.lock().unwrap().as_mut() {
+            if frames.len() < MAX_RECORDED_FRAMES {
+                frames.push((timestamp_ms, jpeg.clone()));
+            }
+        }
+
+        if let Some(tx) = self.frame_tx.lock().unwrap().as_ref() {
+            tx.send_modify(|frame| {
+                frame.seq += 1;
+                frame.jpeg = jpeg;
+                frame.timestamp_ms = timestamp_ms;
+            });
+        }
+    }
+
+    fn stats(&self, running: bool) -> LiveViewStats {
+        LiveViewStats {
+            running,
+            paused: self.paused.load(Ordering::SeqCst),
+            target_fps: self.target_fps.load(Ordering::SeqCst),
+            measured_fps: self.measured_fps_x100.load(Ordering::SeqCst) as f32 / 100.0,
+            last_frame_seq: self.frame_rx.borrow().seq,
+            frames_grabbed: self.frames_grabbed.load(Ordering::SeqCst),
+            frames_empty: self.frames_empty.load(Ordering::SeqCst),
+            grab_errors: self.grab_errors.load(Ordering::SeqCst),
+            late_ticks: self.late_ticks.load(Ordering::SeqCst),
+            frames_sent: self.frames_sent.load(Ordering::SeqCst),
+            frames_dropped: self.frames_dropped.load(Ordering::SeqCst),
+            clients: self.clients.load(Ordering::SeqCst),
+        }
+    }
+}
+
+struct LiveViewStream {
+    shared: Arc<Shared>,
+    port: u16,
+    pump: Option<JoinHandle<()>>,
+}
+
+static STREAM: Mutex<Option<LiveViewStream>> = Mutex::new(None);
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn clamp_fps(fps: u32) -> u32 {
+    fps.clamp(1, MAX_TARGET_FPS)
+}
+
+// =============================================================================
+// Pump
+// =============================================================================
+
+fn run_pump(shared: Arc<Shared>) {
+    let mut next_tick = Instant::now();
+    let mut window_start = Instant::now();
+    let mut window_frames: u32 = 0;
+
+    while !shared.stop.load(Ordering::SeqCst) {
+        let interval =
+            Duration::from_secs_f64(1.0 / shared.target_fps.load(Ordering::SeqCst) as f64);
+
+        if shared.paused.load(Ordering::SeqCst) {
+            std::thread::sleep(PAUSED_POLL_INTERVAL);
+            next_tick = Instant::now();
+            continue;
+        }
+
+        {
+            let _grab = shared.grab_lock.lock().unwrap();
+            // Re-check under the lock: pause() may have won the race
+            if !shared.paused.load(Ordering::SeqCst) {
+                match backend().live_view_frame() {
+                    Ok(Some(jpeg)) => {
+                        shared.frames_grabbed.fetch_add(1, Ordering::SeqCst);
+                        shared.publish(jpeg);
+                        window_frames += 1;
+                    }
+                    Ok(None) => {
+                        shared.frames_empty.fetch_add(1, Ordering::SeqCst);
+                    }
+                    Err(_) => {
+                        // Normal during LV start/stop transitions
+                        shared.grab_errors.fetch_add(1, Ordering::SeqCst);
+                    }
+                }
+            }
+        }
+
+        let elapsed = window_start.elapsed();
+        if elapsed >= Duration::from_secs(1) {
+            let fps = window_frames as f64 / elapsed.as_secs_f64();
+            shared
+                .measured_fps_x100
+                .store((fps * 100.0).round() as u32, Ordering::SeqCst);
+            window_start = Instant::now();
+            window_frames = 0;
+        }
+
+        next_tick += interval;
+        let now = Instant::now();
+        if next_tick > now {
+            std::thread::sleep(next_tick - now);
+        } else {
+            let behind = (now - next_tick).as_nanos() / interval.as_nanos().max(1);
+            shared.late_ticks.fetch_add(behind as u64, Ordering::SeqCst);
+            next_tick = now;
+        }
+    }
+
+    info!("[LiveView] Pump stopped");
+}
+
+// =============================================================================
+// HTTP server
+// =============================================================================
+
+async fn run_server(listener: TcpListener, shared: Arc<Shared>) {
+    let mut shutdown = shared.frame_rx.clone();
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((socket, _)) => {
+                    tokio::spawn(handle_client(socket, shared.clone()));
+                }
+                Err(e) => warn!("[LiveView] Accept failed: {}", e),
+            },
+            // Errors once the sender has been dropped by stop()
+            changed = shutdown.changed() => {
+                if changed.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+    info!("[LiveView] Server stopped");
+}
+
+/// Read the request head and return the request path
+async fn read_request_path(socket: &mut TcpStream) -> Option<String> {
+    let mut head = Vec::with_capacity(512);
+    let mut buf = [0u8; 512];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = socket.read(&mut buf).await.ok()?;
+        if n == 0 || head.len() + n > MAX_REQUEST_HEAD {
+            return None;
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+    let head = String::from_utf8_lossy(&head);
+    let mut parts = head.lines().next()?.split_whitespace();
+    let method = parts.next()?;
+    let target = parts.next()?;
+    if method != "GET" {
+        return None;
+    }
+    Some(target.split('?').next().unwrap_or(target).to_string())
+}
+
+async fn write_response(
+    socket: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &[u8],
+) -> std::io::Result<()> {
+    let header = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    );
+    socket.write_all(header.as_bytes()).await?;
+    socket.write_all(body).await?;
+    socket.flush().await
+}
+
+async fn handle_client(mut socket: TcpStream, shared: Arc<Shared>) {
+    let _ = socket.set_nodelay(true);
+    let Some(path) = read_request_path(&mut socket).await else {
+        return;
+    };
+
+    let _ = match path.as_str() {
+        "/" | "/stream" => stream_mjpeg(socket, shared).await,
+        "/frame.jpg" => {
+            let frame = shared.latest();
+            if frame.seq == 0 {
+                write_response(&mut socket, "204 No Content", "image/jpeg", &[]).await
+            } else {
+                write_response(&mut socket, "200 OK", "image/jpeg", &frame.jpeg).await
+            }
+        }
+        "/stats" => {
+            let running = !shared.stop.load(Ordering::SeqCst);
+            let body = serde_json::to_vec(&shared.stats(running)).unwrap_or_default();
+            write_response(&mut socket, "200 OK", "application/json", &body).await
+        }
+        _ => write_response(&mut socket, "404 Not Found", "text/plain", b"Not Found").await,
+    };
+}
+
+/// Decrements the client counter when a stream connection ends
+struct ClientGuard(Arc<Shared>);
+
+impl Drop for ClientGuard {
+    fn drop(&mut self) {
+        self.0.clients.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+async fn stream_mjpeg(mut socket: TcpStream, shared: Arc<Shared>) -> std::io::Result<()> {
+    shared.clients.fetch_add(1, Ordering::SeqCst);
+    let _guard = ClientGuard(shared.clone());
+
+    let header = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: multipart/x-mixed-replace; boundary={}\r\nCache-Control: no-store\r\nPragma: no-cache\r\nConnection: close\r\n\r\n",
+        MJPEG_BOUNDARY
+    );
+    socket.write_all(header.as_bytes()).await?;
+
+    let mut rx = shared.frame_rx.clone();
+    let mut last_seq = 0u64;
+    loop {
+        let frame = {
+            let frame = rx.borrow_and_update();
+            (frame.seq > last_seq).then(|| frame.clone())
+        };
+
+        match frame {
+            Some(frame) => {
+                // The watch channel only keeps the newest frame: anything
+                // published while we were writing is skipped for this client
+                if last_seq != 0 && frame.seq > last_seq + 1 {
+                    shared
+                        .frames_dropped
+                        .fetch_add(frame.seq - last_seq - 1, Ordering::SeqCst);
+                }
+                last_seq = frame.seq;
+
+                let part = format!(
+                    "--{}\r\nContent-Type: image/jpeg\r\nContent-Length: {}\r\n\r\n",
+                    MJPEG_BOUNDARY,
+                    frame.jpeg.len()
+                );
+                socket.write_all(part.as_bytes()).await?;
+                socket.write_all(&frame.jpeg).await?;
+                socket.write_all(b"\r\n").await?;
+                socket.flush().await?;
+                shared.frames_sent.fetch_add(1, Ordering::SeqCst);
+            }
+            None => {
+                if rx.changed().await.is_err() {
+                    // Stream stopped
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+// =============================================================================
+// Lifecycle
+// =============================================================================
+
+fn start(fps: u32) -> Result<LiveViewStreamInfo, String> {
+    let mut slot = STREAM.lock().unwrap();
+
+    if let Some(stream) = slot.as_ref() {
+        stream.shared.target_fps.store(fps, Ordering::SeqCst);
+        stream.shared.paused.store(false, Ordering::SeqCst);
+        return Ok(info_for(stream));
+    }
+
+    let listener = std::net::TcpListener::bind(("127.0.0.1", 0))
+        .map_err(|e| format!("Failed to bind live view server: {}", e))?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| format!("Failed to configure live view server: {}", e))?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+
+    let shared = Arc::new(Shared::new(fps));
+
+    let server_shared = shared.clone();
+    tauri::async_runtime::spawn(async move {
+        match TcpListener::from_std(listener) {
+            Ok(listener) => run_server(listener, server_shared).await,
+            Err(e) => warn!("[LiveView] Failed to start server: {}", e),
+        }
+    });
+
+    let pump_shared = shared.clone();
+    let pump = std::thread::Builder::new()
+        .name("live-view-pump".to_string())
+        .spawn(move || run_pump(pump_shared))
+        .map_err(|e| format!("Failed to start live view pump: {}", e))?;
+
+    let stream = LiveViewStream {
+        shared,
+        port,
+        pump: Some(pump),
+    };
+    let info = info_for(&stream);
+    info!(
+        "[LiveView] Streaming at {} (target {} fps)",
+        info.url, info.target_fps
+    );
+    *slot = Some(stream);
+    Ok(info)
+}
+
+fn info_for(stream: &LiveViewStream) -> LiveViewStreamInfo {
+    LiveViewStreamInfo {
+        url: format!("http://127.0.0.1:{}/stream", stream.port),
+        port: stream.port,
+        target_fps: stream.shared.target_fps.load(Ordering::SeqCst),
+    }
+}
+
+fn stop() {
+    let Some(mut stream) = STREAM.lock().unwrap().take() else {
+        return;
+    };
+    stream.shared.stop.store(true, Ordering::SeqCst);
+    if let Some(pump) = stream.pump.take() {
+        let _ = pump.join();
+    }
+    // Dropping the sender ends every client stream and the accept loop
+    stream.shared.frame_tx.lock().unwrap().take();
+    info!("[LiveView] Stream stopped");
+}
+
+fn with_shared<T>(f: impl FnOnce(&Arc<Shared>) -> T) -> Option<T> {
+    STREAM.lock().unwrap().as_ref().map(|s| f(&s.shared))
+}
+
+/// Set the pause flag and wait for any in-flight grab. Returns the previous flag.
+fn pause_shared(shared: &Shared) -> bool {
+    let was_paused = shared.paused.swap(true, Ordering::SeqCst);
+    drop(shared.grab_lock.lock().unwrap());
+    was_paused
+}
+
+/// Keeps the pump paused while alive; restores the previous state on drop
+pub(crate) struct PauseGuard(Option<(Arc<Shared>, bool)>);
+
+impl Drop for PauseGuard {
+    fn drop(&mut self) {
+        if let Some((shared, was_paused)) = self.0.take() {
+            shared.paused.store(was_paused, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Pause the pump for an exclusive camera operation (no-op if it isn't running)
+pub(crate) fn pause_for_capture() -> PauseGuard {
+    PauseGuard(with_shared(|shared| {
+        let was_paused = pause_shared(shared);
+        (Arc::clone(shared), was_paused)
+    }))
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+/// Start (or resume) the live view pump and return the MJPEG stream URL.
+/// Call after `canon_start_live_view`.
+#[tauri::command]
+pub fn live_view_stream_start(fps: Option<u32>) -> Result<LiveViewStreamInfo, String> {
+    start(clamp_fps(fps.unwrap_or(DEFAULT_TARGET_FPS)))
+}
+
+/// Stop the pump and close the local server
+#[tauri::command]
+pub fn live_view_stream_stop() {
+    stop();
+}
+
+/// Pause frame grabbing without dropping stream clients. Blocks until any
+/// in-flight grab has finished so the caller has exclusive camera access.
+#[tauri::command]
+pub fn live_view_stream_pause() {
+    with_shared(|shared| pause_shared(shared));
+}
+
+#[tauri::command]
+pub fn live_view_stream_resume() {
+    with_shared(|shared| shared.paused.store(false, Ordering::SeqCst));
+}
+
+#[tauri::command]
+pub fn live_view_stream_set_fps(fps: u32) -> Result<u32, String> {
+    let fps = clamp_fps(fps);
+    with_shared(|shared| shared.target_fps.store(fps, Ordering::SeqCst))
+        .ok_or("Live view stream not running")?;
+    Ok(fps)
+}
+
+#[tauri::command]
+pub fn live_view_stream_stats() -> LiveViewStats {
+    with_shared(|shared| shared.stats(true)).unwrap_or_else(|| Shared::new(0).stats(false))
+}
+
+/// Start buffering pumped frames (fallback when movie recording fails)
+#[tauri::command]
+pub fn live_view_stream_record_start() -> Result<(), String> {
+    with_shared(|shared| *shared.recording.lock().unwrap() = Some(Vec::new()))
+        .ok_or_else(|| "Live view stream not running".to_string())
+}
+
+/// Stop buffering and write the recorded frames to a `recording-…` folder
+/// in the session folder; returns their paths
+#[tauri::command]
+pub fn live_view_stream_record_stop() -> Result<RecordedFrames, String> {
+    let frames = with_shared(|shared| shared.recording.lock().unwrap().take())
+        .flatten()
+        .unwrap_or_default();
+
+    let mut recorded = RecordedFrames {
+        frames: Vec::with_capacity(frames.len()),
+        timestamps: Vec::with_capacity(frames.len()),
+    };
+    if frames.is_empty() {
+        return Ok(recorded);
+    }
+    let dir = crate::workdir::new_dir("recording")?;
+    for (index, (timestamp_ms, jpeg)) in frames.into_iter().enumerate() {
+        let path = dir.join(format!("frame-{:04}.jpg", index));
+        std::fs::write(&path, jpeg.as_slice()).map_err(|e| format!("Write frame error: {}", e))?;
+        recorded.frames.push(path.to_string_lossy().to_string());
+        recorded.timestamps.push(timestamp_ms);
+    }
+    info!("[LiveView] Recorded {} frames to {}", recorded.frames.len(), dir.display());
+    Ok(recorded)
+}
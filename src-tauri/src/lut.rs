@@ -0,0 +1,850 @@
+//! LUT Registry
+//!
+//! Supported filter files:
+//! - `.cube` with `LUT_3D_SIZE`, `LUT_1D_SIZE`, or both (1D shaper + 3D cube)
+//! - `.png` Hald CLUT images (square, side = level³)
+//!
+//! Parses filter files once per process and caches them keyed by path +
+//! modification time, so re-rendering the filter strip never touches the disk
+//! again unless the file actually changed. The cache keeps the
+//! `MAX_CACHED_LUTS` most recently used entries.
+//!
+//! Each cached LUT can also carry a baked lookup table for fast per-pixel
+//! mapping of 8-bit images:
+//! - `Grid64` — the cube resampled onto a 64³ grid (~3 MB)
+//! - `Full8Bit` — a direct 256³ RGB → RGB table, one lookup per pixel (~48 MB)
+//!
+//! Baking is opt-in via the `BONIO_LUT_BAKE` environment variable
+//! (`none` / `grid64` / `full`); by default every pixel samples the source
+//! cube, so output matches the unbaked filter exactly.
+//! Interpolation (trilinear or tetrahedral) is chosen per call via
+//! `load_interpolated`, typically from the filter's manifest entry, and is
+//! used both for sampling the source cube and for looking up a baked grid.
+//! `BONIO_LUT_INTERP=tetrahedral` only changes the default (trilinear).
+//!
+//! Images are processed on the raw RGBA buffer in row bands spread across
+//! all cores with rayon.
+
+use image::RgbaImage;
+use log::info;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::SystemTime;
+
+const BAKE_ENV_VAR: &str = "BONIO_LUT_BAKE";
+const INTERP_ENV_VAR: &str = "BONIO_LUT_INTERP";
+const MIN_LUT_SIZE: usize = 2;
+const MAX_LUT_SIZE: usize = 256;
+const MAX_LUT_1D_SIZE: usize = 65536;
+/// Cube size used when baking a 1D-only LUT for FFmpeg
+const EXPORT_CUBE_SIZE: usize = 33;
+const BAKED_GRID_SIZE: usize = 64;
+/// Registry capacity — a full 256³ bake is ~48 MB, so keep this small
+const MAX_CACHED_LUTS: usize = 8;
+/// Rows handed to each rayon task — big enough to amortize scheduling,
+/// small enough to balance across cores on a 3600px image
+const ROWS_PER_TASK: usize = 16;
+
+// =============================================================================
+// LUT types
+// =============================================================================
+
+/// Per-channel curve, e.g. a `LUT_1D_SIZE` cube or the shaper of a 1D+3D cube
+#[derive(Debug, Clone)]
+pub struct Lut1D {
+    pub size: usize,
+    pub data: Vec<[f32; 3]>,
+    pub domain_min: [f32; 3],
+    pub domain_max: [f32; 3],
+}
+
+#[derive(Debug, Clone)]
+pub struct Lut3D {
+    pub size: usize,
+    /// Red-fastest order: index = b * size² + g * size + r
+    pub data: Vec<[f32; 3]>,
+    pub domain_min: [f32; 3],
+    pub domain_max: [f32; 3],
+}
+
+/// Which kind of file a LUT was loaded from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LutKind {
+    Cube3D,
+    Cube1D,
+    /// 1D shaper followed by a 3D cube in the same .cube file
+    Cube1D3D,
+    HaldClut,
+}
+
+impl LutKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LutKind::Cube3D => "cube_3d",
+            LutKind::Cube1D => "cube_1d",
+            LutKind::Cube1D3D => "cube_1d_3d",
+            LutKind::HaldClut => "hald_clut",
+        }
+    }
+}
+
+/// A colour transform: optional 1D shaper, then optional 3D cube
+#[derive(Debug, Clone)]
+pub struct ColorLut {
+    pub kind: LutKind,
+    pub title: Option<String>,
+    pub shaper: Option<Lut1D>,
+    pub cube: Option<Lut3D>,
+}
+
+/// Parse three whitespace-separated floats, e.g. `DOMAIN_MIN 0 0 0`
+fn parse_triplet(values: &str, keyword: &str, line_no: usize) -> Result<[f32; 3], String> {
+    let values: Vec<f32> = values
+        .split_whitespace()
+        .map(|v| v.parse::<f32>())
+        .collect::<Result<_, _>>()
+        .map_err(|e| format!("Line {}: invalid {} value: {}", line_no, keyword, e))?;
+    if values.len() != 3 {
+        return Err(format!(
+            "Line {}: {} expects 3 values, got {}",
+            line_no,
+            keyword,
+            values.len()
+        ));
+    }
+    Ok([values[0], values[1], values[2]])
+}
+
+/// Parse `LUT_xD_INPUT_RANGE min max` into per-channel domain bounds
+fn parse_input_range(values: &str, line_no: usize) -> Result<([f32; 3], [f32; 3]), String> {
+    let range: Vec<f32> = values
+        .split_whitespace()
+        .filter_map(|v| v.parse().ok())
+        .collect();
+    if range.len() != 2 {
+        return Err(format!("Line {}: invalid input range", line_no));
+    }
+    Ok(([range[0]; 3], [range[1]; 3]))
+}
+
+fn parse_size(values: &str, keyword: &str, line_no: usize, max: usize) -> Result<usize, String> {
+    let size: usize = values
+        .trim()
+        .parse()
+        .map_err(|e| format!("Line {}: invalid {}: {}", line_no, keyword, e))?;
+    if !(MIN_LUT_SIZE..=max).contains(&size) {
+        return Err(format!(
+            "{} {} out of range ({}-{})",
+            keyword, size, MIN_LUT_SIZE, max
+        ));
+    }
+    Ok(size)
+}
+
+fn check_domain(min: [f32; 3], max: [f32; 3]) -> Result<(), String> {
+    for c in 0..3 {
+        if max[c] <= min[c] {
+            return Err(format!(
+                "DOMAIN_MAX must be greater than DOMAIN_MIN (channel {})",
+                c
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Map input into a LUT domain, normalized to 0..1
+fn normalize(rgb: [f32; 3], min: [f32; 3], max: [f32; 3]) -> [f32; 3] {
+    let mut out = [0.0f32; 3];
+    for c in 0..3 {
+        out[c] = ((rgb[c] - min[c]) / (max[c] - min[c])).clamp(0.0, 1.0);
+    }
+    out
+}
+
+impl Lut1D {
+    /// Linear interpolation along each channel's curve
+    pub fn sample(&self, rgb: [f32; 3]) -> [f32; 3] {
+        let unit = normalize(rgb, self.domain_min, self.domain_max);
+        let mut out = [0.0f32; 3];
+        for c in 0..3 {
+            let (i0, i1, f) = cell(unit[c], self.size);
+            out[c] = self.data[i0][c] + (self.data[i1][c] - self.data[i0][c]) * f;
+        }
+        out
+    }
+}
+
+impl Lut3D {
+    /// Load a plain 3D .cube file (errors if the file has no 3D part)
+    pub fn parse_cube_file(path: &str) -> Result<Self, String> {
+        ColorLut::load_file(path)?
+            .cube
+            .ok_or_else(|| format!("{} is not a 3D LUT", path))
+    }
+
+    /// Look up one colour with the given interpolation. Input and output are 0..1.
+    pub fn sample(&self, rgb: [f32; 3], interpolation: Interpolation) -> [f32; 3] {
+        let [r, g, b] = normalize(rgb, self.domain_min, self.domain_max);
+        let out = match interpolation {
+            Interpolation::Trilinear => trilinear(&self.data, self.size, r, g, b),
+            Interpolation::Tetrahedral => tetrahedral(&self.data, self.size, r, g, b),
+        };
+        [
+            out[0].clamp(0.0, 1.0),
+            out[1].clamp(0.0, 1.0),
+            out[2].clamp(0.0, 1.0),
+        ]
+    }
+
+    /// Trilinear lookup. Input and output are 0..1.
+    pub fn apply(&self, r: f32, g: f32, b: f32) -> (f32, f32, f32) {
+        let [r, g, b] = self.sample([r, g, b], Interpolation::Trilinear);
+        (r, g, b)
+    }
+
+    /// Tetrahedral lookup. Input and output are 0..1.
+    pub fn apply_tetrahedral(&self, r: f32, g: f32, b: f32) -> (f32, f32, f32) {
+        let [r, g, b] = self.sample([r, g, b], Interpolation::Tetrahedral);
+        (r, g, b)
+    }
+}
+
+impl ColorLut {
+    /// Load a filter file by extension: `.cube` (1D, 3D or 1D+3D) or `.png` (Hald CLUT)
+    pub fn load_file(path: &str) -> Result<Self, String> {
+        let ext = Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+        match ext.as_str() {
+            "cube" => {
+                let content = fs::read_to_string(path)
+                    .map_err(|e| format!("Read LUT file error: {}", e))?;
+                Self::parse_cube(&content)
+            }
+            "png" => Self::parse_hald(path),
+            _ => Err(format!("Unsupported LUT format: .{}", ext)),
+        }
+        .map_err(|e| format!("Invalid LUT {}: {}", path, e))
+    }
+
+    /// Parse a .cube file. With both `LUT_1D_SIZE` and `LUT_3D_SIZE` present
+    /// the 1D entries come first and act as a shaper in front of the cube;
+    /// `DOMAIN_MIN/MAX` then apply to the shaper and the cube uses
+    /// `LUT_3D_INPUT_RANGE` (default 0..1).
+    pub fn parse_cube(content: &str) -> Result<Self, String> {
+        let mut title = None;
+        let mut size_1d: usize = 0;
+        let mut size_3d: usize = 0;
+        let mut domain: Option<([f32; 3], [f32; 3])> = None;
+        let mut range_1d: Option<([f32; 3], [f32; 3])> = None;
+        let mut range_3d: Option<([f32; 3], [f32; 3])> = None;
+        let mut data: Vec<[f32; 3]> = Vec::new();
+
+        for (i, line) in content.lines().enumerate() {
+            let line_no = i + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            match keyword {
+                "TITLE" => {
+                    title = Some(rest.trim().trim_matches('"').to_string());
+                }
+                "LUT_1D_SIZE" => {
+                    size_1d = parse_size(rest, keyword, line_no, MAX_LUT_1D_SIZE)?;
+                }
+                "LUT_3D_SIZE" => {
+                    size_3d = parse_size(rest, keyword, line_no, MAX_LUT_SIZE)?;
+                    data.reserve(size_3d * size_3d * size_3d);
+                }
+                "DOMAIN_MIN" => {
+                    let min = parse_triplet(rest, keyword, line_no)?;
+                    domain = Some((min, domain.map_or([1.0; 3], |d| d.1)));
+                }
+                "DOMAIN_MAX" => {
+                    let max = parse_triplet(rest, keyword, line_no)?;
+                    domain = Some((domain.map_or([0.0; 3], |d| d.0), max));
+                }
+                "LUT_1D_INPUT_RANGE" => range_1d = Some(parse_input_range(rest, line_no)?),
+                "LUT_3D_INPUT_RANGE" => range_3d = Some(parse_input_range(rest, line_no)?),
+                _ if keyword.starts_with(|c: char| c.is_ascii_alphabetic()) => {
+                    // Unknown keyword — ignore like other .cube readers do
+                }
+                _ => data.push(parse_triplet(line, "data", line_no)?),
+            }
+        }
+
+        if size_1d == 0 && size_3d == 0 {
+            return Err("Missing LUT_1D_SIZE / LUT_3D_SIZE".to_string());
+        }
+        let count_3d = size_3d * size_3d * size_3d;
+        let expected = size_1d + count_3d;
+        if data.len() != expected {
+            return Err(match (size_1d, size_3d) {
+                (0, s) => format!("LUT_3D_SIZE {} expects {} entries, found {}", s, expected, data.len()),
+                (s, 0) => format!("LUT_1D_SIZE {} expects {} entries, found {}", s, expected, data.len()),
+                (s1, s3) => format!(
+                    "LUT_1D_SIZE {} + LUT_3D_SIZE {} expects {} entries, found {}",
+                    s1, s3, expected, data.len()
+                ),
+            });
+        }
+
+        let unit = ([0.0f32; 3], [1.0f32; 3]);
+        let combined = size_1d > 0 && size_3d > 0;
+        // DOMAIN_* belongs to the first stage of the file
+        let (shaper_domain, cube_domain) = if combined {
+            (range_1d.or(domain).unwrap_or(unit), range_3d.unwrap_or(unit))
+        } else if size_1d > 0 {
+            (range_1d.or(domain).unwrap_or(unit), unit)
+        } else {
+            (unit, range_3d.or(domain).unwrap_or(unit))
+        };
+        check_domain(shaper_domain.0, shaper_domain.1)?;
+        check_domain(cube_domain.0, cube_domain.1)?;
+
+        let cube_data = data.split_off(size_1d);
+        let shaper = (size_1d > 0).then_some(Lut1D {
+            size: size_1d,
+            data,
+            domain_min: shaper_domain.0,
+            domain_max: shaper_domain.1,
+        });
+        let cube = (size_3d > 0).then_some(Lut3D {
+            size: size_3d,
+            data: cube_data,
+            domain_min: cube_domain.0,
+            domain_max: cube_domain.1,
+        });
+
+        Ok(ColorLut {
+            kind: match (shaper.is_some(), cube.is_some()) {
+                (true, true) => LutKind::Cube1D3D,
+                (true, false) => LutKind::Cube1D,
+                _ => LutKind::Cube3D,
+            },
+            title,
+            shaper,
+            cube,
+        })
+    }
+
+    /// Parse a Hald CLUT image: a square of side L³ holding an (L²)³ cube in
+    /// red-fastest pixel order — the same order as .cube data.
+    pub fn parse_hald(path: &str) -> Result<Self, String> {
+        let (width, height) = image::image_dimensions(path)
+            .map_err(|e| format!("Read Hald CLUT error: {}", e))?;
+        let level = hald_level(width, height).ok_or_else(|| {
+            format!("{}x{} is not a valid Hald CLUT size", width, height)
+        })?;
+
+        let img = image::open(path)
+            .map_err(|e| format!("Read Hald CLUT error: {}", e))?
+            .to_rgb32f();
+        let data = img.pixels().map(|p| [p[0], p[1], p[2]]).collect();
+
+        Ok(ColorLut {
+            kind: LutKind::HaldClut,
+            title: None,
+            shaper: None,
+            cube: Some(Lut3D {
+                size: (level * level) as usize,
+                data,
+                domain_min: [0.0; 3],
+                domain_max: [1.0; 3],
+            }),
+        })
+    }
+
+    /// Run a colour through the shaper and cube. Input and output are 0..1.
+    pub fn sample(&self, rgb: [f32; 3], interpolation: Interpolation) -> [f32; 3] {
+        let rgb = match &self.shaper {
+            Some(shaper) => shaper.sample(rgb),
+            None => rgb,
+        };
+        match &self.cube {
+            Some(cube) => cube.sample(rgb, interpolation),
+            None => [
+                rgb[0].clamp(0.0, 1.0),
+                rgb[1].clamp(0.0, 1.0),
+                rgb[2].clamp(0.0, 1.0),
+            ],
+        }
+    }
+
+    /// Whether FFmpeg's `lut3d` filter can read the source file as-is
+    pub fn is_plain_cube(&self) -> bool {
+        self.kind == LutKind::Cube3D
+    }
+
+    /// Bake the full transform into a 3D .cube file FFmpeg's `lut3d` accepts.
+    /// Keeps the native cube size when there is one, otherwise 33³.
+    /// `intensity` (0..1) is baked in as a blend with the identity, which is
+    /// the same per-pixel blend `apply_to_rgba` does for stills.
+    pub fn write_cube_3d(&self, out_path: &Path, intensity: f32) -> Result<(), String> {
+        let t = intensity.clamp(0.0, 1.0);
+        let size = self
+            .cube
+            .as_ref()
+            .map_or(EXPORT_CUBE_SIZE, |c| c.size.min(BAKED_GRID_SIZE));
+        let step = 1.0 / (size - 1) as f32;
+
+        let mut out = String::with_capacity(size * size * size * 28 + 64);
+        out.push_str(&format!(
+            "TITLE \"{}\"\nLUT_3D_SIZE {}\n",
+            self.title.as_deref().unwrap_or("Bonio baked LUT"),
+            size
+        ));
+        for b in 0..size {
+            for g in 0..size {
+                for r in 0..size {
+                    let orig = [r as f32 * step, g as f32 * step, b as f32 * step];
+                    let mapped = self.sample(orig, Interpolation::Trilinear);
+                    let [nr, ng, nb] = [0, 1, 2].map(|c| orig[c] + (mapped[c] - orig[c]) * t);
+                    out.push_str(&format!("{:.6} {:.6} {:.6}\n", nr, ng, nb));
+                }
+            }
+        }
+        fs::write(out_path, out).map_err(|e| format!("Write baked LUT error: {}", e))
+    }
+}
+
+/// Hald level L for a square image of side L³ (L = 2..=16)
+fn hald_level(width: u32, height: u32) -> Option<u32> {
+    if width != height {
+        return None;
+    }
+    (2..=16u32).find(|l| l * l * l == width)
+}
+
+/// Cheap check used when listing the filters folder — only reads the PNG header
+pub fn is_hald_png(path: &Path) -> bool {
+    image::image_dimensions(path)
+        .map(|(w, h)| hald_level(w, h).is_some())
+        .unwrap_or(false)
+}
+
+/// Grid cell containing a normalized coordinate: (lower index, upper index, fraction)
+fn cell(v: f32, size: usize) -> (usize, usize, f32) {
+    let max_idx = (size - 1) as f32;
+    let pos = (v * max_idx).clamp(0.0, max_idx);
+    let i0 = pos.floor() as usize;
+    let i1 = (i0 + 1).min(size - 1);
+    (i0, i1, pos - i0 as f32)
+}
+
+fn trilinear(data: &[[f32; 3]], size: usize, r: f32, g: f32, b: f32) -> [f32; 3] {
+    let (r0, r1, rf) = cell(r, size);
+    let (g0, g1, gf) = cell(g, size);
+    let (b0, b1, bf) = cell(b, size);
+    let idx = |r: usize, g: usize, b: usize| -> usize { b * size * size + g * size + r };
+
+    let c000 = data[idx(r0, g0, b0)];
+    let c100 = data[idx(r1, g0, b0)];
+    let c010 = data[idx(r0, g1, b0)];
+    let c110 = data[idx(r1, g1, b0)];
+    let c001 = data[idx(r0, g0, b1)];
+    let c101 = data[idx(r1, g0, b1)];
+    let c011 = data[idx(r0, g1, b1)];
+    let c111 = data[idx(r1, g1, b1)];
+
+    let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+
+    let mut result = [0.0f32; 3];
+    for i in 0..3 {
+        let c00 = lerp(c000[i], c100[i], rf);
+        let c10 = lerp(c010[i], c110[i], rf);
+        let c01 = lerp(c001[i], c101[i], rf);
+        let c11 = lerp(c011[i], c111[i], rf);
+        let c0 = lerp(c00, c10, gf);
+        let c1 = lerp(c01, c11, gf);
+        result[i] = lerp(c0, c1, bf);
+    }
+    result
+}
+
+/// Tetrahedral interpolation — splits each cell into six tetrahedra and
+/// blends only four corners, which is cheaper than trilinear and keeps the
+/// grey axis exact.
+fn tetrahedral(data: &[[f32; 3]], size: usize, r: f32, g: f32, b: f32) -> [f32; 3] {
+    let (r0, r1, fr) = cell(r, size);
+    let (g0, g1, fg) = cell(g, size);
+    let (b0, b1, fb) = cell(b, size);
+    let at = |r: usize, g: usize, b: usize| -> [f32; 3] { data[b * size * size + g * size + r] };
+
+    let c000 = at(r0, g0, b0);
+    let c111 = at(r1, g1, b1);
+
+    // (first corner, second corner, weights) for the tetrahedron containing the point
+    let (ca, cb, w0, w1, w2, w3) = if fr >= fg {
+        if fg >= fb {
+            (at(r1, g0, b0), at(r1, g1, b0), 1.0 - fr, fr - fg, fg - fb, fb)
+        } else if fr >= fb {
+            (at(r1, g0, b0), at(r1, g0, b1), 1.0 - fr, fr - fb, fb - fg, fg)
+        } else {
+            (at(r0, g0, b1), at(r1, g0, b1), 1.0 - fb, fb - fr, fr - fg, fg)
+        }
+    } else if fb >= fg {
+        (at(r0, g0, b1), at(r0, g1, b1), 1.0 - fb, fb - fg, fg - fr, fr)
+    } else if fb >= fr {
+        (at(r0, g1, b0), at(r0, g1, b1), 1.0 - fg, fg - fb, fb - fr, fr)
+    } else {
+        (at(r0, g1, b0), at(r1, g1, b0), 1.0 - fg, fg - fr, fr - fb, fb)
+    };
+
+    let mut result = [0.0f32; 3];
+    for i in 0..3 {
+        result[i] = w0 * c000[i] + w1 * ca[i] + w2 * cb[i] + w3 * c111[i];
+    }
+    result
+}
+
+#[inline]
+fn to_u8(v: f32) -> u8 {
+    (v.clamp(0.0, 1.0) * 255.0 + 0.5) as u8
+}
+
+// =============================================================================
+// Options
+// =============================================================================
+
+/// How the source cube (or baked grid) is sampled between grid points
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Interpolation {
+    Trilinear,
+    Tetrahedral,
+}
+
+impl Interpolation {
+    /// A filter's interpolation: from its manifest entry, else the default
+    pub fn or_default(interpolation: Option<Interpolation>) -> Interpolation {
+        interpolation.unwrap_or(default_options().interpolation)
+    }
+
+    /// Name as in `filters.json`, also FFmpeg's `lut3d` `interp` value
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Interpolation::Trilinear => "trilinear",
+            Interpolation::Tetrahedral => "tetrahedral",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BakeMode {
+    /// Interpolate the source cube for every pixel
+    None,
+    /// 64³ resampled grid, looked up with the same interpolation
+    Grid64,
+    /// Direct 256³ table — fastest, but ~48 MB per LUT
+    Full8Bit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LutOptions {
+    pub bake: BakeMode,
+    pub interpolation: Interpolation,
+}
+
+impl Default for LutOptions {
+    fn default() -> Self {
+        Self {
+            bake: BakeMode::None,
+            interpolation: Interpolation::Trilinear,
+        }
+    }
+}
+
+impl LutOptions {
+    /// Defaults overridden by `BONIO_LUT_BAKE` / `BONIO_LUT_INTERP`
+    fn from_env() -> Self {
+        let env = |key: &str| std::env::var(key).unwrap_or_default().trim().to_lowercase();
+        let mut options = Self::default();
+        match env(BAKE_ENV_VAR).as_str() {
+            "none" | "off" => options.bake = BakeMode::None,
+            "grid64" | "64" => options.bake = BakeMode::Grid64,
+            "full" | "full8bit" | "256" => options.bake = BakeMode::Full8Bit,
+            _ => {}
+        }
+        if matches!(env(INTERP_ENV_VAR).as_str(), "tetrahedral" | "tetra") {
+            options.interpolation = Interpolation::Tetrahedral;
+        }
+        options
+    }
+}
+
+static DEFAULT_OPTIONS: OnceLock<LutOptions> = OnceLock::new();
+
+fn default_options() -> LutOptions {
+    *DEFAULT_OPTIONS.get_or_init(LutOptions::from_env)
+}
+
+// =============================================================================
+// Baked tables
+// =============================================================================
+
+/// Filter strength as passed by the frontend (0–100, default 100) → 0..1
+pub fn intensity_from_percent(percent: Option<f32>) -> f32 {
+    percent.unwrap_or(100.0).clamp(0.0, 100.0) / 100.0
+}
+
+/// Write `orig + (out - orig) * t` back to an RGBA pixel (0..1 inputs)
+#[inline]
+fn blend_into(px: &mut [u8], orig: [f32; 3], out: [f32; 3], t: f32) {
+    for c in 0..3 {
+        px[c] = to_u8(orig[c] + (out[c] - orig[c]) * t);
+    }
+}
+
+#[inline]
+fn rgb8_to_unit(r: u8, g: u8, b: u8) -> [f32; 3] {
+    [r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0]
+}
+
+/// A parsed LUT plus its lazily-baked lookup tables
+pub struct CachedLut {
+    lut: ColorLut,
+    options: LutOptions,
+    grid64: OnceLock<Vec<[f32; 3]>>,
+    full: OnceLock<Vec<[u8; 3]>>,
+}
+
+impl CachedLut {
+    pub fn new(lut: ColorLut, options: LutOptions) -> Self {
+        Self {
+            lut,
+            options,
+            grid64: OnceLock::new(),
+            full: OnceLock::new(),
+        }
+    }
+
+    pub fn lut(&self) -> &ColorLut {
+        &self.lut
+    }
+
+    fn grid64(&self) -> &[[f32; 3]] {
+        self.grid64.get_or_init(|| {
+            let n = BAKED_GRID_SIZE;
+            let step = 1.0 / (n - 1) as f32;
+            (0..n * n * n)
+                .into_par_iter()
+                .map(|i| {
+                    let (r, g, b) = (i % n, (i / n) % n, i / (n * n));
+                    self.lut.sample(
+                        [r as f32 * step, g as f32 * step, b as f32 * step],
+                        self.options.interpolation,
+                    )
+                })
+                .collect()
+        })
+    }
+
+    fn full(&self) -> &[[u8; 3]] {
+        self.full.get_or_init(|| {
+            let started = std::time::Instant::now();
+            let table: Vec<[u8; 3]> = (0..256 * 256 * 256usize)
+                .into_par_iter()
+                .map(|i| {
+                    let out = self.lut.sample(
+                        rgb8_to_unit(i as u8, (i >> 8) as u8, (i >> 16) as u8),
+                        self.options.interpolation,
+                    );
+                    [to_u8(out[0]), to_u8(out[1]), to_u8(out[2])]
+                })
+                .collect();
+            info!("[LUT] Baked 256³ table in {:?}", started.elapsed());
+            table
+        })
+    }
+
+    /// Map one 8-bit RGB triple through the LUT using the baked table
+    #[inline]
+    pub fn apply_rgb8(&self, rgb: [u8; 3]) -> [u8; 3] {
+        let mut px = [rgb[0], rgb[1], rgb[2], 255];
+        self.apply_to_rgba(&mut px, 1.0);
+        [px[0], px[1], px[2]]
+    }
+
+    /// Apply the LUT to a raw RGBA buffer in place on the calling thread.
+    /// `intensity` (0..1) blends the LUT output with the original pixel.
+    /// The bake mode is resolved once so the per-pixel loop stays branch-free.
+    pub fn apply_to_rgba(&self, pixels: &mut [u8], intensity: f32) {
+        let t = intensity.clamp(0.0, 1.0);
+        if t <= 0.0 {
+            return;
+        }
+        match self.options.bake {
+            BakeMode::Full8Bit => {
+                let table = self.full();
+                for px in pixels.chunks_exact_mut(4) {
+                    let out = table
+                        [((px[2] as usize) << 16) | ((px[1] as usize) << 8) | px[0] as usize];
+                    if t >= 1.0 {
+                        px[..3].copy_from_slice(&out);
+                    } else {
+                        for c in 0..3 {
+                            let orig = px[c] as f32;
+                            px[c] = (orig + (out[c] as f32 - orig) * t + 0.5) as u8;
+                        }
+                    }
+                }
+            }
+            BakeMode::Grid64 => {
+                let grid = self.grid64();
+                let lookup = match self.options.interpolation {
+                    Interpolation::Trilinear => trilinear,
+                    Interpolation::Tetrahedral => tetrahedral,
+                };
+                for px in pixels.chunks_exact_mut(4) {
+                    let [r, g, b] = rgb8_to_unit(px[0], px[1], px[2]);
+                    let out = lookup(grid, BAKED_GRID_SIZE, r, g, b);
+                    blend_into(px, [r, g, b], out, t);
+                }
+            }
+            BakeMode::None => {
+                let interpolation = self.options.interpolation;
+                for px in pixels.chunks_exact_mut(4) {
+                    let orig = rgb8_to_unit(px[0], px[1], px[2]);
+                    let out = self.lut.sample(orig, interpolation);
+                    blend_into(px, orig, out, t);
+                }
+            }
+        }
+    }
+
+    /// Apply the LUT to every pixel of an RGBA image in place (alpha untouched),
+    /// processing bands of rows in parallel. `intensity` is 0..1.
+    pub fn apply_to_image(&self, img: &mut RgbaImage, intensity: f32) {
+        let row_len = img.width() as usize * 4;
+        if row_len == 0 || intensity <= 0.0 {
+            return;
+        }
+        // Bake up front so worker threads don't all block on the OnceLock
+        match self.options.bake {
+            BakeMode::Full8Bit => {
+                self.full();
+            }
+            BakeMode::Grid64 => {
+                self.grid64();
+            }
+            BakeMode::None => {}
+        }
+        let pixels: &mut [u8] = img;
+        pixels
+            .par_chunks_mut(row_len * ROWS_PER_TASK)
+            .for_each(|rows| self.apply_to_rgba(rows, intensity));
+    }
+}
+
+// =============================================================================
+// Registry
+// =============================================================================
+
+struct RegistryEntry {
+    modified: Option<SystemTime>,
+    len: u64,
+    lut: Arc<CachedLut>,
+    /// Registry tick of the last lookup, for LRU eviction
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct Registry {
+    entries: HashMap<RegistryKey, RegistryEntry>,
+    tick: u64,
+}
+
+/// Keyed by path and options so the same file can be cached with different bakes
+type RegistryKey = (PathBuf, LutOptions);
+
+static LUT_REGISTRY: OnceLock<Mutex<Registry>> = OnceLock::new();
+
+fn registry() -> &'static Mutex<Registry> {
+    LUT_REGISTRY.get_or_init(|| Mutex::new(Registry::default()))
+}
+
+/// Get a parsed LUT, re-parsing only if the file changed since it was cached
+pub fn load(path: &str) -> Result<Arc<CachedLut>, String> {
+    load_with(path, default_options())
+}
+
+/// Same as `load`, with an explicit interpolation (`None` = default)
+pub fn load_interpolated(
+    path: &str,
+    interpolation: Option<Interpolation>,
+) -> Result<Arc<CachedLut>, String> {
+    let mut options = default_options();
+    if let Some(interpolation) = interpolation {
+        options.interpolation = interpolation;
+    }
+    load_with(path, options)
+}
+
+/// Same as `load`, with explicit bake / interpolation options
+pub fn load_with(path: &str, options: LutOptions) -> Result<Arc<CachedLut>, String> {
+    let key = (Path::new(path).to_path_buf(), options);
+    let meta = fs::metadata(&key.0).map_err(|e| format!("LUT file not found: {} ({})", path, e))?;
+    let modified = meta.modified().ok();
+    let len = meta.len();
+
+    {
+        let mut reg = registry().lock().unwrap();
+        reg.tick += 1;
+        let tick = reg.tick;
+        if let Some(entry) = reg.entries.get_mut(&key) {
+            if entry.modified == modified && entry.len == len {
+                entry.last_used = tick;
+                return Ok(entry.lut.clone());
+            }
+        }
+    }
+
+    // Parse outside the lock so other LUTs stay available meanwhile
+    let lut = Arc::new(CachedLut::new(ColorLut::load_file(path)?, options));
+    info!(
+        "[LUT] Loaded {} ({:?}{})",
+        path,
+        lut.lut.kind,
+        lut.lut
+            .title
+            .as_deref()
+            .map(|t| format!(", \"{}\"", t))
+            .unwrap_or_default()
+    );
+
+    let mut reg = registry().lock().unwrap();
+    reg.tick += 1;
+    let last_used = reg.tick;
+    reg.entries.insert(
+        key,
+        RegistryEntry {
+            modified,
+            len,
+            lut: lut.clone(),
+            last_used,
+        },
+    );
+    while reg.entries.len() > MAX_CACHED_LUTS {
+        let Some(oldest) = reg
+            .entries
+            .iter()
+            .min_by_key(|(_, e)| e.last_used)
+            .map(|(k, _)| k.clone())
+        else {
+            break;
+        };
+        reg.entries.remove(&oldest);
+    }
+    Ok(lut)
+}
@@ -0,0 +1,348 @@
+//! Machine Configuration
+//!
+//! The settings that make a PC "this booth" — machine id and port, camera and
+//! its mount rotation, printer, paper position and API environment —
+//! persisted to `machine.json` in the app config dir. [`load`] runs from
+//! `AppState::new`, the `set_*` commands write the file back through [`save`]
+//! (via a temp file, so a crash or power cut never leaves it half written).
+//!
+//! The file carries a `version`. Older files are brought up to
+//! [`CONFIG_VERSION`] by [`MIGRATIONS`] on load (the original is kept as
+//! `machine.json.v<n>`); keys this build doesn't know — e.g. written by a
+//! newer app — are kept as they are.
+//!
+//! `export_machine_config` / `import_machine_config` copy the file between
+//! PCs to clone a configured booth.
+
+use crate::api::{AppState, PaperPositionConfig};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use tauri::{AppHandle, Manager};
+
+const CONFIG_FILE: &str = "machine.json";
+pub const CONFIG_VERSION: u32 = 1;
+
+/// `MIGRATIONS[n]` turns a version `n` file into version `n + 1`
+const MIGRATIONS: &[fn(&mut Map<String, Value>)] = &[v0_to_v1];
+
+static STORE: LazyLock<Mutex<Store>> = LazyLock::new(|| {
+    Mutex::new(Store {
+        path: None,
+        version: CONFIG_VERSION,
+        api_environment: None,
+        api_base_url: None,
+        updated_at: None,
+        extra: Map::new(),
+    })
+});
+
+/// What the file holds besides `AppState`'s settings. The lock also
+/// serializes writes.
+struct Store {
+    /// `None` when there is no app config dir
+    path: Option<PathBuf>,
+    /// Stored version, newer than [`CONFIG_VERSION`] for files of a newer app
+    version: u32,
+    /// API environment, owned by `backend`
+    api_environment: Option<String>,
+    api_base_url: Option<String>,
+    /// When the file was last written, `None` until it exists
+    updated_at: Option<String>,
+    extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MachineConfig {
+    pub version: u32,
+    pub machine_id: String,
+    pub machine_port: String,
+    /// "webcam" or "canon"
+    pub camera_type: String,
+    pub selected_webcam_id: String,
+    pub selected_camera_name: String,
+    /// Clockwise degrees the camera is mounted at (see `orientation`)
+    pub camera_mount_rotation: u32,
+    pub selected_printer: String,
+    pub paper_portrait: PaperPositionConfig,
+    pub paper_landscape: PaperPositionConfig,
+    /// See `backend` — `None` is production
+    pub api_environment: Option<String>,
+    pub api_base_url: Option<String>,
+    pub updated_at: Option<String>,
+    /// Keys from other app versions, written back untouched
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Default for MachineConfig {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            machine_id: String::new(),
+            machine_port: "44444".to_string(),
+            camera_type: "webcam".to_string(),
+            selected_webcam_id: String::new(),
+            selected_camera_name: String::new(),
+            camera_mount_rotation: 0,
+            selected_printer: String::new(),
+            paper_portrait: PaperPositionConfig::default(),
+            paper_landscape: PaperPositionConfig::default(),
+            api_environment: None,
+            api_base_url: None,
+            updated_at: None,
+            extra: Map::new(),
+        }
+    }
+}
+
+// =============================================================================
+// Migrations
+// =============================================================================
+
+/// Unversioned files (written by hand before the store existed) used
+/// `AppState`'s field names, and sometimes a numeric port
+fn v0_to_v1(config: &mut Map<String, Value>) {
+    const RENAMES: &[(&str, &str)] = &[
+        ("machine_id", "machineId"),
+        ("machine_port", "machinePort"),
+        ("camera_type", "cameraType"),
+        ("selected_webcam_id", "selectedWebcamId"),
+        ("selected_camera_name", "selectedCameraName"),
+        ("selected_printer", "selectedPrinter"),
+        ("paper_config_portrait", "paperPortrait"),
+        ("paper_config_landscape", "paperLandscape"),
+    ];
+    for (old, new) in RENAMES {
+        if let Some(value) = config.remove(*old) {
+            config.entry(*new).or_insert(value);
+        }
+    }
+    if let Some(port) = config.get("machinePort").and_then(Value::as_u64) {
+        config.insert("machinePort".to_string(), Value::String(port.to_string()));
+    }
+}
+
+/// Bring a config file of any version up to [`CONFIG_VERSION`]. Returns the
+/// config and the version it was stored as.
+fn migrate(value: Value) -> Result<(MachineConfig, u32), String> {
+    let Value::Object(mut config) = value else {
+        return Err("not a JSON object".to_string());
+    };
+    let stored = config.get("version").and_then(Value::as_u64).unwrap_or(0) as u32;
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(stored as usize) {
+        migration(&mut config);
+        log::info!("[MachineConfig] Migrated config v{} → v{}", from, from + 1);
+    }
+    // Files from a newer app keep their version so it doesn't migrate them again
+    config.insert("version".to_string(), Value::from(stored.max(CONFIG_VERSION)));
+    let config = serde_json::from_value(Value::Object(config)).map_err(|e| e.to_string())?;
+    Ok((config, stored))
+}
+
+fn parse(content: &str) -> Result<(MachineConfig, u32), String> {
+    serde_json::from_str::<Value>(content)
+        .map_err(|e| e.to_string())
+        .and_then(migrate)
+}
+
+// =============================================================================
+// Store
+// =============================================================================
+
+fn write(path: &Path, config: &MachineConfig) -> Result<(), String> {
+    let json = serde_json::to_vec_pretty(config).map_err(|e| e.to_string())?;
+    crate::persist::write_atomic(path, &json)
+}
+
+/// Load the stored config — called from `AppState::new`. Defaults when there
+/// is no file yet (or it can't be read).
+pub fn load(app: &AppHandle) -> MachineConfig {
+    let dir = match app.path().app_config_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            log::error!("[MachineConfig] No app config dir, config won't be saved: {}", e);
+            return MachineConfig::default();
+        }
+    };
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        log::error!("[MachineConfig] Failed to create {}: {}", dir.display(), e);
+    }
+    let path = dir.join(CONFIG_FILE);
+    STORE.lock().unwrap().path = Some(path.clone());
+
+    match crate::persist::load_or_quarantine(&path, parse) {
+        Ok(None) => {
+            log::info!("[MachineConfig] No {} yet, using defaults", path.display());
+            MachineConfig::default()
+        }
+        Ok(Some((mut config, stored))) => {
+            if stored < CONFIG_VERSION {
+                // Keep the original for a downgrade, then store the migrated file
+                let backup = dir.join(format!("{}.v{}", CONFIG_FILE, stored));
+                let _ = std::fs::copy(&path, &backup);
+                config.updated_at = Some(now());
+                if let Err(e) = write(&path, &config) {
+                    log::error!("[MachineConfig] {}", e);
+                }
+            }
+            log::info!(
+                "[MachineConfig] Loaded {} (v{}): machine '{}', camera {}, printer '{}'",
+                path.display(),
+                config.version,
+                config.machine_id,
+                config.camera_type,
+                config.selected_printer
+            );
+            let mut store = STORE.lock().unwrap();
+            store.version = config.version;
+            store.api_environment = config.api_environment.clone();
+            store.api_base_url = config.api_base_url.clone();
+            store.updated_at = config.updated_at.clone();
+            store.extra = config.extra.clone();
+            config
+        }
+        Err(e) => {
+            log::error!("[MachineConfig] {}", e);
+            MachineConfig::default()
+        }
+    }
+}
+
+fn now() -> String {
+    chrono::Local::now().to_rfc3339()
+}
+
+/// The current settings of `state`, with the version, write time and unknown
+/// keys of the stored file
+fn snapshot(state: &AppState, store: &Store) -> MachineConfig {
+    MachineConfig {
+        version: store.version,
+        machine_id: state.machine_id.lock().unwrap().clone(),
+        machine_port: state.machine_port.lock().unwrap().clone(),
+        camera_type: state.camera_type.lock().unwrap().clone(),
+        selected_webcam_id: state.selected_webcam_id.lock().unwrap().clone(),
+        selected_camera_name: state.selected_camera_name.lock().unwrap().clone(),
+        camera_mount_rotation: crate::orientation::mount_rotation(),
+        selected_printer: state.selected_printer.lock().unwrap().clone(),
+        paper_portrait: state.paper_config_portrait.lock().unwrap().clone(),
+        paper_landscape: state.paper_config_landscape.lock().unwrap().clone(),
+        api_environment: store.api_environment.clone(),
+        api_base_url: store.api_base_url.clone(),
+        updated_at: store.updated_at.clone(),
+        extra: store.extra.clone(),
+    }
+}
+
+/// Write the settings of `state` to disk. Errors are logged — a failed write
+/// must not fail the setter, the setting is still live in memory.
+pub fn save(state: &AppState) {
+    let mut store = STORE.lock().unwrap();
+    let Some(path) = store.path.as_ref() else {
+        return;
+    };
+    let config = MachineConfig {
+        updated_at: Some(now()),
+        ..snapshot(state, &store)
+    };
+    match write(path, &config) {
+        Ok(()) => store.updated_at = config.updated_at,
+        Err(e) => log::error!("[MachineConfig] {}", e),
+    }
+}
+
+/// Store the API environment picked through `set_backend_environment`
+pub fn set_backend(state: &AppState, environment: crate::backend::Environment, base_url: Option<String>) {
+    {
+        let mut store = STORE.lock().unwrap();
+        store.api_environment = Some(environment.name().to_string());
+        store.api_base_url = base_url;
+    }
+    save(state);
+}
+
+/// Take over every setting of `config`
+fn apply(state: &AppState, config: MachineConfig) {
+    {
+        let mut store = STORE.lock().unwrap();
+        store.version = config.version;
+        store.api_environment = config.api_environment.clone();
+        store.api_base_url = config.api_base_url.clone();
+        store.extra = config.extra;
+    }
+    crate::backend::init(config.api_environment.as_deref(), config.api_base_url.as_deref());
+    crate::jpeg_meta::set_machine_id(&config.machine_id);
+    crate::paper::set_selected(
+        config.paper_portrait.paper_profile.as_deref(),
+        config.paper_landscape.paper_profile.as_deref(),
+    );
+    if let Err(e) = crate::orientation::set_mount_rotation(config.camera_mount_rotation) {
+        log::warn!("[MachineConfig] {}, keeping the current one", e);
+    }
+    *state.machine_id.lock().unwrap() = config.machine_id;
+    *state.machine_port.lock().unwrap() = config.machine_port;
+    *state.camera_type.lock().unwrap() = config.camera_type;
+    *state.selected_webcam_id.lock().unwrap() = config.selected_webcam_id;
+    *state.selected_camera_name.lock().unwrap() = config.selected_camera_name;
+    *state.selected_printer.lock().unwrap() = config.selected_printer;
+    *state.paper_config_portrait.lock().unwrap() = config.paper_portrait;
+    *state.paper_config_landscape.lock().unwrap() = config.paper_landscape;
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+/// The machine config as stored, `None` when there is no `machine.json` yet
+/// (booths set up before it have their settings in localStorage only)
+#[tauri::command]
+pub fn get_machine_config() -> Result<Option<MachineConfig>, String> {
+    let Some(path) = STORE.lock().unwrap().path.clone().filter(|p| p.exists()) else {
+        return Ok(None);
+    };
+    let content = std::fs::read_to_string(&path).map_err(|e| format!("Read {} failed: {}", path.display(), e))?;
+    let (config, _) = parse(&content).map_err(|e| format!("Invalid {}: {}", path.display(), e))?;
+    Ok(Some(config))
+}
+
+/// Write the machine config to `path` (e.g. a USB stick) for
+/// `import_machine_config` on another PC. Returns the written path.
+#[tauri::command]
+pub fn export_machine_config(state: tauri::State<'_, AppState>, path: String) -> Result<String, String> {
+    let path = PathBuf::from(path);
+    let config = snapshot(&state, &STORE.lock().unwrap());
+    let json = serde_json::to_vec_pretty(&config).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| format!("Write {} failed: {}", path.display(), e))?;
+    log::info!("[MachineConfig] Exported to {}", path.display());
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Take over a config exported from another booth (migrated like a stored
+/// file) and save it. `keep_machine_id` keeps this PC's machine id and port —
+/// for setting up a second booth from a configured one.
+#[tauri::command]
+pub fn import_machine_config(
+    state: tauri::State<'_, AppState>,
+    path: String,
+    keep_machine_id: Option<bool>,
+) -> Result<MachineConfig, String> {
+    let content = std::fs::read_to_string(&path).map_err(|e| format!("Read {} failed: {}", path, e))?;
+    let (mut config, stored) = parse(&content).map_err(|e| format!("Invalid machine config {}: {}", path, e))?;
+    if keep_machine_id.unwrap_or(false) {
+        config.machine_id = state.machine_id.lock().unwrap().clone();
+        config.machine_port = state.machine_port.lock().unwrap().clone();
+    }
+    log::info!(
+        "[MachineConfig] Importing {} (v{}): machine '{}', camera {}, printer '{}'",
+        path,
+        stored,
+        config.machine_id,
+        config.camera_type,
+        config.selected_printer
+    );
+    apply(&state, config);
+    save(&state);
+    Ok(snapshot(&state, &STORE.lock().unwrap()))
+}
@@ -0,0 +1,541 @@
+//! Media Counters
+//!
+//! Local count of the media left in each printer, kept per loaded roll in
+//! `media/counters.json` in the app data folder. The backend only knows a
+//! single `paperLevel` integer; this is the booth-side source of truth that
+//! it is reconciled against.
+//!
+//! Everything is counted in sheets of the roll's media. Cut profiles print
+//! several products on one sheet, so a 2x6 job uses one 4x6 sheet — never
+//! two. CUT driver variants ("DS-RX1 (CUT)") share their base printer's
+//! counter.
+//!
+//! Levels drop through `ok → low → critical → empty` at per-printer
+//! thresholds. Every change is emitted as `media-status`; crossing into a
+//! lower level also emits `media-warning`. Payment is refused when the
+//! selected package needs more sheets than are left (see [`package_sheets`]
+//! and [`ensure_available`]).
+
+use crate::api::AppState;
+use crate::paper::{self, PaperProfile};
+use crate::printer_backend;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Emitter, Manager};
+
+const MEDIA_DIR: &str = "media";
+const COUNTERS_FILE: &str = "counters.json";
+const DEFAULT_WARNING_AT: u32 = 50;
+const DEFAULT_CRITICAL_AT: u32 = 10;
+/// Finished rolls kept per printer
+const KEEP_ROLLS: usize = 20;
+
+static COUNTERS: Mutex<Option<Counters>> = Mutex::new(None);
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaRoll {
+    pub id: String,
+    /// Sheet size, short side first ("4x6")
+    pub media: String,
+    pub capacity: u32,
+    pub remaining: u32,
+    /// Sheets counted on this roll
+    pub printed: u32,
+    pub loaded_at: String,
+    #[serde(default)]
+    pub finished_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrinterMedia {
+    pub printer_name: String,
+    pub roll: Option<MediaRoll>,
+    /// Finished rolls, newest first
+    #[serde(default)]
+    pub history: Vec<MediaRoll>,
+    pub warning_at: u32,
+    pub critical_at: u32,
+    /// Last `paperLevel` seen from the backend
+    #[serde(default)]
+    pub backend_level: Option<i32>,
+    #[serde(default)]
+    pub reconciled_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MediaLevel {
+    Empty,
+    Critical,
+    Low,
+    Ok,
+    /// No roll is being counted
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaStatus {
+    pub printer_name: String,
+    pub level: MediaLevel,
+    pub media: Option<String>,
+    pub capacity: u32,
+    pub remaining: Option<u32>,
+    pub warning_at: u32,
+    pub critical_at: u32,
+    pub backend_level: Option<i32>,
+    pub roll: Option<MediaRoll>,
+}
+
+/// Answer to "can this package be printed?"
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaCheck {
+    pub ok: bool,
+    pub printer_name: String,
+    pub needed: u32,
+    /// `None` when the printer isn't counted (always ok)
+    pub remaining: Option<u32>,
+    pub message: Option<String>,
+}
+
+struct Counters {
+    path: PathBuf,
+    printers: Vec<PrinterMedia>,
+}
+
+fn now() -> String {
+    chrono::Local::now().to_rfc3339()
+}
+
+/// Sheet media a profile prints on, e.g. "4x6" for the 2x6 strips
+pub fn media_for(profile: &PaperProfile) -> String {
+    let (w, h) = profile.sheet_pixels();
+    let inches = |px: u32| {
+        let v = (px as f64 / profile.dpi as f64 * 10.0).round() / 10.0;
+        if v.fract() == 0.0 {
+            format!("{}", v as u32)
+        } else {
+            format!("{:.1}", v)
+        }
+    };
+    format!("{}x{}", inches(w.min(h)), inches(w.max(h)))
+}
+
+/// Sheets a package of `prints` photos takes on the selected paper. The
+/// orientation isn't known before the frame is picked, so it is the larger
+/// count of the two; without a selected profile each photo is its own sheet.
+pub fn package_sheets(state: &AppState, prints: u32) -> u32 {
+    [&state.paper_config_portrait, &state.paper_config_landscape]
+        .iter()
+        .map(|config| {
+            let selected = config.lock().unwrap().paper_profile.clone();
+            selected
+                .and_then(|id| paper::get(&id).ok())
+                .map_or(prints.max(1), |profile| profile.sheets_for(prints))
+        })
+        .max()
+        .unwrap_or(prints.max(1))
+}
+
+/// Sheet media of the selected paper profile (portrait, then landscape),
+/// for rolls the booth starts counting on its own. "4x6" when none is set.
+pub fn selected_media(state: &AppState) -> String {
+    let selected = [&state.paper_config_portrait, &state.paper_config_landscape]
+        .iter()
+        .find_map(|config| config.lock().unwrap().paper_profile.clone());
+    selected
+        .and_then(|id| paper::get(&id).ok())
+        .map(|profile| media_for(&profile))
+        .unwrap_or_else(|| "4x6".to_string())
+}
+
+impl PrinterMedia {
+    fn new(printer_name: &str) -> Self {
+        PrinterMedia {
+            printer_name: printer_name.to_string(),
+            roll: None,
+            history: Vec::new(),
+            warning_at: DEFAULT_WARNING_AT,
+            critical_at: DEFAULT_CRITICAL_AT,
+            backend_level: None,
+            reconciled_at: None,
+        }
+    }
+
+    fn level(&self) -> MediaLevel {
+        match &self.roll {
+            None => MediaLevel::Unknown,
+            Some(roll) if roll.remaining == 0 => MediaLevel::Empty,
+            Some(roll) if roll.remaining <= self.critical_at => MediaLevel::Critical,
+            Some(roll) if roll.remaining <= self.warning_at => MediaLevel::Low,
+            Some(_) => MediaLevel::Ok,
+        }
+    }
+
+    fn status(&self) -> MediaStatus {
+        MediaStatus {
+            printer_name: self.printer_name.clone(),
+            level: self.level(),
+            media: self.roll.as_ref().map(|r| r.media.clone()),
+            capacity: self.roll.as_ref().map_or(0, |r| r.capacity),
+            remaining: self.roll.as_ref().map(|r| r.remaining),
+            warning_at: self.warning_at,
+            critical_at: self.critical_at,
+            backend_level: self.backend_level,
+            roll: self.roll.clone(),
+        }
+    }
+
+    /// Replace the current roll, moving the old one to the history
+    fn load_roll(&mut self, media: &str, capacity: u32, remaining: u32) {
+        if let Some(mut old) = self.roll.take() {
+            old.finished_at = Some(now());
+            self.history.insert(0, old);
+            self.history.truncate(KEEP_ROLLS);
+        }
+        self.roll = Some(MediaRoll {
+            id: uuid::Uuid::new_v4().simple().to_string(),
+            media: media.to_string(),
+            capacity: capacity.max(remaining),
+            remaining,
+            printed: 0,
+            loaded_at: now(),
+            finished_at: None,
+        });
+    }
+}
+
+// =============================================================================
+// Persistence
+// =============================================================================
+
+impl Counters {
+    /// Write `counters.json` (see `persist::write_atomic`)
+    fn save(&self) {
+        let result = serde_json::to_vec_pretty(&self.printers)
+            .map_err(|e| e.to_string())
+            .and_then(|json| crate::persist::write_atomic(&self.path, &json));
+        if let Err(e) = result {
+            log::error!("[Media] Failed to save {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+/// Load the counters — called on startup
+pub fn init(app: &AppHandle) {
+    let dir = match app.path().app_data_dir() {
+        Ok(dir) => dir.join(MEDIA_DIR),
+        Err(e) => {
+            log::error!("[Media] No app data dir, media counters disabled: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        log::error!("[Media] Failed to create {}: {}", dir.display(), e);
+        return;
+    }
+
+    let path = dir.join(COUNTERS_FILE);
+    let parse = |content: &str| serde_json::from_str::<Vec<PrinterMedia>>(content).map_err(|e| e.to_string());
+    let printers = match crate::persist::load_or_quarantine(&path, parse) {
+        Ok(printers) => printers.unwrap_or_default(),
+        Err(e) => {
+            log::error!("[Media] {}", e);
+            Vec::new()
+        }
+    };
+    for printer in &printers {
+        let status = printer.status();
+        log::info!(
+            "[Media] {}: {:?} ({}/{} {})",
+            printer.printer_name,
+            status.level,
+            status.remaining.map_or("-".to_string(), |r| r.to_string()),
+            status.capacity,
+            status.media.unwrap_or_default()
+        );
+    }
+
+    *COUNTERS.lock().unwrap() = Some(Counters { path, printers });
+    let _ = APP.set(app.clone());
+}
+
+// =============================================================================
+// Counter Operations
+// =============================================================================
+
+/// Change a printer's counter (created on first use), persist and notify
+fn update<T>(printer_name: &str, f: impl FnOnce(&mut PrinterMedia) -> T) -> Option<(T, MediaStatus)> {
+    let key = printer_backend::base_driver_name(printer_name);
+    let mut guard = COUNTERS.lock().unwrap();
+    let counters = guard.as_mut()?;
+    let index = match counters.printers.iter().position(|p| p.printer_name == key) {
+        Some(index) => index,
+        None => {
+            counters.printers.push(PrinterMedia::new(&key));
+            counters.printers.len() - 1
+        }
+    };
+    let printer = &mut counters.printers[index];
+    let before = printer.level();
+    let result = f(printer);
+    let status = printer.status();
+    counters.save();
+    drop(guard);
+
+    if let Some(app) = APP.get() {
+        let _ = app.emit("media-status", &status);
+        if before != MediaLevel::Unknown && status.level < before {
+            log::warn!(
+                "[Media] {} media {:?}: {} sheets left",
+                status.printer_name,
+                status.level,
+                status.remaining.unwrap_or(0)
+            );
+            let _ = app.emit("media-warning", &status);
+        }
+    }
+    Some((result, status))
+}
+
+pub fn status(printer_name: &str) -> Option<MediaStatus> {
+    let key = printer_backend::base_driver_name(printer_name);
+    let guard = COUNTERS.lock().unwrap();
+    let counters = guard.as_ref()?;
+    Some(
+        counters
+            .printers
+            .iter()
+            .find(|p| p.printer_name == key)
+            .map(PrinterMedia::status)
+            .unwrap_or_else(|| PrinterMedia::new(&key).status()),
+    )
+}
+
+/// Count sheets that were spooled to `printer_name`. A job is one sheet per
+/// copy whatever the cut layout.
+pub fn record_printed(printer_name: &str, profile: &PaperProfile, sheets: u32) {
+    let media = media_for(profile);
+    update(printer_name, |printer| {
+        let Some(roll) = printer.roll.as_mut() else {
+            return;
+        };
+        if roll.media != media {
+            log::warn!(
+                "[Media] '{}' printed on {} but the loaded roll is {}",
+                profile.id,
+                media,
+                roll.media
+            );
+        }
+        roll.remaining = roll.remaining.saturating_sub(sheets);
+        roll.printed += sheets;
+    });
+}
+
+/// Fold in the backend's `paperLevel`. A level that went up since the last
+/// sync is a refill recorded on the backend and is taken as is; otherwise the
+/// lower of the two counts wins (reductions can still be in flight). Without
+/// a roll, counting starts on a roll of `media`.
+pub fn reconcile(printer_name: &str, backend_level: i32, media: &str) {
+    if printer_name.is_empty() {
+        return;
+    }
+    let level = backend_level.max(0) as u32;
+    update(printer_name, |printer| {
+        let previous = printer.backend_level.replace(backend_level);
+        printer.reconciled_at = Some(now());
+        let refilled = previous.is_some_and(|p| backend_level > p);
+        match printer.roll.as_mut() {
+            None => {
+                log::info!("[Media] {}: counting from backend level {}", printer.printer_name, level);
+                printer.load_roll(media, level, level);
+            }
+            Some(roll) if refilled => {
+                log::info!(
+                    "[Media] {}: backend refill {} → {}",
+                    printer.printer_name,
+                    previous.unwrap_or(0),
+                    level
+                );
+                roll.remaining = level;
+                roll.capacity = roll.capacity.max(level);
+            }
+            Some(roll) if level < roll.remaining => {
+                log::info!(
+                    "[Media] {}: backend has {} left, local count {} — using backend",
+                    printer.printer_name,
+                    level,
+                    roll.remaining
+                );
+                roll.remaining = level;
+            }
+            Some(roll) => {
+                if level > roll.remaining {
+                    log::debug!(
+                        "[Media] {}: backend {} ahead of local count {}",
+                        printer.printer_name,
+                        level,
+                        roll.remaining
+                    );
+                }
+            }
+        }
+    });
+}
+
+/// Operator correction of the current roll's count (starts a roll of
+/// `media` if none). The backend level is only updated once the backend has
+/// taken the new level (see [`accepted_level`]).
+pub fn set_remaining(printer_name: &str, remaining: u32, media: &str) {
+    if printer_name.is_empty() {
+        return;
+    }
+    update(printer_name, |printer| match printer.roll.as_mut() {
+        Some(roll) => {
+            roll.remaining = remaining;
+            roll.capacity = roll.capacity.max(remaining);
+        }
+        None => printer.load_roll(media, remaining, remaining),
+    });
+}
+
+/// The backend accepted `level` as its `paperLevel`
+pub fn accepted_level(printer_name: &str, level: i32) {
+    update(printer_name, |printer| {
+        printer.backend_level = Some(level);
+        printer.reconciled_at = Some(now());
+    });
+}
+
+/// Send the local count to the backend so both agree
+async fn push_level(state: &AppState, printer_name: &str, remaining: u32) {
+    match crate::api::send_paper_level(state, remaining as i32).await {
+        Ok(res) if crate::outbox::is_queued(&res) => log::info!("[Media] Paper level queued until the backend is reachable"),
+        Ok(res) if res.success => accepted_level(printer_name, remaining as i32),
+        Ok(res) => log::warn!("[Media] Backend rejected paper level: {:?}", res.error),
+        Err(e) => log::warn!("[Media] Failed to send paper level: {}", e),
+    }
+}
+
+/// Whether `sheets` more sheets are left. Printers without a counted roll always
+/// pass — the counter is opt-in per printer.
+pub fn check(printer_name: &str, sheets: u32) -> MediaCheck {
+    let remaining = status(printer_name).and_then(|s| s.remaining);
+    let ok = remaining.is_none_or(|r| r >= sheets);
+    MediaCheck {
+        ok,
+        printer_name: printer_name.to_string(),
+        needed: sheets,
+        remaining,
+        message: (!ok).then(|| {
+            format!(
+                "Not enough media: {} sheet(s) needed, {} left",
+                sheets,
+                remaining.unwrap_or(0)
+            )
+        }),
+    }
+}
+
+/// `check` as an error, for payment commands
+pub fn ensure_available(printer_name: &str, sheets: u32) -> Result<(), String> {
+    match check(printer_name, sheets) {
+        MediaCheck { ok: true, .. } => Ok(()),
+        MediaCheck { message, .. } => {
+            let message = message.unwrap_or_default();
+            log::warn!("[Media] Payment blocked on '{}': {}", printer_name, message);
+            Err(message)
+        }
+    }
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+/// Counters of every known printer, or just `printer_name`
+#[tauri::command]
+pub fn get_media_status(printer_name: Option<String>) -> Result<Vec<MediaStatus>, String> {
+    if let Some(name) = printer_name.filter(|n| !n.is_empty()) {
+        return status(&name).map(|s| vec![s]).ok_or("Media counters are not available".to_string());
+    }
+    let guard = COUNTERS.lock().unwrap();
+    let counters = guard.as_ref().ok_or("Media counters are not available")?;
+    Ok(counters.printers.iter().map(PrinterMedia::status).collect())
+}
+
+/// A new roll was loaded. Pushes the level to the backend so both agree.
+#[tauri::command]
+pub async fn load_media_roll(
+    state: tauri::State<'_, AppState>,
+    printer_name: String,
+    media: String,
+    capacity: u32,
+    remaining: Option<u32>,
+) -> Result<MediaStatus, String> {
+    if printer_name.is_empty() {
+        return Err("No printer selected".to_string());
+    }
+    if capacity == 0 {
+        return Err("Roll capacity must be at least 1".to_string());
+    }
+    let remaining = remaining.unwrap_or(capacity).min(capacity);
+    let (_, loaded) = update(&printer_name, |printer| printer.load_roll(&media, capacity, remaining))
+        .ok_or("Media counters are not available")?;
+    log::info!("[Media] {}: loaded {} roll, {}/{}", loaded.printer_name, media, remaining, capacity);
+
+    push_level(&state, &printer_name, remaining).await;
+    status(&printer_name).ok_or("Media counters are not available".to_string())
+}
+
+/// Operator correction of the count. Pushes the level to the backend too.
+#[tauri::command]
+pub async fn set_media_remaining(
+    state: tauri::State<'_, AppState>,
+    printer_name: String,
+    remaining: u32,
+) -> Result<MediaStatus, String> {
+    if printer_name.is_empty() {
+        return Err("No printer selected".to_string());
+    }
+    set_remaining(&printer_name, remaining, &selected_media(&state));
+    push_level(&state, &printer_name, remaining).await;
+    status(&printer_name).ok_or("Media counters are not available".to_string())
+}
+
+#[tauri::command]
+pub fn set_media_thresholds(printer_name: String, warning_at: u32, critical_at: u32) -> Result<MediaStatus, String> {
+    if critical_at > warning_at {
+        return Err("Critical threshold must not be above the warning threshold".to_string());
+    }
+    update(&printer_name, |printer| {
+        printer.warning_at = warning_at;
+        printer.critical_at = critical_at;
+    })
+    .map(|(_, status)| status)
+    .ok_or("Media counters are not available".to_string())
+}
+
+/// Whether the package (`prints` photos, see [`package_sheets`]) can be sold
+/// on `printer_name` (default: the selected printer)
+#[tauri::command]
+pub fn check_media_available(
+    state: tauri::State<'_, AppState>,
+    printer_name: Option<String>,
+    prints: u32,
+) -> Result<MediaCheck, String> {
+    let printer_name = printer_name
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| state.selected_printer.lock().unwrap().clone());
+    Ok(check(&printer_name, package_sheets(&state, prints)))
+}
@@ -0,0 +1,104 @@
+//! Photo Orientation
+//!
+//! `image::load_from_memory` ignores the EXIF Orientation tag, so every
+//! decode that feeds the pipeline goes through [`decode_upright`] instead.
+//!
+//! Two corrections are applied, in this order:
+//! 1. EXIF orientation (read with kamadak-exif) — any photo
+//! 2. Mount rotation — captures only, for booths whose camera is physically
+//!    rotated (e.g. a DSLR mounted in portrait that doesn't tag its files)
+//!
+//! Outputs are written with upright pixels and no Orientation tag (see
+//! `workdir::jpeg_options`), so a photo is never rotated twice.
+
+use image::metadata::Orientation;
+use image::DynamicImage;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Clockwise degrees the camera is mounted at: 0, 90, 180 or 270
+static MOUNT_ROTATION: AtomicU32 = AtomicU32::new(0);
+
+pub fn mount_rotation() -> u32 {
+    MOUNT_ROTATION.load(Ordering::SeqCst)
+}
+
+/// Set the mount rotation: 0, 90, 180 or 270
+pub fn set_mount_rotation(degrees: u32) -> Result<(), String> {
+    if !matches!(degrees, 0 | 90 | 180 | 270) {
+        return Err(format!("Invalid mount rotation {} (use 0, 90, 180 or 270)", degrees));
+    }
+    MOUNT_ROTATION.store(degrees, Ordering::SeqCst);
+    Ok(())
+}
+
+/// EXIF orientation of an encoded image (1 = upright / no tag)
+pub fn exif_orientation(bytes: &[u8]) -> u8 {
+    crate::jpeg_meta::orientation(bytes)
+        .filter(|o| (1..=8).contains(o))
+        .unwrap_or(1) as u8
+}
+
+/// Decode and turn the pixels upright. With `is_capture` the mount rotation
+/// is applied on top of the EXIF orientation.
+pub fn decode_upright(bytes: &[u8], is_capture: bool) -> Result<DynamicImage, String> {
+    let mut img = image::load_from_memory(bytes).map_err(|e| format!("Image load error: {}", e))?;
+
+    let orientation = exif_orientation(bytes);
+    if orientation != 1 {
+        if let Some(orientation) = Orientation::from_exif(orientation) {
+            img.apply_orientation(orientation);
+        }
+    }
+    if is_capture {
+        img = rotate(img, mount_rotation());
+    }
+    Ok(img)
+}
+
+/// Whether [`decode_upright`] would change anything
+pub fn needs_rotation(bytes: &[u8], is_capture: bool) -> bool {
+    exif_orientation(bytes) != 1 || (is_capture && mount_rotation() != 0)
+}
+
+/// Turn `img` clockwise by 0, 90, 180 or 270 degrees
+pub fn rotate(img: DynamicImage, degrees: u32) -> DynamicImage {
+    match degrees {
+        90 => img.rotate90(),
+        180 => img.rotate180(),
+        270 => img.rotate270(),
+        _ => img,
+    }
+}
+
+/// ffmpeg filter that applies the mount rotation to captured video
+pub fn mount_rotation_filter() -> Option<&'static str> {
+    match mount_rotation() {
+        90 => Some("transpose=clock"),
+        180 => Some("hflip,vflip"),
+        270 => Some("transpose=cclock"),
+        _ => None,
+    }
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+/// Set how the camera is physically mounted (clockwise degrees the captures
+/// must be turned to be upright): 0, 90, 180 or 270. Stored in the machine
+/// config.
+#[tauri::command]
+pub async fn set_camera_mount_rotation(
+    state: tauri::State<'_, crate::api::AppState>,
+    degrees: u32,
+) -> Result<(), String> {
+    set_mount_rotation(degrees)?;
+    log::info!("[Orientation] Camera mount rotation: {}°", degrees);
+    crate::machine_config::save(&state);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_camera_mount_rotation() -> Result<u32, String> {
+    Ok(mount_rotation())
+}
@@ -0,0 +1,817 @@
+//! Offline Outbox
+//!
+//! Backend calls that must not get lost when the venue's internet drops —
+//! photo deliveries, paper level changes, device reports — go through
+//! [`submit`]. While the outbox is empty a call is sent right away, once; if
+//! it fails with a retryable error (see `ApiError::is_retryable`) it is
+//! queued and the caller gets `success: true` with `{ queued: true,
+//! outboxId }`. While anything is queued, new calls queue behind it, so they
+//! reach the backend in order.
+//!
+//! A photo delivery is one item for the whole upload of a transaction:
+//! presign, PUT of every file, confirm. Presigned URLs expire, so a queued
+//! delivery presigns again each time it is sent.
+//!
+//! The queue is an append-only journal, `outbox/journal.jsonl` in the app
+//! data folder: one line per item added, changed or removed, synced to disk
+//! before the call returns. It is replayed and compacted on startup; a torn
+//! last line from a crash is skipped. Delivered files are copied next to it.
+//!
+//! A background worker sends queued items oldest first. A retryable failure
+//! means the network is still down for all of them, so the whole queue backs
+//! off (5 s doubling to 5 min, with jitter); it is woken early when the SSE
+//! connection comes back or on `drain_outbox`. Items the backend rejects are
+//! kept as `failed` for the admin menu to retry or discard. Every change is
+//! emitted as `outbox-status` ([`OutboxStatus`]).
+//!
+//! An item's id is the `Idempotency-Key` of every attempt (except the
+//! presign, which must not be answered from a cache), so one whose answer
+//! was lost isn't applied twice. A new absolute paper level replaces queued
+//! paper changes, a new device status report the queued one, a new delivery
+//! the queued one of the same transaction.
+
+use crate::api::{ApiResponse, AppState};
+use crate::booth_api::{self, ApiError, BoothApiClient, RetryPolicy};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+const OUTBOX_DIR: &str = "outbox";
+const JOURNAL_FILE: &str = "journal.jsonl";
+/// Compact the journal once it has this many lines more than live items
+const COMPACT_AFTER: usize = 500;
+/// Look for due items at least this often
+const IDLE_POLL: Duration = Duration::from_secs(30);
+/// Wait after retryable failures in a row
+const BACKOFF: RetryPolicy = RetryPolicy {
+    max_attempts: u32::MAX,
+    base_delay: Duration::from_secs(5),
+    max_delay: Duration::from_secs(300),
+};
+
+static OUTBOX: Mutex<Option<Outbox>> = Mutex::new(None);
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+fn wake() -> &'static tokio::sync::Notify {
+    static WAKE: OnceLock<tokio::sync::Notify> = OnceLock::new();
+    WAKE.get_or_init(tokio::sync::Notify::new)
+}
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// A backend call to deliver
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum OutboxRequest {
+    /// A transaction's photos and video: presign, PUT of each file, confirm
+    Delivery {
+        transaction_id: String,
+        transaction_code: Option<String>,
+        /// In upload order
+        files: Vec<DeliveryFile>,
+        /// Presign the frontend made for the QR code. Used by the direct
+        /// attempt only — its URLs expire, so it is never journaled.
+        #[serde(skip)]
+        presigned: Option<Presigned>,
+    },
+    PaperLevel(booth_api::PaperLevelRequest),
+    PaperReduce(booth_api::PaperReduceRequest),
+    DeviceAlert(booth_api::DeviceAlertRequest),
+    DeviceReconnected(booth_api::DeviceReconnectedRequest),
+    DeviceStatusReport(booth_api::DeviceStatusReport),
+}
+
+impl OutboxRequest {
+    fn kind(&self) -> &'static str {
+        match self {
+            OutboxRequest::Delivery { .. } => "delivery",
+            OutboxRequest::PaperLevel(_) => "paperLevel",
+            OutboxRequest::PaperReduce(_) => "paperReduce",
+            OutboxRequest::DeviceAlert(_) => "deviceAlert",
+            OutboxRequest::DeviceReconnected(_) => "deviceReconnected",
+            OutboxRequest::DeviceStatusReport(_) => "deviceStatusReport",
+        }
+    }
+
+    /// Whether this request makes a queued `older` one pointless
+    fn supersedes(&self, older: &OutboxRequest) -> bool {
+        use OutboxRequest::*;
+        match (self, older) {
+            (Delivery { transaction_id, .. }, Delivery { transaction_id: older_id, .. }) => transaction_id == older_id,
+            _ => matches!(
+                (self, older),
+                (PaperLevel(_), PaperLevel(_) | PaperReduce(_)) | (DeviceStatusReport(_), DeviceStatusReport(_))
+            ),
+        }
+    }
+
+    /// Files copied into the outbox folder
+    fn files(&self) -> &[DeliveryFile] {
+        match self {
+            OutboxRequest::Delivery { files, .. } => files,
+            _ => &[],
+        }
+    }
+
+    fn is_paper(&self) -> bool {
+        matches!(self, OutboxRequest::PaperLevel(_) | OutboxRequest::PaperReduce(_))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryFile {
+    pub file_path: String,
+    pub content_type: String,
+    /// "photo" or "video", as in the presign request
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+/// Upload target in a create-presign-upload answer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadUrl {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub order: i64,
+    pub upload_url: String,
+    pub key: String,
+}
+
+/// Photo session and upload URLs of a presign
+#[derive(Debug, Clone)]
+pub struct Presigned {
+    pub session_id: String,
+    pub upload_urls: Vec<UploadUrl>,
+}
+
+impl Presigned {
+    fn from_answer(answer: &booth_api::PresignUpload) -> Result<Self, String> {
+        let session_id = answer
+            .extra
+            .get("photoSession")
+            .and_then(|session| session.get("id"))
+            .and_then(Value::as_str)
+            .ok_or("Presign answer has no photoSession.id")?;
+        let upload_urls = answer.extra.get("uploadUrls").cloned().unwrap_or_default();
+        Ok(Self {
+            session_id: session_id.to_string(),
+            upload_urls: serde_json::from_value(upload_urls).map_err(|e| format!("Presign answer uploadUrls: {}", e))?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemStatus {
+    Pending,
+    /// Rejected by the backend — waits for the operator
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutboxItem {
+    /// Also the `Idempotency-Key` of every attempt
+    pub id: String,
+    pub request: OutboxRequest,
+    pub status: ItemStatus,
+    /// Attempts from the outbox (the first, direct one not counted)
+    pub attempts: u32,
+    pub error: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// One journal line
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "camelCase")]
+enum Record {
+    /// Item added or changed
+    Put { item: OutboxItem },
+    Remove { id: String },
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutboxStatus {
+    /// Items waiting to be sent
+    pub depth: usize,
+    /// Items the backend rejected
+    pub failed: usize,
+    pub oldest_created_at: Option<String>,
+    /// Age of the oldest waiting item
+    pub oldest_age_secs: Option<i64>,
+    /// Retryable failures in a row
+    pub failures: u32,
+    pub last_error: Option<String>,
+    /// Time to the next attempt while backing off
+    pub next_attempt_secs: Option<u64>,
+}
+
+struct Outbox {
+    dir: PathBuf,
+    items: Vec<OutboxItem>,
+    /// Lines in the journal
+    records: usize,
+    failures: u32,
+    last_error: Option<String>,
+    /// Backoff: nothing is sent before this
+    retry_at: Option<Instant>,
+}
+
+fn now() -> String {
+    chrono::Local::now().to_rfc3339()
+}
+
+// =============================================================================
+// Persistence
+// =============================================================================
+
+impl Outbox {
+    fn journal_path(&self) -> PathBuf {
+        self.dir.join(JOURNAL_FILE)
+    }
+
+    /// Append to the journal and sync it to disk
+    fn append(&mut self, records: &[Record]) {
+        let result = (|| -> Result<(), String> {
+            let mut lines = Vec::new();
+            for record in records {
+                serde_json::to_writer(&mut lines, record).map_err(|e| e.to_string())?;
+                lines.push(b'\n');
+            }
+            let mut file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(self.journal_path())
+                .map_err(|e| e.to_string())?;
+            file.write_all(&lines).map_err(|e| e.to_string())?;
+            file.sync_data().map_err(|e| e.to_string())
+        })();
+        match result {
+            Ok(()) => self.records += records.len(),
+            Err(e) => log::error!("[Outbox] Failed to append to {}: {}", self.journal_path().display(), e),
+        }
+        if self.records > self.items.len() + COMPACT_AFTER {
+            self.compact();
+        }
+    }
+
+    /// Rewrite the journal with one line per live item (see
+    /// `persist::write_atomic`)
+    fn compact(&mut self) {
+        let result = (|| -> Result<(), String> {
+            let mut lines = Vec::new();
+            for item in &self.items {
+                serde_json::to_writer(&mut lines, &Record::Put { item: item.clone() }).map_err(|e| e.to_string())?;
+                lines.push(b'\n');
+            }
+            crate::persist::write_atomic(&self.journal_path(), &lines)
+        })();
+        match result {
+            Ok(()) => self.records = self.items.len(),
+            Err(e) => log::error!("[Outbox] Failed to compact {}: {}", self.journal_path().display(), e),
+        }
+    }
+
+    fn put(&mut self, item: OutboxItem) {
+        match self.items.iter_mut().find(|i| i.id == item.id) {
+            Some(existing) => *existing = item.clone(),
+            None => self.items.push(item.clone()),
+        }
+        self.append(&[Record::Put { item }]);
+    }
+
+    /// Drop items, with their file copies
+    fn remove(&mut self, ids: &[String]) {
+        let (removed, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut self.items)
+            .into_iter()
+            .partition(|i| ids.contains(&i.id));
+        self.items = kept;
+        if removed.is_empty() {
+            return;
+        }
+        for file in removed.iter().flat_map(|i| i.request.files()) {
+            let _ = std::fs::remove_file(&file.file_path);
+        }
+        let records: Vec<Record> = removed.into_iter().map(|i| Record::Remove { id: i.id }).collect();
+        self.append(&records);
+    }
+}
+
+/// Replay the journal. Lines that don't parse (a write torn by a crash) are
+/// skipped, keeping a copy of the journal for inspection.
+fn load(dir: &Path) -> Vec<OutboxItem> {
+    let path = dir.join(JOURNAL_FILE);
+    let content = match std::fs::read(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Vec::new(),
+        Err(e) => {
+            let backup = dir.join(format!("{}.corrupt", JOURNAL_FILE));
+            let _ = std::fs::rename(&path, &backup);
+            log::error!("[Outbox] Can't read {} ({}), moved to {}", path.display(), e, backup.display());
+            return Vec::new();
+        }
+    };
+
+    let mut items: Vec<OutboxItem> = Vec::new();
+    let mut skipped = 0;
+    for line in content.split(|&b| b == b'\n').filter(|line| !line.trim_ascii().is_empty()) {
+        match serde_json::from_slice::<Record>(line) {
+            Ok(Record::Put { item }) => match items.iter_mut().find(|i| i.id == item.id) {
+                Some(existing) => *existing = item,
+                None => items.push(item),
+            },
+            Ok(Record::Remove { id }) => items.retain(|i| i.id != id),
+            Err(e) => {
+                skipped += 1;
+                log::warn!("[Outbox] Skipping unreadable journal line: {}", e);
+            }
+        }
+    }
+    if skipped > 0 {
+        let backup = dir.join(format!("{}.corrupt", JOURNAL_FILE));
+        let _ = std::fs::copy(&path, &backup);
+        log::error!("[Outbox] {} unreadable lines in {}, copy kept as {}", skipped, path.display(), backup.display());
+    }
+    items
+}
+
+/// Load the outbox and start the worker — called on startup
+pub fn init(app: &AppHandle) {
+    let dir = match app.path().app_data_dir() {
+        Ok(dir) => dir.join(OUTBOX_DIR),
+        Err(e) => {
+            log::error!("[Outbox] No app data dir, outbox disabled: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        log::error!("[Outbox] Failed to create {}: {}", dir.display(), e);
+        return;
+    }
+
+    let items = load(&dir);
+    // File copies no item refers to (the app stopped between copy and journal)
+    if let Ok(entries) = std::fs::read_dir(&dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_journal = path.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with(JOURNAL_FILE));
+            let referenced = items
+                .iter()
+                .flat_map(|i| i.request.files())
+                .any(|file| Path::new(&file.file_path) == path);
+            if !is_journal && !referenced {
+                let _ = std::fs::remove_file(&path);
+            }
+        }
+    }
+
+    let mut outbox = Outbox {
+        dir,
+        items,
+        records: 0,
+        failures: 0,
+        last_error: None,
+        retry_at: None,
+    };
+    outbox.compact();
+    let status = outbox.status();
+    log::info!(
+        "[Outbox] {} waiting, {} failed in {}",
+        status.depth,
+        status.failed,
+        outbox.dir.display()
+    );
+    *OUTBOX.lock().unwrap() = Some(outbox);
+
+    if APP.set(app.clone()).is_ok() {
+        tauri::async_runtime::spawn(worker());
+    }
+}
+
+// =============================================================================
+// Queue Operations
+// =============================================================================
+
+impl Outbox {
+    fn status(&self) -> OutboxStatus {
+        let pending: Vec<&OutboxItem> = self.items.iter().filter(|i| i.status == ItemStatus::Pending).collect();
+        let oldest = pending.first().map(|i| i.created_at.clone());
+        let oldest_age_secs = oldest
+            .as_deref()
+            .and_then(|at| chrono::DateTime::parse_from_rfc3339(at).ok())
+            .map(|at| (chrono::Local::now().fixed_offset() - at).num_seconds().max(0));
+        OutboxStatus {
+            depth: pending.len(),
+            failed: self.items.len() - pending.len(),
+            oldest_created_at: oldest,
+            oldest_age_secs,
+            failures: self.failures,
+            last_error: self.last_error.clone(),
+            next_attempt_secs: self
+                .retry_at
+                .map(|at| at.saturating_duration_since(Instant::now()).as_secs()),
+        }
+    }
+
+    fn has_pending(&self) -> bool {
+        self.items.iter().any(|i| i.status == ItemStatus::Pending)
+    }
+}
+
+/// Change the outbox and tell the frontend
+fn update<T>(f: impl FnOnce(&mut Outbox) -> T) -> Option<T> {
+    let mut guard = OUTBOX.lock().unwrap();
+    let outbox = guard.as_mut()?;
+    let result = f(outbox);
+    let status = outbox.status();
+    drop(guard);
+    if let Some(app) = APP.get() {
+        let _ = app.emit("outbox-status", &status);
+    }
+    Some(result)
+}
+
+/// Queue `request` under `id`. Delivered files are copied into the outbox
+/// folder so they outlive the session folder.
+fn enqueue(id: String, request: OutboxRequest) -> Result<OutboxItem, String> {
+    let item = update(|outbox| -> Result<OutboxItem, String> {
+        let request = match request {
+            OutboxRequest::Delivery {
+                transaction_id,
+                transaction_code,
+                files,
+                presigned: _,
+            } => {
+                let mut copies: Vec<DeliveryFile> = Vec::with_capacity(files.len());
+                for (n, file) in files.into_iter().enumerate() {
+                    let source = Path::new(&file.file_path);
+                    let ext = source.extension().and_then(|e| e.to_str()).unwrap_or("bin");
+                    let target = outbox.dir.join(format!("{}-{}.{}", id, n, ext));
+                    if let Err(e) = std::fs::copy(source, &target) {
+                        for copy in &copies {
+                            let _ = std::fs::remove_file(&copy.file_path);
+                        }
+                        return Err(format!("Failed to copy {}: {}", file.file_path, e));
+                    }
+                    copies.push(DeliveryFile {
+                        file_path: target.to_string_lossy().to_string(),
+                        ..file
+                    });
+                }
+                OutboxRequest::Delivery {
+                    transaction_id,
+                    transaction_code,
+                    files: copies,
+                    presigned: None,
+                }
+            }
+            other => other,
+        };
+
+        let superseded: Vec<String> = outbox
+            .items
+            .iter()
+            .filter(|i| i.status == ItemStatus::Pending && request.supersedes(&i.request))
+            .map(|i| i.id.clone())
+            .collect();
+        outbox.remove(&superseded);
+
+        let item = OutboxItem {
+            id,
+            request,
+            status: ItemStatus::Pending,
+            attempts: 0,
+            error: None,
+            created_at: now(),
+            updated_at: now(),
+        };
+        outbox.put(item.clone());
+        log::info!(
+            "[Outbox] Queued {} {} ({} waiting)",
+            item.request.kind(),
+            item.id,
+            outbox.status().depth
+        );
+        Ok(item)
+    })
+    .ok_or("Outbox is not available")??;
+    wake().notify_one();
+    Ok(item)
+}
+
+/// Make the call. The outer `Err` is a local failure (file unreadable,
+/// presign answer without upload URLs).
+async fn send(client: &BoothApiClient, request: &OutboxRequest) -> Result<Result<Value, ApiError>, String> {
+    Ok(match request {
+        OutboxRequest::Delivery {
+            transaction_id,
+            transaction_code,
+            files,
+            presigned,
+        } => {
+            if let Some(presigned) = presigned {
+                match upload_and_confirm(client, files, presigned).await? {
+                    // Storage refused the URLs — they may have expired meanwhile
+                    Err(ApiError::Http { status: 403, .. }) => {
+                        log::warn!("[Outbox] Presigned URLs of {} refused, presigning again", transaction_id);
+                    }
+                    result => return Ok(result),
+                }
+            }
+            let request = booth_api::PresignUploadRequest {
+                transaction_id: transaction_id.clone(),
+                files: files
+                    .iter()
+                    .map(|file| json!({ "type": file.kind, "contentType": file.content_type }))
+                    .collect(),
+                transaction_code: transaction_code.clone(),
+            };
+            // A key of its own, so a repeated presign gets fresh URLs
+            let presign = client
+                .clone()
+                .with_idempotency_key(&uuid::Uuid::new_v4().to_string())
+                .create_presign_upload(&request)
+                .await;
+            match presign {
+                Ok(answer) => upload_and_confirm(client, files, &Presigned::from_answer(&answer)?).await?,
+                Err(e) => Err(e),
+            }
+        }
+        OutboxRequest::PaperLevel(body) => client.paper_level(body).await,
+        OutboxRequest::PaperReduce(body) => client.paper_reduce(body).await,
+        OutboxRequest::DeviceAlert(body) => client.device_alert(body).await,
+        OutboxRequest::DeviceReconnected(body) => client.device_reconnected(body).await,
+        OutboxRequest::DeviceStatusReport(body) => client.device_status_report(body).await,
+    })
+}
+
+/// PUT each file to its presigned URL (photos and videos each in the order
+/// of the URLs) and confirm the ones uploaded
+async fn upload_and_confirm(
+    client: &BoothApiClient,
+    files: &[DeliveryFile],
+    presigned: &Presigned,
+) -> Result<Result<Value, ApiError>, String> {
+    let mut targets = presigned.upload_urls.clone();
+    targets.sort_by_key(|target| target.order);
+    let mut uploaded = Vec::new();
+    for file in files {
+        let Some(index) = targets.iter().position(|target| target.kind == file.kind) else {
+            log::warn!("[Outbox] No {} upload URL left for {}", file.kind, file.file_path);
+            continue;
+        };
+        let target = targets.remove(index);
+        let data = tokio::fs::read(&file.file_path)
+            .await
+            .map_err(|e| format!("File read error: {}", e))?;
+        if let Err(e) = client.upload_presigned(&target.upload_url, &file.content_type, data).await {
+            return Ok(Err(e));
+        }
+        uploaded.push(json!({ "key": target.key, "type": target.kind, "order": target.order }));
+    }
+    if uploaded.is_empty() {
+        return Err("Presign answer has no upload URLs for the files".to_string());
+    }
+    let body = booth_api::ConfirmUploadRequest {
+        uploaded_files: Value::Array(uploaded),
+    };
+    Ok(client.confirm_upload(&presigned.session_id, &body).await)
+}
+
+/// Send `request` now, or queue it if the backend can't be reached (or
+/// earlier calls are still queued). Queued calls answer
+/// `success: true, data: { queued: true, outboxId }`.
+pub async fn submit(state: &AppState, request: OutboxRequest) -> Result<ApiResponse, String> {
+    let id = uuid::Uuid::new_v4().simple().to_string();
+    let available = OUTBOX.lock().unwrap().as_ref().map(Outbox::has_pending);
+    if available == Some(true) {
+        return enqueue(id, request).map(|item| queued(&item));
+    }
+
+    // One attempt: if it fails the outbox retries, the caller doesn't wait
+    let client = BoothApiClient::from_state(state)
+        .with_retry_policy(RetryPolicy::NONE)
+        .with_idempotency_key(&id);
+    match send(&client, &request).await? {
+        Err(e) if e.is_retryable() && available.is_some() => {
+            log::warn!("[Outbox] {} - queued for later", e);
+            enqueue(id, request).map(|item| queued(&item))
+        }
+        result => crate::api::respond(result),
+    }
+}
+
+fn queued(item: &OutboxItem) -> ApiResponse {
+    ApiResponse {
+        success: true,
+        data: Some(json!({ "queued": true, "outboxId": item.id })),
+        error: None,
+    }
+}
+
+/// Whether `submit` queued the call instead of delivering it
+pub fn is_queued(res: &ApiResponse) -> bool {
+    res.data.as_ref().and_then(|d| d.get("queued")).and_then(Value::as_bool) == Some(true)
+}
+
+/// Paper changes still waiting — the backend's `paperLevel` is behind
+pub fn paper_pending() -> bool {
+    OUTBOX
+        .lock()
+        .unwrap()
+        .as_ref()
+        .is_some_and(|o| o.items.iter().any(|i| i.status == ItemStatus::Pending && i.request.is_paper()))
+}
+
+/// The backend is reachable again (SSE connected): stop backing off
+pub fn network_restored() {
+    let waiting = update(|outbox| {
+        outbox.retry_at = None;
+        outbox.has_pending()
+    });
+    if waiting == Some(true) {
+        log::info!("[Outbox] Network back, draining");
+        wake().notify_one();
+    }
+}
+
+// =============================================================================
+// Worker
+// =============================================================================
+
+/// Oldest waiting item, unless backing off
+fn next_due() -> Option<OutboxItem> {
+    let guard = OUTBOX.lock().unwrap();
+    let outbox = guard.as_ref()?;
+    if outbox.retry_at.is_some_and(|at| at > Instant::now()) {
+        return None;
+    }
+    outbox.items.iter().find(|i| i.status == ItemStatus::Pending).cloned()
+}
+
+/// How long the worker may sleep
+fn idle_time() -> Duration {
+    let retry_at = OUTBOX.lock().unwrap().as_ref().and_then(|o| o.retry_at);
+    retry_at.map_or(IDLE_POLL, |at| at.saturating_duration_since(Instant::now()).min(IDLE_POLL))
+}
+
+async fn worker() {
+    log::info!("[Outbox] Worker started");
+    loop {
+        match next_due() {
+            Some(item) => deliver(item).await,
+            // Sleep until something is queued, the network is back or the
+            // backoff is over
+            None => {
+                let _ = tokio::time::timeout(idle_time(), wake().notified()).await;
+            }
+        }
+    }
+}
+
+async fn deliver(item: OutboxItem) {
+    let Some(state) = APP.get().and_then(|app| app.try_state::<AppState>()) else {
+        tokio::time::sleep(IDLE_POLL).await;
+        return;
+    };
+    // The outbox does its own backoff
+    let client = BoothApiClient::from_state(&state)
+        .with_retry_policy(RetryPolicy::NONE)
+        .with_idempotency_key(&item.id);
+    let outcome = send(&client, &item.request).await;
+
+    update(|outbox| {
+        // Discarded or superseded meanwhile
+        let Some(mut item) = outbox.items.iter().find(|i| i.id == item.id).cloned() else {
+            return;
+        };
+        item.attempts += 1;
+        item.updated_at = now();
+        let error = match outcome {
+            Ok(Ok(_)) => {
+                outbox.failures = 0;
+                outbox.last_error = None;
+                outbox.retry_at = None;
+                outbox.remove(std::slice::from_ref(&item.id));
+                log::info!(
+                    "[Outbox] Sent {} {} ({} waiting)",
+                    item.request.kind(),
+                    item.id,
+                    outbox.status().depth
+                );
+                return;
+            }
+            Ok(Err(e)) if e.is_retryable() => {
+                outbox.failures += 1;
+                let delay = BACKOFF.backoff(outbox.failures);
+                outbox.retry_at = Some(Instant::now() + delay);
+                outbox.last_error = Some(e.to_string());
+                log::warn!(
+                    "[Outbox] {} - {} waiting, next try in {}s",
+                    e,
+                    outbox.status().depth,
+                    delay.as_secs()
+                );
+                item.error = Some(e.to_string());
+                outbox.put(item);
+                return;
+            }
+            // The backend answered, so the network is fine
+            Ok(Err(e)) => {
+                outbox.failures = 0;
+                outbox.retry_at = None;
+                e.to_string()
+            }
+            Err(e) => e,
+        };
+        log::error!("[Outbox] {} {} failed: {}", item.request.kind(), item.id, error);
+        item.status = ItemStatus::Failed;
+        item.error = Some(error);
+        outbox.put(item);
+    });
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+#[tauri::command]
+pub fn get_outbox_status() -> Result<OutboxStatus, String> {
+    OUTBOX
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(Outbox::status)
+        .ok_or("Outbox is not available".to_string())
+}
+
+/// Waiting and failed items, oldest first
+#[tauri::command]
+pub fn get_outbox_items() -> Result<Vec<OutboxItem>, String> {
+    OUTBOX
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|o| o.items.clone())
+        .ok_or("Outbox is not available".to_string())
+}
+
+/// Try the waiting items now instead of after the backoff
+#[tauri::command]
+pub fn drain_outbox() -> Result<OutboxStatus, String> {
+    let status = update(|outbox| {
+        outbox.retry_at = None;
+        outbox.status()
+    })
+    .ok_or("Outbox is not available")?;
+    wake().notify_one();
+    Ok(status)
+}
+
+/// Queue a failed item again
+#[tauri::command]
+pub fn retry_outbox_item(item_id: String) -> Result<OutboxItem, String> {
+    let item = update(|outbox| {
+        let mut item = outbox
+            .items
+            .iter()
+            .find(|i| i.id == item_id)
+            .cloned()
+            .ok_or_else(|| format!("Outbox item '{}' not found", item_id))?;
+        if item.status != ItemStatus::Failed {
+            return Err("Only failed items can be retried".to_string());
+        }
+        item.status = ItemStatus::Pending;
+        item.updated_at = now();
+        outbox.put(item.clone());
+        Ok(item)
+    })
+    .ok_or("Outbox is not available")??;
+    log::info!("[Outbox] Retrying {} {}", item.request.kind(), item.id);
+    wake().notify_one();
+    Ok(item)
+}
+
+/// Drop an item without sending it
+#[tauri::command]
+pub fn discard_outbox_item(item_id: String) -> Result<(), String> {
+    update(|outbox| {
+        if !outbox.items.iter().any(|i| i.id == item_id) {
+            return Err(format!("Outbox item '{}' not found", item_id));
+        }
+        outbox.remove(std::slice::from_ref(&item_id));
+        log::warn!("[Outbox] Discarded {}", item_id);
+        Ok(())
+    })
+    .ok_or("Outbox is not available")?
+}
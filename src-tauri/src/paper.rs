@@ -0,0 +1,472 @@
+//! Paper Profiles
+//!
+//! A [`PaperProfile`] describes one printed product: the finished print size,
+//! the resolution it is rendered at, how many prints share a sheet and how
+//! the driver should be set up. Capture resizing, `compose_frame`,
+//! `print_photo` and the test print all read their sizes from here.
+//!
+//! The built-in profiles are the original products (4x6, 6x4, 2x6, 6x2). A
+//! `paper_profiles.json` in the app config folder adds profiles or replaces
+//! built-ins with the same `id`:
+//!
+//! ```json
+//! {
+//!   "profiles": [
+//!     { "id": "5x7", "name": "5x7", "widthMm": 127, "heightMm": 177.8, "dpi": 300,
+//!       "driverPaper": ["5x7", "7x5"] },
+//!     { "id": "4x4", "widthMm": 101.6, "heightMm": 101.6, "bleedMm": 1.5,
+//!       "driverPaper": ["4x4"] },
+//!     { "id": "wallet", "name": "Wallet 2-up", "widthMm": 63.5, "heightMm": 88.9,
+//!       "sheetWidthMm": 152.4, "sheetHeightMm": 101.6, "landscape": true,
+//!       "cut": { "columns": 2, "rows": 1, "rotate": 0, "gutterMm": 2, "cutMarks": true },
+//!       "driverPaper": ["6x4"] }
+//!   ]
+//! }
+//! ```
+//!
+//! How copies are laid out on the sheet is up to `imposition.rs`.
+
+use image::{DynamicImage, Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::Manager;
+
+const PROFILES_FILE: &str = "paper_profiles.json";
+const MM_PER_INCH: f64 = 25.4;
+
+static PROFILES: Mutex<Vec<PaperProfile>> = Mutex::new(Vec::new());
+/// Profile ids picked in the paper settings: (portrait, landscape)
+static SELECTED: Mutex<(Option<String>, Option<String>)> = Mutex::new((None, None));
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// How copies of a print are laid out on one sheet (N-up), and how they are
+/// cut apart — by the printer's cut driver or by hand along crop marks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CutLayout {
+    #[serde(default = "default_one")]
+    pub columns: u32,
+    #[serde(default = "default_one")]
+    pub rows: u32,
+    /// Print through the printer's cut driver ("{printer} (CUT)")
+    #[serde(default)]
+    pub driver_cut: bool,
+    /// Space between neighbouring copies
+    #[serde(default)]
+    pub gutter_mm: f64,
+    /// Clockwise degrees each copy is turned on the sheet: 0, 90, 180 or 270
+    #[serde(default)]
+    pub rotate: u32,
+    /// Draw crop marks in the sheet margin, in line with every cut
+    #[serde(default)]
+    pub cut_marks: bool,
+}
+
+impl Default for CutLayout {
+    fn default() -> Self {
+        CutLayout {
+            columns: 1,
+            rows: 1,
+            driver_cut: false,
+            gutter_mm: 0.0,
+            rotate: 0,
+            cut_marks: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaperProfile {
+    /// Stable id, also used by the frontend and the test print file name
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    /// Finished size of one print, as designed (landscape prints are wider)
+    pub width_mm: f64,
+    pub height_mm: f64,
+    #[serde(default = "default_dpi")]
+    pub dpi: u32,
+    /// Extra image around the sheet edge, cut off by borderless printing
+    #[serde(default)]
+    pub bleed_mm: f64,
+    #[serde(default)]
+    pub cut: CutLayout,
+    /// Physical sheet size; defaults to exactly fit the grid of copies
+    #[serde(default)]
+    pub sheet_width_mm: Option<f64>,
+    #[serde(default)]
+    pub sheet_height_mm: Option<f64>,
+    /// Driver orientation for the sheet
+    #[serde(default)]
+    pub landscape: bool,
+    /// Driver paper names to select, in order of preference (matched
+    /// case-insensitively as substrings)
+    #[serde(default)]
+    pub driver_paper: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct ProfilesFile {
+    #[serde(default)]
+    profiles: Vec<PaperProfile>,
+}
+
+fn default_one() -> u32 {
+    1
+}
+
+fn default_dpi() -> u32 {
+    600
+}
+
+fn builtin(id: &str, width_in: f64, height_in: f64, columns: u32, rows: u32) -> PaperProfile {
+    let cut = columns * rows > 1;
+    PaperProfile {
+        id: id.to_string(),
+        name: if cut { format!("{} (cut)", id) } else { id.to_string() },
+        width_mm: width_in * MM_PER_INCH,
+        height_mm: height_in * MM_PER_INCH,
+        dpi: default_dpi(),
+        bleed_mm: 0.0,
+        cut: CutLayout {
+            columns,
+            rows,
+            driver_cut: cut,
+            ..CutLayout::default()
+        },
+        sheet_width_mm: None,
+        sheet_height_mm: None,
+        landscape: width_in * columns as f64 > height_in * rows as f64,
+        // Cut products still print on a full 4x6 sheet
+        driver_paper: vec!["4x6".to_string(), "6x4".to_string()],
+    }
+}
+
+fn builtin_profiles() -> Vec<PaperProfile> {
+    vec![
+        builtin("4x6", 4.0, 6.0, 1, 1),
+        builtin("6x4", 6.0, 4.0, 1, 1),
+        builtin("2x6", 2.0, 6.0, 2, 1),
+        builtin("6x2", 6.0, 2.0, 1, 2),
+    ]
+}
+
+// =============================================================================
+// Geometry
+// =============================================================================
+
+pub fn mm_to_px(mm: f64, dpi: u32) -> u32 {
+    (mm / MM_PER_INCH * dpi as f64).round().max(1.0) as u32
+}
+
+impl PaperProfile {
+    /// One print at `dpi`, without bleed
+    pub fn print_pixels(&self) -> (u32, u32) {
+        (mm_to_px(self.width_mm, self.dpi), mm_to_px(self.height_mm, self.dpi))
+    }
+
+    /// One copy as placed on the sheet (after `cut.rotate`)
+    pub fn cell_pixels(&self) -> (u32, u32) {
+        let (w, h) = self.print_pixels();
+        if self.cut.rotate % 180 == 90 {
+            (h, w)
+        } else {
+            (w, h)
+        }
+    }
+
+    pub fn gutter_pixels(&self) -> u32 {
+        if self.cut.gutter_mm > 0.0 {
+            mm_to_px(self.cut.gutter_mm, self.dpi)
+        } else {
+            0
+        }
+    }
+
+    /// All copies with their gutters
+    pub fn grid_pixels(&self) -> (u32, u32) {
+        let (cell_w, cell_h) = self.cell_pixels();
+        let gutter = self.gutter_pixels();
+        let (cols, rows) = (self.cut.columns, self.cut.rows);
+        (cell_w * cols + gutter * (cols - 1), cell_h * rows + gutter * (rows - 1))
+    }
+
+    /// Whole sheet at `dpi`, including bleed
+    pub fn sheet_pixels(&self) -> (u32, u32) {
+        let (grid_w, grid_h) = self.grid_pixels();
+        let w = self.sheet_width_mm.map_or(grid_w, |mm| mm_to_px(mm, self.dpi));
+        let h = self.sheet_height_mm.map_or(grid_h, |mm| mm_to_px(mm, self.dpi));
+        let bleed = if self.bleed_mm > 0.0 { mm_to_px(self.bleed_mm, self.dpi) * 2 } else { 0 };
+        (w + bleed, h + bleed)
+    }
+
+    /// Copies per sheet
+    pub fn copies_per_sheet(&self) -> u32 {
+        self.cut.columns * self.cut.rows
+    }
+
+    /// Sheets `prints` photos take, each sheet filled before the next
+    /// (a 2-up strip profile prints 2 photos on one sheet)
+    pub fn sheets_for(&self, prints: u32) -> u32 {
+        prints.max(1).div_ceil(self.copies_per_sheet().max(1))
+    }
+
+    /// Width / height of one print
+    pub fn aspect(&self) -> f64 {
+        self.width_mm / self.height_mm
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.id.trim().is_empty() {
+            return Err("Paper profile without id".to_string());
+        }
+        let sizes = [self.width_mm, self.height_mm];
+        if sizes.iter().any(|v| !(v.is_finite() && *v > 0.0)) {
+            return Err(format!("Paper '{}': invalid size {}x{} mm", self.id, self.width_mm, self.height_mm));
+        }
+        if !(self.bleed_mm.is_finite() && self.bleed_mm >= 0.0) {
+            return Err(format!("Paper '{}': invalid bleed {} mm", self.id, self.bleed_mm));
+        }
+        if !(72..=2400).contains(&self.dpi) {
+            return Err(format!("Paper '{}': dpi {} out of range", self.id, self.dpi));
+        }
+        if self.cut.columns == 0 || self.cut.rows == 0 {
+            return Err(format!("Paper '{}': cut layout needs at least 1x1", self.id));
+        }
+        if !matches!(self.cut.rotate, 0 | 90 | 180 | 270) {
+            return Err(format!("Paper '{}': rotate must be 0, 90, 180 or 270", self.id));
+        }
+        if !(self.cut.gutter_mm.is_finite() && self.cut.gutter_mm >= 0.0) {
+            return Err(format!("Paper '{}': invalid gutter {} mm", self.id, self.cut.gutter_mm));
+        }
+        let sheet = [self.sheet_width_mm, self.sheet_height_mm];
+        if sheet.iter().flatten().any(|v| !(v.is_finite() && *v > 0.0)) {
+            return Err(format!("Paper '{}': invalid sheet size", self.id));
+        }
+        // The copies must fit on the sheet (1 px slack for mm rounding)
+        let (grid_w, grid_h) = self.grid_pixels();
+        let sheet_w = self.sheet_width_mm.map_or(grid_w, |mm| mm_to_px(mm, self.dpi));
+        let sheet_h = self.sheet_height_mm.map_or(grid_h, |mm| mm_to_px(mm, self.dpi));
+        if grid_w > sheet_w + 1 || grid_h > sheet_h + 1 {
+            return Err(format!(
+                "Paper '{}': {}x{} copies don't fit on the sheet",
+                self.id, self.cut.columns, self.cut.rows
+            ));
+        }
+        Ok(())
+    }
+}
+
+// =============================================================================
+// Registry
+// =============================================================================
+
+fn profiles_path(app: &tauri::AppHandle) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join(PROFILES_FILE))
+}
+
+/// Built-ins merged with `paper_profiles.json` (invalid entries are skipped
+/// with a warning)
+fn load_profiles(app: &tauri::AppHandle) -> Vec<PaperProfile> {
+    let mut profiles = builtin_profiles();
+    let Some(path) = profiles_path(app).filter(|p| p.exists()) else {
+        return profiles;
+    };
+
+    let parsed = std::fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|content| serde_json::from_str::<ProfilesFile>(&content).map_err(|e| e.to_string()));
+    let file = match parsed {
+        Ok(file) => file,
+        Err(e) => {
+            log::warn!("[Paper] Invalid {}: {}", path.display(), e);
+            return profiles;
+        }
+    };
+
+    for mut profile in file.profiles {
+        if let Err(e) = profile.validate() {
+            log::warn!("[Paper] Skipping profile: {}", e);
+            continue;
+        }
+        if profile.name.is_empty() {
+            profile.name = profile.id.clone();
+        }
+        match profiles.iter_mut().find(|p| p.id == profile.id) {
+            Some(existing) => *existing = profile,
+            None => profiles.push(profile),
+        }
+    }
+    profiles
+}
+
+/// (Re)load the profiles — called on startup
+pub fn init(app: &tauri::AppHandle) {
+    let profiles = load_profiles(app);
+    log::info!(
+        "[Paper] Profiles: {}",
+        profiles.iter().map(|p| p.id.as_str()).collect::<Vec<_>>().join(", ")
+    );
+    *PROFILES.lock().unwrap() = profiles;
+}
+
+pub fn profiles() -> Vec<PaperProfile> {
+    let profiles = PROFILES.lock().unwrap();
+    if profiles.is_empty() {
+        builtin_profiles()
+    } else {
+        profiles.clone()
+    }
+}
+
+pub fn get(id: &str) -> Result<PaperProfile, String> {
+    profiles()
+        .into_iter()
+        .find(|p| p.id == id)
+        .ok_or_else(|| format!("Unknown paper profile '{}'", id))
+}
+
+/// The profile whose print aspect is closest to a `width` × `height` image
+pub fn for_image(width: u32, height: u32) -> PaperProfile {
+    let aspect = width as f64 / height.max(1) as f64;
+    let distance = |p: &PaperProfile| (p.aspect() / aspect).ln().abs();
+    profiles()
+        .into_iter()
+        .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+        .expect("at least the built-in profiles")
+}
+
+/// Profile by id, or by the image's aspect when no id is given
+pub fn resolve(id: Option<&str>, width: u32, height: u32) -> Result<PaperProfile, String> {
+    match id.filter(|id| !id.is_empty()) {
+        Some(id) => get(id),
+        None => Ok(for_image(width, height)),
+    }
+}
+
+/// Remember the profiles picked for portrait and landscape prints (from the
+/// paper config), for outputs that have no frame of their own
+pub fn set_selected(portrait: Option<&str>, landscape: Option<&str>) {
+    *SELECTED.lock().unwrap() = (portrait.map(str::to_string), landscape.map(str::to_string));
+}
+
+/// The selected profile of the image's orientation, by aspect when none is
+/// selected (or it no longer exists)
+pub fn selected_for(width: u32, height: u32) -> PaperProfile {
+    let id = {
+        let selected = SELECTED.lock().unwrap();
+        if width > height {
+            selected.1.clone()
+        } else {
+            selected.0.clone()
+        }
+    };
+    resolve(id.as_deref(), width, height).unwrap_or_else(|e| {
+        log::warn!("[Paper] {}, using the closest profile", e);
+        for_image(width, height)
+    })
+}
+
+/// Longest print edge of any profile — captures never need to be larger
+pub fn capture_max_dimension() -> u32 {
+    profiles()
+        .iter()
+        .map(|p| {
+            let (w, h) = p.print_pixels();
+            w.max(h)
+        })
+        .max()
+        .unwrap_or(3600)
+}
+
+// =============================================================================
+// Test Chart
+// =============================================================================
+
+/// Alignment chart for profiles without a "Print test {id}.png": trim border,
+/// centre cross, corner marks and colour patches, at one print's size.
+pub fn test_chart(profile: &PaperProfile) -> DynamicImage {
+    let (w, h) = profile.print_pixels();
+    let mut img = RgbaImage::from_pixel(w, h, Rgba([255, 255, 255, 255]));
+    let line = (profile.dpi / 100).max(2);
+    let black = Rgba([0, 0, 0, 255]);
+
+    let fill = |img: &mut RgbaImage, x: u32, y: u32, rw: u32, rh: u32, color: Rgba<u8>| {
+        for py in y..(y + rh).min(h) {
+            for px in x..(x + rw).min(w) {
+                img.put_pixel(px, py, color);
+            }
+        }
+    };
+
+    // Trim border
+    fill(&mut img, 0, 0, w, line, black);
+    fill(&mut img, 0, h.saturating_sub(line), w, line, black);
+    fill(&mut img, 0, 0, line, h, black);
+    fill(&mut img, w.saturating_sub(line), 0, line, h, black);
+
+    // Centre cross
+    let arm = w.min(h) / 8;
+    fill(&mut img, w / 2 - arm, (h / 2).saturating_sub(line / 2), arm * 2, line, black);
+    fill(&mut img, (w / 2).saturating_sub(line / 2), h / 2 - arm, line, arm * 2, black);
+
+    // Corner marks 3 mm in from the trim
+    // (saturating so a tiny custom profile can't underflow)
+    let inset = mm_to_px(3.0, profile.dpi);
+    let far_x = |size: u32| w.saturating_sub(inset + size);
+    let far_y = |size: u32| h.saturating_sub(inset + size);
+    for (cx, cy) in [(inset, inset), (far_x(arm / 2), inset), (inset, far_y(line)), (far_x(arm / 2), far_y(line))] {
+        fill(&mut img, cx, cy, arm / 2, line, black);
+    }
+    for (cx, cy) in [(inset, inset), (far_x(line), inset), (inset, far_y(arm / 2)), (far_x(line), far_y(arm / 2))] {
+        fill(&mut img, cx, cy, line, arm / 2, black);
+    }
+
+    // Colour patches + grey ramp under the cross
+    let colors = [
+        [255, 0, 0], [0, 255, 0], [0, 0, 255],
+        [0, 255, 255], [255, 0, 255], [255, 255, 0],
+        [0, 0, 0], [64, 64, 64], [128, 128, 128], [192, 192, 192],
+    ];
+    let patch = (w * 3 / 4) / colors.len() as u32;
+    let start_x = (w - patch * colors.len() as u32) / 2;
+    let y = (h / 2 + arm + line * 4).min(h.saturating_sub(patch + inset));
+    for (i, c) in colors.iter().enumerate() {
+        fill(&mut img, start_x + i as u32 * patch, y, patch, patch, Rgba([c[0], c[1], c[2], 255]));
+    }
+
+    DynamicImage::ImageRgba8(img)
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+#[tauri::command]
+pub async fn get_paper_profiles() -> Result<Vec<PaperProfile>, String> {
+    Ok(profiles())
+}
+
+/// Re-read `paper_profiles.json` (after editing it on the machine)
+/// Sheets to print (the print job's `copies`) for `prints` photos of a
+/// `width` × `height` frame on `paper_profile` (default: by the frame's aspect)
+#[tauri::command]
+pub async fn get_print_sheets(
+    paper_profile: Option<String>,
+    width: u32,
+    height: u32,
+    prints: u32,
+) -> Result<u32, String> {
+    Ok(resolve(paper_profile.as_deref(), width, height)?.sheets_for(prints))
+}
+
+#[tauri::command]
+pub async fn reload_paper_profiles(app: tauri::AppHandle) -> Result<Vec<PaperProfile>, String> {
+    init(&app);
+    Ok(profiles())
+}
@@ -0,0 +1,85 @@
+//! Image PDF
+//!
+//! Minimal PDF writer for print output: one JPEG image per page, filling a
+//! page of the given size. Used where a job has to travel as a single
+//! multi-page document (IPP printers, file printer proofs).
+
+use image::RgbImage;
+
+/// PDF with one page per entry of `order` (indexes into `images`), each
+/// `width_mm` × `height_mm`. Every image is embedded once however many pages
+/// show it.
+pub fn image_pdf(images: &[RgbImage], order: &[usize], width_mm: f64, height_mm: f64) -> Result<Vec<u8>, String> {
+    if let Some(&index) = order.iter().find(|&&i| i >= images.len()) {
+        return Err(format!("PDF page shows image {} of {}", index, images.len()));
+    }
+    let width_pt = width_mm / 25.4 * 72.0;
+    let height_pt = height_mm / 25.4 * 72.0;
+    let content = format!("q {:.2} 0 0 {:.2} 0 0 cm /Im0 Do Q", width_pt, height_pt);
+
+    let mut pdf: Vec<u8> = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+    let mut offsets = Vec::new();
+    let mut object = |pdf: &mut Vec<u8>, body: &[u8]| {
+        offsets.push(pdf.len());
+        pdf.extend(format!("{} 0 obj\n", offsets.len()).as_bytes());
+        pdf.extend(body);
+        pdf.extend(b"\nendobj\n");
+    };
+
+    // Objects 1 and 2 are the catalog and page tree, 3 the content stream all
+    // pages share; image i is object 4 + i, followed by one object per page
+    let first_page = 4 + images.len();
+    let kids: Vec<String> = (0..order.len()).map(|i| format!("{} 0 R", first_page + i)).collect();
+    object(&mut pdf, b"<< /Type /Catalog /Pages 2 0 R >>");
+    object(
+        &mut pdf,
+        format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), order.len()).as_bytes(),
+    );
+    object(
+        &mut pdf,
+        format!("<< /Length {} >>\nstream\n{}\nendstream", content.len(), content).as_bytes(),
+    );
+    for image in images {
+        let mut jpeg = Vec::new();
+        image::codecs::jpeg::JpegEncoder::new_with_quality(&mut jpeg, 95)
+            .encode_image(image)
+            .map_err(|e| format!("Encode error: {}", e))?;
+        let mut image_object = format!(
+            "<< /Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /DCTDecode /Length {} >>\nstream\n",
+            image.width(),
+            image.height(),
+            jpeg.len()
+        )
+        .into_bytes();
+        image_object.extend(&jpeg);
+        image_object.extend(b"\nendstream");
+        object(&mut pdf, &image_object);
+    }
+    for &index in order {
+        object(
+            &mut pdf,
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.2} {:.2}] /Resources << /XObject << /Im0 {} 0 R >> >> /Contents 3 0 R >>",
+                width_pt,
+                height_pt,
+                4 + index
+            )
+            .as_bytes(),
+        );
+    }
+
+    let xref = pdf.len();
+    pdf.extend(format!("xref\n0 {}\n0000000000 65535 f \n", offsets.len() + 1).as_bytes());
+    for offset in &offsets {
+        pdf.extend(format!("{:010} 00000 n \n", offset).as_bytes());
+    }
+    pdf.extend(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            offsets.len() + 1,
+            xref
+        )
+        .as_bytes(),
+    );
+    Ok(pdf)
+}
@@ -0,0 +1,50 @@
+//! Persisted Files
+//!
+//! How the booth's own state files (`machine.json`, the print queue, the
+//! media counters, the outbox journal) are written and read back:
+//! - [`write_atomic`] writes a temp file next to the target, syncs it to disk
+//!   and renames it over the target, so a crash or power cut leaves either
+//!   the old or the new file, never a half-written one
+//! - [`load_or_quarantine`] moves a file that can't be read to
+//!   `<name>.corrupt`, kept for inspection rather than overwritten
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// `<path>.tmp` / `<path>.corrupt`
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Replace `path` with `contents` via a synced temp file
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<(), String> {
+    let tmp = sibling(path, ".tmp");
+    let written = std::fs::File::create(&tmp).and_then(|mut file| {
+        file.write_all(contents)?;
+        file.sync_all()
+    });
+    written.map_err(|e| format!("Write {} failed: {}", tmp.display(), e))?;
+    std::fs::rename(&tmp, path).map_err(|e| format!("Write {} failed: {}", path.display(), e))
+}
+
+/// Read `path` and `parse` it. `Ok(None)` when there is no file yet. A file
+/// that can't be read or parsed is moved to `<name>.corrupt`; the error says
+/// so.
+pub fn load_or_quarantine<T>(
+    path: &Path,
+    parse: impl FnOnce(&str) -> Result<T, String>,
+) -> Result<Option<T>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    match std::fs::read_to_string(path).map_err(|e| e.to_string()).and_then(|content| parse(&content)) {
+        Ok(value) => Ok(Some(value)),
+        Err(e) => {
+            let backup = sibling(path, ".corrupt");
+            let _ = std::fs::rename(path, &backup);
+            Err(format!("Invalid {} ({}), moved to {}", path.display(), e, backup.display()))
+        }
+    }
+}
@@ -0,0 +1,593 @@
+//! Print Queue
+//!
+//! Booth prints are not spooled straight from the page: they become
+//! [`PrintJob`]s in a queue persisted to `print_queue/jobs.json` in the app
+//! data folder, with a copy of its images next to it, so a job survives an
+//! app restart and can be reprinted from the admin menu later. A job is one
+//! spool document: its pages (`imagePath`, then `images`) × `copies`.
+//!
+//! A background worker prints queued jobs oldest first:
+//!
+//! ```text
+//! queued → spooling → printing → printed   (the spooler completed it)
+//!             ↓           ↓
+//!             ↓         failed   (cancelled / aborted in the spooler)
+//!             ↓
+//!          queued (retry) → … → failed   (after MAX_ATTEMPTS spooler errors)
+//! ```
+//!
+//! While a job's printer is offline (out of paper, unplugged, paused) the job
+//! stays `queued` and is picked up again as soon as the printer reports
+//! online. Every change is emitted to the frontend as `print-job-updated`.
+
+use crate::printer;
+use crate::printer_backend::SpoolState;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+const QUEUE_DIR: &str = "print_queue";
+const JOBS_FILE: &str = "jobs.json";
+/// Spooler errors (with the printer online) before a job is marked failed
+const MAX_ATTEMPTS: u32 = 3;
+const RETRY_DELAY: Duration = Duration::from_secs(15);
+/// How often an offline printer is checked again
+const OFFLINE_POLL: Duration = Duration::from_secs(5);
+/// Finished jobs kept in the history (with their images)
+const KEEP_FINISHED: usize = 200;
+
+static QUEUE: Mutex<Option<Queue>> = Mutex::new(None);
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+fn wake() -> &'static tokio::sync::Notify {
+    static WAKE: OnceLock<tokio::sync::Notify> = OnceLock::new();
+    WAKE.get_or_init(tokio::sync::Notify::new)
+}
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Queued,
+    Spooling,
+    /// Handed to the spooler, waiting for it to finish the job
+    Printing,
+    Printed,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    fn is_finished(self) -> bool {
+        matches!(self, JobStatus::Printed | JobStatus::Failed | JobStatus::Cancelled)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrintJob {
+    pub id: String,
+    /// Copy of the print owned by the queue (first page)
+    pub image_path: String,
+    /// Copies of further pages, printed after `image_path`
+    #[serde(default)]
+    pub images: Vec<String>,
+    pub printer_name: String,
+    pub paper_profile: Option<String>,
+    pub scale: f64,
+    pub vertical_offset: f64,
+    pub horizontal_offset: f64,
+    pub copies: u32,
+    /// Copies print the whole set before the next one (1 2 1 2)
+    #[serde(default = "default_collate")]
+    pub collate: bool,
+    pub copies_printed: u32,
+    pub status: JobStatus,
+    /// Spooler errors so far
+    pub attempts: u32,
+    /// Last error, or why a queued job is waiting
+    pub error: Option<String>,
+    /// Job this one reprints
+    pub reprint_of: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    /// Not before this instant (retry back-off, offline printer)
+    #[serde(skip)]
+    retry_at: Option<Instant>,
+}
+
+/// Print settings of a new job
+#[derive(Debug, Clone)]
+pub struct PrintRequest {
+    pub image_path: String,
+    pub images: Vec<String>,
+    pub printer_name: String,
+    pub paper_profile: Option<String>,
+    pub scale: Option<f64>,
+    pub vertical_offset: Option<f64>,
+    pub horizontal_offset: Option<f64>,
+    pub copies: Option<u32>,
+    pub collate: Option<bool>,
+}
+
+struct Queue {
+    dir: PathBuf,
+    jobs: Vec<PrintJob>,
+}
+
+fn now() -> String {
+    chrono::Local::now().to_rfc3339()
+}
+
+fn default_collate() -> bool {
+    true
+}
+
+impl PrintJob {
+    /// Every page of the job, in order
+    fn pages(&self) -> Vec<String> {
+        std::iter::once(self.image_path.clone()).chain(self.images.iter().cloned()).collect()
+    }
+}
+
+// =============================================================================
+// Persistence
+// =============================================================================
+
+impl Queue {
+    fn jobs_path(&self) -> PathBuf {
+        self.dir.join(JOBS_FILE)
+    }
+
+    /// Write `jobs.json` (see `persist::write_atomic`)
+    fn save(&self) {
+        let result = serde_json::to_vec_pretty(&self.jobs)
+            .map_err(|e| e.to_string())
+            .and_then(|json| crate::persist::write_atomic(&self.jobs_path(), &json));
+        if let Err(e) = result {
+            log::error!("[PrintQueue] Failed to save {}: {}", self.jobs_path().display(), e);
+        }
+    }
+
+    /// Drop the oldest finished jobs beyond `KEEP_FINISHED`, with their images
+    /// (unless a newer job still uses the same file)
+    fn prune(&mut self) {
+        let finished = self.jobs.iter().filter(|j| j.status.is_finished()).count();
+        let mut excess = finished.saturating_sub(KEEP_FINISHED);
+        if excess == 0 {
+            return;
+        }
+        let mut removed = Vec::new();
+        self.jobs.retain(|job| {
+            if excess > 0 && job.status.is_finished() {
+                excess -= 1;
+                removed.extend(job.pages());
+                false
+            } else {
+                true
+            }
+        });
+        for path in removed {
+            if !self.jobs.iter().any(|j| j.pages().contains(&path)) {
+                let _ = std::fs::remove_file(&path);
+            }
+        }
+    }
+}
+
+fn load(dir: &Path) -> Vec<PrintJob> {
+    let path = dir.join(JOBS_FILE);
+    let parse = |content: &str| serde_json::from_str::<Vec<PrintJob>>(content).map_err(|e| e.to_string());
+    match crate::persist::load_or_quarantine(&path, parse) {
+        Ok(jobs) => jobs.unwrap_or_default(),
+        Err(e) => {
+            log::error!("[PrintQueue] {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Load the queue and start the worker — called on startup
+pub fn init(app: &AppHandle) {
+    let dir = match app.path().app_data_dir() {
+        Ok(dir) => dir.join(QUEUE_DIR),
+        Err(e) => {
+            log::error!("[PrintQueue] No app data dir, print queue disabled: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        log::error!("[PrintQueue] Failed to create {}: {}", dir.display(), e);
+        return;
+    }
+
+    let mut jobs = load(&dir);
+    // The app stopped mid-spool or mid-print: the sheet may or may not have
+    // come out, so leave the decision to the operator instead of printing it twice
+    for job in jobs
+        .iter_mut()
+        .filter(|j| matches!(j.status, JobStatus::Spooling | JobStatus::Printing))
+    {
+        job.status = JobStatus::Failed;
+        job.error = Some("Interrupted while printing — check the printer before reprinting".to_string());
+        job.updated_at = now();
+    }
+    let queued = jobs.iter().filter(|j| j.status == JobStatus::Queued).count();
+    log::info!("[PrintQueue] {} jobs loaded from {} ({} queued)", jobs.len(), dir.display(), queued);
+
+    let queue = Queue { dir, jobs };
+    queue.save();
+    *QUEUE.lock().unwrap() = Some(queue);
+
+    if APP.set(app.clone()).is_ok() {
+        tauri::async_runtime::spawn(worker());
+    }
+}
+
+// =============================================================================
+// Queue Operations
+// =============================================================================
+
+fn emit(job: &PrintJob) {
+    if let Some(app) = APP.get() {
+        let _ = app.emit("print-job-updated", job);
+    }
+}
+
+/// Change a job, persist the queue and notify the frontend
+fn update(id: &str, f: impl FnOnce(&mut PrintJob)) -> Option<PrintJob> {
+    let mut guard = QUEUE.lock().unwrap();
+    let queue = guard.as_mut()?;
+    let job = queue.jobs.iter_mut().find(|j| j.id == id)?;
+    f(job);
+    job.updated_at = now();
+    let job = job.clone();
+    queue.save();
+    drop(guard);
+    emit(&job);
+    Some(job)
+}
+
+fn find(id: &str) -> Result<PrintJob, String> {
+    QUEUE
+        .lock()
+        .unwrap()
+        .as_ref()
+        .ok_or("Print queue is not available")?
+        .jobs
+        .iter()
+        .find(|j| j.id == id)
+        .cloned()
+        .ok_or_else(|| format!("Print job '{}' not found", id))
+}
+
+/// Add a job. The images are copied into the queue folder so they outlive
+/// the session folder they came from.
+pub fn enqueue(request: PrintRequest, reprint_of: Option<String>) -> Result<PrintJob, String> {
+    if request.printer_name.is_empty() {
+        return Err("No printer selected".to_string());
+    }
+    let copies = request.copies.unwrap_or(1).max(1);
+    let id = uuid::Uuid::new_v4().simple().to_string();
+
+    let mut guard = QUEUE.lock().unwrap();
+    let queue = guard.as_mut().ok_or("Print queue is not available")?;
+
+    let mut pages = Vec::new();
+    for (page, input) in std::iter::once(&request.image_path).chain(&request.images).enumerate() {
+        let source = crate::workdir::resolve_path(input)?;
+        let path = if source.starts_with(&queue.dir) {
+            source
+        } else {
+            let ext = source.extension().and_then(|e| e.to_str()).unwrap_or("jpg");
+            let name = if page == 0 { format!("{}.{}", id, ext) } else { format!("{}-p{}.{}", id, page + 1, ext) };
+            let target = queue.dir.join(name);
+            std::fs::copy(&source, &target).map_err(|e| format!("Failed to copy print image: {}", e))?;
+            target
+        };
+        pages.push(path.to_string_lossy().to_string());
+    }
+    let image_path = pages.remove(0);
+
+    let job = PrintJob {
+        id,
+        image_path,
+        images: pages,
+        printer_name: request.printer_name,
+        paper_profile: request.paper_profile.filter(|p| !p.is_empty()),
+        scale: request.scale.unwrap_or(100.0),
+        vertical_offset: request.vertical_offset.unwrap_or(0.0),
+        horizontal_offset: request.horizontal_offset.unwrap_or(0.0),
+        copies,
+        collate: request.collate.unwrap_or(true),
+        copies_printed: 0,
+        status: JobStatus::Queued,
+        attempts: 0,
+        error: None,
+        reprint_of,
+        created_at: now(),
+        updated_at: now(),
+        retry_at: None,
+    };
+    log::info!(
+        "[PrintQueue] Queued {} ({} pages x {} copies on '{}')",
+        job.id,
+        job.images.len() + 1,
+        job.copies,
+        job.printer_name
+    );
+    queue.jobs.push(job.clone());
+    queue.prune();
+    queue.save();
+    drop(guard);
+
+    emit(&job);
+    wake().notify_one();
+    Ok(job)
+}
+
+/// Oldest queued job that is due
+fn next_job() -> Option<PrintJob> {
+    let now = Instant::now();
+    QUEUE
+        .lock()
+        .unwrap()
+        .as_ref()?
+        .jobs
+        .iter()
+        .find(|j| j.status == JobStatus::Queued && j.retry_at.is_none_or(|at| at <= now))
+        .cloned()
+}
+
+// =============================================================================
+// Worker
+// =============================================================================
+
+async fn worker() {
+    log::info!("[PrintQueue] Worker started");
+    loop {
+        match next_job() {
+            Some(job) => run_job(job).await,
+            // Sleep until a job is queued, or a retry may have come due
+            None => {
+                let _ = tokio::time::timeout(OFFLINE_POLL, wake().notified()).await;
+            }
+        }
+    }
+}
+
+/// `Err(reason)` when the printer reports offline. A failed status query
+/// counts as online — the spooler gets the final say.
+async fn printer_online(printer_name: &str) -> Result<(), String> {
+    match printer::check_printer_status(printer_name.to_string()).await {
+        Ok(info) if info.is_online => Ok(()),
+        Ok(info) => Err(format!("Printer offline ({})", info.status)),
+        Err(e) => {
+            log::warn!("[PrintQueue] Status of '{}' unknown: {}", printer_name, e);
+            Ok(())
+        }
+    }
+}
+
+/// Keep the job queued until its printer is back
+fn wait_for_printer(job: &PrintJob, reason: String) {
+    let retry_at = Some(Instant::now() + OFFLINE_POLL);
+    if job.error.as_deref() == Some(reason.as_str()) {
+        // Already reported — don't rewrite the file every poll
+        if let Some(queue) = QUEUE.lock().unwrap().as_mut() {
+            if let Some(j) = queue.jobs.iter_mut().find(|j| j.id == job.id) {
+                j.retry_at = retry_at;
+            }
+        }
+        return;
+    }
+    log::warn!("[PrintQueue] {} waiting: {}", job.id, reason);
+    update(&job.id, |j| {
+        j.status = JobStatus::Queued;
+        j.error = Some(reason);
+        j.retry_at = retry_at;
+    });
+}
+
+async fn run_job(job: PrintJob) {
+    if let Err(reason) = printer_online(&job.printer_name).await {
+        wait_for_printer(&job, reason);
+        return;
+    }
+
+    // Re-checked under the lock: the job may have been cancelled meanwhile
+    let mut started = false;
+    let Some(job) = update(&job.id, |j| {
+        if j.status == JobStatus::Queued {
+            j.status = JobStatus::Spooling;
+            j.error = None;
+            started = true;
+        }
+    }) else {
+        return;
+    };
+    if !started {
+        return;
+    }
+    log::info!("[PrintQueue] Spooling {} ({}/{} printed)", job.id, job.copies_printed, job.copies);
+
+    // All remaining copies go out as one spool document
+    let spool_job = job.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let settings = printer::PrintSettings {
+            paper_profile: spool_job.paper_profile.as_deref(),
+            scale: spool_job.scale,
+            vertical_offset: spool_job.vertical_offset,
+            horizontal_offset: spool_job.horizontal_offset,
+            copies: spool_job.copies - spool_job.copies_printed,
+            collate: spool_job.collate,
+        };
+        printer::print_file(&spool_job.pages(), &spool_job.printer_name, &settings)
+    })
+    .await
+    .unwrap_or_else(|e| Err(format!("Print task panicked: {}", e)));
+
+    match result {
+        Ok(spooled) => {
+            log::info!(
+                "[PrintQueue] {} copies {}-{} are spool job {} on '{}'",
+                job.id,
+                job.copies_printed + 1,
+                job.copies,
+                spooled.job.id,
+                spooled.job.printer_name
+            );
+            if update(&job.id, |j| j.status = JobStatus::Printing).is_none() {
+                return;
+            }
+            // The job is printed (and its paper counted) once the spooler is
+            // done with it. Waiting doesn't hold up the next job.
+            let Some(app) = APP.get().cloned() else {
+                return;
+            };
+            tauri::async_runtime::spawn(async move {
+                let completed = printer::report_when_complete(&app, &job.printer_name, spooled).await;
+                finish_printing(&job.id, completed.job_id, completed.state);
+            });
+        }
+        Err(e) => {
+            log::error!("[PrintQueue] {} failed: {}", job.id, e);
+            // Printer dropped out mid-job: wait for it without using up an attempt
+            if let Err(reason) = printer_online(&job.printer_name).await {
+                wait_for_printer(&job, format!("{} — {}", reason, e));
+                return;
+            }
+            update(&job.id, |j| {
+                j.attempts += 1;
+                j.error = Some(e);
+                if j.attempts >= MAX_ATTEMPTS {
+                    j.status = JobStatus::Failed;
+                } else {
+                    j.status = JobStatus::Queued;
+                    j.retry_at = Some(Instant::now() + RETRY_DELAY * j.attempts);
+                }
+            });
+        }
+    }
+}
+
+/// Take the spooler's final state of a printing job. A job cancelled or
+/// aborted in the spooler fails with its copies still to print, so a reprint
+/// from the admin menu picks them up.
+fn finish_printing(id: &str, spool_job_id: i32, state: SpoolState) {
+    match state {
+        SpoolState::Canceled | SpoolState::Aborted => {
+            let error = format!("Spool job {} {:?} — check the printer and reprint", spool_job_id, state);
+            log::error!("[PrintQueue] {} failed: {}", id, error);
+            update(id, |j| {
+                j.status = JobStatus::Failed;
+                j.error = Some(error);
+            });
+        }
+        _ => {
+            log::info!("[PrintQueue] Printed {}", id);
+            update(id, |j| {
+                j.copies_printed = j.copies;
+                j.status = JobStatus::Printed;
+            });
+        }
+    }
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+/// Queue a print (same settings as `print_photo`). Progress arrives as
+/// `print-job-updated` events.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn enqueue_print_job(
+    image_path: String,
+    images: Option<Vec<String>>,
+    printer_name: String,
+    paper_profile: Option<String>,
+    scale: Option<f64>,
+    vertical_offset: Option<f64>,
+    horizontal_offset: Option<f64>,
+    copies: Option<u32>,
+    collate: Option<bool>,
+) -> Result<PrintJob, String> {
+    enqueue(
+        PrintRequest {
+            image_path,
+            images: images.unwrap_or_default(),
+            printer_name,
+            paper_profile,
+            scale,
+            vertical_offset,
+            horizontal_offset,
+            copies,
+            collate,
+        },
+        None,
+    )
+}
+
+/// All jobs, newest first
+#[tauri::command]
+pub async fn get_print_jobs() -> Result<Vec<PrintJob>, String> {
+    let guard = QUEUE.lock().unwrap();
+    let queue = guard.as_ref().ok_or("Print queue is not available")?;
+    Ok(queue.jobs.iter().rev().cloned().collect())
+}
+
+/// Failed or cancelled jobs go back in the queue for their remaining copies;
+/// any other finished job is printed again as a new job of `copies` copies
+/// (default: the original count).
+#[tauri::command]
+pub async fn reprint_print_job(job_id: String, copies: Option<u32>) -> Result<PrintJob, String> {
+    let job = find(&job_id)?;
+    match job.status {
+        JobStatus::Queued | JobStatus::Spooling | JobStatus::Printing => {
+            Err(format!("Print job '{}' is still {:?}", job_id, job.status))
+        }
+        JobStatus::Failed | JobStatus::Cancelled if copies.is_none() => {
+            log::info!("[PrintQueue] Retrying {}", job_id);
+            let job = update(&job_id, |j| {
+                j.status = JobStatus::Queued;
+                j.attempts = 0;
+                j.error = None;
+                j.retry_at = None;
+            })
+            .ok_or_else(|| format!("Print job '{}' not found", job_id))?;
+            wake().notify_one();
+            Ok(job)
+        }
+        _ => enqueue(
+            PrintRequest {
+                image_path: job.image_path,
+                images: job.images,
+                printer_name: job.printer_name,
+                paper_profile: job.paper_profile,
+                scale: Some(job.scale),
+                vertical_offset: Some(job.vertical_offset),
+                horizontal_offset: Some(job.horizontal_offset),
+                copies: Some(copies.unwrap_or(job.copies)),
+                collate: Some(job.collate),
+            },
+            Some(job.id),
+        ),
+    }
+}
+
+/// Remove a job that has not been spooled yet
+#[tauri::command]
+pub async fn cancel_print_job(job_id: String) -> Result<PrintJob, String> {
+    let job = find(&job_id)?;
+    if job.status != JobStatus::Queued {
+        return Err(format!("Only queued jobs can be cancelled ('{}' is {:?})", job_id, job.status));
+    }
+    log::info!("[PrintQueue] Cancelled {}", job_id);
+    update(&job_id, |j| j.status = JobStatus::Cancelled).ok_or_else(|| format!("Print job '{}' not found", job_id))
+}
@@ -1,7 +1,12 @@
 use log::error;
 use serde::{Deserialize, Serialize};
 use std::process::Command;
-use tauri::Manager;
+use crate::imposition;
+use crate::paper;
+use crate::printer_backend::{self, MarkerLevel, PrintDocument, SpoolJob, SpoolState};
+#[cfg(target_os = "windows")]
+use crate::printer_backend::PrinterBackend;
+use tauri::{Emitter, Manager};
 
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
@@ -43,6 +48,32 @@ mod print_ffi {
 
     pub const DC_PAPERNAMES: u16 = 16;
     pub const DC_PAPERS: u16 = 2;
+
+    /// PRINTER_INFO_2W (the windows crate only exposes it with Win32_Security)
+    #[repr(C)]
+    pub struct PRINTER_INFO_2W {
+        pub server_name: *const u16,
+        pub printer_name: *const u16,
+        pub share_name: *const u16,
+        pub port_name: *const u16,
+        pub driver_name: *const u16,
+        pub comment: *const u16,
+        pub location: *const u16,
+        pub dev_mode: *const u8,
+        pub sep_file: *const u16,
+        pub print_processor: *const u16,
+        pub datatype: *const u16,
+        pub parameters: *const u16,
+        pub security_descriptor: *const u8,
+        pub attributes: u32,
+        pub priority: u32,
+        pub default_priority: u32,
+        pub start_time: u32,
+        pub until_time: u32,
+        pub status: u32,
+        pub jobs: u32,
+        pub average_ppm: u32,
+    }
 }
 
 /// Helper: convert Rust string to null-terminated wide string (UTF-16)
@@ -131,9 +162,9 @@ fn win32_printer_exists(printer_name: &str) -> bool {
 
 /// Print an image using native Win32 GDI API.
 /// No PowerShell, no popup windows, full control over paper size and orientation.
-/// Auto-switches to "{printer_name} (CUT)" driver for cut frames if available.
+/// Auto-switches to "{printer_name} (CUT)" driver for cut profiles if available.
 #[cfg(target_os = "windows")]
-fn win32_gdi_print(printer_name: &str, image_path: &str, frame_type: &str) -> Result<(), String> {
+fn win32_gdi_print(printer_name: &str, document: &PrintDocument) -> Result<SpoolJob, String> {
     use windows::Win32::Graphics::Gdi::*;
     use windows::Win32::Graphics::Printing::{
         OpenPrinterW, ClosePrinter, DocumentPropertiesW,
@@ -141,57 +172,11 @@ fn win32_gdi_print(printer_name: &str, image_path: &str, frame_type: &str) -> Re
     use windows::Win32::Foundation::{HANDLE, HWND};
     use windows::core::PCWSTR;
 
-    let needs_cut = frame_type == "2x6" || frame_type == "6x2";
-    let is_landscape = frame_type == "6x4" || frame_type == "6x2";
+    let profile = document.profile;
+    let needs_cut = profile.cut.driver_cut;
+    let is_landscape = profile.landscape;
 
-    // Auto-switch drivers: if needs_cut, try various CUT driver name patterns
-    // If no-cut, use base name (strip CUT suffix if present)
-    let actual_printer = if needs_cut {
-        let upper = printer_name.to_uppercase();
-        if upper.contains("CUT") {
-            // Already a CUT driver name
-            printer_name.to_string()
-        } else {
-            // Try common CUT driver naming patterns seen on customer machines:
-            // "DS-RX1 (CUT)", "DS-RX1 (Cut)", "DS-RX1 CUT", "DS-RX1 Cut"
-            let candidates = [
-                format!("{} (CUT)", printer_name),
-                format!("{} (Cut)", printer_name),
-                format!("{} CUT", printer_name),
-                format!("{} Cut", printer_name),
-            ];
-            let mut found: Option<String> = None;
-            for candidate in &candidates {
-                if win32_printer_exists(candidate) {
-                    log::info!("[Printer] Auto-switching to CUT driver: '{}'", candidate);
-                    found = Some(candidate.clone());
-                    break;
-                }
-            }
-            if let Some(cut_name) = found {
-                cut_name
-            } else {
-                log::info!("[Printer] No CUT driver variant found for '{}', using as-is", printer_name);
-                printer_name.to_string()
-            }
-        }
-    } else {
-        // For no-cut: strip any CUT suffix to get base driver name
-        let upper = printer_name.to_uppercase();
-        let base_name = if upper.ends_with(" (CUT)") || upper.ends_with(" (CUT)") {
-            printer_name[..printer_name.len() - 6].to_string()
-        } else if upper.ends_with(" CUT") {
-            printer_name[..printer_name.len() - 4].to_string()
-        } else {
-            printer_name.to_string()
-        };
-        if base_name != printer_name && win32_printer_exists(&base_name) {
-            log::info!("[Printer] Using base (no-cut) driver: '{}'", base_name);
-            base_name
-        } else {
-            printer_name.to_string()
-        }
-    };
+    let actual_printer = printer_backend::driver_for_job(printer_name, needs_cut, win32_printer_exists);
 
     log::info!("[Printer] Actual printer for job: '{}'", actual_printer);
 
@@ -242,26 +227,13 @@ fn win32_gdi_print(printer_name: &str, image_path: &str, frame_type: &str) -> Re
 
         // 4. Find and set paper size
         let paper_sizes = win32_get_paper_sizes(&actual_printer)?;
-        log::info!("[Printer] frame_type=\"{}\" needs_cut={} is_landscape={}", frame_type, needs_cut, is_landscape);
+        log::info!("[Printer] paper=\"{}\" needs_cut={} is_landscape={}", profile.id, needs_cut, is_landscape);
         log::info!("[Printer] Available paper sizes for '{}' ({} found):", actual_printer, paper_sizes.len());
         for (id, name) in &paper_sizes {
             log::info!("[Printer]   id={} name=\"{}\"", id, name);
         }
 
-        // Always select 4x6 (or 6x4) paper size — even for cut frames.
-        // The CUT driver handles cutting automatically; we just need to
-        // print the full 4x6 sheet and let the printer cut it.
-        let selected = paper_sizes.iter().find(|(_, n)| {
-            let lower = n.to_lowercase();
-            (lower.contains("4x6") || lower.contains("6x4"))
-                && !lower.contains("cut") && !lower.contains("2x6")
-        }).or_else(|| {
-            // Fallback: if no explicit 4x6 name, try any paper without "cut" keyword
-            paper_sizes.iter().find(|(_, n)| {
-                let lower = n.to_lowercase();
-                !lower.contains("cut") && !lower.contains("2x6")
-            })
-        });
+        let selected = printer_backend::select_driver_paper(profile, &paper_sizes);
 
         let dm = &mut *dm_ptr;
 
@@ -270,13 +242,20 @@ fn win32_gdi_print(printer_name: &str, image_path: &str, frame_type: &str) -> Re
             dm.dmFields |= DM_PAPERSIZE;
             log::info!("[Printer] Selected paper: \"{}\" (id={})", paper_name, paper_id);
         } else {
-            log::warn!("[Printer] No matching paper for frame_type={}, using driver default", frame_type);
+            log::warn!("[Printer] No matching paper for '{}', using driver default", profile.id);
         }
 
         // Set orientation
         dm.Anonymous1.Anonymous1.dmOrientation = if is_landscape { 2 } else { 1 };
         dm.dmFields |= DM_ORIENTATION;
 
+        // One sheet: copies are made by the driver. More pages: every copy is
+        // drawn in page order below, so collation doesn't depend on the driver.
+        let multi_page = document.pages.len() > 1;
+        let driver_copies = if multi_page { 1 } else { document.copies };
+        dm.Anonymous1.Anonymous1.dmCopies = driver_copies.clamp(1, i16::MAX as u32) as i16;
+        dm.dmFields |= DM_COPIES;
+
         // Apply modified DEVMODE
         DocumentPropertiesW(
             HWND::default(),
@@ -306,29 +285,34 @@ fn win32_gdi_print(printer_name: &str, image_path: &str, frame_type: &str) -> Re
         let page_h = GetDeviceCaps(hdc, VERTRES);
         log::info!("[Printer] Page: {}x{} device units", page_w, page_h);
 
-        // 7. Load image and convert to BGRA bottom-up (Windows bitmap format)
-        let img = image::open(image_path)
-            .map_err(|e| format!("Failed to open image for printing: {}", e))?;
-        let rgba = img.to_rgba8();
-        let (img_w, img_h) = (rgba.width(), rgba.height());
-        let raw = rgba.as_raw();
-        let stride = (img_w * 4) as usize;
-        let mut bgra = vec![0u8; stride * img_h as usize];
-        for y in 0..img_h as usize {
-            let src_row = y * stride;
-            let dst_row = (img_h as usize - 1 - y) * stride;
-            for x in 0..img_w as usize {
-                let si = src_row + x * 4;
-                let di = dst_row + x * 4;
-                bgra[di]     = raw[si + 2]; // B
-                bgra[di + 1] = raw[si + 1]; // G
-                bgra[di + 2] = raw[si];     // R
-                bgra[di + 3] = raw[si + 3]; // A
+        // 7. Load the sheets and convert to BGRA bottom-up (Windows bitmap format)
+        let mut sheets = Vec::new();
+        for image_path in document.pages {
+            let bytes = std::fs::read(image_path)
+                .map_err(|e| format!("Failed to open image for printing: {}", e))?;
+            let img = crate::orientation::decode_upright(&bytes, false)?;
+            let rgba = img.to_rgba8();
+            let (img_w, img_h) = (rgba.width(), rgba.height());
+            let raw = rgba.as_raw();
+            let stride = (img_w * 4) as usize;
+            let mut bgra = vec![0u8; stride * img_h as usize];
+            for y in 0..img_h as usize {
+                let src_row = y * stride;
+                let dst_row = (img_h as usize - 1 - y) * stride;
+                for x in 0..img_w as usize {
+                    let si = src_row + x * 4;
+                    let di = dst_row + x * 4;
+                    bgra[di]     = raw[si + 2]; // B
+                    bgra[di + 1] = raw[si + 1]; // G
+                    bgra[di + 2] = raw[si];     // R
+                    bgra[di + 3] = raw[si + 3]; // A
+                }
             }
+            sheets.push((img_w, img_h, bgra));
         }
 
         // 8. Print
-        let doc_name = to_wide("Bonio Booth Print");
+        let doc_name = to_wide(document.job_name);
         let doc_info = print_ffi::DOCINFOW {
             cb_size: std::mem::size_of::<print_ffi::DOCINFOW>() as i32,
             doc_name: doc_name.as_ptr(),
@@ -341,72 +325,356 @@ fn win32_gdi_print(printer_name: &str, image_path: &str, frame_type: &str) -> Re
         // CreatedHDC -> HDC -> isize (all repr(transparent))
         let raw_hdc: isize = std::mem::transmute_copy(&hdc);
 
-        if print_ffi::StartDocW(raw_hdc, &doc_info) <= 0 {
+        // StartDoc returns the spooler job id
+        let job_id = print_ffi::StartDocW(raw_hdc, &doc_info);
+        if job_id <= 0 {
             return Err("StartDoc failed".into());
         }
 
-        if print_ffi::StartPage(raw_hdc) <= 0 {
-            print_ffi::EndDoc(raw_hdc);
-            return Err("StartPage failed".into());
+        let page_order = if multi_page { document.page_order() } else { vec![0] };
+        for index in page_order {
+            let (img_w, img_h, bgra) = &sheets[index];
+            let (img_w, img_h) = (*img_w, *img_h);
+
+            if print_ffi::StartPage(raw_hdc) <= 0 {
+                print_ffi::EndDoc(raw_hdc);
+                return Err("StartPage failed".into());
+            }
+
+            SetStretchBltMode(hdc, HALFTONE);
+
+            // Preserve image aspect ratio: fit image inside page and center (no stretch distortion)
+            let (dst_x, dst_y, dst_w, dst_h) = printer_backend::fit_on_page(page_w, page_h, img_w, img_h);
+            log::info!(
+                "[Printer] Page {}: fit on page {}x{}, image {}x{}, draw at ({},{}) size {}x{}",
+                index + 1, page_w, page_h, img_w, img_h, dst_x, dst_y, dst_w, dst_h
+            );
+
+            let bmi = BITMAPINFO {
+                bmiHeader: BITMAPINFOHEADER {
+                    biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                    biWidth: img_w as i32,
+                    biHeight: img_h as i32,
+                    biPlanes: 1,
+                    biBitCount: 32,
+                    biCompression: 0, // BI_RGB
+                    biSizeImage: 0,
+                    biXPelsPerMeter: 0,
+                    biYPelsPerMeter: 0,
+                    biClrUsed: 0,
+                    biClrImportant: 0,
+                },
+                bmiColors: [RGBQUAD::default()],
+            };
+
+            StretchDIBits(
+                hdc,
+                dst_x, dst_y, dst_w, dst_h,
+                0, 0, img_w as i32, img_h as i32,
+                Some(bgra.as_ptr() as *const _),
+                &bmi,
+                DIB_RGB_COLORS,
+                SRCCOPY,
+            );
+
+            print_ffi::EndPage(raw_hdc);
         }
+        print_ffi::EndDoc(raw_hdc);
 
-        SetStretchBltMode(hdc, HALFTONE);
-
-        // Preserve image aspect ratio: fit image inside page and center (no stretch distortion).
-        // Previously we stretched to (page_w, page_h) which distorted ratio when page aspect != image aspect.
-        let page_w_f = page_w as f64;
-        let page_h_f = page_h as f64;
-        let img_w_f = img_w as f64;
-        let img_h_f = img_h as f64;
-        let scale_w = page_w_f / img_w_f;
-        let scale_h = page_h_f / img_h_f;
-        let scale = scale_w.min(scale_h); // same scale on both axes → ratio preserved
-        let dst_w = (img_w_f * scale).round() as i32;
-        let dst_h = (img_h_f * scale).round() as i32;
-        let dst_x = (page_w - dst_w) / 2;
-        let dst_y = (page_h - dst_h) / 2;
-        log::info!(
-            "[Printer] Aspect-preserved fit: page {}x{}, image {}x{}, scale {:.4}, draw at ({},{}) size {}x{}",
-            page_w, page_h, img_w, img_h, scale, dst_x, dst_y, dst_w, dst_h
-        );
+        log::info!("[Printer] Print job {} sent successfully via Win32 GDI", job_id);
+        Ok(SpoolJob {
+            id: job_id,
+            printer_name: actual_printer,
+            state: SpoolState::Pending,
+            reasons: Vec::new(),
+        })
+    }
+}
+
+// =============================================================================
+// Win32 Backend
+// =============================================================================
+
+/// Windows spooler printers, printed through GDI
+#[cfg(target_os = "windows")]
+pub struct Win32Backend;
+
+#[cfg(target_os = "windows")]
+pub static WIN32: Win32Backend = Win32Backend;
+
+/// PRINTER_INFO_2 `Status` bits → printer state reasons
+#[cfg(target_os = "windows")]
+const WIN32_STATUS_REASONS: &[(u32, &str)] = {
+    use windows::Win32::Graphics::Printing::*;
+    &[
+        (PRINTER_STATUS_PAUSED, "paused"),
+        (PRINTER_STATUS_ERROR, "other-error"),
+        (PRINTER_STATUS_PENDING_DELETION, "shutdown"),
+        (PRINTER_STATUS_PAPER_JAM, "media-jam"),
+        (PRINTER_STATUS_PAPER_OUT, "media-empty"),
+        (PRINTER_STATUS_MANUAL_FEED, "media-needed"),
+        (PRINTER_STATUS_PAPER_PROBLEM, "media-needed"),
+        (PRINTER_STATUS_OFFLINE, "offline"),
+        (PRINTER_STATUS_OUTPUT_BIN_FULL, "output-area-full"),
+        (PRINTER_STATUS_NOT_AVAILABLE, "offline"),
+        (PRINTER_STATUS_TONER_LOW, "marker-supply-low-warning"),
+        (PRINTER_STATUS_NO_TONER, "marker-supply-empty"),
+        (PRINTER_STATUS_USER_INTERVENTION, "other-error"),
+        (PRINTER_STATUS_OUT_OF_MEMORY, "other-error"),
+        (PRINTER_STATUS_DOOR_OPEN, "door-open"),
+        (PRINTER_STATUS_SERVER_OFFLINE, "offline"),
+        (PRINTER_STATUS_DRIVER_UPDATE_NEEDED, "driver-update-needed-warning"),
+        (PRINTER_STATUS_SERVER_UNKNOWN, "server-unknown-warning"),
+        (PRINTER_STATUS_PAGE_PUNT, "page-punt-error"),
+        // Informational (PRINTING/PROCESSING feed the "Printing" label instead)
+        (PRINTER_STATUS_BUSY, "busy-report"),
+        (PRINTER_STATUS_IO_ACTIVE, "io-active-report"),
+        (PRINTER_STATUS_WAITING, "waiting-report"),
+        (PRINTER_STATUS_INITIALIZING, "initializing-report"),
+        (PRINTER_STATUS_WARMING_UP, "warming-up-report"),
+        (PRINTER_STATUS_POWER_SAVE, "power-save-report"),
+    ]
+};
+
+#[cfg(target_os = "windows")]
+struct Win32Printer {
+    name: String,
+    port: String,
+    status: u32,
+    attributes: u32,
+}
+
+#[cfg(target_os = "windows")]
+unsafe fn from_wide_ptr(ptr: *const u16) -> String {
+    if ptr.is_null() {
+        return String::new();
+    }
+    let mut len = 0;
+    while *ptr.add(len) != 0 {
+        len += 1;
+    }
+    String::from_utf16_lossy(std::slice::from_raw_parts(ptr, len))
+}
+
+/// Local and connected printers with their spooler status (EnumPrinters level 2)
+#[cfg(target_os = "windows")]
+fn win32_enum_printers() -> Result<Vec<Win32Printer>, String> {
+    use windows::Win32::Graphics::Printing::{EnumPrintersW, PRINTER_ENUM_CONNECTIONS, PRINTER_ENUM_LOCAL};
+    use windows::core::PCWSTR;
+
+    let flags = PRINTER_ENUM_LOCAL | PRINTER_ENUM_CONNECTIONS;
+    unsafe {
+        let mut needed = 0u32;
+        let mut returned = 0u32;
+        // First call only reports the buffer size
+        let _ = EnumPrintersW(flags, PCWSTR::null(), 2, None, &mut needed, &mut returned);
+        if needed == 0 {
+            return Ok(vec![]);
+        }
+
+        // u64 storage keeps the PRINTER_INFO_2W array aligned
+        let mut buf = vec![0u64; (needed as usize).div_ceil(8)];
+        let ptr = buf.as_mut_ptr() as *mut u8;
+        EnumPrintersW(
+            flags,
+            PCWSTR::null(),
+            2,
+            Some(std::slice::from_raw_parts_mut(ptr, needed as usize)),
+            &mut needed,
+            &mut returned,
+        )
+        .map_err(|e| format!("EnumPrinters failed: {}", e))?;
+
+        let infos = std::slice::from_raw_parts(ptr as *const print_ffi::PRINTER_INFO_2W, returned as usize);
+        Ok(infos
+            .iter()
+            .map(|info| Win32Printer {
+                name: from_wide_ptr(info.printer_name),
+                port: from_wide_ptr(info.port_name),
+                status: info.status,
+                attributes: info.attributes,
+            })
+            .collect())
+    }
+}
+
+/// Friendly names of the USB printers PnP currently sees as connected.
+/// The spooler keeps reporting an unplugged USB printer as ready, so this is
+/// the only reliable presence check for them.
+#[cfg(target_os = "windows")]
+fn usb_printers_present() -> Option<Vec<String>> {
+    let output = hidden_command("powershell")
+        .args([
+            "-NoProfile",
+            "-Command",
+            "Get-PnpDevice -Class Printer -Status OK -ErrorAction SilentlyContinue | ForEach-Object { $_.FriendlyName }",
+        ])
+        .output()
+        .ok()?;
+    Some(
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty())
+            .collect(),
+    )
+}
+
+#[cfg(target_os = "windows")]
+fn win32_printer_info(printer: &Win32Printer, usb_present: Option<&[String]>) -> PrinterInfo {
+    use windows::Win32::Graphics::Printing::{
+        PRINTER_ATTRIBUTE_WORK_OFFLINE, PRINTER_STATUS_PRINTING, PRINTER_STATUS_PROCESSING,
+    };
+
+    let mut reasons: Vec<String> = WIN32_STATUS_REASONS
+        .iter()
+        .filter(|(bit, _)| printer.status & bit != 0)
+        .map(|(_, reason)| reason.to_string())
+        .collect();
+    if printer.attributes & PRINTER_ATTRIBUTE_WORK_OFFLINE != 0 {
+        reasons.push("offline".to_string());
+    }
+    // CUT driver variants share the base printer's PnP device
+    let unplugged = printer.port.starts_with("USB")
+        && usb_present.is_some_and(|present| !present.iter().any(|n| printer.name.starts_with(n.as_str())));
+    if unplugged {
+        reasons.push("offline".to_string());
+    }
+    reasons.sort();
+    reasons.dedup();
+
+    let printing = printer.status & (PRINTER_STATUS_PRINTING | PRINTER_STATUS_PROCESSING) != 0;
+    PrinterInfo {
+        name: printer.name.clone(),
+        status: printer_backend::status_label(&reasons, printing),
+        is_online: printer_backend::blocking_reason(&reasons).is_none(),
+        state_reasons: reasons,
+        markers: Vec::new(),
+        backend: "win32".to_string(),
+    }
+}
 
-        let bmi = BITMAPINFO {
-            bmiHeader: BITMAPINFOHEADER {
-                biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
-                biWidth: img_w as i32,
-                biHeight: img_h as i32,
-                biPlanes: 1,
-                biBitCount: 32,
-                biCompression: 0, // BI_RGB
-                biSizeImage: 0,
-                biXPelsPerMeter: 0,
-                biYPelsPerMeter: 0,
-                biClrUsed: 0,
-                biClrImportant: 0,
-            },
-            bmiColors: [RGBQUAD::default()],
+#[cfg(target_os = "windows")]
+impl PrinterBackend for Win32Backend {
+    fn id(&self) -> &'static str {
+        "win32"
+    }
+
+    fn owns(&self, printer_name: &str) -> bool {
+        win32_printer_exists(printer_name)
+    }
+
+    fn printers(&self) -> Result<Vec<PrinterInfo>, String> {
+        let printers = win32_enum_printers()?;
+        let usb_present = if printers.iter().any(|p| p.port.starts_with("USB")) {
+            usb_printers_present()
+        } else {
+            None
         };
+        Ok(printers
+            .iter()
+            .map(|p| win32_printer_info(p, usb_present.as_deref()))
+            .collect())
+    }
 
-        StretchDIBits(
-            hdc,
-            dst_x, dst_y, dst_w, dst_h,
-            0, 0, img_w as i32, img_h as i32,
-            Some(bgra.as_ptr() as *const _),
-            &bmi,
-            DIB_RGB_COLORS,
-            SRCCOPY,
-        );
+    fn status(&self, printer_name: &str) -> Result<PrinterInfo, String> {
+        let printers = win32_enum_printers()?;
+        let printer = printers
+            .iter()
+            .find(|p| p.name == printer_name)
+            .ok_or_else(|| format!("Printer '{}' not found", printer_name))?;
+        let usb_present = if printer.port.starts_with("USB") {
+            usb_printers_present()
+        } else {
+            None
+        };
+        Ok(win32_printer_info(printer, usb_present.as_deref()))
+    }
 
-        print_ffi::EndPage(raw_hdc);
-        print_ffi::EndDoc(raw_hdc);
+    fn paper_sizes(&self, printer_name: &str) -> Result<Vec<String>, String> {
+        let paper_sizes = win32_get_paper_sizes(printer_name)?;
+        Ok(paper_sizes.iter().map(|(id, name)| format!("{} (id={})", name, id)).collect())
+    }
 
-        log::info!("[Printer] Print job sent successfully via Win32 GDI");
-        Ok(())
+    fn print(&self, printer_name: &str, document: &PrintDocument) -> Result<SpoolJob, String> {
+        win32_gdi_print(printer_name, document)
+    }
+
+    fn job(&self, printer_name: &str, job_id: i32) -> Result<SpoolJob, String> {
+        use windows::Win32::Foundation::HANDLE;
+        use windows::Win32::Graphics::Printing::*;
+        use windows::core::PCWSTR;
+
+        let wide_name = to_wide(printer_name);
+        unsafe {
+            let mut handle = HANDLE::default();
+            OpenPrinterW(PCWSTR(wide_name.as_ptr()), &mut handle, None)
+                .map_err(|e| format!("OpenPrinter failed: {}", e))?;
+
+            let mut needed = 0u32;
+            let _ = GetJobW(handle, job_id as u32, 1, None, &mut needed);
+            let mut buf = vec![0u64; (needed as usize).div_ceil(8).max(1)];
+            let ptr = buf.as_mut_ptr() as *mut u8;
+            let found = needed > 0
+                && GetJobW(
+                    handle,
+                    job_id as u32,
+                    1,
+                    Some(std::slice::from_raw_parts_mut(ptr, needed as usize)),
+                    &mut needed,
+                )
+                .as_bool();
+            let _ = ClosePrinter(handle);
+
+            // The spooler drops jobs once they are printed
+            if !found {
+                return Ok(SpoolJob {
+                    id: job_id,
+                    printer_name: printer_name.to_string(),
+                    state: SpoolState::Completed,
+                    reasons: vec!["job-no-longer-in-spooler".to_string()],
+                });
+            }
+
+            let status = (*(ptr as *const JOB_INFO_1W)).Status;
+            let mut reasons = Vec::new();
+            for (bit, reason) in [
+                (JOB_STATUS_PAPEROUT, "media-empty"),
+                (JOB_STATUS_OFFLINE, "printer-stopped"),
+                (JOB_STATUS_ERROR, "job-error"),
+                (JOB_STATUS_BLOCKED_DEVQ, "job-blocked"),
+                (JOB_STATUS_USER_INTERVENTION, "job-user-intervention"),
+            ] {
+                if status & bit != 0 {
+                    reasons.push(reason.to_string());
+                }
+            }
+            let state = if status & (JOB_STATUS_PRINTED | JOB_STATUS_COMPLETE) != 0 {
+                SpoolState::Completed
+            } else if status & (JOB_STATUS_DELETED | JOB_STATUS_DELETING) != 0 {
+                SpoolState::Canceled
+            } else if !reasons.is_empty() {
+                SpoolState::Stopped
+            } else if status & JOB_STATUS_PAUSED != 0 {
+                SpoolState::Held
+            } else if status & (JOB_STATUS_PRINTING | JOB_STATUS_SPOOLING) != 0 {
+                SpoolState::Processing
+            } else {
+                SpoolState::Pending
+            };
+            Ok(SpoolJob {
+                id: job_id,
+                printer_name: printer_name.to_string(),
+                state,
+                reasons,
+            })
+        }
     }
 }
 
 /// Create a Command that hides the console window on Windows
 fn hidden_command(program: &str) -> Command {
+    #[cfg_attr(not(target_os = "windows"), allow(unused_mut))]
     let mut cmd = Command::new(program);
     #[cfg(target_os = "windows")]
     cmd.creation_flags(CREATE_NO_WINDOW);
@@ -418,6 +686,15 @@ pub struct PrinterInfo {
     pub name: String,
     pub status: String,
     pub is_online: bool,
+    /// IPP `printer-state-reasons` style keywords ("media-empty", "media-jam", …)
+    #[serde(default)]
+    pub state_reasons: Vec<String>,
+    /// Ink/ribbon levels, when the printer reports them
+    #[serde(default)]
+    pub markers: Vec<MarkerLevel>,
+    /// Backend driving the printer ("win32", "ipp")
+    #[serde(default)]
+    pub backend: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -490,236 +767,127 @@ pub async fn list_dslr_cameras() -> Result<Vec<DslrCameraInfo>, String> {
 
 #[tauri::command]
 pub async fn get_printers() -> Result<Vec<PrinterInfo>, String> {
-    // Use Win32_Printer (WMI/CIM) instead of Get-Printer cmdlet.
-    // Win32_Printer reflects physical USB disconnect much faster than Get-Printer.
-    // WorkOffline from Get-Printer often stays false even after USB unplug.
-    //
-    // We also cross-check with Get-PnpDevice for USB printers —
-    // if the USB device is physically gone, mark the printer as offline
-    // regardless of what WMI reports.
-    let ps_cmd = r#"
-        $printers = Get-CimInstance Win32_Printer | Select-Object Name,
-            @{N='PrinterStatus';E={$_.PrinterState}},
-            WorkOffline
-        # Get list of currently connected USB printer PnP devices
-        $usbPrinters = @()
-        try {
-            $pnp = Get-PnpDevice -Class Printer -Status OK -ErrorAction SilentlyContinue
-            foreach ($d in $pnp) { $usbPrinters += $d.FriendlyName }
-        } catch {}
-        # Build result: mark USB printers as offline if PnP device is gone
-        $result = @()
-        foreach ($p in $printers) {
-            $portInfo = Get-PrinterPort -Name (Get-Printer -Name $p.Name -ErrorAction SilentlyContinue).PortName -ErrorAction SilentlyContinue
-            $isUsb = $false
-            if ($portInfo -and $portInfo.Description -like '*USB*') { $isUsb = $true }
-            # For USB printers: cross-check with PnP device list
-            $pnpConnected = $true
-            if ($isUsb) {
-                $pnpConnected = $usbPrinters -contains $p.Name
-            }
-            $result += [PSCustomObject]@{
-                Name = $p.Name
-                PrinterStatus = $p.PrinterStatus
-                WorkOffline = $p.WorkOffline
-                PnpConnected = $pnpConnected
-                IsUsb = $isUsb
-            }
-        }
-        if ($result.Count -eq 0) { "[]" } else { $result | ConvertTo-Json }
-    "#;
-
-    let output = hidden_command("powershell")
-        .args(&["-NoProfile", "-Command", ps_cmd])
-        .output()
-        .map_err(|e| format!("Failed to get printers: {}", e))?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-
-    if stdout.trim().is_empty() || stdout.trim() == "[]" {
-        return Ok(vec![]);
-    }
-
-    let parsed: serde_json::Value =
-        serde_json::from_str(&stdout.trim()).map_err(|e| format!("Parse error: {}", e))?;
-
-    let printers_array = if parsed.is_array() {
-        parsed.as_array().unwrap().clone()
-    } else {
-        vec![parsed]
-    };
-
-    let printers: Vec<PrinterInfo> = printers_array
-        .iter()
-        .map(|p| {
-            let name = p
-                .get("Name")
-                .and_then(|n| n.as_str())
-                .unwrap_or("")
-                .to_string();
-            let status_num = p
-                .get("PrinterStatus")
-                .and_then(|s| s.as_u64())
-                .unwrap_or(0);
-            let work_offline = p
-                .get("WorkOffline")
-                .and_then(|w| w.as_bool())
-                .unwrap_or(false);
-            let pnp_connected = p
-                .get("PnpConnected")
-                .and_then(|w| w.as_bool())
-                .unwrap_or(true);
-            let is_usb = p
-                .get("IsUsb")
-                .and_then(|w| w.as_bool())
-                .unwrap_or(false);
-
-            let status = match status_num {
-                0 => "Normal".to_string(),
-                1 => "Paused".to_string(),
-                2 => "Error".to_string(),
-                3 => "Deleting".to_string(),
-                4 => "PaperJam".to_string(),
-                5 => "PaperOut".to_string(),
-                _ => format!("Unknown({})", status_num),
-            };
-
-            // Printer is online only if:
-            // 1. Not marked as WorkOffline
-            // 2. Status is Normal (0), Printing (1024), Processing (128), or Busy (10)
-            //    (Win32_Printer.PrinterState values: 0=Idle, 1=Paused, 2=Error, 3=Deleting, 4=PaperJam, 5=PaperOut)
-            //    Common active states: 1024 (Printing), 128 (Processing)
-            // 3. For USB printers: PnP device must be physically present
-            //
-            // We treat 0 (Normal) and specific active states as "Online".
-            // We treat Error(2), PaperJam(4), PaperOut(5) as "Offline/Error".
-            let is_online = !work_offline
-                && (status_num == 0 || status_num == 1024 || status_num == 128 || status_num == 10)
-                && (!is_usb || pnp_connected);
-
-            PrinterInfo {
-                name,
-                is_online,
-                status,
-            }
-        })
-        .collect();
-
-    Ok(printers)
+    tauri::async_runtime::spawn_blocking(printer_backend::all_printers)
+        .await
+        .map_err(|e| format!("Failed to get printers: {}", e))
 }
 
 #[tauri::command]
 pub async fn check_printer_status(printer_name: String) -> Result<PrinterInfo, String> {
-    // Use same detection logic as get_printers: WMI + PnP cross-check
-    let ps_cmd = format!(
-        r#"
-        $p = Get-CimInstance Win32_Printer -Filter "Name='{}'" | Select-Object Name, @{{N='PrinterStatus';E={{$_.PrinterState}}}}, WorkOffline
-        if (-not $p) {{ Write-Output ''; exit }}
-        $portInfo = Get-PrinterPort -Name (Get-Printer -Name '{}' -ErrorAction SilentlyContinue).PortName -ErrorAction SilentlyContinue
-        $isUsb = $false
-        if ($portInfo -and $portInfo.Description -like '*USB*') {{ $isUsb = $true }}
-        $pnpConnected = $true
-        if ($isUsb) {{
-            $pnp = @()
-            try {{ $pnp = Get-PnpDevice -Class Printer -Status OK -ErrorAction SilentlyContinue | ForEach-Object {{ $_.FriendlyName }} }} catch {{}}
-            $pnpConnected = $pnp -contains $p.Name
-        }}
-        [PSCustomObject]@{{
-            Name = $p.Name
-            PrinterStatus = $p.PrinterStatus
-            WorkOffline = $p.WorkOffline
-            PnpConnected = $pnpConnected
-            IsUsb = $isUsb
-        }} | ConvertTo-Json
-        "#,
-        printer_name.replace('\'', "''"),
-        printer_name.replace('\'', "''")
-    );
-
-    let output = hidden_command("powershell")
-        .args(&["-NoProfile", "-Command", &ps_cmd])
-        .output()
-        .map_err(|e| format!("Failed to check printer: {}", e))?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-
-    if stdout.trim().is_empty() {
-        return Err(format!("Printer '{}' not found", printer_name));
-    }
-
-    let parsed: serde_json::Value =
-        serde_json::from_str(&stdout.trim()).map_err(|e| format!("Parse error: {}", e))?;
-
-    let name = parsed
-        .get("Name")
-        .and_then(|n| n.as_str())
-        .unwrap_or("")
-        .to_string();
-    let status_num = parsed
-        .get("PrinterStatus")
-        .and_then(|s| s.as_u64())
-        .unwrap_or(0);
-    let work_offline = parsed
-        .get("WorkOffline")
-        .and_then(|w| w.as_bool())
-        .unwrap_or(false);
-    let pnp_connected = parsed
-        .get("PnpConnected")
-        .and_then(|w| w.as_bool())
-        .unwrap_or(true);
-    let is_usb = parsed
-        .get("IsUsb")
-        .and_then(|w| w.as_bool())
-        .unwrap_or(false);
-
-    let status = match status_num {
-        0 => "Normal".to_string(),
-        1 => "Paused".to_string(),
-        2 => "Error".to_string(),
-        _ => format!("Unknown({})", status_num),
-    };
+    tauri::async_runtime::spawn_blocking(move || {
+        printer_backend::for_printer(&printer_name).status(&printer_name)
+    })
+    .await
+    .map_err(|e| format!("Failed to check printer: {}", e))?
+}
 
-    Ok(PrinterInfo {
-        name,
-        is_online: !work_offline && status_num == 0 && (!is_usb || pnp_connected),
-        status,
+/// State of a spooled job (`print_photo` / the print queue log the id)
+#[tauri::command]
+pub async fn get_spool_job(printer_name: String, job_id: i32) -> Result<SpoolJob, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        printer_backend::for_printer(&printer_name).job(&printer_name, job_id)
     })
+    .await
+    .map_err(|e| format!("Failed to get job: {}", e))?
 }
 
+/// Print `image_path` — plus any further `images`, e.g. a bonus strip — as one
+/// spool document of `copies` copies (collated unless `collate` is false).
+/// Each image is laid out on the paper profile `paper_profile` (picked from
+/// the image's aspect ratio when omitted); `scale` / offsets nudge the content
+/// within the print before it is laid out on the sheet.
+///
+/// Returns once the job is spooled; the paper it used is counted when it
+/// completes (`print-completed`). Booth prints go through `print_queue`
+/// instead.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn print_photo(
+    app: tauri::AppHandle,
     image_path: String,
+    images: Option<Vec<String>>,
     printer_name: String,
-    frame_type: String,
+    paper_profile: Option<String>,
     scale: Option<f64>,
     vertical_offset: Option<f64>,
     horizontal_offset: Option<f64>,
-    _is_landscape: Option<bool>,
+    copies: Option<u32>,
+    collate: Option<bool>,
 ) -> Result<bool, String> {
-    let scale_val = scale.unwrap_or(100.0);
-    let vert_val = vertical_offset.unwrap_or(0.0);
-    let horiz_val = horizontal_offset.unwrap_or(0.0);
-
-    // Load original image (auto-detect format from content, not extension)
-    let img = {
-        let reader = image::ImageReader::open(&image_path)
-            .map_err(|e| format!("Failed to open image file: {}", e))?
-            .with_guessed_format()
-            .map_err(|e| format!("Failed to guess image format: {}", e))?;
-        reader.decode()
-            .map_err(|e| format!("Failed to decode image: {}", e))?
-    };
+    let mut pages = vec![image_path];
+    pages.extend(images.unwrap_or_default());
+    let name = printer_name.clone();
+    let spooled = tauri::async_runtime::spawn_blocking(move || {
+        let settings = PrintSettings {
+            paper_profile: paper_profile.as_deref(),
+            scale: scale.unwrap_or(100.0),
+            vertical_offset: vertical_offset.unwrap_or(0.0),
+            horizontal_offset: horizontal_offset.unwrap_or(0.0),
+            copies: copies.unwrap_or(1).max(1),
+            collate: collate.unwrap_or(true),
+        };
+        print_file(&pages, &name, &settings)
+    })
+    .await
+    .map_err(|e| format!("Print task panicked: {}", e))??;
+
+    tauri::async_runtime::spawn(async move {
+        report_when_complete(&app, &printer_name, spooled).await;
+    });
+    Ok(true)
+}
+
+/// Layout and spooling options of a print
+pub struct PrintSettings<'a> {
+    pub paper_profile: Option<&'a str>,
+    pub scale: f64,
+    pub vertical_offset: f64,
+    pub horizontal_offset: f64,
+    pub copies: u32,
+    pub collate: bool,
+}
+
+/// A print accepted by the print system
+pub struct SpooledPrint {
+    pub job: SpoolJob,
+    pub profile: paper::PaperProfile,
+    /// Sheets of media the job uses (pages × copies)
+    pub sheets: u32,
+}
+
+/// Lay out `image_path` on its sheet
+fn render_sheet(image_path: &str, settings: &PrintSettings) -> Result<(paper::PaperProfile, image::DynamicImage), String> {
+    // Load original image (format sniffed from content, EXIF orientation applied)
+    let bytes = std::fs::read(image_path)
+        .map_err(|e| format!("Failed to open image file: {}", e))?;
+    let img = crate::orientation::decode_upright(&bytes, false)?;
 
     let original_width = img.width();
     let original_height = img.height();
+    let profile = paper::resolve(settings.paper_profile, original_width, original_height)?;
+    log::info!("[Printer] Paper profile '{}' for {}x{} image", profile.id, original_width, original_height);
+
+    let processed = position_content(img, settings.scale, settings.vertical_offset, settings.horizontal_offset);
+
+    // Lay the print out on the sheet at the profile's DPI — N-up profiles get
+    // one copy per cell (2x6 → two strips side by side on a 4x6 sheet)
+    let sheet = imposition::impose(&processed, &profile);
+    Ok((profile, sheet))
 
-    // Apply scale: zoom content within fixed output dimensions
-    let scale_factor = scale_val / 100.0;
+}
+
+/// Apply the paper position settings: zoom the content by `scale` percent
+/// within the image's own bounds (white padding when zooming out, centre crop
+/// when zooming in) and shift it by the offsets, in image pixels
+pub(crate) fn position_content(img: image::DynamicImage, scale: f64, vertical_offset: f64, horizontal_offset: f64) -> image::DynamicImage {
+    let (original_width, original_height) = (img.width(), img.height());
+    let scale_factor = scale / 100.0;
+    let (vert_val, horiz_val) = (vertical_offset, horizontal_offset);
 
     let scaled_w = (original_width as f64 * scale_factor) as u32;
     let scaled_h = (original_height as f64 * scale_factor) as u32;
 
-    let processed: image::DynamicImage = if scale_factor < 1.0 {
+    if scale_factor < 1.0 {
         // Zoom out: shrink content, white padding
         let resized = img.resize_exact(scaled_w, scaled_h, image::imageops::FilterType::Lanczos3);
 
@@ -767,67 +935,156 @@ pub async fn print_photo(
         } else {
             img
         }
-    };
+    }
+}
 
-    // For cut frames: duplicate image to fill the full 4x6 paper so the printer can cut
-    // 2x6 (portrait-cut): place two copies side-by-side → 4x6 paper (portrait orientation)
-    // 6x2 (landscape-cut): place two copies top-to-bottom → 6x4 paper (landscape orientation)
-    let final_image: image::DynamicImage = match frame_type.as_str() {
-        "2x6" => {
-            // Place two copies side-by-side → portrait 4x6 paper, cut vertically
-            let w = processed.width();
-            let h = processed.height();
-            let mut canvas = image::RgbaImage::from_pixel(w * 2, h, image::Rgba([255, 255, 255, 255]));
-            image::imageops::overlay(&mut canvas, &processed.to_rgba8(), 0, 0);
-            image::imageops::overlay(&mut canvas, &processed.to_rgba8(), w as i64, 0);
-            image::DynamicImage::ImageRgba8(canvas)
-        }
-        "6x2" => {
-            // Place two copies top-to-bottom → landscape 6x4 paper, cut horizontally
-            let w = processed.width();
-            let h = processed.height();
-            let mut canvas = image::RgbaImage::from_pixel(w, h * 2, image::Rgba([255, 255, 255, 255]));
-            image::imageops::overlay(&mut canvas, &processed.to_rgba8(), 0, 0);
-            image::imageops::overlay(&mut canvas, &processed.to_rgba8(), 0, h as i64);
-            image::DynamicImage::ImageRgba8(canvas)
+/// Lay out `images` and spool them through the printer's backend as one
+/// document. All images have to print on the same paper and driver.
+/// Blocks until the print system has accepted the job.
+pub fn print_file(images: &[String], printer_name: &str, settings: &PrintSettings) -> Result<SpooledPrint, String> {
+    let mut profile: Option<paper::PaperProfile> = None;
+    let mut sheets = Vec::new();
+    for image_path in images {
+        let (page_profile, sheet) = render_sheet(image_path, settings)?;
+        match &profile {
+            None => profile = Some(page_profile),
+            Some(job_profile) => {
+                let same_media = page_profile.sheet_pixels() == job_profile.sheet_pixels()
+                    && page_profile.landscape == job_profile.landscape
+                    && page_profile.cut.driver_cut == job_profile.cut.driver_cut;
+                if !same_media {
+                    return Err(format!(
+                        "'{}' needs paper '{}' but the job prints on '{}' — print it separately",
+                        image_path, page_profile.id, job_profile.id
+                    ));
+                }
+            }
         }
-        _ => processed,
-    };
+        sheets.push(sheet);
+    }
+    let profile = profile.ok_or("Nothing to print")?;
 
-    // Save final image to temp PNG
+    // Save the sheets to temp PNGs (unique names — the queue and a test print
+    // may spool at the same time)
     let temp_dir = std::env::temp_dir().join("bonio-booth");
     std::fs::create_dir_all(&temp_dir)
         .map_err(|e| format!("Failed to create temp dir: {}", e))?;
-    let temp_path = temp_dir.join("print-processed.png");
-    final_image
-        .save(&temp_path)
-        .map_err(|e| format!("Failed to save processed image: {}", e))?;
-
-    let temp_path_str = temp_path.to_string_lossy().to_string();
+    let job_tag = uuid::Uuid::new_v4().simple().to_string()[..8].to_string();
+    let mut pages = Vec::new();
+    let mut saved = Ok(());
+    for (i, sheet) in sheets.iter().enumerate() {
+        let temp_path = temp_dir.join(format!("print-processed-{}-{}.png", job_tag, i + 1));
+        saved = sheet
+            .save(&temp_path)
+            .map_err(|e| format!("Failed to save processed image: {}", e));
+        if saved.is_err() {
+            break;
+        }
+        pages.push(temp_path.to_string_lossy().to_string());
+    }
 
-    // Print using native Win32 GDI API - no PowerShell, no popup windows
-    #[cfg(target_os = "windows")]
-    {
-        win32_gdi_print(&printer_name, &temp_path_str, &frame_type)
-            .map(|_| true)
+    let result = saved.and_then(|_| {
+        let document = PrintDocument {
+            pages: &pages,
+            profile: &profile,
+            copies: settings.copies.max(1),
+            collate: settings.collate,
+            job_name: "Bonio Booth Print",
+        };
+        let sheets = document.sheets();
+        printer_backend::for_printer(printer_name)
+            .print(printer_name, &document)
+            .map(|job| (job, sheets))
+    });
+    for page in &pages {
+        let _ = std::fs::remove_file(page);
     }
+    let (job, sheets) = result?;
+    Ok(SpooledPrint { job, profile, sheets })
+}
 
-    #[cfg(not(target_os = "windows"))]
-    {
-        let output = hidden_command("lpr")
-            .args(&["-P", &printer_name, &temp_path_str])
-            .output()
-            .map_err(|e| format!("Print failed: {}", e))?;
+// =============================================================================
+// Job Completion
+// =============================================================================
+
+const COMPLETION_POLL: std::time::Duration = std::time::Duration::from_secs(2);
+/// Give up waiting (and count the sheets anyway) after this long
+const COMPLETION_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(600);
+/// Job queries failing in a row before the job is taken as gone (printed)
+const COMPLETION_MAX_ERRORS: u32 = 5;
+
+/// Payload of `print-completed`
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PrintCompleted {
+    pub printer_name: String,
+    pub job_id: i32,
+    pub state: SpoolState,
+    /// Sheets counted against the media (0 for cancelled / aborted jobs)
+    pub sheets: u32,
+}
 
-        if output.status.success() {
-            Ok(true)
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            Err(format!("Print error: {}", stderr))
+/// Wait until the spooler is done with `spooled`, then count its sheets on
+/// the media counter and the backend paper level and emit `print-completed`
+pub async fn report_when_complete(app: &tauri::AppHandle, printer_name: &str, spooled: SpooledPrint) -> PrintCompleted {
+    let queue = spooled.job.printer_name.clone();
+    let job_id = spooled.job.id;
+    let started = std::time::Instant::now();
+    let mut state = spooled.job.state;
+    let mut errors = 0;
+    while !matches!(state, SpoolState::Completed | SpoolState::Canceled | SpoolState::Aborted) {
+        if started.elapsed() >= COMPLETION_TIMEOUT {
+            log::warn!("[Printer] Job {} on '{}' still {:?}, counting it as printed", job_id, queue, state);
+            break;
+        }
+        tokio::time::sleep(COMPLETION_POLL).await;
+        let name = queue.clone();
+        let job = tauri::async_runtime::spawn_blocking(move || printer_backend::for_printer(&name).job(&name, job_id))
+            .await
+            .unwrap_or_else(|e| Err(format!("Job query failed: {}", e)));
+        match job {
+            Ok(job) => {
+                errors = 0;
+                state = job.state;
+            }
+            Err(e) => {
+                errors += 1;
+                if errors >= COMPLETION_MAX_ERRORS {
+                    log::warn!("[Printer] Job {} on '{}' can't be queried ({}), counting it as printed", job_id, queue, e);
+                    state = SpoolState::Completed;
+                }
+            }
+        }
+    }
+
+    let sheets = if matches!(state, SpoolState::Canceled | SpoolState::Aborted) { 0 } else { spooled.sheets };
+    log::info!("[Printer] Job {} on '{}' finished {:?}: {} sheets", job_id, queue, state, sheets);
+    if sheets > 0 {
+        crate::media::record_printed(printer_name, &spooled.profile, sheets);
+        // Proofs on the virtual file printer use no paper on the backend
+        if printer_backend::for_printer(printer_name).id() != "file" {
+            if let Some(state) = app.try_state::<crate::api::AppState>() {
+                match crate::api::send_paper_reduce(&state, sheets as i32).await {
+                    Ok(res) if res.success => {}
+                    Ok(res) => log::warn!("[Printer] Paper level reduce rejected: {:?}", res.error),
+                    Err(e) => log::warn!("[Printer] Paper level reduce failed: {}", e),
+                }
+            }
         }
     }
+
+    let completed = PrintCompleted {
+        printer_name: printer_name.to_string(),
+        job_id,
+        state,
+        sheets,
+    };
+    let _ = app.emit("print-completed", &completed);
+    completed
 }
 
+/// Print "Print test {paper_profile}.png", or a generated alignment chart for
+/// profiles that don't ship a test image
 #[tauri::command]
 pub async fn print_test_photo(
     app: tauri::AppHandle,
@@ -835,8 +1092,10 @@ pub async fn print_test_photo(
     scale: f64,
     vertical_offset: f64,
     horizontal_offset: f64,
-    frame_type: String,
+    paper_profile: String,
 ) -> Result<bool, String> {
+    let profile = paper::get(&paper_profile)?;
+
     // Find test.jpg - check multiple possible locations
     let test_image_path = {
         let mut found_path: Option<String> = None;
@@ -852,8 +1111,8 @@ pub async fn print_test_photo(
             exists
         };
 
-        // File name pattern: "Print test {frame_type}.png" e.g. "Print test 4x6.png"
-        let test_filename = format!("Print test {}.png", frame_type);
+        // File name pattern: "Print test {profile id}.png" e.g. "Print test 4x6.png"
+        let test_filename = format!("Print test {}.png", profile.id);
 
         // 1. Resource directory (Tauri bundled)
         if let Ok(resource_dir) = app.path().resource_dir() {
@@ -892,23 +1151,39 @@ pub async fn print_test_photo(
             log::info!("[Printer] test.jpg search:\n  {}", search_log);
         }
 
-        found_path.ok_or_else(|| {
-            let msg = format!("{} not found. Searched paths:\n  {}", test_filename, search_log);
-            error!("[Printer] {}", msg);
-            msg
-        })?
+        match found_path {
+            Some(path) => path,
+            None => {
+                log::info!(
+                    "[Printer] {} not found, printing a generated chart. Searched paths:\n  {}",
+                    test_filename, search_log
+                );
+                let chart_path = std::env::temp_dir()
+                    .join("bonio-booth")
+                    .join(format!("print-test-{}.png", profile.id));
+                std::fs::create_dir_all(chart_path.parent().unwrap())
+                    .map_err(|e| format!("Failed to create temp dir: {}", e))?;
+                paper::test_chart(&profile).save(&chart_path).map_err(|e| {
+                    let msg = format!("Failed to save test chart: {}", e);
+                    error!("[Printer] {}", msg);
+                    msg
+                })?;
+                chart_path.to_string_lossy().to_string()
+            }
+        }
     };
 
-    let is_landscape = frame_type == "6x4" || frame_type == "6x2";
-
     print_photo(
+        app,
         test_image_path,
+        None,
         printer_name,
-        frame_type,
+        Some(profile.id),
         Some(scale),
         Some(vertical_offset),
         Some(horizontal_offset),
-        Some(is_landscape),
+        None,
+        None,
     )
     .await
 }
@@ -919,47 +1194,15 @@ pub async fn reduce_paper_level(
     state: tauri::State<'_, crate::api::AppState>,
     copies: i32,
 ) -> Result<crate::api::ApiResponse, String> {
-    let machine_id = state.machine_id.lock().unwrap().clone();
-    let machine_port = state.machine_port.lock().unwrap().clone();
-    let client = &state.http_client;
-    let url = "https://api-booth.boniolabs.com/api/machines-public/paper-level/reduce";
-
-    let res = client
-        .post(url)
-        .header("X-Machine-Id", &machine_id)
-        .header("X-Machine-Port", &machine_port)
-        .query(&[("machineId", &machine_id)])
-        .json(&serde_json::json!({ "reduceBy": copies }))
-        .send()
-        .await
-        .map_err(|e| format!("Request error: {}", e))?;
-
-    let status = res.status();
-    let body: serde_json::Value = res.json().await.map_err(|e| format!("Parse error: {}", e))?;
-
-    Ok(crate::api::ApiResponse {
-        success: status.is_success(),
-        data: Some(body),
-        error: if !status.is_success() {
-            Some(format!("Status: {}", status))
-        } else {
-            None
-        },
-    })
+    crate::api::send_paper_reduce(&state, copies).await
 }
 
 /// Get available paper sizes for a specific printer (for debugging and UI)
 #[tauri::command]
 pub async fn get_printer_paper_sizes(printer_name: String) -> Result<Vec<String>, String> {
-    #[cfg(target_os = "windows")]
-    {
-        let paper_sizes = win32_get_paper_sizes(&printer_name)?;
-        Ok(paper_sizes.iter().map(|(id, name)| format!("{} (id={})", name, id)).collect())
-    }
-
-    #[cfg(not(target_os = "windows"))]
-    {
-        let _ = printer_name;
-        Ok(vec!["Paper size query not supported on this platform".to_string()])
-    }
+    tauri::async_runtime::spawn_blocking(move || {
+        printer_backend::for_printer(&printer_name).paper_sizes(&printer_name)
+    })
+    .await
+    .map_err(|e| format!("Failed to get paper sizes: {}", e))?
 }
@@ -0,0 +1,638 @@
+//! Printer Backends
+//!
+//! Everything that talks to a print system goes through [`PrinterBackend`]:
+//!
+//! - `Win32Backend` (`printer.rs`) — Windows spooler + GDI, the DNP/Citizen
+//!   booths
+//! - [`IppBackend`] — CUPS, or any IPP Everywhere printer such as an
+//!   `ippeveprinter` stand-in, for Linux booths and CI
+//! - `FileBackend` (`file_printer.rs`) — virtual printer writing the page to
+//!   a folder, for testing and proofing
+//!
+//! Each printer name belongs to one backend; [`for_printer`] routes commands
+//! by name and [`all_printers`] merges the lists for `get_printers`.
+//!
+//! The IPP backend is configured through the environment:
+//! - `BONIO_CUPS_SERVER` — cupsd to list queues from (`host:port`, default
+//!   `localhost:631` off Windows, `none` to disable)
+//! - `BONIO_IPP_PRINTERS` — extra printer URIs, comma separated, e.g.
+//!   `ipp://localhost:8631/ipp/print`
+
+use crate::ipp::{self, Attribute, Group};
+use crate::paper::PaperProfile;
+use crate::printer::PrinterInfo;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// One supply as reported by the printer (IPP `marker-*` attributes)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MarkerLevel {
+    pub name: String,
+    pub color: String,
+    /// e.g. "ink-ribbon", "toner"
+    pub kind: String,
+    /// Percent remaining, `None` when the printer can't tell
+    pub level: Option<u8>,
+}
+
+/// Rendered sheets ready to be spooled as one document
+pub struct PrintDocument<'a> {
+    /// Sheet images (PNG) at the profile's sheet size, one per page
+    pub pages: &'a [String],
+    pub profile: &'a PaperProfile,
+    pub copies: u32,
+    /// Collated copies print the whole set before the next one (1 2 1 2);
+    /// uncollated repeat each page (1 1 2 2)
+    pub collate: bool,
+    pub job_name: &'a str,
+}
+
+impl PrintDocument<'_> {
+    /// Page indexes in the order the sheets come out of the printer
+    pub fn page_order(&self) -> Vec<usize> {
+        let copies = self.copies.max(1) as usize;
+        if self.collate {
+            (0..copies).flat_map(|_| 0..self.pages.len()).collect()
+        } else {
+            (0..self.pages.len()).flat_map(|page| std::iter::repeat_n(page, copies)).collect()
+        }
+    }
+
+    /// Sheets of media the job uses
+    pub fn sheets(&self) -> u32 {
+        self.pages.len() as u32 * self.copies.max(1)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SpoolState {
+    Pending,
+    Held,
+    Processing,
+    Stopped,
+    Canceled,
+    Aborted,
+    Completed,
+    Unknown,
+}
+
+/// A job as seen by the print system
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SpoolJob {
+    pub id: i32,
+    pub printer_name: String,
+    pub state: SpoolState,
+    /// IPP `job-state-reasons` style keywords
+    pub reasons: Vec<String>,
+}
+
+pub trait PrinterBackend: Send + Sync {
+    /// Short name reported in `PrinterInfo::backend`
+    fn id(&self) -> &'static str;
+    /// Whether `printer_name` is one of this backend's printers
+    fn owns(&self, printer_name: &str) -> bool;
+    fn printers(&self) -> Result<Vec<PrinterInfo>, String>;
+    fn status(&self, printer_name: &str) -> Result<PrinterInfo, String>;
+    /// Media the driver/printer offers, for the admin UI and debugging
+    fn paper_sizes(&self, printer_name: &str) -> Result<Vec<String>, String>;
+    /// Spool a rendered sheet. Blocks until the print system has the job.
+    fn print(&self, printer_name: &str, document: &PrintDocument) -> Result<SpoolJob, String>;
+    fn job(&self, printer_name: &str, job_id: i32) -> Result<SpoolJob, String>;
+}
+
+// =============================================================================
+// Registry
+// =============================================================================
+
+static IPP: IppBackend = IppBackend;
+
+/// Backends in routing order — the platform backend comes last and takes any
+/// name nobody else claims
+fn backends() -> Vec<&'static dyn PrinterBackend> {
+    #[cfg(target_os = "windows")]
+    {
+        vec![&crate::file_printer::FILE, &IPP, &crate::printer::WIN32]
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        vec![&crate::file_printer::FILE, &IPP]
+    }
+}
+
+pub fn for_printer(printer_name: &str) -> &'static dyn PrinterBackend {
+    let backends = backends();
+    backends
+        .iter()
+        .find(|b| b.owns(printer_name))
+        .copied()
+        .unwrap_or(backends[backends.len() - 1])
+}
+
+/// Printers of every backend (a failing backend is logged and skipped)
+pub fn all_printers() -> Vec<PrinterInfo> {
+    backends()
+        .into_iter()
+        .flat_map(|backend| match backend.printers() {
+            Ok(printers) => printers,
+            Err(e) => {
+                log::warn!("[Printer] {} backend: {}", backend.id(), e);
+                Vec::new()
+            }
+        })
+        .collect()
+}
+
+/// Printer state reasons that stop a job from printing. `-report` and
+/// `-warning` reasons are informational.
+pub fn blocking_reason(reasons: &[String]) -> Option<&str> {
+    const BLOCKING: &[&str] = &[
+        "media-empty",
+        "media-needed",
+        "media-jam",
+        "door-open",
+        "cover-open",
+        "input-tray-missing",
+        "marker-supply-empty",
+        "offline",
+        "paused",
+        "shutdown",
+        "stopped",
+        "output-area-full",
+        "other-error",
+    ];
+    reasons.iter().map(String::as_str).find(|reason| {
+        if reason.ends_with("-report") || reason.ends_with("-warning") {
+            return false;
+        }
+        let base = reason.trim_end_matches("-error");
+        reason.ends_with("-error") || BLOCKING.contains(&base)
+    })
+}
+
+/// Status label shown in the UI, in the vocabulary the frontend already uses
+pub fn status_label(reasons: &[String], printing: bool) -> String {
+    let has = |keyword: &str| reasons.iter().any(|r| r.trim_end_matches("-error") == keyword);
+    if has("media-jam") {
+        "PaperJam".to_string()
+    } else if has("media-empty") || has("media-needed") {
+        "PaperOut".to_string()
+    } else if has("paused") {
+        "Paused".to_string()
+    } else if has("offline") || has("shutdown") {
+        "Offline".to_string()
+    } else if blocking_reason(reasons).is_some() {
+        "Error".to_string()
+    } else if printing {
+        "Printing".to_string()
+    } else {
+        "Normal".to_string()
+    }
+}
+
+/// Printer name without a CUT driver suffix — both drivers feed the same
+/// physical printer
+pub fn base_driver_name(printer_name: &str) -> String {
+    let upper = printer_name.to_uppercase();
+    [" (CUT)", " CUT", "_CUT", "-CUT"]
+        .iter()
+        .find(|suffix| upper.ends_with(*suffix))
+        .map(|suffix| printer_name[..printer_name.len() - suffix.len()].to_string())
+        .unwrap_or_else(|| printer_name.to_string())
+}
+
+/// The queue to spool to. Cut profiles use the printer's CUT driver variant
+/// ("DS-RX1 (CUT)") when one exists; uncut prints go to the base driver.
+pub fn driver_for_job(printer_name: &str, needs_cut: bool, exists: impl Fn(&str) -> bool) -> String {
+    let upper = printer_name.to_uppercase();
+    if needs_cut {
+        if upper.contains("CUT") {
+            // Already a CUT driver name
+            return printer_name.to_string();
+        }
+        // Common CUT driver naming patterns seen on customer machines:
+        // "DS-RX1 (CUT)", "DS-RX1 (Cut)", "DS-RX1 CUT", "DS-RX1 Cut", and
+        // CUPS queue names, which can't contain spaces: "DS-RX1_CUT"
+        let candidates = [
+            format!("{} (CUT)", printer_name),
+            format!("{} (Cut)", printer_name),
+            format!("{} CUT", printer_name),
+            format!("{} Cut", printer_name),
+            format!("{}_CUT", printer_name),
+            format!("{}-CUT", printer_name),
+        ];
+        match candidates.into_iter().find(|c| exists(c)) {
+            Some(cut_name) => {
+                log::info!("[Printer] Auto-switching to CUT driver: '{}'", cut_name);
+                cut_name
+            }
+            None => {
+                log::info!("[Printer] No CUT driver variant found for '{}', using as-is", printer_name);
+                printer_name.to_string()
+            }
+        }
+    } else {
+        // For no-cut: strip any CUT suffix to get base driver name
+        let base_name = base_driver_name(printer_name);
+        if base_name != printer_name && exists(&base_name) {
+            log::info!("[Printer] Using base (no-cut) driver: '{}'", base_name);
+            base_name
+        } else {
+            printer_name.to_string()
+        }
+    }
+}
+
+/// Driver paper for the profile from `(id, name)` pairs: the profile's
+/// `driverPaper` names in order (skipping CUT papers unless asked for), else
+/// the first plain paper. For cut profiles that is the full sheet (e.g. 4x6):
+/// the CUT driver handles cutting automatically.
+pub fn select_driver_paper<'a, T>(profile: &PaperProfile, papers: &'a [(T, String)]) -> Option<&'a (T, String)> {
+    profile
+        .driver_paper
+        .iter()
+        .find_map(|wanted| {
+            let wanted = wanted.to_lowercase();
+            papers.iter().find(|(_, n)| {
+                let lower = n.to_lowercase();
+                lower.contains(&wanted) && (wanted.contains("cut") || !lower.contains("cut"))
+            })
+        })
+        .or_else(|| {
+            // Fallback: if no explicit name matches, try any paper without "cut" keyword
+            papers.iter().find(|(_, n)| {
+                let lower = n.to_lowercase();
+                !lower.contains("cut") && !lower.contains("2x6")
+            })
+        })
+}
+
+/// Where the sheet lands on the page: fitted inside and centred, aspect
+/// ratio preserved. Returns `(x, y, width, height)` in page units.
+pub fn fit_on_page(page_w: i32, page_h: i32, img_w: u32, img_h: u32) -> (i32, i32, i32, i32) {
+    let scale = (page_w as f64 / img_w as f64).min(page_h as f64 / img_h as f64);
+    let dst_w = (img_w as f64 * scale).round() as i32;
+    let dst_h = (img_h as f64 * scale).round() as i32;
+    ((page_w - dst_w) / 2, (page_h - dst_h) / 2, dst_w, dst_h)
+}
+
+// =============================================================================
+// CUPS / IPP Backend
+// =============================================================================
+
+/// Printer name → printer URI, refreshed whenever printers are listed
+static IPP_PRINTERS: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+const PRINTER_ATTRIBUTES: &[&str] = &[
+    "printer-name",
+    "printer-state",
+    "printer-state-reasons",
+    "printer-is-accepting-jobs",
+    "marker-names",
+    "marker-levels",
+    "marker-colors",
+    "marker-types",
+    "media-ready",
+    "media-supported",
+    "document-format-supported",
+];
+
+pub struct IppBackend;
+
+fn cups_server() -> Option<String> {
+    match std::env::var("BONIO_CUPS_SERVER") {
+        Ok(server) if server.is_empty() || server.eq_ignore_ascii_case("none") => None,
+        Ok(server) => Some(server),
+        Err(_) if cfg!(target_os = "windows") => None,
+        Err(_) => Some("localhost:631".to_string()),
+    }
+}
+
+fn configured_uris() -> Vec<String> {
+    std::env::var("BONIO_IPP_PRINTERS")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|uri| !uri.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn registered_uri(printer_name: &str) -> Option<String> {
+    IPP_PRINTERS
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|(name, _)| name == printer_name)
+        .map(|(_, uri)| uri.clone())
+}
+
+impl IppBackend {
+    fn uri_for(&self, printer_name: &str) -> Result<String, String> {
+        if let Some(uri) = registered_uri(printer_name) {
+            return Ok(uri);
+        }
+        if printer_name.starts_with("ipp://") {
+            return Ok(printer_name.to_string());
+        }
+        match cups_server() {
+            Some(server) => Ok(format!("ipp://{}/printers/{}", server, printer_name)),
+            None => Err(format!("Printer '{}' not found", printer_name)),
+        }
+    }
+
+    fn client(&self, printer_name: &str) -> Result<ipp::Client, String> {
+        ipp::Client::new(&self.uri_for(printer_name)?)
+    }
+}
+
+/// Printer status from Get-Printer-Attributes / CUPS-Get-Printers attributes
+pub fn info_from_attributes(name: &str, attrs: &Group) -> PrinterInfo {
+    let state = attrs.get("printer-state").and_then(Attribute::first_int).unwrap_or(0);
+    let accepting = attrs
+        .get("printer-is-accepting-jobs")
+        .and_then(|a| a.values.first())
+        .is_none_or(|v| *v != ipp::Value::Boolean(false));
+    let mut state_reasons: Vec<String> = attrs
+        .get("printer-state-reasons")
+        .map(Attribute::strings)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|r| r != "none")
+        .collect();
+    if state == ipp::printer_state::STOPPED && !state_reasons.iter().any(|r| r.starts_with("paused")) {
+        state_reasons.push("stopped".to_string());
+    }
+
+    let strings = |key: &str| attrs.get(key).map(Attribute::strings).unwrap_or_default();
+    let names = strings("marker-names");
+    let colors = strings("marker-colors");
+    let kinds = strings("marker-types");
+    let levels = attrs.get("marker-levels").map(Attribute::ints).unwrap_or_default();
+    let markers = names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| MarkerLevel {
+            name: name.clone(),
+            color: colors.get(i).cloned().unwrap_or_default(),
+            kind: kinds.get(i).cloned().unwrap_or_default(),
+            // -1 other, -2 unknown, -3 "some remaining"
+            level: levels.get(i).filter(|l| (0..=100).contains(*l)).map(|l| *l as u8),
+        })
+        .collect();
+
+    let is_online = accepting && state != ipp::printer_state::STOPPED && blocking_reason(&state_reasons).is_none();
+    PrinterInfo {
+        name: name.to_string(),
+        status: status_label(&state_reasons, state == ipp::printer_state::PROCESSING),
+        is_online,
+        state_reasons,
+        markers,
+        backend: "ipp".to_string(),
+    }
+}
+
+/// Size of a PWG self-describing media name ("na_index-4x6_4x6in",
+/// "iso_a6_105x148mm") in millimetres
+fn pwg_media_size_mm(media: &str) -> Option<(f64, f64)> {
+    let dims = media.rsplit('_').next()?;
+    let (dims, per_unit) = if let Some(d) = dims.strip_suffix("in") {
+        (d, 25.4)
+    } else {
+        (dims.strip_suffix("mm")?, 1.0)
+    };
+    let (w, h) = dims.split_once('x')?;
+    Some((w.parse::<f64>().ok()? * per_unit, h.parse::<f64>().ok()? * per_unit))
+}
+
+/// Media keyword for the profile's sheet: loaded media first, then any
+/// supported size within 1 mm (either orientation), then a `driverPaper`
+/// name match
+pub fn select_media(profile: &PaperProfile, ready: &[String], supported: &[String]) -> Option<String> {
+    let sheet_w = profile.sheet_width_mm.unwrap_or(profile.width_mm * profile.cut.columns as f64);
+    let sheet_h = profile.sheet_height_mm.unwrap_or(profile.height_mm * profile.cut.rows as f64);
+    let (short, long) = (sheet_w.min(sheet_h), sheet_w.max(sheet_h));
+    let fits = |media: &&String| {
+        pwg_media_size_mm(media).is_some_and(|(w, h)| (w.min(h) - short).abs() <= 1.0 && (w.max(h) - long).abs() <= 1.0)
+    };
+    ready
+        .iter()
+        .find(fits)
+        .or_else(|| supported.iter().find(fits))
+        .or_else(|| {
+            profile.driver_paper.iter().find_map(|wanted| {
+                let wanted = wanted.to_lowercase();
+                supported.iter().find(|m| m.to_lowercase().contains(&wanted))
+            })
+        })
+        .cloned()
+}
+
+fn spool_job(printer_name: &str, job_id: i32, attrs: &Group) -> SpoolJob {
+    let state = match attrs.get("job-state").and_then(Attribute::first_int) {
+        Some(ipp::job_state::PENDING) => SpoolState::Pending,
+        Some(ipp::job_state::PENDING_HELD) => SpoolState::Held,
+        Some(ipp::job_state::PROCESSING) => SpoolState::Processing,
+        Some(ipp::job_state::PROCESSING_STOPPED) => SpoolState::Stopped,
+        Some(ipp::job_state::CANCELED) => SpoolState::Canceled,
+        Some(ipp::job_state::ABORTED) => SpoolState::Aborted,
+        Some(ipp::job_state::COMPLETED) => SpoolState::Completed,
+        _ => SpoolState::Unknown,
+    };
+    SpoolJob {
+        id: job_id,
+        printer_name: printer_name.to_string(),
+        state,
+        reasons: attrs
+            .get("job-state-reasons")
+            .map(Attribute::strings)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|r| r != "none")
+            .collect(),
+    }
+}
+
+impl PrinterBackend for IppBackend {
+    fn id(&self) -> &'static str {
+        "ipp"
+    }
+
+    fn owns(&self, printer_name: &str) -> bool {
+        printer_name.starts_with("ipp://") || registered_uri(printer_name).is_some()
+    }
+
+    fn printers(&self) -> Result<Vec<PrinterInfo>, String> {
+        let mut registry = Vec::new();
+        let mut printers = Vec::new();
+
+        if let Some(server) = cups_server() {
+            let cups = ipp::Client::new(&format!("ipp://{}/", server))?;
+            match cups.cups_printers(PRINTER_ATTRIBUTES) {
+                Ok(queues) => {
+                    for attrs in queues {
+                        let Some(name) = attrs.get("printer-name").and_then(Attribute::first_str) else {
+                            continue;
+                        };
+                        registry.push((name.to_string(), format!("ipp://{}/printers/{}", server, name)));
+                        printers.push(info_from_attributes(name, &attrs));
+                    }
+                }
+                Err(e) => log::debug!("[Printer] CUPS at {} unavailable: {}", server, e),
+            }
+        }
+
+        for uri in configured_uris() {
+            let attrs = ipp::Client::new(&uri).and_then(|c| c.printer_attributes(PRINTER_ATTRIBUTES));
+            match attrs {
+                Ok(attrs) => {
+                    let name = attrs
+                        .get("printer-name")
+                        .and_then(Attribute::first_str)
+                        .unwrap_or(&uri)
+                        .to_string();
+                    printers.push(info_from_attributes(&name, &attrs));
+                    registry.push((name, uri));
+                }
+                Err(e) => {
+                    // Keep listing it (offline) so a stand-in that is down shows up
+                    log::warn!("[Printer] IPP printer {} unavailable: {}", uri, e);
+                    printers.push(PrinterInfo {
+                        name: uri.clone(),
+                        status: "Offline".to_string(),
+                        is_online: false,
+                        state_reasons: vec!["offline".to_string()],
+                        markers: Vec::new(),
+                        backend: "ipp".to_string(),
+                    });
+                }
+            }
+        }
+
+        *IPP_PRINTERS.lock().unwrap() = registry;
+        Ok(printers)
+    }
+
+    fn status(&self, printer_name: &str) -> Result<PrinterInfo, String> {
+        let attrs = self.client(printer_name)?.printer_attributes(PRINTER_ATTRIBUTES)?;
+        Ok(info_from_attributes(printer_name, &attrs))
+    }
+
+    fn paper_sizes(&self, printer_name: &str) -> Result<Vec<String>, String> {
+        let attrs = self
+            .client(printer_name)?
+            .printer_attributes(&["media-supported", "media-ready"])?;
+        let ready = attrs.get("media-ready").map(Attribute::strings).unwrap_or_default();
+        Ok(attrs
+            .get("media-supported")
+            .map(Attribute::strings)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|m| if ready.contains(&m) { format!("{} (loaded)", m) } else { m })
+            .collect())
+    }
+
+    fn print(&self, printer_name: &str, document: &PrintDocument) -> Result<SpoolJob, String> {
+        let profile = document.profile;
+        // CUT variants are looked up in the queue list rather than probed one
+        // IPP request at a time; list once if nothing has been listed yet
+        if IPP_PRINTERS.lock().unwrap().is_empty() {
+            if let Err(e) = self.printers() {
+                log::warn!("[Printer] Listing IPP printers failed: {}", e);
+            }
+        }
+        let queue = driver_for_job(printer_name, profile.cut.driver_cut, |name| registered_uri(name).is_some());
+        log::info!("[Printer] Actual printer for job: '{}' (ipp)", queue);
+        let client = self.client(&queue)?;
+
+        let attrs = client.printer_attributes(&["document-format-supported", "media-supported", "media-ready"])?;
+        let strings = |key: &str| attrs.get(key).map(Attribute::strings).unwrap_or_default();
+        let formats = strings("document-format-supported");
+
+        // A single sheet goes as PNG, or JPEG for printers that only take that.
+        // More pages have to travel as one PDF to stay one job. Either way the
+        // document holds each page once; the printer makes the copies.
+        let supports = |format: &str| formats.iter().any(|f| f == format);
+        let (format, data) = if document.pages.len() > 1 {
+            if !supports("application/pdf") {
+                return Err(format!(
+                    "Printer '{}' can't take multi-page jobs: no application/pdf ({})",
+                    queue,
+                    formats.join(", ")
+                ));
+            }
+            let mut sheets = Vec::new();
+            for page in document.pages {
+                let bytes = std::fs::read(page).map_err(|e| format!("Failed to open image for printing: {}", e))?;
+                sheets.push(crate::orientation::decode_upright(&bytes, false)?.to_rgb8());
+            }
+            let order: Vec<usize> = (0..sheets.len()).collect();
+            let (w, h) = profile.sheet_pixels();
+            let to_mm = |px: u32| px as f64 * 25.4 / profile.dpi as f64;
+            ("application/pdf", crate::pdf::image_pdf(&sheets, &order, to_mm(w), to_mm(h))?)
+        } else {
+            let png = std::fs::read(&document.pages[0]).map_err(|e| format!("Failed to open image for printing: {}", e))?;
+            if supports("image/png") || supports("application/octet-stream") {
+                ("image/png", png)
+            } else if supports("image/jpeg") {
+                let img = image::load_from_memory(&png).map_err(|e| format!("Image load error: {}", e))?;
+                let mut jpeg = Vec::new();
+                image::codecs::jpeg::JpegEncoder::new_with_quality(&mut jpeg, 95)
+                    .encode_image(&img.to_rgb8())
+                    .map_err(|e| format!("Encode error: {}", e))?;
+                ("image/jpeg", jpeg)
+            } else {
+                return Err(format!(
+                    "Printer '{}' accepts neither image/png nor image/jpeg ({})",
+                    queue,
+                    formats.join(", ")
+                ));
+            }
+        };
+
+        let mut job_attributes = Vec::new();
+        match select_media(profile, &strings("media-ready"), &strings("media-supported")) {
+            Some(media) => {
+                log::info!("[Printer] Selected media: \"{}\"", media);
+                job_attributes.push(Attribute::keyword("media", &media));
+            }
+            None => log::warn!("[Printer] No matching media for '{}', using printer default", profile.id),
+        }
+        if document.copies > 1 {
+            job_attributes.push(Attribute::integer("copies", document.copies as i32));
+            if document.pages.len() > 1 {
+                let handling = if document.collate {
+                    "separate-documents-collated-copies"
+                } else {
+                    "separate-documents-uncollated-copies"
+                };
+                job_attributes.push(Attribute::keyword("multiple-document-handling", handling));
+            }
+        }
+
+        let job_id = client.print_job(document.job_name, format, job_attributes, data)?;
+        log::info!(
+            "[Printer] IPP job {} sent to '{}' ({}, {} pages x {} copies)",
+            job_id,
+            queue,
+            format,
+            document.pages.len(),
+            document.copies
+        );
+        Ok(self.job(&queue, job_id).unwrap_or(SpoolJob {
+            id: job_id,
+            printer_name: queue,
+            state: SpoolState::Pending,
+            reasons: Vec::new(),
+        }))
+    }
+
+    fn job(&self, printer_name: &str, job_id: i32) -> Result<SpoolJob, String> {
+        let attrs = self.client(printer_name)?.job_attributes(job_id)?;
+        Ok(spool_job(printer_name, job_id, &attrs))
+    }
+}
@@ -0,0 +1,278 @@
+//! Printer Health Monitor
+//!
+//! Samples the selected printer in the background (every [`POLL_INTERVAL`])
+//! through its backend, decodes the state reasons into [`Condition`]s and
+//! tells the frontend about every change:
+//!
+//! - `printer-health` — [`PrinterHealth`] after each change
+//! - `printer-health-changed` — [`HealthChange`] with the conditions that
+//!   appeared and cleared
+//!
+//! The ops channel is told through `device-alert` / `device-reconnected`,
+//! debounced so flapping USB connections don't spam it: a printer has to be
+//! unhealthy for [`ALERT_AFTER`] before an alert goes out, healthy for
+//! [`RECONNECT_AFTER`] before the matching reconnect, and alerts for the same
+//! printer are at least [`ALERT_COOLDOWN`] apart.
+
+use crate::printer::PrinterInfo;
+use crate::printer_backend;
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+const ALERT_AFTER: Duration = Duration::from_secs(20);
+const RECONNECT_AFTER: Duration = Duration::from_secs(15);
+const ALERT_COOLDOWN: Duration = Duration::from_secs(300);
+
+static HEALTH: Mutex<Option<PrinterHealth>> = Mutex::new(None);
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// What is wrong with (or worth knowing about) a printer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Condition {
+    Offline,
+    NotFound,
+    Paused,
+    PaperJam,
+    PaperOut,
+    PaperLow,
+    DoorOpen,
+    RibbonOut,
+    RibbonLow,
+    OutputFull,
+    DriverError,
+}
+
+impl Condition {
+    /// Warnings don't stop printing
+    pub fn is_warning(self) -> bool {
+        matches!(self, Condition::PaperLow | Condition::RibbonLow)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrinterHealth {
+    pub printer_name: String,
+    pub online: bool,
+    pub status: String,
+    pub conditions: Vec<Condition>,
+    /// Raw state reasons from the backend
+    pub state_reasons: Vec<String>,
+    pub backend: String,
+    pub error: Option<String>,
+    pub sampled_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthChange {
+    pub health: PrinterHealth,
+    pub was_online: Option<bool>,
+    pub added: Vec<Condition>,
+    pub cleared: Vec<Condition>,
+}
+
+/// Decode printer state reasons (IPP keywords, also produced by the Win32
+/// status bitmask) into conditions
+pub fn conditions(reasons: &[String]) -> Vec<Condition> {
+    let mut conditions = Vec::new();
+    for reason in reasons {
+        let base = reason
+            .trim_end_matches("-report")
+            .trim_end_matches("-warning")
+            .trim_end_matches("-error");
+        let condition = match base {
+            "offline" | "shutdown" | "stopped" | "connecting-to-device" | "timed-out" => Condition::Offline,
+            "paused" | "moving-to-paused" => Condition::Paused,
+            "media-jam" | "input-tray-jam" | "output-tray-jam" => Condition::PaperJam,
+            "media-empty" | "media-needed" | "input-tray-missing" => Condition::PaperOut,
+            "media-low" => Condition::PaperLow,
+            "door-open" | "cover-open" | "interlock-open" => Condition::DoorOpen,
+            "marker-supply-empty" | "toner-empty" | "marker-supply-missing" => Condition::RibbonOut,
+            "marker-supply-low" | "toner-low" => Condition::RibbonLow,
+            "output-area-full" | "output-area-almost-full" => Condition::OutputFull,
+            // Any other hard error is the driver/printer failing
+            _ if reason.ends_with("-error") || reason == "other" => Condition::DriverError,
+            _ => continue,
+        };
+        if !conditions.contains(&condition) {
+            conditions.push(condition);
+        }
+    }
+    conditions
+}
+
+fn health_from(printer_name: &str, info: Result<PrinterInfo, String>) -> PrinterHealth {
+    let sampled_at = chrono::Local::now().to_rfc3339();
+    match info {
+        Ok(info) => PrinterHealth {
+            printer_name: printer_name.to_string(),
+            online: info.is_online,
+            status: info.status,
+            conditions: conditions(&info.state_reasons),
+            state_reasons: info.state_reasons,
+            backend: info.backend,
+            error: None,
+            sampled_at,
+        },
+        Err(e) => PrinterHealth {
+            printer_name: printer_name.to_string(),
+            online: false,
+            status: "Offline".to_string(),
+            conditions: vec![if e.contains("not found") { Condition::NotFound } else { Condition::Offline }],
+            state_reasons: Vec::new(),
+            backend: printer_backend::for_printer(printer_name).id().to_string(),
+            error: Some(e),
+            sampled_at,
+        },
+    }
+}
+
+// =============================================================================
+// Monitor
+// =============================================================================
+
+/// Debounce state for the ops alerts of one printer
+#[derive(Default)]
+struct AlertState {
+    /// When the current healthy/unhealthy streak started
+    unhealthy_since: Option<Instant>,
+    healthy_since: Option<Instant>,
+    alerted: bool,
+    last_alert: Option<Instant>,
+}
+
+enum Notify {
+    Alert,
+    Reconnected,
+}
+
+impl AlertState {
+    fn sample(&mut self, online: bool, now: Instant) -> Option<Notify> {
+        if online {
+            self.unhealthy_since = None;
+            let since = *self.healthy_since.get_or_insert(now);
+            if self.alerted && now.duration_since(since) >= RECONNECT_AFTER {
+                self.alerted = false;
+                return Some(Notify::Reconnected);
+            }
+        } else {
+            self.healthy_since = None;
+            let since = *self.unhealthy_since.get_or_insert(now);
+            let cooled_down = self.last_alert.is_none_or(|t| now.duration_since(t) >= ALERT_COOLDOWN);
+            if !self.alerted && cooled_down && now.duration_since(since) >= ALERT_AFTER {
+                self.alerted = true;
+                self.last_alert = Some(now);
+                return Some(Notify::Alert);
+            }
+        }
+        None
+    }
+}
+
+/// Start the monitor — called on startup
+pub fn init(app: &AppHandle) {
+    tauri::async_runtime::spawn(monitor(app.clone()));
+}
+
+async fn monitor(app: AppHandle) {
+    let mut current_printer = String::new();
+    let mut alerts = AlertState::default();
+
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+
+        let Some(state) = app.try_state::<crate::api::AppState>() else {
+            continue;
+        };
+        let printer_name = state.selected_printer.lock().unwrap().clone();
+        if printer_name != current_printer {
+            // New printer selected: start over without alerting about the old one
+            current_printer = printer_name.clone();
+            alerts = AlertState::default();
+            *HEALTH.lock().unwrap() = None;
+        }
+        if printer_name.is_empty() {
+            continue;
+        }
+
+        let name = printer_name.clone();
+        let info = tauri::async_runtime::spawn_blocking(move || printer_backend::for_printer(&name).status(&name))
+            .await
+            .unwrap_or_else(|e| Err(format!("Status check failed: {}", e)));
+        let health = health_from(&printer_name, info);
+
+        let previous = HEALTH.lock().unwrap().replace(health.clone());
+        let changed = previous.as_ref().is_none_or(|p| {
+            p.online != health.online || p.conditions != health.conditions || p.status != health.status
+        });
+        if changed {
+            let old = previous.as_ref().map(|p| p.conditions.clone()).unwrap_or_default();
+            let change = HealthChange {
+                added: health.conditions.iter().filter(|c| !old.contains(c)).copied().collect(),
+                cleared: old.iter().filter(|c| !health.conditions.contains(c)).copied().collect(),
+                was_online: previous.as_ref().map(|p| p.online),
+                health: health.clone(),
+            };
+            log::info!(
+                "[PrinterMonitor] {}: {} online={} conditions={:?}",
+                printer_name,
+                health.status,
+                health.online,
+                health.conditions
+            );
+            let _ = app.emit("printer-health", &health);
+            let _ = app.emit("printer-health-changed", &change);
+        }
+
+        match alerts.sample(health.online, Instant::now()) {
+            Some(Notify::Alert) => {
+                let available: Vec<String> = tauri::async_runtime::spawn_blocking(|| {
+                    printer_backend::all_printers().into_iter().map(|p| p.name).collect()
+                })
+                .await
+                .unwrap_or_default();
+                let reasons: Vec<String> = health
+                    .conditions
+                    .iter()
+                    .filter(|c| !c.is_warning())
+                    .map(|c| format!("{:?}", c))
+                    .collect();
+                let device_name = if reasons.is_empty() {
+                    printer_name.clone()
+                } else {
+                    format!("{} ({})", printer_name, reasons.join(", "))
+                };
+                log::warn!("[PrinterMonitor] Alerting ops: {}", device_name);
+                if let Err(e) = crate::api::post_device_alert(&state, "printer", &device_name, &available).await {
+                    log::warn!("[PrinterMonitor] device-alert failed: {}", e);
+                }
+            }
+            Some(Notify::Reconnected) => {
+                log::info!("[PrinterMonitor] Printer back: {}", printer_name);
+                let device_name = format!("Main: {}", printer_name);
+                if let Err(e) = crate::api::post_device_reconnected(&state, "printer", &device_name).await {
+                    log::warn!("[PrinterMonitor] device-reconnected failed: {}", e);
+                }
+            }
+            None => {}
+        }
+    }
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+/// Last sample of the selected printer (`None` before the first one)
+#[tauri::command]
+pub fn get_printer_health() -> Option<PrinterHealth> {
+    HEALTH.lock().unwrap().clone()
+}
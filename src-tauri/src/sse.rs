@@ -7,8 +7,6 @@ use std::sync::{Arc, Mutex};
 use tauri::{AppHandle, Emitter, Manager};
 use tokio::sync::Notify;
 
-const API_BASE_URL: &str = "https://api-booth.boniolabs.com";
-
 /// SSE Client that runs in the Rust backend.
 /// Maintains a persistent HTTP connection to the backend SSE endpoint.
 /// When the connection drops (app close/crash), the backend detects it
@@ -68,7 +66,7 @@ impl SseClient {
 
                 let url = format!(
                     "{}/api/sse/machine/connect?machineId={}",
-                    API_BASE_URL, machine_id
+                    crate::backend::base_url(), machine_id
                 );
                 info!("[SSE] Connecting to: {}", url);
 
@@ -99,6 +97,7 @@ impl SseClient {
                             reconnect_delay = 5;
                             reconnect_attempts = 0;
                             info!("[SSE] Connected successfully");
+                            crate::outbox::network_restored();
 
                             let _ = app.emit("sse-status", serde_json::json!({
                                 "connected": true
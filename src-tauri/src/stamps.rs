@@ -0,0 +1,306 @@
+//! Text & QR Stamps
+//!
+//! Renders a layout's `layers` (see `layout.rs`) onto an RGBA canvas at the
+//! canvas' own resolution, so stamps stay sharp at print size. Used directly
+//! by `compose_frame`, and by `compose_frame_video` to build a transparent
+//! overlay that ffmpeg lays over the video.
+//!
+//! Text and QR data are templates: `{name}` is replaced from the values the
+//! frontend passes (e.g. `transactionCode`, `eventName`, `qrUrl`), plus the
+//! built-ins `{date}` and `{time}`. Unknown placeholders become empty.
+
+use crate::image_processing::rotate_image_around_center;
+use crate::layout::{parse_color, FrameLayout, Layer, QrLayer, TextAlign, TextLayer};
+use ab_glyph::{point, Font, FontArc, PxScale, ScaleFont};
+use image::{Rgba, RgbaImage};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+const DEFAULT_DATE_FORMAT: &str = "%d/%m/%Y";
+const DEFAULT_TIME_FORMAT: &str = "%H:%M";
+/// Upper bound on a remote `fontFile` download so a dead CDN can't stall composing
+const FONT_DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Tried in order when a text layer has no `fontFile`
+const SYSTEM_FONTS: &[&str] = &[
+    "C:\\Windows\\Fonts\\segoeui.ttf",
+    "C:\\Windows\\Fonts\\tahoma.ttf",
+    "C:\\Windows\\Fonts\\arial.ttf",
+    "/System/Library/Fonts/Supplemental/Arial.ttf",
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+];
+
+/// Parsed fonts keyed by resolved path / URL
+static FONT_CACHE: OnceLock<Mutex<HashMap<String, FontArc>>> = OnceLock::new();
+
+// =============================================================================
+// Templates
+// =============================================================================
+
+/// Replace `{key}` placeholders. `{date}` / `{time}` default to now unless the
+/// caller supplied them.
+pub fn fill_template(template: &str, values: &HashMap<String, String>, date_format: Option<&str>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('}') else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+        let key = &after[..end];
+        match values.get(key) {
+            Some(value) => out.push_str(value),
+            None => {
+                let now = chrono::Local::now();
+                match key {
+                    "date" => out.push_str(&now.format(date_format.unwrap_or(DEFAULT_DATE_FORMAT)).to_string()),
+                    "time" => out.push_str(&now.format(DEFAULT_TIME_FORMAT).to_string()),
+                    _ => log::warn!("[Stamps] No value for {{{}}}", key),
+                }
+            }
+        }
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+// =============================================================================
+// Fonts
+// =============================================================================
+
+fn cached_font(key: &str) -> Option<FontArc> {
+    FONT_CACHE
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap()
+        .get(key)
+        .cloned()
+}
+
+fn cache_font(key: &str, bytes: Vec<u8>) -> Result<FontArc, String> {
+    let font = FontArc::try_from_vec(bytes).map_err(|e| format!("Font '{}': {}", key, e))?;
+    FONT_CACHE
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap()
+        .insert(key.to_string(), font.clone());
+    Ok(font)
+}
+
+/// Load a layer's font: URL, absolute path, or a name inside `fonts_dir`
+async fn load_font(font_file: Option<&str>, fonts_dir: Option<&Path>) -> Result<FontArc, String> {
+    let Some(font_file) = font_file.filter(|f| !f.is_empty()) else {
+        let path = SYSTEM_FONTS
+            .iter()
+            .find(|p| Path::new(p).exists())
+            .ok_or("No fontFile set and no system font found")?;
+        return load_font_path(Path::new(path));
+    };
+
+    if font_file.starts_with("http://") || font_file.starts_with("https://") {
+        if let Some(font) = cached_font(font_file) {
+            return Ok(font);
+        }
+        let bytes = reqwest::Client::new()
+            .get(font_file)
+            .timeout(FONT_DOWNLOAD_TIMEOUT)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("Font download error: {}", e))?
+            .bytes()
+            .await
+            .map_err(|e| format!("Font bytes error: {}", e))?;
+        return cache_font(font_file, bytes.to_vec());
+    }
+
+    let path = PathBuf::from(font_file);
+    let path = match fonts_dir {
+        Some(dir) if path.is_relative() => dir.join(path),
+        _ => path,
+    };
+    load_font_path(&path)
+}
+
+fn load_font_path(path: &Path) -> Result<FontArc, String> {
+    let key = path.to_string_lossy().to_string();
+    if let Some(font) = cached_font(&key) {
+        return Ok(font);
+    }
+    let bytes = std::fs::read(path).map_err(|e| format!("Font '{}': {}", key, e))?;
+    cache_font(&key, bytes)
+}
+
+// =============================================================================
+// Rendering
+// =============================================================================
+
+/// Draw every layer of `layout` onto `canvas`, scaling design units to the
+/// canvas size. `fonts_dir` resolves relative `fontFile` names.
+pub async fn render_layers(
+    canvas: &mut RgbaImage,
+    layout: &FrameLayout,
+    values: &HashMap<String, String>,
+    fonts_dir: Option<&Path>,
+) -> Result<(), String> {
+    let (scale_x, scale_y) = layout.scale_for(canvas.width(), canvas.height());
+    for (i, layer) in layout.layers.iter().enumerate() {
+        match layer {
+            Layer::Text(text) => {
+                let font = load_font(text.font_file.as_deref(), fonts_dir).await?;
+                let content = fill_template(&text.text, values, text.date_format.as_deref());
+                draw_text(canvas, text, &content, &font, scale_x, scale_y)?;
+            }
+            Layer::Qr(qr) => {
+                let data = fill_template(&qr.data, values, None);
+                if data.trim().is_empty() {
+                    log::warn!("[Stamps] Layer {}: QR data is empty, skipping", i + 1);
+                    continue;
+                }
+                draw_qr(canvas, qr, &data, scale_x, scale_y)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Paste `img` with its top-left at `x`,`y`; rotated stamps keep the same centre.
+fn place(canvas: &mut RgbaImage, img: RgbaImage, x: f64, y: f64, rotate: f64) {
+    let (w, h) = img.dimensions();
+    if rotate.abs() > 0.1 {
+        let rotated = rotate_image_around_center(&img, rotate);
+        let (rw, rh) = rotated.dimensions();
+        let offset_x = x.round() as i64 - (rw as i64 - w as i64) / 2;
+        let offset_y = y.round() as i64 - (rh as i64 - h as i64) / 2;
+        image::imageops::overlay(canvas, &rotated, offset_x, offset_y);
+    } else {
+        image::imageops::overlay(canvas, &img, x.round() as i64, y.round() as i64);
+    }
+}
+
+fn draw_text(
+    canvas: &mut RgbaImage,
+    layer: &TextLayer,
+    content: &str,
+    font: &FontArc,
+    scale_x: f64,
+    scale_y: f64,
+) -> Result<(), String> {
+    if content.trim().is_empty() {
+        return Ok(());
+    }
+    let color = parse_color(&layer.color)?;
+    let px = (layer.font_size * scale_y) as f32;
+    let scale = PxScale::from(px);
+    let scaled = font.as_scaled(scale);
+    let line_height = scaled.height() + scaled.line_gap();
+
+    let lines: Vec<&str> = content.lines().collect();
+    let widths: Vec<f32> = lines
+        .iter()
+        .map(|line| {
+            let mut width = 0.0;
+            let mut prev = None;
+            for c in line.chars() {
+                let id = scaled.glyph_id(c);
+                if let Some(prev) = prev {
+                    width += scaled.kern(prev, id);
+                }
+                width += scaled.h_advance(id);
+                prev = Some(id);
+            }
+            width
+        })
+        .collect();
+
+    let box_w = (layer.width * scale_x) as f32;
+    let text_w = widths.iter().cloned().fold(0.0, f32::max);
+    let img_w = box_w.max(text_w).ceil() as u32 + 2;
+    let img_h = (line_height * lines.len() as f32).ceil() as u32 + 2;
+    let mut img = RgbaImage::new(img_w, img_h);
+
+    for (row, (line, width)) in lines.iter().zip(&widths).enumerate() {
+        let start_x = match layer.align {
+            TextAlign::Left => 0.0,
+            TextAlign::Center => (img_w as f32 - width) / 2.0,
+            TextAlign::Right => img_w as f32 - width,
+        };
+        let baseline = scaled.ascent() + line_height * row as f32;
+        let mut caret = start_x;
+        let mut prev = None;
+        for c in line.chars() {
+            let id = scaled.glyph_id(c);
+            if let Some(prev) = prev {
+                caret += scaled.kern(prev, id);
+            }
+            let glyph = id.with_scale_and_position(scale, point(caret, baseline));
+            caret += scaled.h_advance(id);
+            prev = Some(id);
+
+            let Some(outlined) = font.outline_glyph(glyph) else {
+                continue;
+            };
+            let bounds = outlined.px_bounds();
+            outlined.draw(|gx, gy, coverage| {
+                let px_x = bounds.min.x as i64 + gx as i64;
+                let px_y = bounds.min.y as i64 + gy as i64;
+                if px_x < 0 || px_y < 0 || px_x >= img_w as i64 || px_y >= img_h as i64 {
+                    return;
+                }
+                let pixel = img.get_pixel_mut(px_x as u32, px_y as u32);
+                let alpha = (coverage.clamp(0.0, 1.0) * color[3] as f32) as u8;
+                if alpha > pixel[3] {
+                    *pixel = Rgba([color[0], color[1], color[2], alpha]);
+                }
+            });
+        }
+    }
+
+    // Anchor the rendered box: a zero-width layer aligns around `x`
+    let left = layer.x * scale_x
+        - match (layer.width > 0.0, layer.align) {
+            (true, _) | (false, TextAlign::Left) => 0.0,
+            (false, TextAlign::Center) => img_w as f64 / 2.0,
+            (false, TextAlign::Right) => img_w as f64,
+        };
+    place(canvas, img, left, layer.y * scale_y, layer.rotate);
+    Ok(())
+}
+
+fn draw_qr(
+    canvas: &mut RgbaImage,
+    layer: &QrLayer,
+    data: &str,
+    scale_x: f64,
+    scale_y: f64,
+) -> Result<(), String> {
+    let code = qrcode::QrCode::new(data.as_bytes()).map_err(|e| format!("QR encode error: {}", e))?;
+    let dark = parse_color(&layer.dark_color)?;
+    let light = parse_color(&layer.light_color)?;
+    let modules = code.width();
+    let colors = code.to_colors();
+    let margin = layer.margin as usize;
+    let total = modules + margin * 2;
+
+    // Square at the smaller of the two axis scales
+    let size = (layer.size * scale_x.min(scale_y)).round().max(total as f64) as u32;
+    let img = RgbaImage::from_fn(size, size, |px, py| {
+        // Nearest module — keeps edges crisp at any size
+        let mx = (px as usize * total / size as usize) as isize - margin as isize;
+        let my = (py as usize * total / size as usize) as isize - margin as isize;
+        let is_dark = mx >= 0
+            && my >= 0
+            && (mx as usize) < modules
+            && (my as usize) < modules
+            && colors[my as usize * modules + mx as usize] == qrcode::Color::Dark;
+        Rgba(if is_dark { dark } else { light })
+    });
+
+    place(canvas, img, layer.x * scale_x, layer.y * scale_y, layer.rotate);
+    Ok(())
+}
@@ -1,5 +1,8 @@
+use crate::layout::FrameLayout;
+use crate::lut::Interpolation;
 use base64::{engine::general_purpose::STANDARD, Engine};
 use image::GenericImageView;
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
@@ -10,7 +13,8 @@ use std::os::windows::process::CommandExt;
 const CREATE_NO_WINDOW: u32 = 0x08000000;
 
 /// Create a Command that hides the console window on Windows
-fn hidden_command(program: &str) -> std::process::Command {
+pub(crate) fn hidden_command(program: &str) -> std::process::Command {
+    #[cfg_attr(not(target_os = "windows"), allow(unused_mut))]
     let mut cmd = std::process::Command::new(program);
     #[cfg(target_os = "windows")]
     cmd.creation_flags(CREATE_NO_WINDOW);
@@ -73,17 +77,55 @@ fn get_ffmpeg_path() -> String {
 
 /// Copy LUT file to temp directory and return just the filename.
 /// This avoids all FFmpeg filter path escaping issues by using cwd instead of absolute paths.
-fn prepare_lut_in_temp(lut_path: &str, temp_dir: &Path) -> Result<String, String> {
+///
+/// Plain 3D cubes at full strength are copied as-is. 1D cubes, 1D+3D cubes,
+/// Hald CLUT PNGs and any LUT below 100% intensity are baked into a plain 3D
+/// cube first, so the same `lut3d` filter handles every case and the video
+/// matches the stills (`intensity` is 0..1).
+fn prepare_lut_in_temp(
+    lut_path: &str,
+    temp_dir: &Path,
+    intensity: f32,
+    interpolation: Option<Interpolation>,
+) -> Result<String, String> {
     let lut_src = Path::new(lut_path);
     let lut_filename = lut_src
         .file_name()
         .ok_or_else(|| format!("Invalid LUT path: {}", lut_path))?
         .to_string_lossy()
         .to_string();
-    let temp_lut = temp_dir.join(&lut_filename);
-    fs::copy(lut_src, &temp_lut)
-        .map_err(|e| format!("Failed to copy LUT to temp: {}", e))?;
-    Ok(lut_filename)
+
+    let lut = crate::lut::load_interpolated(lut_path, interpolation)?;
+    if lut.lut().is_plain_cube() && intensity >= 1.0 {
+        let temp_lut = temp_dir.join(&lut_filename);
+        fs::copy(lut_src, &temp_lut)
+            .map_err(|e| format!("Failed to copy LUT to temp: {}", e))?;
+        return Ok(lut_filename);
+    }
+
+    let stem = lut_src
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "filter".to_string());
+    let baked_filename = if intensity >= 1.0 {
+        format!("{}_baked.cube", stem)
+    } else {
+        format!("{}_baked_{}.cube", stem, (intensity * 100.0).round() as u32)
+    };
+    lut.lut().write_cube_3d(&temp_dir.join(&baked_filename), intensity)?;
+    println!(
+        "[prepare_lut_in_temp] Baked {} ({}) -> {}",
+        lut_filename,
+        lut.lut().kind.as_str(),
+        baked_filename
+    );
+    Ok(baked_filename)
+}
+
+/// `lut3d` filter for a LUT from `prepare_lut_in_temp`, sampled with the
+/// same interpolation as the stills (FFmpeg's own default is tetrahedral)
+fn lut3d_filter(lut_filename: &str, interpolation: Option<Interpolation>) -> String {
+    format!("lut3d=file={}:interp={}", lut_filename, Interpolation::or_default(interpolation).as_str())
 }
 
 /// Ensure FFmpeg is available (from @ffmpeg-installer/ffmpeg or system PATH)
@@ -254,27 +296,31 @@ pub async fn create_looped_video(
     Ok(output_path.to_string_lossy().to_string())
 }
 
-/// Apply LUT filter to video using ffmpeg
+/// Apply LUT filter to video using ffmpeg (`intensity` 0–100, default 100;
+/// `interpolation` from the filter's manifest entry, as for the stills)
 #[tauri::command]
 pub async fn apply_lut_to_video(
     input_path: String,
     lut_path: String,
     output_filename: String,
+    intensity: Option<f32>,
+    interpolation: Option<Interpolation>,
 ) -> Result<String, String> {
+    let intensity = crate::lut::intensity_from_percent(intensity);
     let temp_dir = std::env::temp_dir().join("bonio-booth").join("videos");
     fs::create_dir_all(&temp_dir).map_err(|e| format!("Create dir error: {}", e))?;
 
     let output_path = temp_dir.join(&output_filename);
 
-    if lut_path.is_empty() {
+    if lut_path.is_empty() || intensity <= 0.0 {
         // No filter - just copy
         fs::copy(&input_path, &output_path).map_err(|e| format!("Copy error: {}", e))?;
         return Ok(output_path.to_string_lossy().to_string());
     }
 
     // Copy LUT to temp dir and use just the filename (avoids path escaping issues)
-    let lut_filename = prepare_lut_in_temp(&lut_path, &temp_dir)?;
-    let lut_filter = format!("lut3d={}", lut_filename);
+    let lut_filename = prepare_lut_in_temp(&lut_path, &temp_dir, intensity, interpolation)?;
+    let lut_filter = lut3d_filter(&lut_filename, interpolation);
 
     let ffmpeg = get_ffmpeg_path();
     let status = hidden_command(&ffmpeg)
@@ -348,7 +394,10 @@ pub async fn process_frame_video(
     video_path: String,
     lut_path: String,
     output_filename: String,
+    intensity: Option<f32>,
+    interpolation: Option<Interpolation>,
 ) -> Result<String, String> {
+    let intensity = crate::lut::intensity_from_percent(intensity);
     let temp_dir = std::env::temp_dir().join("bonio-booth").join("videos");
     fs::create_dir_all(&temp_dir).map_err(|e| format!("Create dir error: {}", e))?;
 
@@ -377,10 +426,10 @@ pub async fn process_frame_video(
     // Step 2: Apply LUT filter if provided
     let output_path = temp_dir.join(&output_filename);
 
-    if !lut_path.is_empty() && Path::new(&lut_path).exists() {
+    if !lut_path.is_empty() && intensity > 0.0 && Path::new(&lut_path).exists() {
         // Copy LUT to temp dir and use just the filename (avoids path escaping issues)
-        let lut_filename = prepare_lut_in_temp(&lut_path, &temp_dir)?;
-        let lut_filter = format!("lut3d={}", lut_filename);
+        let lut_filename = prepare_lut_in_temp(&lut_path, &temp_dir, intensity, interpolation)?;
+        let lut_filter = lut3d_filter(&lut_filename, interpolation);
 
         let filter_status = hidden_command(&ffmpeg)
             .current_dir(&temp_dir)
@@ -416,16 +465,23 @@ pub async fn process_frame_video(
 /// Handles: loop (3s→9s), LUT filter, scale/crop to slots, overlay on frame image.
 /// This replaces the old multi-pass pipeline (loop → LUT → compose) with one efficient pass.
 /// Output: 9-second video at 1080p equivalent resolution.
+/// Text / QR stamps from the layout are rendered at output resolution into a
+/// transparent PNG and overlaid last, same as `compose_frame`.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn compose_frame_video(
+    app: tauri::AppHandle,
     frame_image_url: String,
     video_paths: Vec<String>,
-    slots: Vec<serde_json::Value>,
-    frame_width: u32,
-    frame_height: u32,
+    layout: FrameLayout,
     lut_path: Option<String>,
     output_filename: String,
+    lut_intensity: Option<f32>,
+    lut_interpolation: Option<Interpolation>,
+    stamp_values: Option<HashMap<String, String>>,
 ) -> Result<String, String> {
+    layout.validate_for(video_paths.len())?;
+    let lut_intensity = crate::lut::intensity_from_percent(lut_intensity);
     let temp_dir = std::env::temp_dir().join("bonio-booth").join("videos");
     fs::create_dir_all(&temp_dir).map_err(|e| format!("Create dir error: {}", e))?;
 
@@ -461,21 +517,39 @@ pub async fn compose_frame_video(
     if out_w % 2 != 0 { out_w += 1; }
     if out_h % 2 != 0 { out_h += 1; }
 
-    let scale_x = out_w as f64 / frame_width as f64;
-    let scale_y = out_h as f64 / frame_height as f64;
+    // Even-aligned placement — yuv420p needs even offsets and sizes
+    let placed = layout.place(out_w, out_h, video_paths.len(), 2);
 
     // Prepare LUT if provided
     let lut_filename = match &lut_path {
-        Some(lp) if !lp.is_empty() && Path::new(lp).exists() => {
-            Some(prepare_lut_in_temp(lp, &temp_dir)?)
+        Some(lp) if !lp.is_empty() && lut_intensity > 0.0 && Path::new(lp).exists() => {
+            Some(prepare_lut_in_temp(lp, &temp_dir, lut_intensity, lut_interpolation)?)
         }
         _ => None,
     };
 
-    println!("[compose_frame_video] frame: {}x{}, output: {}x{}, grid: {}x{}, scale: {:.3}/{:.3}, lut: {:?}",
-        orig_w, orig_h, out_w, out_h, frame_width, frame_height, scale_x, scale_y, lut_filename);
+    println!("[compose_frame_video] frame: {}x{}, output: {}x{}, grid: {}x{}, lut: {:?}",
+        orig_w, orig_h, out_w, out_h, layout.width, layout.height, lut_filename);
+
+    let num_videos = video_paths.len();
+
+    // Render stamps once at output size
+    let stamps_path = if layout.layers.is_empty() {
+        None
+    } else {
+        let mut stamps_img = image::RgbaImage::new(out_w, out_h);
+        let fonts_dir = crate::get_fonts_dir(app);
+        let values = stamp_values.unwrap_or_default();
+        crate::stamps::render_layers(&mut stamps_img, &layout, &values, fonts_dir.as_deref()).await?;
+        // Unique per call so concurrent compositions don't share an overlay
+        let tag = &uuid::Uuid::new_v4().simple().to_string()[..8];
+        let path = temp_dir.join(format!("stamps-overlay-{}.png", tag));
+        stamps_img
+            .save(&path)
+            .map_err(|e| format!("Stamps write error: {}", e))?;
+        Some(path)
+    };
 
-    let num_videos = video_paths.len().min(slots.len());
     let output_path = temp_dir.join(&output_filename);
 
     // Build FFmpeg arguments
@@ -492,37 +566,31 @@ pub async fn compose_frame_video(
     }
     // Frame image as last input
     final_args.extend(vec!["-i".to_string(), frame_path.to_string_lossy().to_string()]);
+    // Stamps overlay right after it
+    if let Some(ref path) = stamps_path {
+        final_args.extend(vec!["-i".to_string(), path.to_string_lossy().to_string()]);
+    }
 
     // Build filter_complex: scale → [lut] → crop → format → overlay
     let mut filter_parts: Vec<String> = Vec::new();
 
-    for i in 0..num_videos {
-        let slot = &slots[i];
-        let orig_sx = (slot.get("x").and_then(|v| v.as_f64()).unwrap_or(0.0) * scale_x).floor() as i64;
-        let orig_sy = (slot.get("y").and_then(|v| v.as_f64()).unwrap_or(0.0) * scale_y).floor() as i64;
-        let orig_sw = (slot.get("width").and_then(|v| v.as_f64()).unwrap_or(100.0) * scale_x).ceil() as i64;
-        let orig_sh = (slot.get("height").and_then(|v| v.as_f64()).unwrap_or(100.0) * scale_y).ceil() as i64;
-        
-        let mut sx = orig_sx;
-        let mut sy = orig_sy;
-        if sx % 2 != 0 { sx -= 1; }
-        if sy % 2 != 0 { sy -= 1; }
-        
-        let mut sw = orig_sx + orig_sw - sx;
-        let mut sh = orig_sy + orig_sh - sy;
-        if sw % 2 != 0 { sw += 1; }
-        if sh % 2 != 0 { sh += 1; }
+    for slot in &placed {
+        let (i, sw, sh) = (slot.index, slot.width, slot.height);
 
         // Chain: trim to exactly 9s → reset pts → scale to cover → crop to exact slot → optional LUT → format
         // trim=duration=9 ensures all slots have identical duration
         // scaling: add 2 extra pixels (+2) to width/height to ensure it covers the slot fully
         // this prevents single-pixel white gaps due to rounding errors
+        // Camera mounted sideways: turn upright before scaling into the slot
+        let rotate = crate::orientation::mount_rotation_filter()
+            .map(|f| format!("{},", f))
+            .unwrap_or_default();
         let mut chain = format!(
-            "[{}:v]trim=duration=9,setpts=PTS-STARTPTS,scale={}:{}:force_original_aspect_ratio=increase,crop={}:{}",
-            i, sw+2, sh+2, sw, sh
+            "[{}:v]trim=duration=9,setpts=PTS-STARTPTS,{}scale={}:{}:force_original_aspect_ratio=increase,crop={}:{}",
+            i, rotate, sw+2, sh+2, sw, sh
         );
         if let Some(ref lut_fn) = lut_filename {
-            chain.push_str(&format!(",lut3d={}", lut_fn));
+            chain.push_str(&format!(",{}", lut3d_filter(lut_fn, lut_interpolation)));
         }
         chain.push_str(&format!(",format=yuv420p[v{}]", i));
         filter_parts.push(chain);
@@ -544,22 +612,10 @@ pub async fn compose_frame_video(
     let mut prev = "bg".to_string();
 
     // Background slots (zIndex < 0)
-    for i in 0..num_videos {
-        let slot = &slots[i];
-        let orig_sx = (slot.get("x").and_then(|v| v.as_f64()).unwrap_or(0.0) * scale_x).floor() as i64;
-        let orig_sy = (slot.get("y").and_then(|v| v.as_f64()).unwrap_or(0.0) * scale_y).floor() as i64;
-        
-        let mut sx = orig_sx;
-        let mut sy = orig_sy;
-        if sx % 2 != 0 { sx -= 1; }
-        if sy % 2 != 0 { sy -= 1; }
-
-        let z_index = slot.get("zIndex").and_then(|v| v.as_f64()).unwrap_or(0.0) as i64;
-        if z_index < 0 {
-            let out = format!("b{}", i);
-            filter_parts.push(format!("[{}][v{}]overlay={}:{}:eof_action=repeat[{}]", prev, i, sx, sy, out));
-            prev = out;
-        }
+    for slot in placed.iter().filter(|s| s.behind_frame) {
+        let out = format!("b{}", slot.index);
+        filter_parts.push(format!("[{}][v{}]overlay={}:{}:eof_action=repeat[{}]", prev, slot.index, slot.x, slot.y, out));
+        prev = out;
     }
 
     // Frame overlay (eof_action=repeat so the single-frame PNG repeats for full duration)
@@ -567,22 +623,20 @@ pub async fn compose_frame_video(
     prev = "af".to_string();
 
     // Foreground slots (zIndex >= 0)
-    for i in 0..num_videos {
-        let slot = &slots[i];
-        let orig_sx = (slot.get("x").and_then(|v| v.as_f64()).unwrap_or(0.0) * scale_x).floor() as i64;
-        let orig_sy = (slot.get("y").and_then(|v| v.as_f64()).unwrap_or(0.0) * scale_y).floor() as i64;
-        
-        let mut sx = orig_sx;
-        let mut sy = orig_sy;
-        if sx % 2 != 0 { sx -= 1; }
-        if sy % 2 != 0 { sy -= 1; }
-
-        let z_index = slot.get("zIndex").and_then(|v| v.as_f64()).unwrap_or(0.0) as i64;
-        if z_index >= 0 {
-            let out = format!("f{}", i);
-            filter_parts.push(format!("[{}][v{}]overlay={}:{}:eof_action=repeat[{}]", prev, i, sx, sy, out));
-            prev = out;
-        }
+    for slot in placed.iter().filter(|s| !s.behind_frame) {
+        let out = format!("f{}", slot.index);
+        filter_parts.push(format!("[{}][v{}]overlay={}:{}:eof_action=repeat[{}]", prev, slot.index, slot.x, slot.y, out));
+        prev = out;
+    }
+
+    // Stamps on top (already at output size, same colour handling as the frame)
+    if stamps_path.is_some() {
+        filter_parts.push(format!(
+            "[{}:v]scale=in_range=pc:out_range=tv:out_color_matrix=bt709:flags=accurate_rnd+full_chroma_int,format=yuva420p[stamps_img]",
+            num_videos + 1
+        ));
+        filter_parts.push(format!("[{}][stamps_img]overlay=0:0:eof_action=repeat[st]", prev));
+        prev = "st".to_string();
     }
 
     let filter_complex = filter_parts.join(";");
@@ -606,8 +660,11 @@ pub async fn compose_frame_video(
     let status = hidden_command(&get_ffmpeg_path())
         .current_dir(&temp_dir)
         .args(&final_args)
-        .output()
-        .map_err(|e| format!("FFmpeg compose error: {}", e))?;
+        .output();
+    if let Some(path) = &stamps_path {
+        let _ = fs::remove_file(path);
+    }
+    let status = status.map_err(|e| format!("FFmpeg compose error: {}", e))?;
 
     // Always log stderr for debugging
     let stderr_str = String::from_utf8_lossy(&status.stderr);
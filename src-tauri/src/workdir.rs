@@ -0,0 +1,295 @@
+//! Session Working Directory
+//!
+//! Every customer session gets its own folder under
+//! `%TEMP%/bonio-booth/sessions/<session id>/`. Captures, filtered photos and
+//! composed frames are written there once and passed around by file id or
+//! path, so multi-megabyte JPEGs no longer travel through IPC as base64.
+//!
+//! Image inputs accepted by the pipeline commands (see [`read_input`]):
+//! - a file id returned by an earlier command (e.g. `"capture-3f2a9c1e.jpg"`)
+//! - an absolute file path
+//! - a `data:` URL (legacy callers, decoded in memory)
+//!
+//! The frontend displays outputs through the asset protocol
+//! (`convertFileSrc(path)`).
+
+use crate::jpeg_meta::{self, BoothInfo, MetadataOptions};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use image::{DynamicImage, ImageFormat};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Old session folders kept around (e.g. for a reprint) before pruning
+const KEEP_SESSIONS: usize = 3;
+
+static CURRENT: Mutex<Option<Session>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Session {
+    pub id: String,
+    pub dir: String,
+}
+
+/// A file in the session folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageFile {
+    /// File name inside the session folder — pass it back as an input
+    pub id: String,
+    pub path: String,
+    pub width: u32,
+    pub height: u32,
+    pub size_bytes: u64,
+}
+
+fn sessions_root() -> PathBuf {
+    std::env::temp_dir().join("bonio-booth").join("sessions")
+}
+
+/// Create a fresh session folder and prune old ones
+fn start_session() -> Result<Session, String> {
+    let id = uuid::Uuid::new_v4().simple().to_string();
+    let dir = sessions_root().join(&id);
+    fs::create_dir_all(&dir).map_err(|e| format!("Create session dir error: {}", e))?;
+    prune_sessions(&id);
+
+    let session = Session {
+        id,
+        dir: dir.to_string_lossy().to_string(),
+    };
+    log::info!("[Session] Started {}", session.dir);
+    *CURRENT.lock().unwrap() = Some(session.clone());
+    Ok(session)
+}
+
+/// Delete all but the newest `KEEP_SESSIONS` folders (never the current one)
+fn prune_sessions(current_id: &str) {
+    let Ok(entries) = fs::read_dir(sessions_root()) else {
+        return;
+    };
+    let mut dirs: Vec<(std::time::SystemTime, PathBuf)> = entries
+        .flatten()
+        .filter(|e| e.file_name().to_string_lossy() != current_id)
+        .filter_map(|e| {
+            let modified = e.metadata().and_then(|m| m.modified()).ok()?;
+            Some((modified, e.path()))
+        })
+        .collect();
+    dirs.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    for (_, dir) in dirs.into_iter().skip(KEEP_SESSIONS) {
+        if let Err(e) = fs::remove_dir_all(&dir) {
+            log::warn!("[Session] Failed to remove {}: {}", dir.display(), e);
+        }
+    }
+}
+
+/// Folder of the current session, starting one if needed
+pub fn session_dir() -> Result<PathBuf, String> {
+    if let Some(session) = CURRENT.lock().unwrap().as_ref() {
+        let dir = PathBuf::from(&session.dir);
+        if dir.exists() {
+            return Ok(dir);
+        }
+    }
+    start_session().map(|s| PathBuf::from(s.dir))
+}
+
+/// `{stem}-{8 hex}` with the stem made file-name safe
+fn unique_name(stem: &str) -> String {
+    let stem: String = stem
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '-' })
+        .collect();
+    format!("{}-{}", stem, &uuid::Uuid::new_v4().simple().to_string()[..8])
+}
+
+/// A new, unused path in the session folder: `{stem}-{8 hex}.{ext}`
+pub fn new_file_path(stem: &str, ext: &str) -> Result<PathBuf, String> {
+    Ok(session_dir()?.join(format!("{}.{}", unique_name(stem), ext)))
+}
+
+/// A new, empty folder in the session folder: `{stem}-{8 hex}`
+pub fn new_dir(stem: &str) -> Result<PathBuf, String> {
+    let dir = session_dir()?.join(unique_name(stem));
+    fs::create_dir_all(&dir).map_err(|e| format!("Create dir error: {}", e))?;
+    Ok(dir)
+}
+
+/// `{stem}-…` files in the current and kept session folders, newest first
+/// (e.g. the last composed prints, for the paper position preview)
+pub fn recent_files(stem: &str) -> Vec<PathBuf> {
+    let prefix = format!("{}-", stem);
+    let Ok(sessions) = fs::read_dir(sessions_root()) else {
+        return Vec::new();
+    };
+    let mut files: Vec<(std::time::SystemTime, PathBuf)> = sessions
+        .flatten()
+        .filter_map(|session| fs::read_dir(session.path()).ok())
+        .flat_map(|entries| entries.flatten())
+        .filter(|e| e.file_name().to_string_lossy().starts_with(&prefix))
+        .filter_map(|e| {
+            let modified = e.metadata().and_then(|m| m.modified()).ok()?;
+            Some((modified, e.path()))
+        })
+        .collect();
+    files.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    files.into_iter().map(|(_, path)| path).collect()
+}
+
+// =============================================================================
+// Inputs
+// =============================================================================
+
+/// Resolve a file id or path to an existing file
+pub fn resolve_path(input: &str) -> Result<PathBuf, String> {
+    let path = Path::new(input);
+    if path.is_absolute() && path.exists() {
+        return Ok(path.to_path_buf());
+    }
+    // Ids are bare file names — never let them climb out of the session folder
+    if !input.is_empty() && !input.contains(['/', '\\']) && input != ".." {
+        let in_session = session_dir()?.join(input);
+        if in_session.exists() {
+            return Ok(in_session);
+        }
+    }
+    Err(format!("Image not found: {}", input))
+}
+
+/// Raw bytes of an input: file id, path, or data URL
+pub fn read_input(input: &str) -> Result<Vec<u8>, String> {
+    if input.starts_with("data:") {
+        let clean = input.split(',').nth(1).unwrap_or("");
+        return STANDARD
+            .decode(clean)
+            .map_err(|e| format!("Base64 decode error: {}", e));
+    }
+    let path = resolve_path(input)?;
+    fs::read(&path).map_err(|e| format!("Read {} error: {}", path.display(), e))
+}
+
+/// Decode an input image upright (format sniffed from content, not the
+/// extension; EXIF orientation applied)
+pub fn load_input(input: &str) -> Result<DynamicImage, String> {
+    let bytes = read_input(input)?;
+    crate::orientation::decode_upright(&bytes, false)
+}
+
+// =============================================================================
+// Outputs
+// =============================================================================
+
+fn image_file(path: &Path, width: u32, height: u32) -> Result<ImageFile, String> {
+    let size_bytes = fs::metadata(path).map(|m| m.len()).map_err(|e| e.to_string())?;
+    Ok(ImageFile {
+        id: path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        path: path.to_string_lossy().to_string(),
+        width,
+        height,
+        size_bytes,
+    })
+}
+
+/// Id of the current session, if one was started
+pub fn current_session_id() -> Option<String> {
+    CURRENT.lock().unwrap().as_ref().map(|s| s.id.clone())
+}
+
+/// Print metadata for a `width` × `height` output of the current session (see
+/// `jpeg_meta`), at the DPI of the selected paper profile. Pixels are always
+/// decoded upright, so the source Orientation is dropped.
+pub fn jpeg_options(width: u32, height: u32) -> MetadataOptions {
+    MetadataOptions {
+        dpi: crate::paper::selected_for(width, height).dpi as u16,
+        reset_orientation: true,
+        booth: Some(BoothInfo::now(&current_session_id().unwrap_or_default())),
+        ..MetadataOptions::default()
+    }
+}
+
+/// Encode as JPEG with print metadata and write it into the session folder.
+/// Camera EXIF from `source` is kept according to the EXIF policy.
+pub fn write_jpeg(
+    img: &DynamicImage,
+    stem: &str,
+    quality: u8,
+    source: Option<&[u8]>,
+) -> Result<ImageFile, String> {
+    write_jpeg_with(img, stem, quality, source, &jpeg_options(img.width(), img.height()))
+}
+
+/// [`write_jpeg`] with explicit metadata (e.g. a paper profile's DPI)
+pub fn write_jpeg_with(
+    img: &DynamicImage,
+    stem: &str,
+    quality: u8,
+    source: Option<&[u8]>,
+    options: &MetadataOptions,
+) -> Result<ImageFile, String> {
+    let buf = jpeg_meta::encode_jpeg(img, quality, source, options)?;
+    let path = new_file_path(stem, "jpg")?;
+    fs::write(&path, &buf).map_err(|e| format!("Write error: {}", e))?;
+    image_file(&path, img.width(), img.height())
+}
+
+/// Store already-encoded image bytes in the session folder as-is (extension
+/// and size come from the image header)
+pub fn write_bytes(bytes: &[u8], stem: &str) -> Result<ImageFile, String> {
+    let format = image::guess_format(bytes).map_err(|e| format!("Unknown image format: {}", e))?;
+    let ext = format.extensions_str().first().copied().unwrap_or("bin");
+    let (width, height) = image::ImageReader::with_format(std::io::Cursor::new(bytes), format)
+        .into_dimensions()
+        .map_err(|e| format!("Image header error: {}", e))?;
+
+    let path = new_file_path(stem, ext)?;
+    fs::write(&path, bytes).map_err(|e| format!("Write error: {}", e))?;
+    image_file(&path, width, height)
+}
+
+/// Write as PNG (keeps alpha) into the session folder
+pub fn write_png(img: &DynamicImage, stem: &str) -> Result<ImageFile, String> {
+    let path = new_file_path(stem, "png")?;
+    img.save_with_format(&path, ImageFormat::Png)
+        .map_err(|e| format!("Write error: {}", e))?;
+    image_file(&path, img.width(), img.height())
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+/// Start a new customer session (call when shooting begins)
+#[tauri::command]
+pub async fn session_start() -> Result<Session, String> {
+    start_session()
+}
+
+/// Store an image the frontend already holds (webcam frame) in the session
+/// folder. DSLR captures don't come through here — the capture commands write
+/// them straight into the session folder. The bytes are written as-is unless
+/// the photo has to be turned upright (EXIF orientation, or the camera mount
+/// rotation when `apply_mount_rotation` is set).
+#[tauri::command]
+pub async fn session_import_image(
+    image_data_base64: String,
+    name: Option<String>,
+    apply_mount_rotation: Option<bool>,
+) -> Result<ImageFile, String> {
+    let input = if image_data_base64.starts_with("data:") {
+        image_data_base64
+    } else {
+        format!("data:;base64,{}", image_data_base64)
+    };
+    let bytes = read_input(&input)?;
+    let stem = name.as_deref().unwrap_or("capture");
+    let is_capture = apply_mount_rotation.unwrap_or(false);
+    if crate::orientation::needs_rotation(&bytes, is_capture) {
+        let img = crate::orientation::decode_upright(&bytes, is_capture)?;
+        return write_jpeg(&img, stem, 95, Some(&bytes));
+    }
+    write_bytes(&bytes, stem)
+}
@@ -58,6 +58,33 @@ export interface FrameSlot {
   rotate?: number; // Rotation in degrees (0-360)
 }
 
+/** Text / QR stamp drawn over the composed frame (see src-tauri/src/layout.rs) */
+export type FrameLayer =
+  | {
+      type: "text";
+      x: number;
+      y: number;
+      width?: number;
+      text: string; // template, e.g. "{eventName} {date}"
+      fontFile?: string;
+      fontSize: number;
+      color?: string;
+      align?: "left" | "center" | "right";
+      rotate?: number;
+      dateFormat?: string;
+    }
+  | {
+      type: "qr";
+      x: number;
+      y: number;
+      size: number;
+      data: string; // template, e.g. "{qrUrl}"
+      darkColor?: string;
+      lightColor?: string;
+      margin?: number;
+      rotate?: number;
+    };
+
 export interface FrameData {
   _id: string;
   name: string;
@@ -70,16 +97,50 @@ export interface FrameData {
     width: number;
     height: number;
     slots: FrameSlot[];
+    layers?: FrameLayer[];
   };
   [key: string]: any;
 }
 
+/** Paper position and profile per orientation (`PaperPositionConfig` in api.rs) */
+interface PaperPositionConfig {
+  scale: number;
+  vertical: number;
+  horizontal: number;
+  paper_profile: string | null;
+}
+
+/** Stored machine config (src-tauri/src/machine_config.rs) */
+export interface MachineConfig {
+  version: number;
+  machineId: string;
+  machinePort: string;
+  cameraType: string;
+  selectedWebcamId: string;
+  selectedCameraName: string;
+  cameraMountRotation: number;
+  selectedPrinter: string;
+  paperPortrait: PaperPositionConfig;
+  paperLandscape: PaperPositionConfig;
+  updatedAt: string | null;
+}
+
 export interface Capture {
-  photo: string;
+  photo: string; // display URL (asset URL for session files)
+  photoPath?: string; // file in the session working directory
   video: string;
   videoPath?: string;
 }
 
+/** Image written to the session working directory (src-tauri/src/workdir.rs) */
+export interface ImageFile {
+  id: string;
+  path: string;
+  width: number;
+  height: number;
+  size_bytes: number;
+}
+
 function App() {
   const [machineData, setMachineData] = useState<MachineData | null>(null);
   const [themeData, setThemeData] = useState<ThemeData | null>(null);
@@ -92,22 +153,50 @@ function App() {
 
   const initRetryTimerRef = useRef<ReturnType<typeof setInterval> | null>(null);
 
-  // Restore camera type from localStorage to Rust AppState on startup
-  // Otherwise AppState defaults to "webcam" even if user configured "canon"
+  // The machine config is stored by Rust (machine.json). Booths set up before
+  // that only have it in localStorage — push it once so it gets stored.
   useEffect(() => {
-    const savedCameraType = localStorage.getItem("cameraType");
-    if (savedCameraType) {
-      invoke("set_camera_type", { cameraType: savedCameraType }).catch(
-        () => {},
-      );
-    }
-  }, []);
+    (async () => {
+      // null when machine.json doesn't exist yet
+      const config = await invoke<MachineConfig | null>(
+        "get_machine_config",
+      ).catch(() => null);
+      if (config) {
+        const keep = (key: string, value: string) =>
+          value ? localStorage.setItem(key, value) : localStorage.removeItem(key);
+        keep("machineId", config.machineId);
+        keep("machinePort", config.machinePort);
+        keep("cameraType", config.cameraType);
+        keep("selectedPrinter", config.selectedPrinter);
+        if (config.machineId) {
+          initMachine(config.machineId);
+        }
+        return;
+      }
 
-  useEffect(() => {
-    const savedMachineId = localStorage.getItem("machineId");
-    if (savedMachineId) {
-      initMachine(savedMachineId);
-    }
+      const savedCameraType = localStorage.getItem("cameraType");
+      if (savedCameraType) {
+        invoke("set_camera_type", { cameraType: savedCameraType }).catch(
+          () => {},
+        );
+      }
+      const savedRotation = Number(localStorage.getItem("cameraMountRotation"));
+      if (savedRotation) {
+        invoke("set_camera_mount_rotation", { degrees: savedRotation }).catch(
+          () => {},
+        );
+      }
+      const savedPrinter = localStorage.getItem("selectedPrinter");
+      if (savedPrinter) {
+        invoke("set_selected_printer", { printerName: savedPrinter }).catch(
+          () => {},
+        );
+      }
+      const savedMachineId = localStorage.getItem("machineId");
+      if (savedMachineId) {
+        initMachine(savedMachineId);
+      }
+    })();
   }, []);
 
   const initMachine = useCallback(
@@ -22,17 +22,21 @@ export default function CameraConfigModal({ open, onClose }: Props) {
   const [dslrCameras, setDslrCameras] = useState<DslrCamera[]>([]);
   const [selectedWebcam, setSelectedWebcam] = useState<string>("");
   const [selectedDslr, setSelectedDslr] = useState<string>("");
+  const [mountRotation, setMountRotation] = useState<number>(0);
   const [loading, setLoading] = useState(false);
   const [saving, setSaving] = useState(false);
   const [savedMessage, setSavedMessage] = useState("");
 
-  // Load current config from localStorage
+  // Load current config (the mount rotation is kept in the machine config)
   useEffect(() => {
     if (!open) return;
     const savedType = localStorage.getItem("cameraType") || "webcam";
     setTab(savedType as "webcam" | "canon");
     setSelectedWebcam(localStorage.getItem("selectedWebcamId") || "");
     setSelectedDslr(localStorage.getItem("selectedCameraName") || "");
+    invoke<number>("get_camera_mount_rotation")
+      .then(setMountRotation)
+      .catch(() => setMountRotation(0));
     setSavedMessage("");
   }, [open]);
 
@@ -97,6 +101,8 @@ export default function CameraConfigModal({ open, onClose }: Props) {
         localStorage.setItem("selectedCameraName", selectedDslr);
       }
 
+      await invoke("set_camera_mount_rotation", { degrees: mountRotation });
+
       setSavedMessage("✅ บันทึกสำเร็จ!");
       setTimeout(() => setSavedMessage(""), 2000);
     } catch (err) {
@@ -195,6 +201,22 @@ export default function CameraConfigModal({ open, onClose }: Props) {
               </button>
             </>
           )}
+
+          {/* Camera physically rotated on the booth — captures are turned upright */}
+          <p className="config-label config-rotation-label">
+            การหมุนกล้อง / Camera Mount Rotation
+          </p>
+          <div className="config-rotation-options">
+            {[0, 90, 180, 270].map((deg) => (
+              <button
+                key={deg}
+                className={`config-tab ${mountRotation === deg ? "active" : ""}`}
+                onClick={() => setMountRotation(deg)}
+              >
+                {deg}°
+              </button>
+            ))}
+          </div>
         </div>
 
         {savedMessage && (
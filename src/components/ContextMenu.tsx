@@ -5,6 +5,9 @@ import { getVersion } from "@tauri-apps/api/app";
 import CameraConfigModal from "./CameraConfigModal";
 import PrinterConfigModal from "./PrinterConfigModal";
 import PaperPositionModal from "./PaperPositionModal";
+import PrintQueueModal from "./PrintQueueModal";
+import OutboxModal from "./OutboxModal";
+import { formatAge, type OutboxStatus } from "../utils/outbox";
 import { CLOSE_APP_PIN } from "../config/appConfig";
 
 interface Props {
@@ -22,7 +25,7 @@ export default function ContextMenu({
 }: Props) {
   const navigate = useNavigate();
   const [activeModal, setActiveModal] = useState<
-    "camera" | "printer" | "paper" | null
+    "camera" | "printer" | "paper" | "queue" | "outbox" | null
   >(null);
   const [showResetConfirm, setShowResetConfirm] = useState(false);
   const [showPinModal, setShowPinModal] = useState(false);
@@ -30,6 +33,7 @@ export default function ContextMenu({
   const [pinError, setPinError] = useState(false);
   const [cameraStatus, setCameraStatus] = useState("");
   const [printerStatus, setPrinterStatus] = useState("");
+  const [outboxStatus, setOutboxStatus] = useState("");
   const [appVersion, setAppVersion] = useState("");
 
   // Load status summaries when menu opens
@@ -57,6 +61,17 @@ export default function ContextMenu({
     // Printer status
     const printer = localStorage.getItem("selectedPrinter");
     setPrinterStatus(printer || "ยังไม่ได้เลือก");
+
+    // Outbox: what's waiting for the network
+    invoke<OutboxStatus>("get_outbox_status")
+      .then((s) => {
+        const waiting =
+          s.depth === 0
+            ? "ส่งครบแล้ว"
+            : `รอส่ง ${s.depth} • เก่าสุด ${formatAge(s.oldestAgeSecs ?? 0)}`;
+        setOutboxStatus(s.failed > 0 ? `${waiting} • ไม่สำเร็จ ${s.failed}` : waiting);
+      })
+      .catch(() => setOutboxStatus("ไม่พร้อมใช้งาน"));
   }, [open]);
 
   const handleFormatReset = () => {
@@ -67,6 +82,7 @@ export default function ContextMenu({
     localStorage.removeItem("selectedWebcamId");
     localStorage.removeItem("selectedCameraLabel");
     localStorage.removeItem("selectedCameraName");
+    localStorage.removeItem("cameraMountRotation");
     localStorage.removeItem("selectedPrinter");
     localStorage.removeItem("paperConfig");
     localStorage.removeItem("paperConfigPortrait");
@@ -219,6 +235,18 @@ export default function ContextMenu({
     );
   }
 
+  if (activeModal === "queue") {
+    return (
+      <PrintQueueModal open={true} onClose={() => setActiveModal(null)} />
+    );
+  }
+
+  if (activeModal === "outbox") {
+    return (
+      <OutboxModal open={true} onClose={() => setActiveModal(null)} />
+    );
+  }
+
   return (
     <div
       className="context-menu-overlay"
@@ -282,6 +310,36 @@ export default function ContextMenu({
           <span style={{ opacity: 0.4, fontSize: 18 }}>›</span>
         </button>
 
+        {/* Print Queue / reprint */}
+        <button
+          className="context-menu-item context-menu-config-item"
+          onClick={() => setActiveModal("queue")}
+        >
+          <span style={{ fontSize: 24 }}>🧾</span>
+          <div style={{ flex: 1, textAlign: "left" }}>
+            <div style={{ fontWeight: 600 }}>Print Queue</div>
+            <div style={{ fontSize: 11, opacity: 0.6, marginTop: 2 }}>
+              ประวัติการพิมพ์ / พิมพ์ใหม่
+            </div>
+          </div>
+          <span style={{ opacity: 0.4, fontSize: 18 }}>›</span>
+        </button>
+
+        {/* Outbox: uploads and reports waiting for the network */}
+        <button
+          className="context-menu-item context-menu-config-item"
+          onClick={() => setActiveModal("outbox")}
+        >
+          <span style={{ fontSize: 24 }}>📮</span>
+          <div style={{ flex: 1, textAlign: "left" }}>
+            <div style={{ fontWeight: 600 }}>Outbox</div>
+            <div style={{ fontSize: 11, opacity: 0.6, marginTop: 2 }}>
+              {outboxStatus}
+            </div>
+          </div>
+          <span style={{ opacity: 0.4, fontSize: 18 }}>›</span>
+        </button>
+
         <div style={{ borderTop: "1px solid #333", margin: "12px 0" }} />
 
         {/* 4. Request Image (พิมพ์ย้อนหลัง) */}
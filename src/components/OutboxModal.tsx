@@ -0,0 +1,142 @@
+import { useState, useEffect, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { formatAge, type OutboxItem, type OutboxStatus } from "../utils/outbox";
+
+interface Props {
+  open: boolean;
+  onClose: () => void;
+}
+
+const KIND_LABEL: Record<string, string> = {
+  delivery: "📤 อัปโหลดรูป",
+  paperLevel: "📄 ตั้งค่ากระดาษ",
+  paperReduce: "📄 ลดกระดาษ",
+  deviceAlert: "⚠️ แจ้งเตือนอุปกรณ์",
+  deviceReconnected: "🔌 อุปกรณ์กลับมา",
+  deviceStatusReport: "🩺 รายงานสถานะอุปกรณ์",
+};
+
+export default function OutboxModal({ open, onClose }: Props) {
+  const [status, setStatus] = useState<OutboxStatus | null>(null);
+  const [items, setItems] = useState<OutboxItem[]>([]);
+  const [message, setMessage] = useState("");
+
+  const load = useCallback(async () => {
+    try {
+      setStatus(await invoke<OutboxStatus>("get_outbox_status"));
+      setItems(await invoke<OutboxItem[]>("get_outbox_items"));
+    } catch (err) {
+      console.error("[OutboxModal] get_outbox_items:", err);
+      setStatus(null);
+      setItems([]);
+    }
+  }, []);
+
+  // Load and reload on every change while open
+  useEffect(() => {
+    if (!open) return;
+    setMessage("");
+    load();
+    const unlisten = listen<OutboxStatus>("outbox-status", () => load());
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [open, load]);
+
+  const handleDrain = async () => {
+    try {
+      await invoke("drain_outbox");
+      setMessage("🔄 กำลังส่ง...");
+    } catch (err) {
+      setMessage(`❌ ${err}`);
+    }
+  };
+
+  const handleRetry = async (item: OutboxItem) => {
+    try {
+      await invoke("retry_outbox_item", { itemId: item.id });
+    } catch (err) {
+      setMessage(`❌ ${err}`);
+    }
+  };
+
+  const handleDiscard = async (item: OutboxItem) => {
+    try {
+      await invoke("discard_outbox_item", { itemId: item.id });
+    } catch (err) {
+      setMessage(`❌ ${err}`);
+    }
+  };
+
+  if (!open) return null;
+
+  return (
+    <div className="config-modal-overlay" onClick={onClose}>
+      <div className="config-modal" onClick={(e) => e.stopPropagation()}>
+        <div className="config-modal-header">
+          <h3>📮 Outbox</h3>
+          <button className="config-close-btn" onClick={onClose}>✕</button>
+        </div>
+
+        <div className="config-body">
+          {status && (
+            <div style={{ fontSize: 13, marginBottom: 12, textAlign: "left" }}>
+              รอส่ง {status.depth} • ไม่สำเร็จ {status.failed}
+              {status.oldestAgeSecs !== null && ` • เก่าสุด ${formatAge(status.oldestAgeSecs)}`}
+              {status.nextAttemptSecs !== null && status.depth > 0 && (
+                <div style={{ fontSize: 11, opacity: 0.6, marginTop: 2 }}>
+                  ลองใหม่ใน {formatAge(status.nextAttemptSecs)}
+                  {status.lastError && ` • ${status.lastError}`}
+                </div>
+              )}
+            </div>
+          )}
+
+          {items.length === 0 ? (
+            <div className="config-empty">ส่งครบแล้ว / Nothing waiting</div>
+          ) : (
+            <div className="config-device-list">
+              {items.map((item) => (
+                <div key={item.id} className="config-device-item">
+                  <div style={{ flex: 1, textAlign: "left" }}>
+                    <span className="config-device-name">
+                      {item.status === "failed" ? "❌ " : ""}
+                      {KIND_LABEL[item.request.kind] ?? item.request.kind}
+                    </span>
+                    <div style={{ fontSize: 11, opacity: 0.6, marginTop: 2 }}>
+                      {new Date(item.createdAt).toLocaleString()} • {item.attempts} attempts
+                    </div>
+                    {item.error && (
+                      <div style={{ fontSize: 11, color: "#ff6b6b", marginTop: 2 }}>{item.error}</div>
+                    )}
+                  </div>
+                  {item.status === "failed" && (
+                    <button className="config-save-btn" onClick={() => handleRetry(item)}>
+                      ส่งใหม่
+                    </button>
+                  )}
+                  <button className="config-cancel-btn" onClick={() => handleDiscard(item)}>
+                    ทิ้ง
+                  </button>
+                </div>
+              ))}
+            </div>
+          )}
+
+          <button className="config-refresh-btn" onClick={handleDrain}>
+            📤 ส่งตอนนี้ / Send now
+          </button>
+        </div>
+
+        {message && <div className="config-saved-message">{message}</div>}
+
+        <div className="config-footer">
+          <button className="config-cancel-btn" onClick={onClose}>
+            ปิด / Close
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
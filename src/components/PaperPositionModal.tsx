@@ -8,6 +8,27 @@ interface PaperConfig {
   horizontal: number;
 }
 
+/** Paper profile from Rust (`get_paper_profiles`) */
+interface PaperProfile {
+  id: string;
+  name: string;
+  widthMm: number;
+  heightMm: number;
+  dpi: number;
+  landscape: boolean;
+  cut: { columns: number; rows: number; driverCut: boolean };
+}
+
+/** Imposed sheet from Rust (`preview_imposition`) */
+interface ImpositionPreview {
+  paperProfile: string;
+  source: "image" | "lastPrint" | "testChart";
+  image: string;
+  sheetWidth: number;
+  sheetHeight: number;
+  copies: number;
+}
+
 interface Props {
   open: boolean;
   onClose: () => void;
@@ -23,12 +44,14 @@ export default function PaperPositionModal({ open, onClose }: Props) {
   const [tab, setTab] = useState<"portrait" | "landscape">("portrait");
   const [portraitConfig, setPortraitConfig] = useState<PaperConfig>({ ...DEFAULT_CONFIG });
   const [landscapeConfig, setLandscapeConfig] = useState<PaperConfig>({ ...DEFAULT_CONFIG });
-  // Paper size selection per orientation
-  const [portraitPaperSize, setPortraitPaperSize] = useState<"2x6" | "4x6">("4x6");
-  const [landscapePaperSize, setLandscapePaperSize] = useState<"6x2" | "6x4">("6x4");
+  // Paper profile selection per orientation
+  const [profiles, setProfiles] = useState<PaperProfile[]>([]);
+  const [portraitPaperSize, setPortraitPaperSize] = useState<string>("4x6");
+  const [landscapePaperSize, setLandscapePaperSize] = useState<string>("6x4");
   const [saving, setSaving] = useState(false);
   const [printing, setPrinting] = useState(false);
   const [savedMessage, setSavedMessage] = useState("");
+  const [sheetPreview, setSheetPreview] = useState<ImpositionPreview | null>(null);
 
   const currentConfig = tab === "portrait" ? portraitConfig : landscapeConfig;
   const setCurrentConfig = tab === "portrait" ? setPortraitConfig : setLandscapeConfig;
@@ -45,15 +68,57 @@ export default function PaperPositionModal({ open, onClose }: Props) {
       if (savedLandscape) setLandscapeConfig(JSON.parse(savedLandscape));
       else setLandscapeConfig({ ...DEFAULT_CONFIG });
 
-      setPortraitPaperSize((localStorage.getItem("paperSizePortrait") as "2x6" | "4x6") || "4x6");
-      setLandscapePaperSize((localStorage.getItem("paperSizeLandscape") as "6x2" | "6x4") || "6x4");
+      setPortraitPaperSize(localStorage.getItem("paperSizePortrait") || "4x6");
+      setLandscapePaperSize(localStorage.getItem("paperSizeLandscape") || "6x4");
     } catch {
       setPortraitConfig({ ...DEFAULT_CONFIG });
       setLandscapeConfig({ ...DEFAULT_CONFIG });
     }
     setSavedMessage("");
+    invoke<PaperProfile[]>("get_paper_profiles")
+      .then(setProfiles)
+      .catch((err) => console.error("[PaperPositionModal] get_paper_profiles:", err));
   }, [open]);
 
+  const tabProfiles = profiles.filter((p) => p.landscape === (tab === "landscape"));
+  const currentPaperSize = tab === "portrait" ? portraitPaperSize : landscapePaperSize;
+  const setCurrentPaperSize = tab === "portrait" ? setPortraitPaperSize : setLandscapePaperSize;
+  const currentProfile = profiles.find((p) => p.id === currentPaperSize);
+  // Preview box for one print, longest edge 180px
+  const previewMm = currentProfile
+    ? { w: currentProfile.widthMm, h: currentProfile.heightMm }
+    : tab === "portrait" ? { w: 4, h: 6 } : { w: 6, h: 4 };
+  const previewScale = 180 / Math.max(previewMm.w, previewMm.h);
+  const previewSize = {
+    width: Math.round(previewMm.w * previewScale),
+    height: Math.round(previewMm.h * previewScale),
+  };
+
+  // Whole sheet as it will be printed: the last composed print (or the test
+  // chart) with the current scale/offsets, N-up copies, gutters, crop marks
+  useEffect(() => {
+    if (!open || !currentProfile) return;
+    let cancelled = false;
+    const timer = setTimeout(() => {
+      invoke<ImpositionPreview>("preview_imposition", {
+        paperProfile: currentProfile.id,
+        maxSize: 360,
+        lastPrint: true,
+        scale: currentConfig.scale,
+        verticalOffset: currentConfig.vertical,
+        horizontalOffset: currentConfig.horizontal,
+      })
+        .then((preview) => {
+          if (!cancelled) setSheetPreview(preview);
+        })
+        .catch((err) => console.error("[PaperPositionModal] preview_imposition:", err));
+    }, 200);
+    return () => {
+      cancelled = true;
+      clearTimeout(timer);
+    };
+  }, [open, currentProfile?.id, currentConfig.scale, currentConfig.vertical, currentConfig.horizontal]);
+
   const handleSave = async () => {
     setSaving(true);
     try {
@@ -63,12 +128,14 @@ export default function PaperPositionModal({ open, onClose }: Props) {
         scale: portraitConfig.scale,
         vertical: portraitConfig.vertical,
         horizontal: portraitConfig.horizontal,
+        paperProfile: portraitPaperSize,
       });
       await invoke("set_paper_config", {
         orientation: "landscape",
         scale: landscapeConfig.scale,
         vertical: landscapeConfig.vertical,
         horizontal: landscapeConfig.horizontal,
+        paperProfile: landscapePaperSize,
       });
 
       // Save to localStorage for persistence
@@ -93,10 +160,10 @@ export default function PaperPositionModal({ open, onClose }: Props) {
       return;
     }
 
-    const frameType = tab === "portrait" ? portraitPaperSize : landscapePaperSize;
+    const paperProfile = currentPaperSize;
 
     setPrinting(true);
-    setSavedMessage(`🖨️ กำลัง Test Print (${frameType})...`);
+    setSavedMessage(`🖨️ กำลัง Test Print (${paperProfile})...`);
     
     // Set printing state BEFORE printing to prevent device check notifications
     setPrintingState(true, 45000); // 45 second timeout
@@ -111,14 +178,9 @@ export default function PaperPositionModal({ open, onClose }: Props) {
         scale: currentConfig.scale,
         verticalOffset: currentConfig.vertical,
         horizontalOffset: currentConfig.horizontal,
-        frameType,
+        paperProfile,
       });
-      // ลด paper level ที่หลังบ้าน 1 แผ่น (เส้นเดียวกับ bonio-booth: POST paper-level/reduce)
-      try {
-        await invoke("reduce_paper_level", { copies: 1 });
-      } catch (e) {
-        console.warn("[PaperPositionModal] reduce_paper_level failed (non-blocking):", e);
-      }
+      // The paper level is reduced by Rust once the job completes (print-completed)
       setSavedMessage("✅ Test Print สำเร็จ!");
     } catch (err: any) {
       setSavedMessage(`❌ Print Error: ${err?.toString()?.slice(0, 60)}`);
@@ -129,7 +191,7 @@ export default function PaperPositionModal({ open, onClose }: Props) {
       setPrintingState(false);
       setTimeout(() => setSavedMessage(""), 4000);
     }
-  }, [currentConfig, tab, portraitPaperSize, landscapePaperSize]);
+  }, [currentConfig, currentPaperSize]);
 
   const handleReset = () => {
     setCurrentConfig({ ...DEFAULT_CONFIG });
@@ -184,63 +246,28 @@ export default function PaperPositionModal({ open, onClose }: Props) {
           </button>
         </div>
 
-        {/* Paper size selector */}
-        <div style={{ display: "flex", gap: 8, padding: "10px 20px 0" }}>
-          {tab === "portrait" ? (
-            <>
-              <button
-                onClick={() => setPortraitPaperSize("4x6")}
-                style={{
-                  flex: 1, padding: "8px 0", borderRadius: 8, border: "2px solid",
-                  borderColor: portraitPaperSize === "4x6" ? "#51cf66" : "#555",
-                  background: portraitPaperSize === "4x6" ? "rgba(81,207,102,0.15)" : "transparent",
-                  color: portraitPaperSize === "4x6" ? "#51cf66" : "#aaa",
-                  fontWeight: 600, cursor: "pointer", fontSize: 13,
-                }}
-              >
-                4x6 (ไม่ตัด)
-              </button>
-              <button
-                onClick={() => setPortraitPaperSize("2x6")}
-                style={{
-                  flex: 1, padding: "8px 0", borderRadius: 8, border: "2px solid",
-                  borderColor: portraitPaperSize === "2x6" ? "#ff6b6b" : "#555",
-                  background: portraitPaperSize === "2x6" ? "rgba(255,107,107,0.15)" : "transparent",
-                  color: portraitPaperSize === "2x6" ? "#ff6b6b" : "#aaa",
-                  fontWeight: 600, cursor: "pointer", fontSize: 13,
-                }}
-              >
-                ✂️ 2x6 (ตัดกระดาษ)
-              </button>
-            </>
-          ) : (
-            <>
-              <button
-                onClick={() => setLandscapePaperSize("6x4")}
-                style={{
-                  flex: 1, padding: "8px 0", borderRadius: 8, border: "2px solid",
-                  borderColor: landscapePaperSize === "6x4" ? "#51cf66" : "#555",
-                  background: landscapePaperSize === "6x4" ? "rgba(81,207,102,0.15)" : "transparent",
-                  color: landscapePaperSize === "6x4" ? "#51cf66" : "#aaa",
-                  fontWeight: 600, cursor: "pointer", fontSize: 13,
-                }}
-              >
-                6x4 (ไม่ตัด)
-              </button>
+        {/* Paper size selector — one button per profile of this orientation */}
+        <div style={{ display: "flex", gap: 8, padding: "10px 20px 0", flexWrap: "wrap" }}>
+          {tabProfiles.map((profile) => {
+            const selected = currentPaperSize === profile.id;
+            const isCut = profile.cut.columns * profile.cut.rows > 1;
+            const color = isCut ? "#ff6b6b" : "#51cf66";
+            return (
               <button
-                onClick={() => setLandscapePaperSize("6x2")}
+                key={profile.id}
+                onClick={() => setCurrentPaperSize(profile.id)}
                 style={{
                   flex: 1, padding: "8px 0", borderRadius: 8, border: "2px solid",
-                  borderColor: landscapePaperSize === "6x2" ? "#ff6b6b" : "#555",
-                  background: landscapePaperSize === "6x2" ? "rgba(255,107,107,0.15)" : "transparent",
-                  color: landscapePaperSize === "6x2" ? "#ff6b6b" : "#aaa",
+                  borderColor: selected ? color : "#555",
+                  background: selected ? `${color}26` : "transparent",
+                  color: selected ? color : "#aaa",
                   fontWeight: 600, cursor: "pointer", fontSize: 13,
                 }}
               >
-                ✂️ 6x2 (ตัดกระดาษ)
+                {isCut ? `✂️ ${profile.name} (ตัดกระดาษ)` : `${profile.name} (ไม่ตัด)`}
               </button>
-            </>
-          )}
+            );
+          })}
         </div>
 
         <div className="config-body">
@@ -376,12 +403,8 @@ export default function PaperPositionModal({ open, onClose }: Props) {
             <div
               className="config-paper"
               style={{
-                width: tab === "portrait"
-                  ? (portraitPaperSize === "2x6" ? 60 : 120)
-                  : (landscapePaperSize === "6x2" ? 180 : 180),
-                height: tab === "portrait"
-                  ? 180
-                  : (landscapePaperSize === "6x2" ? 60 : 120),
+                width: previewSize.width,
+                height: previewSize.height,
               }}
             >
               <div
@@ -396,8 +419,17 @@ export default function PaperPositionModal({ open, onClose }: Props) {
               </div>
             </div>
             <p style={{ fontSize: 11, opacity: 0.5, marginTop: 4 }}>
-              ตัวอย่างตำแหน่งรูปบนกระดาษ ({tab === "portrait" ? portraitPaperSize : landscapePaperSize})
+              ตัวอย่างตำแหน่งรูปบนกระดาษ ({currentProfile?.name ?? currentPaperSize})
             </p>
+            {sheetPreview && sheetPreview.paperProfile === currentPaperSize && (
+              <>
+                <img className="config-sheet-preview" src={sheetPreview.image} alt="sheet preview" />
+                <p style={{ fontSize: 11, opacity: 0.5, marginTop: 4 }}>
+                  ตัวอย่างกระดาษทั้งแผ่น ({sheetPreview.copies} รูป/แผ่น,{" "}
+                  {sheetPreview.source === "lastPrint" ? "รูปที่ปริ้นล่าสุด" : "Test Print"})
+                </p>
+              </>
+            )}
           </div>
 
           {/* Action row */}
@@ -0,0 +1,128 @@
+import { useState, useEffect, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import type { PrintJob } from "../utils/printQueue";
+
+interface Props {
+  open: boolean;
+  onClose: () => void;
+}
+
+const STATUS_LABEL: Record<PrintJob["status"], string> = {
+  queued: "🕒 รอพิมพ์",
+  spooling: "⏳ กำลังส่งงานพิมพ์",
+  printing: "🖨️ กำลังพิมพ์",
+  printed: "✅ พิมพ์แล้ว",
+  failed: "❌ ไม่สำเร็จ",
+  cancelled: "🚫 ยกเลิก",
+};
+
+export default function PrintQueueModal({ open, onClose }: Props) {
+  const [jobs, setJobs] = useState<PrintJob[]>([]);
+  const [message, setMessage] = useState("");
+
+  const loadJobs = useCallback(async () => {
+    try {
+      setJobs(await invoke<PrintJob[]>("get_print_jobs"));
+    } catch (err) {
+      console.error("[PrintQueueModal] get_print_jobs:", err);
+      setJobs([]);
+    }
+  }, []);
+
+  // Load history and keep it live while open
+  useEffect(() => {
+    if (!open) return;
+    setMessage("");
+    loadJobs();
+    const unlisten = listen<PrintJob>("print-job-updated", (event) => {
+      const job = event.payload;
+      setJobs((prev) =>
+        prev.some((j) => j.id === job.id)
+          ? prev.map((j) => (j.id === job.id ? job : j))
+          : [job, ...prev],
+      );
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [open, loadJobs]);
+
+  const handleReprint = async (job: PrintJob) => {
+    try {
+      await invoke("reprint_print_job", { jobId: job.id });
+      setMessage("✅ ส่งพิมพ์ใหม่แล้ว");
+    } catch (err) {
+      setMessage(`❌ ${err}`);
+    }
+  };
+
+  const handleCancel = async (job: PrintJob) => {
+    try {
+      await invoke("cancel_print_job", { jobId: job.id });
+    } catch (err) {
+      setMessage(`❌ ${err}`);
+    }
+  };
+
+  if (!open) return null;
+
+  return (
+    <div className="config-modal-overlay" onClick={onClose}>
+      <div className="config-modal" onClick={(e) => e.stopPropagation()}>
+        <div className="config-modal-header">
+          <h3>🧾 Print Queue</h3>
+          <button className="config-close-btn" onClick={onClose}>✕</button>
+        </div>
+
+        <div className="config-body">
+          {jobs.length === 0 ? (
+            <div className="config-empty">ยังไม่มีงานพิมพ์ / No print jobs</div>
+          ) : (
+            <div className="config-device-list">
+              {jobs.map((job) => (
+                <div key={job.id} className="config-device-item">
+                  <div style={{ flex: 1, textAlign: "left" }}>
+                    <span className="config-device-name">
+                      {STATUS_LABEL[job.status]} • {job.paperProfile ?? "auto"} × {job.copies}
+                    </span>
+                    <div style={{ fontSize: 11, opacity: 0.6, marginTop: 2 }}>
+                      {new Date(job.createdAt).toLocaleString()} • {job.printerName} • {job.copiesPrinted}/
+                      {job.copies}
+                      {job.reprintOf && " • reprint"}
+                    </div>
+                    {job.error && (
+                      <div style={{ fontSize: 11, color: "#ff6b6b", marginTop: 2 }}>{job.error}</div>
+                    )}
+                  </div>
+                  {job.status === "queued" && (
+                    <button className="config-cancel-btn" onClick={() => handleCancel(job)}>
+                      ยกเลิก
+                    </button>
+                  )}
+                  {!["queued", "spooling", "printing"].includes(job.status) && (
+                    <button className="config-save-btn" onClick={() => handleReprint(job)}>
+                      🖨️ พิมพ์ใหม่
+                    </button>
+                  )}
+                </div>
+              ))}
+            </div>
+          )}
+
+          <button className="config-refresh-btn" onClick={loadJobs}>
+            🔄 Refresh
+          </button>
+        </div>
+
+        {message && <div className="config-saved-message">{message}</div>}
+
+        <div className="config-footer">
+          <button className="config-cancel-btn" onClick={onClose}>
+            ปิด / Close
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -5,8 +5,31 @@ interface PrinterInfo {
   name: string;
   status: string;
   is_online: boolean;
+  state_reasons: string[];
+  markers: { name: string; color: string; kind: string; level: number | null }[];
+  backend: string;
 }
 
+/** Media counter from Rust (`media.rs`) */
+interface MediaStatus {
+  printerName: string;
+  level: "empty" | "critical" | "low" | "ok" | "unknown";
+  media: string | null;
+  capacity: number;
+  remaining: number | null;
+  warningAt: number;
+  criticalAt: number;
+  backendLevel: number | null;
+}
+
+const MEDIA_LEVEL_COLOR: Record<MediaStatus["level"], string> = {
+  empty: "#ff6b6b",
+  critical: "#ff6b6b",
+  low: "#fcc419",
+  ok: "#51cf66",
+  unknown: "#888",
+};
+
 interface Props {
   open: boolean;
   onClose: () => void;
@@ -18,6 +41,11 @@ export default function PrinterConfigModal({ open, onClose }: Props) {
   const [loading, setLoading] = useState(false);
   const [saving, setSaving] = useState(false);
   const [savedMessage, setSavedMessage] = useState("");
+  const [media, setMedia] = useState<MediaStatus | null>(null);
+  const [rollMedia, setRollMedia] = useState("4x6");
+  const [rollCapacity, setRollCapacity] = useState(400);
+  const [warningAt, setWarningAt] = useState(50);
+  const [criticalAt, setCriticalAt] = useState(10);
 
   // Load current config
   useEffect(() => {
@@ -38,6 +66,57 @@ export default function PrinterConfigModal({ open, onClose }: Props) {
     setLoading(false);
   }, []);
 
+  // Media counter of the highlighted printer
+  useEffect(() => {
+    if (!open || !selectedPrinter) {
+      setMedia(null);
+      return;
+    }
+    invoke<MediaStatus[]>("get_media_status", { printerName: selectedPrinter })
+      .then(([status]) => {
+        setMedia(status ?? null);
+        if (status) {
+          setWarningAt(status.warningAt);
+          setCriticalAt(status.criticalAt);
+          if (status.media) setRollMedia(status.media);
+          if (status.capacity > 0) setRollCapacity(status.capacity);
+        }
+      })
+      .catch(() => setMedia(null));
+  }, [open, selectedPrinter]);
+
+  const handleLoadRoll = async () => {
+    try {
+      setMedia(
+        await invoke<MediaStatus>("load_media_roll", {
+          printerName: selectedPrinter,
+          media: rollMedia,
+          capacity: rollCapacity,
+        }),
+      );
+      setSavedMessage("✅ เปลี่ยนม้วนกระดาษแล้ว / Roll loaded");
+    } catch (err) {
+      setSavedMessage(`❌ ${err}`);
+    }
+    setTimeout(() => setSavedMessage(""), 2000);
+  };
+
+  const handleSaveThresholds = async () => {
+    try {
+      setMedia(
+        await invoke<MediaStatus>("set_media_thresholds", {
+          printerName: selectedPrinter,
+          warningAt,
+          criticalAt,
+        }),
+      );
+      setSavedMessage("✅ บันทึกสำเร็จ!");
+    } catch (err) {
+      setSavedMessage(`❌ ${err}`);
+    }
+    setTimeout(() => setSavedMessage(""), 2000);
+  };
+
   const handleSave = async () => {
     setSaving(true);
     try {
@@ -104,8 +183,17 @@ export default function PrinterConfigModal({ open, onClose }: Props) {
                         <span style={{ color: "#51cf66" }}>ออนไลน์</span>
                       ) : (
                         <span style={{ color: "#ff6b6b" }}>ออฟไลน์</span>
-                      )}
+                      )}{" "}
+                      • {printer.backend}
                     </div>
+                    {(printer.state_reasons.length > 0 || printer.markers.length > 0) && (
+                      <div style={{ fontSize: 11, opacity: 0.6, marginTop: 2 }}>
+                        {[
+                          ...printer.state_reasons,
+                          ...printer.markers.map((m) => `${m.name} ${m.level ?? "?"}%`),
+                        ].join(" • ")}
+                      </div>
+                    )}
                   </div>
                   {selectedPrinter === printer.name && (
                     <span className="config-device-check">✓</span>
@@ -118,6 +206,64 @@ export default function PrinterConfigModal({ open, onClose }: Props) {
           <button className="config-refresh-btn" onClick={loadPrinters}>
             🔄 Refresh
           </button>
+
+          {selectedPrinter && (
+            <>
+              <div className="config-label config-rotation-label">กระดาษ / Media</div>
+              <div className="config-info-box">
+                {media?.remaining != null ? (
+                  <>
+                    <span style={{ color: MEDIA_LEVEL_COLOR[media.level] }}>
+                      {media.remaining}/{media.capacity} ({media.media})
+                    </span>
+                    {media.backendLevel != null && ` • backend ${media.backendLevel}`}
+                  </>
+                ) : (
+                  "ยังไม่ได้นับกระดาษ / Not counted"
+                )}
+              </div>
+              <div className="config-actions-row">
+                <input
+                  value={rollMedia}
+                  onChange={(e) => setRollMedia(e.target.value)}
+                  style={{ width: 60 }}
+                  title="Sheet size"
+                />
+                <input
+                  type="number"
+                  min={1}
+                  value={rollCapacity}
+                  onChange={(e) => setRollCapacity(Number(e.target.value))}
+                  style={{ width: 70 }}
+                  title="Prints per roll"
+                />
+                <button className="config-test-print-btn" onClick={handleLoadRoll}>
+                  🧻 ม้วนใหม่ / New roll
+                </button>
+              </div>
+              <div className="config-actions-row">
+                <input
+                  type="number"
+                  min={0}
+                  value={warningAt}
+                  onChange={(e) => setWarningAt(Number(e.target.value))}
+                  style={{ width: 60 }}
+                  title="Low media warning at"
+                />
+                <input
+                  type="number"
+                  min={0}
+                  value={criticalAt}
+                  onChange={(e) => setCriticalAt(Number(e.target.value))}
+                  style={{ width: 60 }}
+                  title="Critical at"
+                />
+                <button className="config-reset-btn" onClick={handleSaveThresholds}>
+                  ⚠️ แจ้งเตือน / Thresholds
+                </button>
+              </div>
+            </>
+          )}
         </div>
 
         {savedMessage && (
@@ -1,65 +1,84 @@
 /**
- * Static filter configuration
- * Each filter maps to a .cube LUT file in the /filters directory
- * Using config-based approach avoids filesystem scanning and
- * issues with special characters in filenames (e.g., B&W, Orange & Teal)
+ * Filter catalogue
+ * The list comes from the Rust side (`get_available_filters`), which scans
+ * the /filters directory and merges it with the optional `filters.json`
+ * manifest (display names, order, default intensity, interpolation).
  */
 
+import { invoke } from "@tauri-apps/api/core";
+
 export interface FilterConfig {
   id: string;
   name: string;
+  nameTh?: string;
   lutFile: string; // filename in /filters directory (e.g., 'B&W.cube')
+  filePath: string; // absolute path of the LUT, "" for no filter
   type: "lut" | "none";
+  intensity?: number; // 0–100 blend with the original, defaults to 100
+  interpolation?: "trilinear" | "tetrahedral"; // defaults to trilinear
+  swatchPath?: string; // absolute path of the preview swatch
+}
+
+/** Mirrors `FilterInfo` in filters.rs */
+interface FilterInfo {
+  id: string;
+  name: string;
+  name_th: string | null;
+  file_path: string;
+  kind: string;
+  order: number;
+  default_intensity: number;
+  interpolation: "trilinear" | "tetrahedral" | null;
+  swatch_path: string | null;
+  enabled: boolean;
+}
+
+interface AvailableFilters {
+  filters: FilterInfo[];
+  errors: { file: string; error: string }[];
 }
 
-export const FILTERS: FilterConfig[] = [
-  { id: "none", name: "No Filter", lutFile: "", type: "none" },
-  {
-    id: "matte-brown-mono",
-    name: "Matte Brown",
-    lutFile: "Matte_Brown_Mono.cube",
-    type: "lut",
-  },
-  {
-    id: "sepia-brown",
-    name: "Sepia Brown",
-    lutFile: "Sepia_Brown.cube",
-    type: "lut",
-  },
-  {
-    id: "timelab-1",
-    name: "Classic",
-    lutFile: "Timelab 1.cube",
-    type: "lut",
-  },
-  {
-    id: "timelab-2",
-    name: "Cool",
-    lutFile: "Timelab 2.cube",
-    type: "lut",
-  },
-  {
-    id: "warm-light",
-    name: "Warm Light",
-    lutFile: "Warm Light.cube",
-    type: "lut",
-  },
-  {
-    id: "bw",
-    name: "Black & White",
-    lutFile: "B&W.cube",
-    type: "lut",
-  },
-  {
-    id: "evolution",
-    name: "Evolution",
-    lutFile: "Evolution.cube",
-    type: "lut",
-  },
-  {
-    id: "orange-teal",
-    name: "Orange & Teal",
-    lutFile: "Orange & Teal.cube",
-    type: "lut",
-  },
-];
+export const NO_FILTER: FilterConfig = {
+  id: "none",
+  name: "No Filter",
+  lutFile: "",
+  filePath: "",
+  type: "none",
+};
+
+const fileName = (path: string) => path.split(/[\\/]/).pop() || "";
+
+/**
+ * Load the filter list for the filter page. Falls back to "No Filter" only
+ * when the filters folder can't be read.
+ */
+export async function loadFilters(): Promise<FilterConfig[]> {
+  try {
+    const filtersDir = await invoke<string>("get_app_dir");
+    const { filters, errors } = await invoke<AvailableFilters>(
+      "get_available_filters",
+      { filtersDir },
+    );
+    for (const err of errors) {
+      console.warn(`[filters] ${err.file}: ${err.error}`);
+    }
+    return filters.map((info) =>
+      info.kind === "none"
+        ? { ...NO_FILTER, name: info.name, nameTh: info.name_th ?? undefined }
+        : {
+            id: info.id,
+            name: info.name,
+            nameTh: info.name_th ?? undefined,
+            lutFile: fileName(info.file_path),
+            filePath: info.file_path,
+            type: "lut",
+            intensity: info.default_intensity,
+            interpolation: info.interpolation ?? undefined,
+            swatchPath: info.swatch_path ?? undefined,
+          },
+    );
+  } catch (err) {
+    console.error("[filters] Failed to load filters:", err);
+    return [NO_FILTER];
+  }
+}
@@ -1,31 +1,46 @@
 import { useState, useRef, useCallback, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import type { ImageFile } from "../App";
 
 export interface CanonState {
   initialized: boolean;
   connected: boolean;
   liveViewActive: boolean;
-  liveViewFrame: string; // base64 data URL
+  liveViewFrame: string; // MJPEG stream URL (use as <img src>)
   error: string;
 }
 
 /**
  * Hook to manage Canon DSLR camera lifecycle via Tauri commands.
  *
- * Lifecycle: initialize → connect → openSession → startLiveView → poll frames
- * Capture:   takePicture (stops LV internally, returns base64, restarts LV)
+ * Lifecycle: initialize → connect → openSession → startLiveView → MJPEG stream
+ * Capture:   takePicture (stops LV internally, returns the session file, restarts LV)
  * Cleanup:   stopLiveView → closeSession → terminate
  *
+ * Live view frames are grabbed by a Rust-side pump and served as an MJPEG
+ * stream on localhost (see live_view.rs) — no per-frame IPC or base64.
+ *
  * IMPORTANT — EDSDK is single-threaded COM:
  * ALL EDSDK FFI calls MUST be serialized (never two calls on different threads
  * at the same time).  We achieve this by:
- *   • Having only ONE polling interval active at a time (LV frame polling).
- *     The Rust side of canon_get_live_view_frame also pumps EdsGetEvent().
- *   • Stopping all polling BEFORE any other EDSDK invoke (start/stop LV,
- *     take picture, etc.) and restarting AFTER.
+ *   • Having only ONE frame grabber (the Rust pump).  Each grab also pumps
+ *     EdsGetEvent().
+ *   • Pausing the pump BEFORE any other EDSDK invoke (start/stop LV,
+ *     take picture, etc.) and resuming AFTER.  The pause command returns only
+ *     once any in-flight grab has finished.
  *   • No separate event-polling interval — events are processed inside the
  *     LV frame grab, and during capture the Rust event loop handles them.
  */
+/** Mirrors `CaptureResult` in canon.rs — the photo is already in the session folder */
+interface CaptureResult {
+  success: boolean;
+  error?: string | null;
+  file?: ImageFile | null;
+}
+
+/** Target live view frame rate for the Rust pump */
+const LIVE_VIEW_FPS = 30;
+
 export function useCanon() {
   const [state, setState] = useState<CanonState>({
     initialized: false,
@@ -35,57 +50,39 @@ export function useCanon() {
     error: "",
   });
 
-  const liveViewIntervalRef = useRef<ReturnType<typeof setInterval> | null>(null);
-  const latestFrameRef = useRef<string>("");
+  const streamActiveRef = useRef(false);
   const isCleanedUpRef = useRef(false);
 
   // Capture guards
   const isCapturingRef = useRef(false);
   const captureNumberRef = useRef(0);
 
-  // Frame recording for video/boomerang
-  const isRecordingRef = useRef(false);
-  const recordedFramesRef = useRef<string[]>([]);
-  const recordedTimestampsRef = useRef<number[]>([]);
+  // ----- Helper: start/pause the Rust LV pump -----
+  // The pump is the ONLY frame grabber.  Each grab also calls EdsGetEvent()
+  // so SDK events are processed automatically.
 
-  // ----- Helper: start/stop LV frame polling -----
-  // This is the ONLY polling interval.  The Rust function also calls
-  // EdsGetEvent() so SDK events are processed automatically.
-
-  const startLiveViewPolling = useCallback(() => {
-    if (liveViewIntervalRef.current) {
-      clearInterval(liveViewIntervalRef.current);
+  const startLiveViewPolling = useCallback(async () => {
+    if (isCleanedUpRef.current) return;
+    try {
+      const info = await invoke<{ url: string; target_fps: number }>(
+        "live_view_stream_start",
+        { fps: LIVE_VIEW_FPS }
+      );
+      streamActiveRef.current = true;
+      setState((s) =>
+        s.liveViewFrame === info.url ? s : { ...s, liveViewFrame: info.url }
+      );
+    } catch (err) {
+      console.error("[useCanon] Failed to start live view stream:", err);
     }
-
-    liveViewIntervalRef.current = setInterval(async () => {
-      if (isCleanedUpRef.current || isCapturingRef.current) return;
-      try {
-        const result = await invoke<{ data: string } | null>(
-          "canon_get_live_view_frame"
-        );
-        if (result && result.data) {
-          const dataUrl = result.data.startsWith("data:")
-            ? result.data
-            : `data:image/jpeg;base64,${result.data}`;
-          latestFrameRef.current = dataUrl;
-          setState((s) => ({ ...s, liveViewFrame: dataUrl }));
-
-          // If recording, accumulate frames
-          if (isRecordingRef.current) {
-            recordedFramesRef.current.push(dataUrl);
-            recordedTimestampsRef.current.push(Date.now());
-          }
-        }
-      } catch {
-        // frame fetch failed — skip (normal during transitions)
-      }
-    }, 33); // ~30fps
   }, []);
 
-  const stopLiveViewPolling = useCallback(() => {
-    if (liveViewIntervalRef.current) {
-      clearInterval(liveViewIntervalRef.current);
-      liveViewIntervalRef.current = null;
+  const stopLiveViewPolling = useCallback(async () => {
+    if (!streamActiveRef.current) return;
+    try {
+      await invoke("live_view_stream_pause");
+    } catch {
+      // stream not running — nothing to pause
     }
   }, []);
 
@@ -132,7 +129,7 @@ export function useCanon() {
 
   // Stop live view
   const stopLiveView = useCallback(async () => {
-    stopLiveViewPolling();
+    await stopLiveViewPolling();
     try {
       await invoke("canon_stop_live_view");
     } catch {
@@ -142,7 +139,7 @@ export function useCanon() {
   }, [stopLiveViewPolling]);
 
   /**
-   * Take a picture — returns base64 JPEG data URL.
+   * Take a picture — returns the photo written to the session folder (null on failure).
    *
    * Flow:
    * 1. Guard: prevent concurrent captures
@@ -151,11 +148,11 @@ export function useCanon() {
    * 4. Send capture command (Rust side calls EdsGetEvent in its own loop)
    * 5. Restart live view + polling
    */
-  const takePicture = useCallback(async (): Promise<string> => {
+  const takePicture = useCallback(async (): Promise<ImageFile | null> => {
     // 1. Prevent concurrent captures
     if (isCapturingRef.current) {
       console.warn("[useCanon] Capture already in progress, skipping");
-      return "";
+      return null;
     }
 
     captureNumberRef.current++;
@@ -165,8 +162,8 @@ export function useCanon() {
     console.log(`[useCanon] Starting capture #${captureNum}...`);
 
     try {
-      // 2. Stop ALL polling first — no EDSDK calls from JS after this point
-      stopLiveViewPolling();
+      // 2. Pause the LV pump first — no other EDSDK calls after this point
+      await stopLiveViewPolling();
 
       // 3. Stop camera live view
       try {
@@ -179,11 +176,7 @@ export function useCanon() {
 
       // 4. Take picture (Rust function blocks until image download completes,
       //    pumps EdsGetEvent internally)
-      const result = await invoke<{
-        success: boolean;
-        image_data?: string;
-        error?: string;
-      }>("canon_take_picture");
+      const result = await invoke<CaptureResult>("canon_take_picture");
 
       // 5. Restart live view + polling
       isCapturingRef.current = false;
@@ -196,16 +189,13 @@ export function useCanon() {
         startLiveViewPolling();
       }
 
-      if (result.success && result.image_data) {
-        const dataUrl = result.image_data.startsWith("data:")
-          ? result.image_data
-          : `data:image/jpeg;base64,${result.image_data}`;
+      if (result.success && result.file) {
         console.log(`[useCanon] Capture #${captureNum} success`);
-        return dataUrl;
+        return result.file;
       }
 
       console.error(`[useCanon] Capture #${captureNum} failed:`, result.error);
-      return "";
+      return null;
     } catch (err: any) {
       console.error(`[useCanon] Capture #${captureNum} error:`, err);
 
@@ -218,7 +208,7 @@ export function useCanon() {
         startLiveViewPolling();
       }
 
-      return "";
+      return null;
     }
   }, [stopLiveViewPolling, startLiveViewPolling]);
 
@@ -227,10 +217,10 @@ export function useCanon() {
    * Skips the redundant canon_stop_live_view invoke and 50ms stabilization delay,
    * saving ~150ms of latency between countdown-end and shutter fire.
    */
-  const takePictureQuick = useCallback(async (): Promise<string> => {
+  const takePictureQuick = useCallback(async (): Promise<ImageFile | null> => {
     if (isCapturingRef.current) {
       console.warn("[useCanon] Capture already in progress, skipping");
-      return "";
+      return null;
     }
 
     captureNumberRef.current++;
@@ -244,11 +234,7 @@ export function useCanon() {
       // LV already stopped in Rust by canon_stop_movie_record_fast — skip stop_live_view + 50ms
 
       // Take picture directly (Rust pumps EdsGetEvent internally)
-      const result = await invoke<{
-        success: boolean;
-        image_data?: string;
-        error?: string;
-      }>("canon_take_picture");
+      const result = await invoke<CaptureResult>("canon_take_picture");
 
       // Restart live view + polling
       isCapturingRef.current = false;
@@ -261,16 +247,13 @@ export function useCanon() {
         startLiveViewPolling();
       }
 
-      if (result.success && result.image_data) {
-        const dataUrl = result.image_data.startsWith("data:")
-          ? result.image_data
-          : `data:image/jpeg;base64,${result.image_data}`;
+      if (result.success && result.file) {
         console.log(`[useCanon] Quick capture #${captureNum} success`);
-        return dataUrl;
+        return result.file;
       }
 
       console.error(`[useCanon] Quick capture #${captureNum} failed:`, result.error);
-      return "";
+      return null;
     } catch (err: any) {
       console.error(`[useCanon] Quick capture #${captureNum} error:`, err);
       isCapturingRef.current = false;
@@ -280,20 +263,20 @@ export function useCanon() {
       if (!isCleanedUpRef.current) {
         startLiveViewPolling();
       }
-      return "";
+      return null;
     }
   }, [startLiveViewPolling]);
 
   /**
    * Take a photo WHILE the camera is still recording video.
    * Uses PressShutterButton to snap a still during movie mode, then stops recording.
-   * Returns the photo dataUrl; movie download is deferred to finalizeMovieDownload().
+   * Returns the session file of the photo; movie download is deferred to finalizeMovieDownload().
    * Falls back to stop-then-shoot internally in Rust if the camera doesn't support it.
    */
-  const takePhotoDuringRecording = useCallback(async (): Promise<string> => {
+  const takePhotoDuringRecording = useCallback(async (): Promise<ImageFile | null> => {
     if (isCapturingRef.current) {
       console.warn("[useCanon] Capture already in progress, skipping");
-      return "";
+      return null;
     }
 
     captureNumberRef.current++;
@@ -308,17 +291,13 @@ export function useCanon() {
       // command handles EDSDK event pumping internally; concurrent LV
       // frame fetches will just get stale/empty frames which is fine.
 
-      const result = await invoke<{
-        success: boolean;
-        image_data?: string;
-        error?: string;
-      }>("canon_take_photo_during_recording");
+      const result = await invoke<CaptureResult>("canon_take_photo_during_recording");
 
       isCapturingRef.current = false;
 
       // Rust already switched back to photo mode (MovieSelectSwOFF,
       // LV off, SaveTo=Host).  Just restart our LV polling.
-      stopLiveViewPolling(); // clear any stale timer
+      await stopLiveViewPolling(); // make sure the pump is paused
       try {
         await invoke("canon_start_live_view");
       } catch {
@@ -328,16 +307,13 @@ export function useCanon() {
         startLiveViewPolling();
       }
 
-      if (result.success && result.image_data) {
-        const dataUrl = result.image_data.startsWith("data:")
-          ? result.image_data
-          : `data:image/jpeg;base64,${result.image_data}`;
+      if (result.success && result.file) {
         console.log(`[useCanon] Photo-during-recording #${captureNum} success`);
-        return dataUrl;
+        return result.file;
       }
 
       console.error(`[useCanon] Photo-during-recording #${captureNum} failed:`, result.error);
-      return "";
+      return null;
     } catch (err: any) {
       console.error(`[useCanon] Photo-during-recording #${captureNum} error:`, err);
       isCapturingRef.current = false;
@@ -347,29 +323,40 @@ export function useCanon() {
       if (!isCleanedUpRef.current) {
         startLiveViewPolling();
       }
-      return "";
+      return null;
     }
   }, [startLiveViewPolling, stopLiveViewPolling]);
 
-  // Get the latest live view frame (instant, no async)
-  const getLatestFrame = useCallback((): string => {
-    return latestFrameRef.current;
+  // Sequence number of the latest pumped frame (0 = no frame yet).
+  // Increases by one for every new frame — used for fresh-frame detection.
+  const getFrameSequence = useCallback(async (): Promise<number> => {
+    try {
+      const stats = await invoke<{ last_frame_seq: number }>("live_view_stream_stats");
+      return stats.last_frame_seq;
+    } catch {
+      return 0;
+    }
   }, []);
 
-  // Frame recording for video/boomerang
+  // Frame recording for video/boomerang — buffered by the Rust pump
   const startFrameRecording = useCallback(() => {
-    recordedFramesRef.current = [];
-    recordedTimestampsRef.current = [];
-    isRecordingRef.current = true;
+    invoke("live_view_stream_record_start").catch((err) =>
+      console.error("[useCanon] startFrameRecording error:", err)
+    );
   }, []);
 
-  const stopFrameRecording = useCallback((): { frames: string[]; timestamps: number[] } => {
-    isRecordingRef.current = false;
-    const frames = [...recordedFramesRef.current];
-    const timestamps = [...recordedTimestampsRef.current];
-    recordedFramesRef.current = [];
-    recordedTimestampsRef.current = [];
-    return { frames, timestamps };
+  // Frames come back as file paths in the session folder
+  const stopFrameRecording = useCallback(async (): Promise<{
+    frames: string[];
+    timestamps: number[];
+  }> => {
+    try {
+      return await invoke<{ frames: string[]; timestamps: number[] }>(
+        "live_view_stream_record_stop"
+      );
+    } catch {
+      return { frames: [], timestamps: [] };
+    }
   }, []);
 
   // ===== EDSDK Movie Recording (real camera video) =====
@@ -381,7 +368,7 @@ export function useCanon() {
    */
   const startMovieRecording = useCallback(async (): Promise<boolean> => {
     try {
-      stopLiveViewPolling();
+      await stopLiveViewPolling();
       console.log("[useCanon] Starting movie recording...");
       await invoke("canon_start_movie_record");
       // Resume live view polling so preview remains visible during recording
@@ -407,7 +394,7 @@ export function useCanon() {
    */
   const stopMovieRecording = useCallback(async (): Promise<string> => {
     try {
-      stopLiveViewPolling();
+      await stopLiveViewPolling();
       console.log("[useCanon] Stopping movie recording...");
       const moviePath: string = await invoke("canon_stop_movie_record");
       console.log("[useCanon] Movie file downloaded:", moviePath);
@@ -441,7 +428,7 @@ export function useCanon() {
    */
   const stopMovieRecordingFast = useCallback(async (): Promise<boolean> => {
     try {
-      stopLiveViewPolling();
+      await stopLiveViewPolling();
       console.log("[useCanon] Stopping movie recording (fast)...");
       await invoke("canon_stop_movie_record_fast");
       console.log("[useCanon] Movie recording stopped — camera ready for photo");
@@ -462,7 +449,7 @@ export function useCanon() {
   const finalizeMovieDownload = useCallback(async (): Promise<string> => {
     try {
       // Pause LV polling — EDSDK is single-threaded, can't pump events concurrently
-      stopLiveViewPolling();
+      await stopLiveViewPolling();
       console.log("[useCanon] Finalizing movie download...");
       const moviePath: string = await invoke("canon_finalize_movie_download");
       console.log("[useCanon] Movie file downloaded:", moviePath);
@@ -495,10 +482,10 @@ export function useCanon() {
   // Full cleanup
   const cleanup = useCallback(async () => {
     isCleanedUpRef.current = true;
-    isRecordingRef.current = false;
     isCapturingRef.current = false;
 
-    stopLiveViewPolling();
+    streamActiveRef.current = false;
+    try { await invoke("live_view_stream_stop"); } catch { /* ignore */ }
 
     try { await invoke("canon_stop_live_view"); } catch { /* ignore */ }
     try { await invoke("canon_close_session"); } catch { /* ignore */ }
@@ -511,14 +498,14 @@ export function useCanon() {
       liveViewFrame: "",
       error: "",
     });
-  }, [stopLiveViewPolling]);
+  }, []);
 
   // Cleanup on unmount
   useEffect(() => {
     return () => {
       isCleanedUpRef.current = true;
-      if (liveViewIntervalRef.current) clearInterval(liveViewIntervalRef.current);
       // Best-effort async cleanup
+      invoke("live_view_stream_stop").catch(() => {});
       invoke("canon_stop_live_view").catch(() => {});
       invoke("canon_close_session").catch(() => {});
       invoke("canon_terminate").catch(() => {});
@@ -532,7 +519,7 @@ export function useCanon() {
     startLiveView,
     stopLiveView,
     takePicture,
-    getLatestFrame,
+    getFrameSequence,
     startFrameRecording,
     stopFrameRecording,
     startMovieRecording,
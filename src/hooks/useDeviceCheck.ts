@@ -20,7 +20,8 @@ interface DeviceState {
  * Enhanced device monitoring hook.
  * - Tracks configured camera and printer from localStorage
  * - Detects disconnect/reconnect state transitions
- * - Sends API notifications (device-alert, device-reconnected, device-status-report)
+ * - Sends API notifications (camera device-alert/device-reconnected, device-status-report);
+ *   printer alerts come from the Rust printer monitor (printer_monitor.rs)
  * - Triggers maintenance mode callback on disconnect
  */
 export function useDeviceCheck(options: DeviceCheckOptions = {}) {
@@ -215,7 +216,8 @@ export function useDeviceCheck(options: DeviceCheckOptions = {}) {
         }
       }
 
-      // Printer disconnect transition
+      // Printer disconnect transition — the ops alert itself is sent
+      // (debounced) by the Rust printer monitor
       if (
         isConfiguredPrinter &&
         prevState.printerConnected &&
@@ -223,15 +225,6 @@ export function useDeviceCheck(options: DeviceCheckOptions = {}) {
         !alertSentRef.current.printer
       ) {
         alertSentRef.current.printer = true;
-        try {
-          await invoke("send_device_alert", {
-            deviceType: "printer",
-            deviceName: printerName,
-            availableDevices: availablePrinterNames,
-          });
-        } catch {
-          /* ignore */
-        }
         if (!DEVICE_CHECK.ALLOW_TEST_WITHOUT_DEVICES && onMaintenanceNeeded) onMaintenanceNeeded();
       }
 
@@ -242,14 +235,6 @@ export function useDeviceCheck(options: DeviceCheckOptions = {}) {
         printerConnected
       ) {
         alertSentRef.current.printer = false;
-        try {
-          await invoke("send_device_reconnected", {
-            deviceType: "printer",
-            deviceName: `Main: ${printerName}`,
-          });
-        } catch {
-          /* ignore */
-        }
       }
     }
 
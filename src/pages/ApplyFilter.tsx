@@ -1,9 +1,9 @@
 import { useState, useEffect, useRef } from "react";
 import { useNavigate, useLocation } from "react-router-dom";
-import { invoke } from "@tauri-apps/api/core";
-import type { ThemeData, MachineData, Capture } from "../App";
+import { convertFileSrc, invoke } from "@tauri-apps/api/core";
+import type { ThemeData, MachineData, Capture, ImageFile } from "../App";
 import { useIdleTimeout } from "../hooks/useIdleTimeout";
-import { FILTERS, type FilterConfig } from "../config/filters";
+import { loadFilters, NO_FILTER, type FilterConfig } from "../config/filters";
 import Countdown from "../components/Countdown";
 import { COUNTDOWN } from "../config/appConfig";
 
@@ -19,10 +19,12 @@ export default function ApplyFilter({ theme }: Props) {
 
   const frameCaptures: Capture[] = state.frameCaptures || [];
   const firstPhoto = frameCaptures[0]?.photo || "";
+  // Backend input for the first photo: session file when we have one
+  const firstPhotoSource =
+    frameCaptures[0]?.photoPath || frameCaptures[0]?.photo || "";
 
-  const [selectedFilter, setSelectedFilter] = useState<FilterConfig>(
-    FILTERS[0],
-  );
+  const [filters, setFilters] = useState<FilterConfig[]>([NO_FILTER]);
+  const [selectedFilter, setSelectedFilter] = useState<FilterConfig>(NO_FILTER);
   const [previewImage, setPreviewImage] = useState(firstPhoto);
   const [filterPreviews, setFilterPreviews] = useState<Record<string, string>>(
     {},
@@ -32,47 +34,50 @@ export default function ApplyFilter({ theme }: Props) {
   const [applyProgress, setApplyProgress] = useState("");
 
   const previewCacheRef = useRef<Record<string, string>>({});
-  const resolvedPathsRef = useRef<Record<string, string>>({});
   const scrollContainerRef = useRef<HTMLDivElement>(null);
 
   useIdleTimeout();
 
   useEffect(() => {
-    const resolvePaths = async () => {
-      for (const filter of FILTERS) {
-        if (filter.type === "none" || !filter.lutFile) continue;
-        try {
-          const resolved: string = await invoke("resolve_lut_path", {
-            lutFile: filter.lutFile,
-          });
-          resolvedPathsRef.current[filter.id] = resolved;
-        } catch (err) {
-          console.warn(`LUT not found for ${filter.id}:`, err);
-        }
-      }
+    loadFilters().then((loaded) => {
+      setFilters(loaded);
+      setSelectedFilter(loaded[0] ?? NO_FILTER);
       if (firstPhoto) {
-        generateFilterPreviews();
+        generateFilterPreviews(loaded);
       }
-    };
-    resolvePaths();
+    });
   }, [firstPhoto]);
 
-  const generateFilterPreviews = async () => {
-    const promises = FILTERS.map(async (filter) => {
-      const lutPath = resolvedPathsRef.current[filter.id] || "";
-      try {
-        const result: string = await invoke("apply_lut_filter_preview", {
-          imageDataBase64: firstPhoto,
-          lutFilePath: lutPath,
-          maxSize: 150,
-        });
-        setFilterPreviews((prev) => ({ ...prev, [filter.id]: result }));
-        previewCacheRef.current[filter.id] = result;
-      } catch (err) {
-        setFilterPreviews((prev) => ({ ...prev, [filter.id]: firstPhoto }));
+  const generateFilterPreviews = async (strip: FilterConfig[]) => {
+    // One call for the whole strip — the photo is decoded and resized once
+    const lutPaths = strip.map((filter) => filter.filePath);
+    let results: { image: string | null; error: string | null }[] = [];
+    try {
+      results = await invoke("apply_lut_filter_previews", {
+        image: firstPhotoSource,
+        lutFilePaths: lutPaths,
+        maxSize: 150,
+        intensities: strip.map((filter) => filter.intensity ?? 100),
+        interpolations: strip.map((filter) => filter.interpolation ?? null),
+      });
+    } catch (err) {
+      console.error("Filter previews error:", err);
+    }
+
+    const previews: Record<string, string> = {};
+    strip.forEach((filter, idx) => {
+      const image = results[idx]?.image;
+      if (image) {
+        previews[filter.id] = image;
+        previewCacheRef.current[filter.id] = image;
+      } else {
+        if (results[idx]?.error) {
+          console.warn(`Preview failed for ${filter.id}:`, results[idx].error);
+        }
+        previews[filter.id] = firstPhoto;
       }
     });
-    await Promise.all(promises);
+    setFilterPreviews((prev) => ({ ...prev, ...previews }));
   };
 
   const handleSelectFilter = async (filter: FilterConfig) => {
@@ -86,11 +91,12 @@ export default function ApplyFilter({ theme }: Props) {
         if (previewCacheRef.current[`full_${filter.id}`]) {
           setPreviewImage(previewCacheRef.current[`full_${filter.id}`]);
         } else {
-          const lutPath = resolvedPathsRef.current[filter.id] || "";
           const result: string = await invoke("apply_lut_filter_preview", {
-            imageDataBase64: firstPhoto,
-            lutFilePath: lutPath,
+            image: firstPhotoSource,
+            lutFilePath: filter.filePath,
             maxSize: 800,
+            intensity: filter.intensity ?? 100,
+            interpolation: filter.interpolation,
           });
           setPreviewImage(result);
           previewCacheRef.current[`full_${filter.id}`] = result;
@@ -121,15 +127,21 @@ export default function ApplyFilter({ theme }: Props) {
       }
 
       if (selectedFilter && selectedFilter.type === "lut") {
-        const lutPath = resolvedPathsRef.current[selectedFilter.id] || "";
+        const lutPath = selectedFilter.filePath;
         setApplyProgress("Processing...");
         const photoPromises = frameCaptures.map(async (cap, idx) => {
           setApplyProgress(`Processing${idx + 1}/${frameCaptures.length}...`);
-          const filteredPhoto: string = await invoke("apply_lut_filter", {
-            imageDataBase64: cap.photo,
+          const filtered: ImageFile = await invoke("apply_lut_filter", {
+            image: cap.photoPath || cap.photo,
             lutFilePath: lutPath,
+            intensity: selectedFilter.intensity ?? 100,
+            interpolation: selectedFilter.interpolation,
           });
-          return { ...cap, photo: filteredPhoto };
+          return {
+            ...cap,
+            photo: convertFileSrc(filtered.path),
+            photoPath: filtered.path,
+          };
         });
 
         filteredCaptures = await Promise.all(photoPromises);
@@ -302,7 +314,7 @@ export default function ApplyFilter({ theme }: Props) {
                 cursor: isDragging ? "grabbing" : "grab",
               }}
             >
-              {FILTERS.map((filter) => {
+              {filters.map((filter) => {
                 const isSelected = selectedFilter?.id === filter.id;
                 return (
                   <div
@@ -1,4 +1,4 @@
-import { useState } from "react";
+import { useState, useRef } from "react";
 import { useNavigate, useLocation } from "react-router-dom";
 import { invoke } from "@tauri-apps/api/core";
 import type { ThemeData, MachineData } from "../App";
@@ -27,6 +27,9 @@ export default function CouponEntry({ theme }: Props) {
   const [code, setCode] = useState("");
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState("");
+  // Idempotency key per coupon code: submitting the same code again repeats
+  // the same payment instead of creating another one
+  const paymentKeys = useRef<Record<string, string>>({});
 
   const handleKeyPress = (key: string) => {
     if (code.length < 20) {
@@ -85,10 +88,12 @@ export default function CouponEntry({ theme }: Props) {
 
       // 2. Create payment transaction with couponCodeId (matches reference flow)
       // This gives us a transactionId even for free/discounted transactions
+      paymentKeys.current[code] ??= `${state.paymentKey || crypto.randomUUID()}-${code}`;
       const paymentResult: any = await invoke("create_payment", {
         amount: state.totalPrice || 0,
         numberPhoto: state.quantity || 1,
         couponCodeId: couponCodeId || null,
+        idempotencyKey: paymentKeys.current[code],
       });
 
       console.log("🎟️ [CouponEntry] Payment result:", paymentResult);
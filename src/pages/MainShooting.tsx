@@ -1,11 +1,30 @@
 import { useState, useEffect, useRef, useCallback } from "react";
 import { useNavigate, useLocation } from "react-router-dom";
-import { invoke } from "@tauri-apps/api/core";
+import { convertFileSrc, invoke } from "@tauri-apps/api/core";
 import RecordRTC from "recordrtc";
-import type { ThemeData, MachineData, Capture, FrameSlot } from "../App";
+import type {
+  ThemeData,
+  MachineData,
+  Capture,
+  FrameSlot,
+  ImageFile,
+} from "../App";
 import { useIdleTimeout } from "../hooks/useIdleTimeout";
 import { useCanon } from "../hooks/useCanon";
 
+// A capture: display URL plus its session file. DSLR captures are written to
+// the session folder by the capture command; webcam frames are imported in
+// the background.
+interface Shot {
+  photo: string;
+  file: Promise<ImageFile | null>;
+}
+
+const fileShot = (file: ImageFile | null): Shot => ({
+  photo: file ? convertFileSrc(file.path) : "",
+  file: Promise.resolve(file),
+});
+
 // CropOverlay: shows SVG mask overlay to indicate the crop area based on slot dimensions
 function CropOverlay({
   slotWidth,
@@ -176,6 +195,13 @@ export default function MainShooting({ theme, machineData }: Props) {
       window.removeEventListener("resize", updateContainerDimensions);
   }, []);
 
+  // Fresh session working directory for this customer's captures
+  useEffect(() => {
+    invoke("session_start").catch((err) =>
+      console.error("Session start error:", err),
+    );
+  }, []);
+
   // Initialize camera
   useEffect(() => {
     initCamera();
@@ -397,15 +423,16 @@ export default function MainShooting({ theme, machineData }: Props) {
   }> => {
     if (cameraTypeRef.current === "canon") {
       // Canon: stop frame recording and create video from accumulated frames
-      const recording = canonCamera.stopFrameRecording();
       isRecordingRef.current = false;
       setIsRecording(false);
 
-      if (recording.frames.length > 0) {
-        // Create video from recorded JPEG frames using canvas + MediaRecorder
-        return createVideoFromFrames(recording.frames).then((result) => result);
-      }
-      return Promise.resolve({ url: "", blob: null });
+      return canonCamera.stopFrameRecording().then((recording) => {
+        if (recording.frames.length > 0) {
+          // Create video from recorded JPEG frames using canvas + MediaRecorder
+          return createVideoFromFrames(recording.frames);
+        }
+        return { url: "", blob: null };
+      });
     }
 
     // Webcam: standard RecordRTC flow
@@ -431,15 +458,14 @@ export default function MainShooting({ theme, machineData }: Props) {
     });
   }, []);
 
-  const takePhoto = useCallback(async (): Promise<string> => {
+  const takePhoto = useCallback(async (): Promise<Shot> => {
     if (cameraTypeRef.current === "canon") {
       // Canon: use EDSDK shutter capture
-      const photo = await canonCamera.takePicture();
-      return photo;
+      return fileShot(await canonCamera.takePicture());
     }
 
     // Webcam: grab frame from video element
-    if (!videoRef.current || !canvasRef.current) return "";
+    if (!videoRef.current || !canvasRef.current) return fileShot(null);
 
     const video = videoRef.current;
     const canvas = canvasRef.current;
@@ -457,13 +483,23 @@ export default function MainShooting({ theme, machineData }: Props) {
     canvas.height = h;
 
     const ctx = canvas.getContext("2d");
-    if (!ctx) return "";
+    if (!ctx) return fileShot(null);
 
     ctx.drawImage(video, 0, 0, w, h);
-    return canvas.toDataURL("image/jpeg", 0.92);
+    const photo = canvas.toDataURL("image/jpeg", 0.92);
+    // Store the frame in the session folder in the background (during the
+    // flash) — later steps pass the file path instead of base64
+    const file = invoke<ImageFile>("session_import_image", {
+      imageDataBase64: photo,
+      applyMountRotation: true,
+    }).catch((err) => {
+      console.error("Session import error:", err);
+      return null; // fall back to passing the data URL
+    });
+    return { photo, file };
   }, []);
 
-  // Create video from JPEG frames (used for Canon frame recording)
+  // Create video from JPEG frame files (used for Canon frame recording)
   // Dynamically calculates fps so the output is always exactly `targetDurationSec` (default 3s).
   // e.g. 60 frames → 20fps × 3s, 45 frames → 15fps × 3s, 20 frames → 6.67fps × 3s
   // This guarantees every slot has identical video duration for clean looping.
@@ -533,7 +569,7 @@ export default function MainShooting({ theme, machineData }: Props) {
           img.onload = () => {
             ctx.drawImage(img, 0, 0, offCanvas.width, offCanvas.height);
           };
-          img.src = frames[frameIdx];
+          img.src = convertFileSrc(frames[frameIdx]);
           frameIdx++;
         }, 1000 / dynamicFps);
       });
@@ -581,7 +617,7 @@ export default function MainShooting({ theme, machineData }: Props) {
       const FRESH_FRAME_TIMEOUT = 5000; // 5 seconds max wait
       const POLL_MS = 50;
       let elapsed = 0;
-      let lastFingerprint = "";
+      let lastSeq = 0;
       let freshCount = 0;
       const REQUIRED_FRESH = 2;
 
@@ -589,13 +625,12 @@ export default function MainShooting({ theme, machineData }: Props) {
       setShowGetReady(true);
 
       while (elapsed < FRESH_FRAME_TIMEOUT && freshCount < REQUIRED_FRESH) {
-        const frame = canonCamera.getLatestFrame();
-        if (frame) {
-          // Fingerprint: length + last 100 chars (JPEG tail changes most)
-          const fp = `${frame.length}:${frame.slice(-100)}`;
-          if (fp !== lastFingerprint) {
+        const seq = await canonCamera.getFrameSequence();
+        if (seq > 0) {
+          // The pump bumps the sequence number for every new frame
+          if (seq !== lastSeq) {
             freshCount++;
-            lastFingerprint = fp;
+            lastSeq = seq;
             console.log(
               `[Canon] Fresh frame ${freshCount}/${REQUIRED_FRESH} after ${elapsed}ms`,
             );
@@ -707,7 +742,7 @@ export default function MainShooting({ theme, machineData }: Props) {
       if (cameraTypeRef.current === "canon") {
         if ((window as any).__canonMovieFallback) {
           // Fallback: stop frame recording, get raw frames
-          const recording = canonCamera.stopFrameRecording();
+          const recording = await canonCamera.stopFrameRecording();
           isRecordingRef.current = false;
           setIsRecording(false);
           (window as any).__lastCanonFrames = recording.frames;
@@ -727,15 +762,17 @@ export default function MainShooting({ theme, machineData }: Props) {
       //    Canon: photo-during-recording (PressShutterButton while movie is active,
       //    then stops recording internally — zero mode-switch delay)
       //    Fallback: normal takePhoto
-      let photoData: string;
+      let shot: Shot;
       if (
         cameraTypeRef.current === "canon" &&
         !(window as any).__canonMovieFallback
       ) {
-        photoData = await canonCamera.takePhotoDuringRecording();
+        shot = fileShot(await canonCamera.takePhotoDuringRecording());
       } else {
-        photoData = await takePhoto();
+        shot = await takePhoto();
       }
+      const photoData = shot.photo;
+      const photoFilePromise = shot.file;
 
       // 3. Flash effect
       setShowFlash(true);
@@ -751,6 +788,7 @@ export default function MainShooting({ theme, machineData }: Props) {
         // Canon real movie: show photo now, download + trim video
         const newCapture: Capture = {
           photo: photoData,
+          photoPath: (await photoFilePromise)?.path,
           video: "",
           videoPath: "",
         };
@@ -852,6 +890,7 @@ export default function MainShooting({ theme, machineData }: Props) {
 
         const newCapture: Capture = {
           photo: photoData,
+          photoPath: (await photoFilePromise)?.path,
           video: videoUrl,
           videoPath: videoPath,
         };
@@ -867,6 +906,7 @@ export default function MainShooting({ theme, machineData }: Props) {
         }
         const newCapture: Capture = {
           photo: photoData,
+          photoPath: (await photoFilePromise)?.path,
           video: videoUrl,
           videoPath: videoPath,
         };
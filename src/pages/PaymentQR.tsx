@@ -42,6 +42,7 @@ export default function PaymentQR({ theme }: Props) {
         amount: state.totalPrice || 0,
         numberPhoto: state.quantity || 1,
         couponCodeId: state.couponCodeId || null,
+        idempotencyKey: state.paymentKey || null,
       });
 
       if (result.success && result.data) {
@@ -1,5 +1,6 @@
-import { useCallback } from "react";
+import { useCallback, useEffect, useState } from "react";
 import { useNavigate, useLocation } from "react-router-dom";
+import { invoke } from "@tauri-apps/api/core";
 import type { ThemeData, MachineData } from "../App";
 import { useIdleTimeout } from "../hooks/useIdleTimeout";
 import BackButton from "../components/BackButton";
@@ -20,11 +21,26 @@ export default function PaymentSelection({ theme, machineData }: Props) {
 
   const selectedQuantity = state.quantity || 1;
 
-  // Calculate max quantity from available prices
-  const maxQuantity =
+  // Sheets left on the selected printer (null = not counted)
+  const [mediaRemaining, setMediaRemaining] = useState<number | null>(null);
+  const [mediaError, setMediaError] = useState("");
+
+  useEffect(() => {
+    invoke<{ remaining: number | null }>("check_media_available", { prints: 1 })
+      .then((check) => {
+        setMediaRemaining(check.remaining);
+        if (check.remaining === 0) navigate("/out-of-paper");
+      })
+      .catch((err) => console.error("[PaymentSelection] check_media_available:", err));
+  }, [navigate]);
+
+  // Calculate max quantity from available prices (and the media left)
+  const priceMaxQuantity =
     machineData.prices.length > 0
       ? Math.max(...machineData.prices.map((p) => p.quantity))
       : 10;
+  const maxQuantity =
+    mediaRemaining !== null ? Math.max(1, Math.min(priceMaxQuantity, mediaRemaining)) : priceMaxQuantity;
 
   // Get current price for selected quantity
   const currentPrice =
@@ -47,15 +63,46 @@ export default function PaymentSelection({ theme, machineData }: Props) {
     if (selectedQuantity < maxQuantity) handleSetQuantity(selectedQuantity + 1);
   };
 
-  const handleQRCode = () => {
+  // Don't start a payment for more prints than the printer has media for
+  const hasMediaFor = async (quantity: number) => {
+    try {
+      const check = await invoke<{ ok: boolean; remaining: number | null }>("check_media_available", {
+        prints: quantity,
+      });
+      if (!check.ok) {
+        setMediaRemaining(check.remaining);
+        setMediaError(`กระดาษเหลือพิมพ์ได้อีก ${check.remaining ?? 0} ใบ / Only ${check.remaining ?? 0} prints left`);
+      }
+      return check.ok;
+    } catch (err) {
+      console.error("[PaymentSelection] check_media_available:", err);
+      return true;
+    }
+  };
+
+  // Each payment attempt gets its own idempotency key, so a create_payment
+  // repeated after a lost response doesn't create a second payment
+  const handleQRCode = async () => {
+    if (!(await hasMediaFor(selectedQuantity))) return;
     navigate("/payment-qr", {
-      state: { ...state, quantity: selectedQuantity, totalPrice: currentPrice },
+      state: {
+        ...state,
+        quantity: selectedQuantity,
+        totalPrice: currentPrice,
+        paymentKey: crypto.randomUUID(),
+      },
     });
   };
 
-  const handleCoupon = () => {
+  const handleCoupon = async () => {
+    if (!(await hasMediaFor(selectedQuantity))) return;
     navigate("/coupon-entry", {
-      state: { ...state, quantity: selectedQuantity, totalPrice: currentPrice },
+      state: {
+        ...state,
+        quantity: selectedQuantity,
+        totalPrice: currentPrice,
+        paymentKey: crypto.randomUUID(),
+      },
     });
   };
 
@@ -138,6 +185,10 @@ export default function PaymentSelection({ theme, machineData }: Props) {
           </span>
         </div>
 
+        {mediaError && (
+          <div style={{ color: "#ff6b6b", textAlign: "center", fontSize: 18 }}>{mediaError}</div>
+        )}
+
         {/* Action Buttons - side by side */}
         <div className="action-buttons-container">
           {/* Coupon button - outlined */}
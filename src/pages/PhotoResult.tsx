@@ -1,19 +1,30 @@
 import { useState, useEffect, useRef, useCallback } from "react";
 import { useNavigate, useLocation } from "react-router-dom";
-import { invoke } from "@tauri-apps/api/core";
+import { convertFileSrc, invoke } from "@tauri-apps/api/core";
 import { QRCodeSVG } from "qrcode.react";
-import type { ThemeData, MachineData, Capture, FrameSlot } from "../App";
+import type {
+  ThemeData,
+  MachineData,
+  Capture,
+  FrameSlot,
+  ImageFile,
+} from "../App";
 import { useIdleTimeout } from "../hooks/useIdleTimeout";
 import Countdown from "../components/Countdown";
 import { COUNTDOWN } from "../config/appConfig";
 import { setPrinting } from "../utils/printingState";
+import { printAndWait } from "../utils/printQueue";
+import type { DeliveryFile } from "../utils/outbox";
 
 interface Props {
   theme: ThemeData;
   machineData: MachineData;
 }
 
-export default function PhotoResult({ theme }: Props) {
+// How long composing waits for the presigned QR URL before stamping without it
+const QR_WAIT_MS = 10000;
+
+export default function PhotoResult({ theme, machineData }: Props) {
   const navigate = useNavigate();
   const location = useLocation();
   const state = (location.state as any) || {};
@@ -30,9 +41,36 @@ export default function PhotoResult({ theme }: Props) {
     .map(Number);
   const frameWidth = (_imgW > 0 ? _imgW : selectedFrame?.grid?.width) || 1200;
   const frameHeight = (_imgH > 0 ? _imgH : selectedFrame?.grid?.height) || 1800;
+  // Matches `FrameLayout` on the Rust side (shared by photo + video composers)
+  const frameLayout = {
+    width: frameWidth,
+    height: frameHeight,
+    slots,
+    layers: selectedFrame?.grid?.layers || [],
+  };
+  // Values for `{placeholder}` templates in text / QR layers
+  const stampValues: Record<string, string> = {
+    transactionCode: state.referenceId
+      ? state.referenceId.startsWith("TXN-")
+        ? state.referenceId
+        : `TXN-${state.referenceId}`
+      : "",
+    eventName: machineData?.machineName || "",
+  };
+  // Paper profile picked in PaperPositionModal for this frame's orientation;
+  // undefined lets Rust pick one from the composed image's aspect ratio
+  const isLandscape = frameWidth > frameHeight;
+  const selectedPaperProfile = () =>
+    localStorage.getItem(isLandscape ? "paperSizeLandscape" : "paperSizePortrait") ||
+    undefined;
+  // QR layers encode the gallery URL, which only exists after presigning
+  const hasQrLayer = frameLayout.layers.some((layer: any) => layer.type === "qr");
 
   const [composedImage, setComposedImage] = useState<string>("");
   const [qrCodeUrl, setQrCodeUrl] = useState<string>("");
+  // Presign finished (with or without a QR URL) / waiting for it timed out
+  const [presignSettled, setPresignSettled] = useState(false);
+  const [qrWaitExpired, setQrWaitExpired] = useState(false);
   const [sessionId, setSessionId] = useState<string>("");
   const [uploadUrls, setUploadUrls] = useState<any[]>([]);
   const [uploadStatus, setUploadStatus] = useState<string>("processing");
@@ -59,24 +97,34 @@ export default function PhotoResult({ theme }: Props) {
         slotsCount: slots.length,
         firstSlot: slots[0],
       });
-      const photosBase64 = frameCaptures.map((c: Capture) => c.photo);
+      // Session file paths — the backend reads them straight from disk
+      const photos = frameCaptures.map((c: Capture) => c.photoPath || c.photo);
 
-      const result: string = await invoke("compose_frame", {
+      const result: ImageFile = await invoke("compose_frame", {
         frameImageUrl: selectedFrame?.imageUrl || "",
-        photosBase64,
-        slots: slots,
-        frameWidth: frameWidth,
-        frameHeight: frameHeight,
+        photos,
+        layout: frameLayout,
+        stampValues: { ...stampValues, qrUrl: qrCodeUrl },
+        paperProfile: selectedPaperProfile(),
       });
 
-      setComposedImage(result);
-      return result;
+      setComposedImage(result.path);
+      return result.path;
     } catch (err) {
       console.error("Compose frame error:", err);
       setError("Failed to compose frame");
       return "";
     }
-  }, [frameCaptures, selectedFrame, slots, frameWidth, frameHeight]);
+  }, [
+    frameCaptures,
+    selectedFrame,
+    slots,
+    frameWidth,
+    frameHeight,
+    qrCodeUrl,
+    state.referenceId,
+    machineData?.machineName,
+  ]);
 
   // Step 1: Create presigned upload session immediately
   // This returns qrcodeStorageUrl + presigned URLs + sessionId
@@ -96,6 +144,7 @@ export default function PhotoResult({ theme }: Props) {
       console.error(
         "❌ [PhotoResult] No transactionId found in state! Cannot create presign session.",
       );
+      setPresignSettled(true);
       return;
     }
 
@@ -173,19 +222,22 @@ export default function PhotoResult({ theme }: Props) {
         console.error("❌ [PhotoResult] Error creating presign session:", err);
         hasCreatedPresign.current = false; // Allow retry
       }
+      setPresignSettled(true);
     };
 
     createPresignSession();
   }, [state?.transactionId, state?.referenceId]); // eslint-disable-line
 
-  // Step 2: Upload files to presigned URLs (runs after presign + compose are done)
+  // Step 2: Deliver the files (runs after presign has settled + compose is done)
+  // The outbox presigns, uploads and confirms them as one delivery — queued
+  // while offline, with fresh upload URLs when it is sent
   const uploadFiles = useCallback(
-    async (composedImg: string) => {
+    async (composedPath: string) => {
       if (hasUploadedFiles.current) return;
-      if (!sessionId || uploadUrls.length === 0) {
-        console.warn(
-          "⚠️ [PhotoResult] No sessionId/uploadUrls yet, waiting...",
-        );
+      const transactionId =
+        state.transactionId || state.referenceId || state.transaction_id || "";
+      if (!transactionId) {
+        console.warn("⚠️ [PhotoResult] No transactionId, nothing to upload");
         return;
       }
       hasUploadedFiles.current = true;
@@ -194,173 +246,80 @@ export default function PhotoResult({ theme }: Props) {
         setUploadStatus("uploading");
         setStatusText("กำลังอัปโหลด...");
 
-        // Separate photo and video upload URLs (sorted by order)
-        const photoUrls = uploadUrls
-          .filter((u: any) => u.type === "photo")
-          .sort((a: any, b: any) => a.order - b.order);
-        const videoUrls = uploadUrls
-          .filter((u: any) => u.type === "video")
-          .sort((a: any, b: any) => a.order - b.order);
-
-        console.log(
-          `📤 [PhotoResult] Upload targets: ${photoUrls.length} photo URLs, ${videoUrls.length} video URLs`,
-        );
-
-        const uploadedFiles: { key: string; type: string; order: number }[] =
-          [];
-        let photoIdx = 0;
+        // Composed frame first (finalImage), then the individual captures
+        const files: DeliveryFile[] = [
+          { filePath: composedPath, contentType: "image/jpeg", type: "photo" },
+        ];
+        frameCaptures.forEach((cap: Capture, i: number) => {
+          // Captures are written to the session folder when they're taken
+          if (cap.photoPath) {
+            files.push({ filePath: cap.photoPath, contentType: "image/jpeg", type: "photo" });
+          } else {
+            console.warn(`⚠️ [PhotoResult] Photo ${i + 1} has no session file, skipping`);
+          }
+        });
 
-        // Upload composed frame (order 1 = finalImage)
-        if (photoIdx < photoUrls.length) {
-          setStatusText("กำลังอัปโหลดรูปเฟรม...");
-          const composedPath: string = await invoke("save_temp_image", {
-            imageDataBase64: composedImg,
-            filename: "frame-photo.jpg",
-          });
+        // ONE compiled frame video (all captures in frame layout)
+        const videoPaths = frameCaptures
+          .map((cap: Capture) => cap.videoPath)
+          .filter((p): p is string => !!p);
+        if (videoPaths.length > 0) {
+          setStatusText("กำลังรวมวิดีโอ...");
           try {
-            await invoke("upload_to_presigned_url", {
-              url: photoUrls[photoIdx].uploadUrl,
-              filePath: composedPath,
-              contentType: "image/jpeg",
-            });
-            uploadedFiles.push({
-              key: photoUrls[photoIdx].key,
-              type: "photo",
-              order: photoUrls[photoIdx].order,
-            });
             console.log(
-              `✅ [PhotoResult] Frame photo uploaded (order ${photoUrls[photoIdx].order})`,
+              `🎬 [PhotoResult] Composing framed video with ${videoPaths.length} captures...`,
+            );
+            // LUT path for the video filter (already absolute from get_available_filters)
+            const lutPath: string | null =
+              selectedFilter?.type === "lut" && selectedFilter.filePath
+                ? selectedFilter.filePath
+                : null;
+
+            const composedVideoPath: string = await invoke(
+              "compose_frame_video",
+              {
+                frameImageUrl: selectedFrame?.imageUrl || "",
+                videoPaths,
+                layout: frameLayout,
+                outputFilename: "framed-video.mp4",
+                lutPath: lutPath,
+                lutIntensity: selectedFilter?.intensity ?? 100,
+                lutInterpolation: selectedFilter?.interpolation ?? null,
+                stampValues: { ...stampValues, qrUrl: qrCodeUrl },
+              },
             );
-          } catch (err) {
-            console.error("❌ [PhotoResult] Frame photo upload failed:", err);
-          }
-          photoIdx++;
-        }
-
-        // Upload individual capture photos
-        for (
-          let i = 0;
-          i < frameCaptures.length && photoIdx < photoUrls.length;
-          i++
-        ) {
-          setStatusText(`กำลังอัปโหลดรูป ${i + 1}/${frameCaptures.length}...`);
-          const photoPath: string = await invoke("save_temp_image", {
-            imageDataBase64: frameCaptures[i].photo,
-            filename: `photo-${i + 1}.jpg`,
-          });
-          try {
-            await invoke("upload_to_presigned_url", {
-              url: photoUrls[photoIdx].uploadUrl,
-              filePath: photoPath,
-              contentType: "image/jpeg",
-            });
-            uploadedFiles.push({
-              key: photoUrls[photoIdx].key,
-              type: "photo",
-              order: photoUrls[photoIdx].order,
-            });
             console.log(
-              `✅ [PhotoResult] Photo ${i + 1} uploaded (order ${photoUrls[photoIdx].order})`,
+              `✅ [PhotoResult] Framed video composed: ${composedVideoPath}`,
             );
+            files.push({ filePath: composedVideoPath, contentType: "video/mp4", type: "video" });
           } catch (err) {
-            console.error(
-              `❌ [PhotoResult] Photo ${i + 1} upload failed:`,
-              err,
-            );
+            console.error("❌ [PhotoResult] Video compose failed:", err);
           }
-          photoIdx++;
         }
 
-        // Upload compiled frame video (ONE video with all captures in frame layout)
-        if (videoUrls.length > 0) {
-          // Collect all video paths from captures
-          const videoPaths = frameCaptures
-            .map((cap: Capture) => cap.videoPath)
-            .filter((p): p is string => !!p);
-
-          if (videoPaths.length > 0) {
-            setStatusText("กำลังรวมวิดีโอ...");
-            try {
-              // Compose all capture videos into one framed video
-              console.log(
-                `🎬 [PhotoResult] Composing framed video with ${videoPaths.length} captures...`,
-              );
-              // Resolve LUT path for video filter if a filter is selected
-              let lutPath: string | null = null;
-              if (
-                selectedFilter &&
-                selectedFilter.type === "lut" &&
-                selectedFilter.lutFile
-              ) {
-                try {
-                  lutPath = await invoke<string>("resolve_lut_path", {
-                    lutFile: selectedFilter.lutFile,
-                  });
-                } catch (err) {
-                  console.warn(
-                    "⚠️ [PhotoResult] Could not resolve LUT path for video:",
-                    err,
-                  );
-                }
-              }
-
-              const composedVideoPath: string = await invoke(
-                "compose_frame_video",
-                {
-                  frameImageUrl: selectedFrame?.imageUrl || "",
-                  videoPaths,
-                  slots: slots,
-                  frameWidth: frameWidth,
-                  frameHeight: frameHeight,
-                  outputFilename: "framed-video.mp4",
-                  lutPath: lutPath,
-                },
-              );
-              console.log(
-                `✅ [PhotoResult] Framed video composed: ${composedVideoPath}`,
-              );
-
-              setStatusText("กำลังอัปโหลดวิดีโอ...");
-              await invoke("upload_to_presigned_url", {
-                url: videoUrls[0].uploadUrl,
-                filePath: composedVideoPath,
-                contentType: "video/mp4",
-              });
-              uploadedFiles.push({
-                key: videoUrls[0].key,
-                type: "video",
-                order: videoUrls[0].order,
-              });
-              console.log(
-                `✅ [PhotoResult] Framed video uploaded (order ${videoUrls[0].order})`,
-              );
-            } catch (err) {
-              console.error(
-                "❌ [PhotoResult] Video compose/upload failed:",
-                err,
-              );
-            }
-          }
-        }
+        setStatusText("กำลังอัปโหลด...");
+        console.log(`📤 [PhotoResult] Delivering ${files.length} files...`);
+        const result: any = await invoke("deliver_photos", {
+          transactionId,
+          transactionCode: stampValues.transactionCode || null,
+          files,
+          // URLs from step 1, if there are any — the delivery presigns again otherwise
+          sessionId: sessionId || null,
+          uploadUrls: uploadUrls.length > 0 ? uploadUrls : null,
+        });
 
-        // Confirm upload
-        if (uploadedFiles.length > 0) {
-          console.log(
-            `📤 [PhotoResult] Confirming ${uploadedFiles.length} uploaded files...`,
-          );
-          try {
-            await invoke("confirm_upload", {
-              sessionId,
-              uploadedFiles,
-            });
-            console.log("✅ [PhotoResult] Upload confirmed!");
-          } catch (err) {
-            console.error("❌ [PhotoResult] Confirm upload failed:", err);
-          }
+        if (result.data?.queued) {
+          console.log("🕒 [PhotoResult] Offline, upload queued:", result.data.outboxId);
+          setUploadStatus("queued");
+          setStatusText("รออัปโหลดเมื่อออนไลน์");
+        } else if (result.success) {
+          console.log("✅ [PhotoResult] Upload confirmed!");
+          setUploadStatus("done");
+          setStatusText("อัปโหลดเสร็จสิ้น!");
+        } else {
+          console.error("❌ [PhotoResult] Upload rejected:", result.error || result.data);
+          setUploadStatus("error");
         }
-
-        setUploadStatus("done");
-        setStatusText("อัปโหลดเสร็จสิ้น!");
       } catch (err) {
         console.error("❌ [PhotoResult] Upload error:", err);
         setUploadStatus("error");
@@ -372,33 +331,13 @@ export default function PhotoResult({ theme }: Props) {
 
   // Print the composed frame
   const printFrame = useCallback(
-    async (composedImg: string) => {
+    async (printPath: string) => {
       try {
         setPrintStatus("printing");
 
-        // Determine frame type for cutting
-        // Rust print_photo handles duplication automatically for 2x6 and 6x2
-        let frameType = "4x6";
-        let isLandscape = false;
-        if (frameWidth && frameHeight) {
-          const ratio = frameWidth / frameHeight;
-          if (ratio < 0.5) {
-            frameType = "2x6";      // portrait-cut: 2x6 → duplicated to 4x6 by Rust
-          } else if (ratio > 2) {
-            frameType = "6x2";      // landscape-cut: 6x2 → duplicated to 6x4 by Rust
-            isLandscape = true;
-          } else if (ratio > 1) {
-            frameType = "6x4";      // landscape no-cut
-            isLandscape = true;
-          }
-          // else: 4x6 portrait no-cut (default)
-        }
-
-        // Save to temp file (Rust handles 2x6/6x2 duplication internally)
-        const printPath: string = await invoke("save_temp_image", {
-          imageDataBase64: composedImg,
-          filename: "print-frame.png",
-        });
+        // Paper profile (4x6, 2x6 cut, ...) as selected in PaperPositionModal,
+        // else picked by Rust from the composed image's aspect ratio
+        const paperProfile = selectedPaperProfile();
 
         // Load paper position config (per-orientation: paperConfigPortrait / paperConfigLandscape)
         let scale = 100;
@@ -425,38 +364,65 @@ export default function PhotoResult({ theme }: Props) {
         if (!printerName) {
           // Fallback: auto-detect printer
           const printers: any[] = await invoke("get_printers");
+          // The virtual file printer is only used when selected explicitly
           const dnpPrinter = printers.find(
             (p: any) =>
-              p.name.toLowerCase().includes("qw-410") ||
-              p.name.toLowerCase().includes("dnp") ||
-              p.is_online,
+              p.backend !== "file" &&
+              (p.name.toLowerCase().includes("qw-410") ||
+                p.name.toLowerCase().includes("dnp") ||
+                p.is_online),
           );
           if (dnpPrinter) printerName = dnpPrinter.name;
         }
 
         if (printerName) {
+          // The purchased package: one job of `copies` sheets — fewer than
+          // photos on papers that fit several prints on a sheet (2x6 cut)
+          const copies = await invoke<number>("get_print_sheets", {
+            paperProfile: paperProfile ?? null,
+            width: frameWidth,
+            height: frameHeight,
+            prints: Math.max(1, Number(state.quantity) || 1),
+          });
+          const printTimeout = copies * 30000 + 15000;
+
           // Set printing state BEFORE checking printer status to prevent false notifications
           // This must be done synchronously before any async operations
-          setPrinting(true, 45000); // 45 second timeout (longer than print operation)
+          setPrinting(true, printTimeout);
           console.log("[PhotoResult] Printing state set to true before print");
           
           // Small delay to ensure printing state is set before device check runs
           await new Promise(resolve => setTimeout(resolve, 100));
 
           try {
-            await invoke("print_photo", {
-              imagePath: printPath,
-              printerName,
-              frameType,
-              scale,
-              verticalOffset,
-              horizontalOffset,
-              isLandscape,
-            });
-
-            // Reduce paper level
-            await invoke("reduce_paper_level", { copies: 1 });
-            setPrintStatus("done");
+            // Queued in Rust: an offline printer is waited for and the
+            // paper level is reduced once the spooler completed the job
+            const job = await printAndWait(
+              {
+                imagePath: printPath,
+                printerName,
+                paperProfile,
+                scale,
+                verticalOffset,
+                horizontalOffset,
+                copies,
+              },
+              (update) => {
+                if (update.status === "queued" && update.error) setPrintStatus("waiting-printer");
+                else if (update.status === "spooling" || update.status === "printing")
+                  setPrintStatus("printing");
+              },
+              printTimeout,
+            );
+            // Done only once the spooler completed the job — still printing
+            // when the wait ran out stays "printing"
+            setPrintStatus(
+              job.status === "printed"
+                ? "done"
+                : job.status === "printing"
+                  ? "printing"
+                  : "queued",
+            );
           } finally {
             // Clear printing state after print completes (includes grace period)
             console.log("[PhotoResult] Print completed, clearing printing state");
@@ -473,34 +439,42 @@ export default function PhotoResult({ theme }: Props) {
         setPrinting(false);
       }
     },
-    [frameWidth, frameHeight],
+    [frameWidth, frameHeight, state.quantity],
   );
 
-  // Main effect - compose frame + print immediately
-  // Upload is triggered separately when presign data + composed image are both ready
+  // Stop waiting for the QR URL after QR_WAIT_MS (e.g. offline)
+  useEffect(() => {
+    const timer = setTimeout(() => setQrWaitExpired(true), QR_WAIT_MS);
+    return () => clearTimeout(timer);
+  }, []);
+
+  // Main effect - compose frame + print as soon as the stamps are ready
+  // (frames with a QR layer wait for presign so the QR isn't stamped empty)
+  // Upload is triggered separately once presign has settled and the image is composed
   useEffect(() => {
     if (hasStarted.current) return;
+    if (hasQrLayer && !presignSettled && !qrWaitExpired) return;
     hasStarted.current = true;
 
     const process = async () => {
       // Compose frame
-      const composedImg = await composeFrame();
-      if (!composedImg) return;
+      const composedPath = await composeFrame();
+      if (!composedPath) return;
 
       // Print immediately (doesn't need presign)
-      printFrame(composedImg);
+      printFrame(composedPath);
     };
 
     process();
-  }, []); // eslint-disable-line
+  }, [presignSettled, qrWaitExpired]); // eslint-disable-line
 
-  // Trigger upload when presign data arrives AND composedImage is ready
+  // Trigger upload when presign has settled (with or without URLs) AND composedImage is ready
   useEffect(() => {
-    if (!composedImage || !sessionId || uploadUrls.length === 0) return;
+    if (!composedImage || !presignSettled) return;
     if (hasUploadedFiles.current) return;
 
     uploadFiles(composedImage);
-  }, [composedImage, sessionId, uploadUrls, uploadFiles]);
+  }, [composedImage, presignSettled, uploadFiles]);
 
   // Auto-return countdown handled by Countdown component
   /*
@@ -525,9 +499,13 @@ export default function PhotoResult({ theme }: Props) {
     const checkPrinter = setInterval(async () => {
       try {
         const printers: any[] = await invoke("get_printers");
-        const hasPrinter = printers.some((p: any) => p.is_online);
-        if (!hasPrinter && printStatus === "printing") {
-          setError("เครื่องปริ้นถูกถอดออก กรุณาเชื่อมต่อใหม่");
+        const selected = localStorage.getItem("selectedPrinter");
+        const hasPrinter = printers.some(
+          (p: any) => p.is_online && (p.backend !== "file" || p.name === selected),
+        );
+        if (!hasPrinter && (printStatus === "printing" || printStatus === "waiting-printer")) {
+          // The job stays in the print queue and prints once the printer is back
+          setError("เครื่องปริ้นถูกถอดออก รูปจะพิมพ์อัตโนมัติเมื่อเชื่อมต่อใหม่");
           setTimeout(() => navigate("/"), 3000);
         }
       } catch {
@@ -605,7 +583,7 @@ export default function PhotoResult({ theme }: Props) {
             }}
           >
             <img
-              src={composedImage}
+              src={convertFileSrc(composedImage)}
               alt="Final Photo"
               style={{
                 maxWidth: "100%",
@@ -682,11 +660,13 @@ export default function PhotoResult({ theme }: Props) {
           📤{" "}
           {uploadStatus === "done"
             ? "✅ Uploaded"
-            : uploadStatus === "uploading"
-              ? "⏳ Uploading..."
-              : uploadStatus === "error"
-                ? "❌ Error"
-                : "⏳ Processing"}
+            : uploadStatus === "queued"
+              ? "🕒 Queued"
+              : uploadStatus === "uploading"
+                ? "⏳ Uploading..."
+                : uploadStatus === "error"
+                  ? "❌ Error"
+                  : "⏳ Processing"}
         </span>
         <span>
           🖨️{" "}
@@ -694,6 +674,10 @@ export default function PhotoResult({ theme }: Props) {
             ? "✅ Printed"
             : printStatus === "printing"
               ? "⏳ Printing..."
+              : printStatus === "waiting-printer"
+                ? "⏳ Waiting for printer..."
+              : printStatus === "queued"
+                ? "🕒 Queued"
               : printStatus === "no-printer"
                 ? "⚠️ No printer"
                 : printStatus === "error"
@@ -5,6 +5,7 @@ import BackButton from "../components/BackButton";
 import Countdown from "../components/Countdown";
 import type { ThemeData } from "../App";
 import { setPrinting } from "../utils/printingState";
+import { printAndWait } from "../utils/printQueue";
 
 interface Props {
   theme: ThemeData;
@@ -100,16 +101,16 @@ export default function RequestImage({ theme }: Props): React.JSX.Element {
       const isPortraitCut = orientation === "portrait-cut";
       const isLandscapeCut = orientation === "landscape-cut";
 
-      // Determine frameType - Rust print_photo handles duplication for 2x6/6x2 internally
-      let frameType = "4x6";
+      // Paper profile id - Rust print_photo lays 2x6/6x2 out on the full sheet
+      let paperProfile = "4x6";
       let isLandscape = false;
       if (isPortraitCut) {
-        frameType = "2x6";
+        paperProfile = "2x6";
       } else if (isLandscapeCut) {
-        frameType = "6x2";
+        paperProfile = "6x2";
         isLandscape = true;
       } else if (orientation === "landscape") {
-        frameType = "6x4";
+        paperProfile = "6x4";
         isLandscape = true;
       }
 
@@ -148,29 +149,29 @@ export default function RequestImage({ theme }: Props): React.JSX.Element {
       await new Promise(resolve => setTimeout(resolve, 100));
 
       try {
-        // Print for each copy
-        for (let i = 0; i < copies; i++) {
-          console.log(`[RequestImage] Printing copy ${i + 1}/${copies}...`);
-          await invoke("print_photo", {
+        // One queued job, all copies in one collated spool document — the paper
+        // level is reduced (POST paper-level/reduce) once the spooler completes it
+        const job = await printAndWait(
+          {
             imagePath: tempPath,
             printerName: selectedPrinter,
-            frameType,
+            paperProfile,
             scale,
             verticalOffset,
             horizontalOffset,
-            isLandscape,
-          });
-        }
+            copies,
+          },
+          (update) => console.log(`[RequestImage] Job ${update.id}: ${update.status} (${update.copiesPrinted}/${update.copies})`),
+          printTimeout,
+        );
 
-        // ลด paper level ที่หลังบ้าน (เส้นเดียวกับ bonio-booth: POST paper-level/reduce)
-        try {
-          await invoke("reduce_paper_level", { copies });
-        } catch (e) {
-          console.warn("[RequestImage] reduce_paper_level failed (non-blocking):", e);
+        if (job.status === "printed") {
+          console.log("[RequestImage] Print successful!");
+          setPrintStatus("success");
+        } else {
+          setPrintStatus("error");
+          setErrorMessage(job.error ? `รอเครื่องพิมพ์: ${job.error}` : "งานพิมพ์อยู่ในคิว");
         }
-
-        console.log("[RequestImage] Print successful!");
-        setPrintStatus("success");
       } catch (error) {
         console.error("[RequestImage] Error:", error);
         setPrintStatus("error");
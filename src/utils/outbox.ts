@@ -0,0 +1,40 @@
+/**
+ * Outbox helpers
+ * Photo deliveries (presign, uploads, confirm), paper changes and device
+ * reports go through the Rust outbox (outbox.rs), which queues them while the
+ * network is down and sends them when it comes back.
+ */
+
+export interface OutboxStatus {
+  depth: number;
+  failed: number;
+  oldestCreatedAt: string | null;
+  oldestAgeSecs: number | null;
+  failures: number;
+  lastError: string | null;
+  nextAttemptSecs: number | null;
+}
+
+/** A file of a photo delivery — mirrors `DeliveryFile` in outbox.rs */
+export interface DeliveryFile {
+  filePath: string;
+  contentType: string;
+  type: "photo" | "video";
+}
+
+export interface OutboxItem {
+  id: string;
+  request: { kind: string; [key: string]: unknown };
+  status: "pending" | "failed";
+  attempts: number;
+  error: string | null;
+  createdAt: string;
+  updatedAt: string;
+}
+
+/** "45s", "12m", "3h 20m" */
+export function formatAge(secs: number): string {
+  if (secs < 60) return `${secs}s`;
+  if (secs < 3600) return `${Math.floor(secs / 60)}m`;
+  return `${Math.floor(secs / 3600)}h ${Math.floor((secs % 3600) / 60)}m`;
+}
@@ -0,0 +1,99 @@
+/**
+ * Print queue helpers
+ * Prints go through the Rust print queue (print_queue.rs), which persists
+ * jobs, waits for an offline printer and retries failed spools.
+ */
+
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+export type PrintJobStatus =
+  | "queued"
+  | "spooling"
+  | "printing" // spooled, waiting for the spooler to finish it
+  | "printed"
+  | "failed"
+  | "cancelled";
+
+export interface PrintJob {
+  id: string;
+  imagePath: string;
+  images: string[];
+  printerName: string;
+  paperProfile: string | null;
+  scale: number;
+  verticalOffset: number;
+  horizontalOffset: number;
+  copies: number;
+  collate: boolean;
+  copiesPrinted: number;
+  status: PrintJobStatus;
+  attempts: number;
+  error: string | null;
+  reprintOf: string | null;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface PrintJobRequest {
+  imagePath: string;
+  images?: string[]; // further pages, printed after imagePath
+  printerName: string;
+  paperProfile?: string;
+  scale?: number;
+  verticalOffset?: number;
+  horizontalOffset?: number;
+  copies?: number;
+  collate?: boolean; // defaults to true (1 2 1 2)
+}
+
+/**
+ * Queue a print and wait until it is printed (the spooler completed it).
+ * Rejects when the job fails or is cancelled, also in the spooler. After `waitMs` it resolves with
+ * the job as it is (e.g. still queued for an offline printer) — the queue
+ * keeps going in the background.
+ */
+export async function printAndWait(
+  request: PrintJobRequest,
+  onUpdate?: (job: PrintJob) => void,
+  waitMs = 60000,
+): Promise<PrintJob> {
+  let jobId: string | null = null;
+  let latest: PrintJob | null = null;
+  const early: PrintJob[] = [];
+  let settle: (job: PrintJob) => void = () => {};
+
+  const done = new Promise<PrintJob>((resolve, reject) => {
+    settle = (job: PrintJob) => {
+      latest = job;
+      onUpdate?.(job);
+      if (job.status === "printed") resolve(job);
+      else if (job.status === "failed" || job.status === "cancelled")
+        reject(new Error(job.error || `Print job ${job.status}`));
+    };
+  });
+
+  const unlisten = await listen<PrintJob>("print-job-updated", (event) => {
+    if (jobId === null) early.push(event.payload);
+    else if (event.payload.id === jobId) settle(event.payload);
+  });
+
+  try {
+    const job = await invoke<PrintJob>("enqueue_print_job", { ...request });
+    jobId = job.id;
+    settle(job);
+    early.filter((j) => j.id === job.id).forEach((j) => settle(j));
+
+    let timer: ReturnType<typeof setTimeout> | undefined;
+    const timeout = new Promise<PrintJob>((resolve) => {
+      timer = setTimeout(() => resolve(latest ?? job), waitMs);
+    });
+    try {
+      return await Promise.race([done, timeout]);
+    } finally {
+      clearTimeout(timer);
+    }
+  } finally {
+    unlisten();
+  }
+}